@@ -0,0 +1,188 @@
+//! Terminal inspection of sqlite database files, the same parsing as the web UI.
+use std::fs::File;
+use std::io::{self, Write};
+use std::process::ExitCode;
+
+use parser::{Dump, Reader, SeekStorage, StdError};
+
+mod print;
+mod serve;
+
+const USAGE: &str = "Usage: sqlite-repr <database> [--page N] [--tree] [--json]
+       sqlite-repr serve <database> [--port N] [--dist DIR]
+
+Without flags prints the database header and a summary line per page.
+
+  --page N    header, cells and raw bytes of the page N
+  --tree      b-trees with the pages they consist of
+  --json      whole parsed structure, or the page with --page, as JSON
+
+serve opens the web UI for the database at http://127.0.0.1:<port>/sqlite-repr/
+
+  --db PATH   database to show, the same as the positional argument
+  --port N    port to listen on, 8080 by default
+  --dist DIR  built web UI, ui/dist by default";
+
+const DEFAULT_PORT: u16 = 8080;
+const DEFAULT_DIST: &str = "ui/dist";
+
+#[derive(Debug, Default)]
+struct Args {
+    path: String,
+    page: Option<usize>,
+    tree: bool,
+    json: bool,
+    help: bool,
+    /// Serve the web UI instead of printing.
+    serve: bool,
+    port: u16,
+    dist: String,
+}
+
+impl Args {
+    fn parse(args: impl Iterator<Item = String>) -> Result<Self, StdError> {
+        let mut parsed = Self {
+            port: DEFAULT_PORT,
+            dist: DEFAULT_DIST.to_string(),
+            ..Default::default()
+        };
+        let mut path = None;
+        let mut args = args.peekable();
+        if args.peek().is_some_and(|arg| arg == "serve") {
+            args.next();
+            parsed.serve = true;
+        }
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--db" if path.is_none() => path = args.next(),
+                "--db" => Err("Only one database path is expected.")?,
+                "--port" => {
+                    let port = args.next().ok_or("--port expects a port number.")?;
+                    parsed.port = port.parse()?;
+                }
+                "--dist" => parsed.dist = args.next().ok_or("--dist expects a directory.")?,
+                "--page" => {
+                    let page_num = args.next().ok_or("--page expects a page number.")?;
+                    parsed.page = Some(page_num.parse()?);
+                }
+                "--tree" => parsed.tree = true,
+                "--json" => parsed.json = true,
+                "-h" | "--help" => parsed.help = true,
+                flag if flag.starts_with('-') => Err(format!("Unknown flag {}.", flag))?,
+                _ if path.is_none() => path = Some(arg),
+                _ => Err("Only one database path is expected.")?,
+            }
+        }
+        if !parsed.help {
+            parsed.path = path.ok_or("Database path is missing.")?;
+        }
+        Ok(parsed)
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+    match run(&args, &mut io::stdout().lock()) {
+        Ok(()) => ExitCode::SUCCESS,
+        // Output piped into `head` and alike is closed once they've read enough.
+        Err(e) if is_broken_pipe(&e) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn is_broken_pipe(e: &StdError) -> bool {
+    e.downcast_ref::<io::Error>()
+        .is_some_and(|e| e.kind() == io::ErrorKind::BrokenPipe)
+}
+
+/// Print into the output, which is written through once for the whole run.
+fn run(args: &Args, out: &mut impl Write) -> Result<(), StdError> {
+    if args.help {
+        writeln!(out, "{}", USAGE)?;
+        return Ok(());
+    }
+    if args.serve {
+        return serve::serve(&args.path, &args.dist, args.port);
+    }
+    let reader = Reader::with_storage(SeekStorage::new(File::open(&args.path)?)?)?;
+    if let Some(page_num) = args.page {
+        if page_num == 0 || page_num > reader.pages_total() {
+            Err(format!(
+                "Page {} is out of range, the database has {} pages.",
+                page_num,
+                reader.pages_total()
+            ))?;
+        }
+    }
+
+    match args.page {
+        Some(page_num) if args.json => {
+            let page = reader.get_btree_page(page_num)?;
+            writeln!(out, "{}", serde_json::to_string_pretty(&page)?)?;
+        }
+        None if args.json => {
+            let dump = Dump::new(&reader)?;
+            writeln!(out, "{}", serde_json::to_string_pretty(&dump)?)?;
+        }
+        Some(page_num) => print::page(&reader, page_num, out)?,
+        None if args.tree => print::trees(&reader, out)?,
+        None => {
+            print::header(&reader, out)?;
+            writeln!(out)?;
+            print::pages(&reader, out)?;
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Args, StdError> {
+        Args::parse(args.split_whitespace().map(str::to_string))
+    }
+
+    #[test]
+    fn test_args() {
+        let args = parse("db --page 3 --json").unwrap();
+        assert_eq!(args.path, "db");
+        assert_eq!(args.page, Some(3));
+        assert!(args.json && !args.tree && !args.serve);
+
+        let args = parse("serve --db db --port 9000").unwrap();
+        assert!(args.serve);
+        assert_eq!(args.path, "db");
+        assert_eq!(args.port, 9000);
+        assert_eq!(args.dist, DEFAULT_DIST);
+        // Only the first argument is the command, then it's a database path.
+        assert_eq!(
+            parse("db serve").unwrap_err().to_string(),
+            "Only one database path is expected."
+        );
+        assert_eq!(parse("db").unwrap().port, DEFAULT_PORT);
+
+        // Help doesn't need a database.
+        assert!(parse("--help").unwrap().help);
+        assert!(parse("").is_err());
+        assert!(parse("--tree").is_err());
+        assert!(parse("db --page").is_err());
+        assert!(parse("db --page x").is_err());
+        assert!(parse("db --db other").is_err());
+        assert!(parse("db --port 70000").is_err());
+        assert_eq!(
+            parse("db --pages 3").unwrap_err().to_string(),
+            "Unknown flag --pages."
+        );
+    }
+}
@@ -0,0 +1,401 @@
+//! Plain text representation of the parsed database.
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+use parser::*;
+
+/// What a page is used for, figured out by following b-trees and the freelist.
+#[derive(Debug, Clone, PartialEq)]
+enum PageKind {
+    /// Page type and the name of the b-tree.
+    Btree(PageHeaderType, String),
+    Overflow(String),
+    /// Name of the b-tree, which references the page, and the error.
+    Corrupt(String, Error),
+    FreelistTrunk,
+    FreelistLeaf,
+    LockByte,
+    Ptrmap,
+    Unreferenced,
+}
+
+impl std::fmt::Display for PageKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Btree(page_type, name) => write!(f, "{:<16} {}", page_type.to_string(), name),
+            Self::Overflow(name) => write!(f, "{:<16} {}", "Overflow", name),
+            Self::Corrupt(name, error) => write!(f, "{:<16} {}: {}", "Corrupt", name, error),
+            Self::FreelistTrunk => write!(f, "Freelist Trunk"),
+            Self::FreelistLeaf => write!(f, "Freelist Leaf"),
+            Self::LockByte => write!(f, "Lock-byte"),
+            Self::Ptrmap => write!(f, "Pointer Map"),
+            Self::Unreferenced => write!(f, "Unreferenced"),
+        }
+    }
+}
+
+pub fn header(reader: &Reader, out: &mut impl Write) -> io::Result<()> {
+    let h = &reader.db_header;
+    let version = format!(
+        "{}.{}.{}",
+        h.version / 1_000_000,
+        h.version / 1000 % 1000,
+        h.version % 1000
+    );
+    let rows: [(&str, String); 19] = [
+        ("Header string", h.header.trim_end_matches('\0').to_string()),
+        ("Page size", h.page_size.to_string()),
+        ("Write version", h.write_version.to_string()),
+        ("Read version", h.read_version.to_string()),
+        ("Reserved space", h.reserved_page_space.to_string()),
+        ("File change counter", h.file_change_counter.to_string()),
+        ("Database size", h.db_size.to_string()),
+        ("First freelist page", h.first_free_page_num.to_string()),
+        ("Freelist pages", h.freelist_total.to_string()),
+        ("Schema cookie", h.schema_cookie.to_string()),
+        ("Schema format", h.schema_format_num.to_string()),
+        ("Page cache size", h.default_page_cache_size.to_string()),
+        ("Largest root page", h.largest_root.to_string()),
+        ("Text encoding", h.text_encoding.to_string()),
+        ("User version", h.user_version.to_string()),
+        ("Incremental vacuum", h.inc_vacuum_mode.to_string()),
+        ("Application id", h.application_id.to_string()),
+        ("Version valid for", h.version_valid_for_number.to_string()),
+        ("SQLite version", version),
+    ];
+    for (name, value) in rows {
+        writeln!(out, "{:<20} {}", name, value)?;
+    }
+    Ok(())
+}
+
+/// One line per page of the file.
+pub fn pages(reader: &Reader, out: &mut impl Write) -> io::Result<()> {
+    let kinds = page_kinds(reader);
+    for page_num in 1..=reader.pages_total() {
+        let kind = kinds.get(&page_num).unwrap_or(&PageKind::Unreferenced);
+        writeln!(out, "{:>6}  {}", page_num, kind)?;
+    }
+    Ok(())
+}
+
+pub fn trees(reader: &Reader, out: &mut impl Write) -> io::Result<()> {
+    let (trees, failed) = btrees(reader);
+    for tree in trees {
+        writeln!(
+            out,
+            "{} {}, root page {}",
+            tree.ttype, tree.name, tree.root.page_num
+        )?;
+        if !tree.columns.is_empty() {
+            writeln!(out, "  columns: {}", tree.columns.join(", "))?;
+        }
+        if let Some(key) = tree.key_columns {
+            let key = tree.columns.get(..key).unwrap_or_default();
+            writeln!(out, "  without rowid, primary key: {}", key.join(", "))?;
+        }
+        node(&tree.root, 1, out)?;
+        writeln!(out)?;
+    }
+    for (root, error) in failed {
+        writeln!(out, "root page {}: {}", root, error)?;
+    }
+    Ok(())
+}
+
+pub fn page(reader: &Reader, page_num: usize, out: &mut impl Write) -> io::Result<()> {
+    let kinds = page_kinds(reader);
+    let kind = kinds.get(&page_num).unwrap_or(&PageKind::Unreferenced);
+    writeln!(out, "Page {}: {}", page_num, kind)?;
+
+    let PageKind::Btree(..) = kind else {
+        writeln!(out)?;
+        return hexdump(&reader.get_partial_page(page_num), out);
+    };
+    let page = match reader.get_btree_page(page_num) {
+        Ok(page) => page,
+        Err(e) => return writeln!(out, "{}", e),
+    };
+    let header = &page.page_header;
+    writeln!(out, "  cells: {}", header.cell_num)?;
+    writeln!(
+        out,
+        "  cell content area start: {}",
+        header.cell_start_offset
+    )?;
+    writeln!(
+        out,
+        "  fragmented free bytes: {}",
+        header.fragmented_free_bytes
+    )?;
+    if let Some(right) = header.page_num {
+        writeln!(out, "  right most pointer: {}", right)?;
+    }
+    for block in &page.free_blocks {
+        writeln!(
+            out,
+            "  freeblock at {}: {} bytes, next {}",
+            block.offset, block.size, block.next
+        )?;
+    }
+    writeln!(out)?;
+    for (n, (offset, cell)) in page.cell_pointer.array.iter().zip(&page.cells).enumerate() {
+        writeln!(out, "{:>4} @{:<6} {}", n, offset, self::cell(cell))?;
+    }
+    Ok(())
+}
+
+fn node(node: &BTreeNode, depth: usize, out: &mut impl Write) -> io::Result<()> {
+    let indent = "  ".repeat(depth);
+    let header = &node.page.page_header;
+    let overflow = node
+        .overflow
+        .iter()
+        .flatten()
+        .map(|o| o.page_num.to_string())
+        .collect::<Vec<_>>();
+    let overflow = match overflow.is_empty() {
+        true => String::new(),
+        false => format!(", overflow {}", overflow.join(" ")),
+    };
+    writeln!(
+        out,
+        "{}page {}: {}, {} cells{}",
+        indent, node.page_num, header.page_type, header.cell_num, overflow
+    )?;
+    for child in node.children.iter().flatten().flatten() {
+        self::node(child, depth + 1, out)?;
+    }
+    for corrupt in &node.corrupt {
+        writeln!(
+            out,
+            "{}  page {}: corrupt, {}",
+            indent, corrupt.page_num, corrupt.error
+        )?;
+    }
+    Ok(())
+}
+
+fn cell(cell: &Cell) -> String {
+    let overflow = |o: &Option<CellOverflow>| match o {
+        Some(o) => format!(" +overflow page {}", o.page),
+        None => String::new(),
+    };
+    match cell {
+        Cell::TableLeaf(c) => format!(
+            "rowid {}: {}{}",
+            c.rowid_varint.value,
+            record(c.payload()),
+            overflow(&c.overflow)
+        ),
+        Cell::TableInterior(c) => format!(
+            "left page {}, rowid {}",
+            c.left_page_number, c.rowid_varint.value
+        ),
+        Cell::IndexLeaf(c) => format!("{}{}", record(c.payload()), overflow(&c.overflow)),
+        Cell::IndexInterior(c) => format!(
+            "left page {}, {}{}",
+            c.left_page_number,
+            record(c.payload()),
+            overflow(&c.overflow)
+        ),
+    }
+}
+
+/// Values in the SQL literal syntax.
+fn record(record: Result<&Record, Error>) -> String {
+    let record = match record {
+        Ok(record) => record,
+        Err(e) => return format!("<{}>", e),
+    };
+    let values = record
+        .values
+        .iter()
+        .map(|v| match &v.value {
+            RecordType::Null => "NULL".to_string(),
+            RecordType::I8(v) | RecordType::Zero(v) | RecordType::One(v) => v.to_string(),
+            RecordType::I16(v) => v.to_string(),
+            RecordType::I24(v) | RecordType::I32(v) => v.to_string(),
+            RecordType::I48(v) | RecordType::I64(v) => v.to_string(),
+            RecordType::F64(v) => v.to_string(),
+            RecordType::Ten | RecordType::Eleven => "<internal>".to_string(),
+            RecordType::Text(v) => format!("'{}'", v.as_deref().unwrap_or("").replace('\'', "''")),
+            RecordType::Blob(v) => format!("x'{}'", hex(v.as_deref().unwrap_or(&[]))),
+        })
+        .collect::<Vec<_>>();
+    format!("({})", values.join(", "))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Rows of 16 bytes, repeated rows are collapsed into `*` like hexdump does.
+fn hexdump(bytes: &[u8], out: &mut impl Write) -> io::Result<()> {
+    let mut previous = None;
+    let mut collapsed = false;
+    for (n, row) in bytes.chunks(16).enumerate() {
+        if previous == Some(row) {
+            if !collapsed {
+                writeln!(out, "*")?;
+                collapsed = true;
+            }
+            continue;
+        }
+        previous = Some(row);
+        collapsed = false;
+        let hex = row
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let text = row
+            .iter()
+            .map(|&b| match b.is_ascii_graphic() || b == b' ' {
+                true => b as char,
+                false => '.',
+            })
+            .collect::<String>();
+        writeln!(out, "{:08x}  {:<47}  |{}|", n * 16, hex, text)?;
+    }
+    writeln!(out, "{:08x}", bytes.len())?;
+    Ok(())
+}
+
+/// B-trees of the schema, the ones which failed to parse are reported by the root page.
+fn btrees(reader: &Reader) -> (Vec<BTree>, Vec<(usize, Error)>) {
+    let mut trees = vec![];
+    let mut failed = vec![];
+    match reader.get_schema_btree() {
+        Ok(tree) => trees.push(tree),
+        Err(e) => failed.push((1, e)),
+    }
+    for cell in reader.get_schema_cells() {
+        match BTree::new(&cell, reader) {
+            Ok(tree) => trees.push(tree),
+            // Views and triggers have no root page.
+            Err(e) => {
+                let values = cell.payload().map(|p| p.values.as_slice());
+                if let Some(root) = BTree::root_page(values.unwrap_or_default()) {
+                    failed.push((root, e));
+                }
+            }
+        }
+    }
+    (trees, failed)
+}
+
+fn page_kinds(reader: &Reader) -> BTreeMap<usize, PageKind> {
+    let mut kinds = BTreeMap::new();
+    let (trees, failed) = btrees(reader);
+    for tree in &trees {
+        let name = format!("{} {}", tree.ttype, tree.name);
+        collect_kinds(&tree.root, &name, &mut kinds);
+    }
+    for (root, error) in failed {
+        kinds.insert(root, PageKind::Corrupt("schema entry".to_string(), error));
+    }
+    // Broken freelist is shown as far as it goes, the rest stays unreferenced.
+    let freelist = match Dump::freelist(reader) {
+        Ok(freelist) => freelist,
+        Err(e) => {
+            eprintln!("Freelist: {}", e);
+            vec![]
+        }
+    };
+    for trunk in freelist {
+        kinds.insert(trunk.page_num, PageKind::FreelistTrunk);
+        for &leaf in trunk.page.leaf_page_numbers.iter().flatten() {
+            kinds.insert(leaf as usize, PageKind::FreelistLeaf);
+        }
+    }
+    if let Some(page_num) = reader.lock_byte_page() {
+        kinds.insert(page_num, PageKind::LockByte);
+    }
+    for page_num in reader.ptrmap_pages() {
+        kinds.insert(page_num, PageKind::Ptrmap);
+    }
+    kinds
+}
+
+fn collect_kinds(node: &BTreeNode, name: &str, kinds: &mut BTreeMap<usize, PageKind>) {
+    let page_type = node.page.page_header.page_type;
+    kinds.insert(node.page_num, PageKind::Btree(page_type, name.to_string()));
+    for overflow in node.overflow.iter().flatten() {
+        kinds.insert(overflow.page_num, PageKind::Overflow(name.to_string()));
+    }
+    for corrupt in &node.corrupt {
+        let error = corrupt.error.clone();
+        kinds.insert(corrupt.page_num, PageKind::Corrupt(name.to_string(), error));
+    }
+    for child in node.children.iter().flatten().flatten() {
+        collect_kinds(child, name, kinds);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Database, which SQLite creates with the script.
+    fn database(name: &str, script: &str) -> Reader {
+        let path = std::env::temp_dir().join(format!(
+            "sqlite-repr-cli-test-{}-{}.db",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let conn = rusqlite::Connection::open(&path).unwrap();
+        conn.execute_batch(script).unwrap();
+        drop(conn);
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        Reader::new(Box::leak(bytes.into_boxed_slice())).unwrap()
+    }
+
+    #[test]
+    fn test_page_kinds() {
+        // Table at page 2 with its row spilled onto overflow pages from page 3 on, its
+        // index and another table, which pages go onto the freelist once it's dropped.
+        let reader = database(
+            "kinds",
+            "PRAGMA page_size=512;
+            CREATE TABLE t(v);
+            INSERT INTO t VALUES(zeroblob(2000));
+            CREATE INDEX i ON t(v);
+            CREATE TABLE d(v);
+            INSERT INTO d VALUES(zeroblob(1500));
+            DROP TABLE d;",
+        );
+        let kinds = page_kinds(&reader);
+        let table = "table t".to_string();
+        assert_eq!(
+            kinds[&2],
+            PageKind::Btree(PageHeaderType::LeafTable, table.clone())
+        );
+        assert_eq!(kinds[&3], PageKind::Overflow(table));
+        assert!(matches!(
+            &kinds[&1],
+            PageKind::Btree(PageHeaderType::LeafTable, _)
+        ));
+        let index = kinds
+            .values()
+            .filter(|k| matches!(k, PageKind::Btree(PageHeaderType::LeafIndex, name) if name == "index i"))
+            .count();
+        assert_eq!(index, 1);
+        let trunk = reader.db_header.first_free_page_num as usize;
+        assert_eq!(kinds[&trunk], PageKind::FreelistTrunk);
+        let leaves = kinds
+            .values()
+            .filter(|k| **k == PageKind::FreelistLeaf)
+            .count();
+        assert_eq!(leaves, reader.db_header.freelist_total as usize - 1);
+
+        // Every page gets a line, the ones nothing points at too.
+        let mut out = vec![];
+        pages(&reader, &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out.lines().count(), reader.pages_total());
+        assert!(out.starts_with("     1  Leaf Table"));
+    }
+}
@@ -0,0 +1,161 @@
+//! Local server for the web UI, which shows a database file from the disk
+//! without compiling it into the included examples.
+//!
+//! It's meant for a single user on the same machine, so requests are handled
+//! one by one and every connection is closed after the response.
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Component, Path};
+
+use parser::{Journal, Reader, SeekStorage, StdError};
+
+/// The UI is built to be served under this path, see `base_path` in ui/Dioxus.toml.
+const BASE: &str = "/sqlite-repr/";
+/// Paths the UI fetches the local database, its rollback journal and the
+/// super-journal the rollback journal points to from.
+const LOCAL_DB: &str = "local.db";
+const LOCAL_JOURNAL: &str = "local.db-journal";
+const LOCAL_SUPER_JOURNAL: &str = "local.db-superjournal";
+
+pub fn serve(db: &str, dist: &str, port: u16) -> Result<(), StdError> {
+    // Fail early on something, which isn't a database at all.
+    Reader::with_storage(SeekStorage::new(fs::File::open(db)?)?)?;
+    if !Path::new(dist).join("index.html").is_file() {
+        Err(format!(
+            "No built UI in {}, run `dx build --release` in ui/ or pass --dist.",
+            dist
+        ))?;
+    }
+
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+    println!("Serving {} at http://127.0.0.1:{}{}", db, port, BASE);
+    for stream in listener.incoming() {
+        let result = stream
+            .map_err(StdError::from)
+            .and_then(|stream| handle(stream, db, dist));
+        if let Err(e) = result {
+            eprintln!("{}", e);
+        }
+    }
+    Ok(())
+}
+
+fn handle(mut stream: TcpStream, db: &str, dist: &str) -> Result<(), StdError> {
+    let mut reader = BufReader::new(&stream);
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+    // Headers aren't used, but have to be read off the connection.
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+    }
+
+    let mut request = request.split_whitespace();
+    let method = request.next().unwrap_or_default();
+    let target = request.next().unwrap_or("/");
+    let path = target.split(['?', '#']).next().unwrap_or_default();
+    if method != "GET" {
+        return respond(&mut stream, "405 Method Not Allowed", "text/plain", b"");
+    }
+    let Some(path) = path.strip_prefix(BASE) else {
+        return redirect(&mut stream, BASE);
+    };
+
+    match path {
+        // Read on every request, so the changes made by other processes show up on reload.
+        LOCAL_DB => respond(
+            &mut stream,
+            "200 OK",
+            "application/octet-stream",
+            &fs::read(db)?,
+        ),
+        LOCAL_JOURNAL => match fs::read(format!("{}-journal", db)) {
+            Ok(bytes) => respond(&mut stream, "200 OK", "application/octet-stream", &bytes),
+            Err(_) => respond(&mut stream, "404 Not Found", "text/plain", b""),
+        },
+        LOCAL_SUPER_JOURNAL => match super_journal(db) {
+            Some(bytes) => respond(&mut stream, "200 OK", "application/octet-stream", &bytes),
+            None => respond(&mut stream, "404 Not Found", "text/plain", b""),
+        },
+        _ => {
+            // Nothing outside of the UI directory is served.
+            let relative = Path::new(path);
+            if relative
+                .components()
+                .any(|c| !matches!(c, Component::Normal(_)))
+            {
+                return respond(&mut stream, "404 Not Found", "text/plain", b"");
+            }
+            let file = Path::new(dist).join(relative);
+            if file.is_file() {
+                return respond(
+                    &mut stream,
+                    "200 OK",
+                    content_type(&file),
+                    &fs::read(&file)?,
+                );
+            }
+            match relative.extension() {
+                Some(_) => respond(&mut stream, "404 Not Found", "text/plain", b""),
+                // Deep links are routed by the UI itself.
+                None => {
+                    let index = fs::read(Path::new(dist).join("index.html"))?;
+                    respond(&mut stream, "200 OK", "text/html", &index)
+                }
+            }
+        }
+    }
+}
+
+/// Super-journal named in the rollback journal, the name is the path SQLite
+/// has created it at, relative names are taken relative to the database.
+fn super_journal(db: &str) -> Option<Vec<u8>> {
+    let journal = fs::read(format!("{}-journal", db)).ok()?;
+    let pointer = Journal::try_from(journal.as_slice()).ok()?.super_journal?;
+    let path = Path::new(db).parent()?.join(&pointer.name);
+    fs::read(path).ok()
+}
+
+fn content_type(file: &Path) -> &'static str {
+    match file.extension().and_then(|e| e.to_str()) {
+        Some("html") => "text/html",
+        Some("js") => "text/javascript",
+        // Required for the streaming compilation of the UI.
+        Some("wasm") => "application/wasm",
+        Some("css") => "text/css",
+        Some("json") => "application/json",
+        Some("png") => "image/png",
+        Some("svg") => "image/svg+xml",
+        Some("ico") => "image/x-icon",
+        _ => "application/octet-stream",
+    }
+}
+
+fn redirect(stream: &mut TcpStream, location: &str) -> Result<(), StdError> {
+    write!(
+        stream,
+        "HTTP/1.1 302 Found\r\nLocation: {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        location
+    )?;
+    Ok(())
+}
+
+fn respond(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &[u8],
+) -> Result<(), StdError> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    )?;
+    stream.write_all(body)?;
+    Ok(())
+}
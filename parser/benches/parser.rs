@@ -0,0 +1,158 @@
+//! Parsing speed on generated databases from 100 KB to 100 MB, run with `cargo bench -p parser`.
+//!
+//! Databases are created with SQLite on the first run and kept in the temp directory,
+//! the following runs reuse them. Each one is a rowid table with an index on the text
+//! column, every 50th row has a blob, which spills onto overflow pages.
+use std::fs;
+use std::path::Path;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use parser::*;
+use rusqlite::Connection;
+
+const SIZES: &[(&str, usize)] = &[
+    ("100KB", 100 << 10),
+    ("1MB", 1 << 20),
+    ("10MB", 10 << 20),
+    ("100MB", 100 << 20),
+];
+
+/// Rows are inserted in batches until the file is at least of the size.
+const BATCH: usize = 100;
+
+struct Database {
+    bytes: &'static [u8],
+    /// Root page of the table b-tree.
+    root: usize,
+}
+
+fn database(name: &str, size: usize) -> Database {
+    let path = std::env::temp_dir().join(format!("sqlite-repr-bench-{}.db", name));
+    if !path.exists() {
+        generate(&path, size);
+    }
+    let conn = Connection::open(&path).expect("Benchmark database opens");
+    let root = conn
+        .query_row(
+            "SELECT rootpage FROM sqlite_schema WHERE name = 't'",
+            [],
+            |row| row.get(0),
+        )
+        .expect("Benchmark table exists");
+    let bytes = fs::read(&path).expect("Benchmark database is readable");
+    Database {
+        bytes: Box::leak(bytes.into_boxed_slice()),
+        root,
+    }
+}
+
+fn generate(path: &Path, size: usize) {
+    // Half-written database of the interrupted run isn't reused.
+    let tmp = path.with_extension("tmp");
+    let _ = fs::remove_file(&tmp);
+    let mut conn = Connection::open(&tmp).expect("Benchmark database is created");
+    conn.execute_batch(
+        "CREATE TABLE t(id INTEGER PRIMARY KEY, name TEXT, value REAL, data BLOB);
+         CREATE INDEX t_name ON t(name);",
+    )
+    .expect("Benchmark schema is created");
+    let mut id: i64 = 0;
+    loop {
+        let tx = conn.transaction().expect("Transaction starts");
+        {
+            let mut insert = tx
+                .prepare("INSERT INTO t VALUES (?1, ?2, ?3, zeroblob(?4))")
+                .expect("Insert is prepared");
+            for _ in 0..BATCH {
+                id += 1;
+                let blob = if id % 50 == 0 { 5000 } else { 64 };
+                insert
+                    .execute((
+                        id,
+                        format!("name-{:08}", id * 7919 % 100_003),
+                        id as f64 / 3.0,
+                        blob,
+                    ))
+                    .expect("Row is inserted");
+            }
+        }
+        tx.commit().expect("Transaction commits");
+        let pages: usize = conn
+            .query_row("PRAGMA page_count", [], |row| row.get(0))
+            .expect("Page count is known");
+        let page_size: usize = conn
+            .query_row("PRAGMA page_size", [], |row| row.get(0))
+            .expect("Page size is known");
+        if pages * page_size >= size {
+            break;
+        }
+    }
+    conn.close().expect("Benchmark database closes");
+    fs::rename(&tmp, path).expect("Benchmark database is moved into place");
+}
+
+/// Raw records of the leaf cells, which are fully on their b-tree pages.
+fn records(node: &BTreeNode, records: &mut Vec<Vec<u8>>) {
+    for cell in &node.page.cells {
+        if let Cell::TableLeaf(cell) = cell {
+            if cell.overflow.is_none() {
+                records.push(cell.record.bytes().to_vec());
+            }
+        }
+    }
+    for child in node.children.iter().flatten().flatten() {
+        self::records(child, records);
+    }
+}
+
+fn full_file(c: &mut Criterion) {
+    let mut group = c.benchmark_group("full_file");
+    group.sample_size(10);
+    for &(name, size) in SIZES {
+        let db = database(name, size);
+        group.throughput(Throughput::Bytes(db.bytes.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), &db, |b, db| {
+            b.iter(|| Dump::new(&Reader::new(db.bytes).unwrap()).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn btree_traversal(c: &mut Criterion) {
+    let mut group = c.benchmark_group("btree_traversal");
+    group.sample_size(10);
+    for &(name, size) in SIZES {
+        let db = database(name, size);
+        let reader = Reader::new(db.bytes).unwrap();
+        group.throughput(Throughput::Bytes(db.bytes.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), &db, |b, db| {
+            b.iter(|| BTreeNode::new(db.root, &reader).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn record_decoding(c: &mut Criterion) {
+    let mut group = c.benchmark_group("record_decoding");
+    group.sample_size(10);
+    for &(name, size) in SIZES {
+        let db = database(name, size);
+        let reader = Reader::new(db.bytes).unwrap();
+        let encoding = reader.db_header.text_encoding;
+        let mut raw = vec![];
+        records(&BTreeNode::new(db.root, &reader).unwrap(), &mut raw);
+        let total = raw.iter().map(Vec::len).sum::<usize>();
+        group.throughput(Throughput::Bytes(total as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), &raw, |b, raw| {
+            b.iter(|| {
+                for bytes in raw {
+                    Record::try_from((encoding, bytes.as_slice())).unwrap();
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, full_file, btree_traversal, record_decoding);
+criterion_main!(benches);
@@ -2,26 +2,76 @@
 use crate::*;
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct BTreeNode {
     pub page: Page,
     pub page_num: usize,
-    pub children: Option<Vec<BTreeNode>>,
+    /// Child of every cell and of the right-most pointer at its position, the one,
+    /// which failed to parse, is None and kept in `corrupt` instead.
+    pub children: Option<Vec<Option<BTreeNode>>>,
     pub overflow: Option<Vec<OverflowNode>>,
+    /// Child pages, which are referenced from this node, but failed to parse.
+    pub corrupt: Vec<CorruptNode>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CorruptNode {
+    pub page_num: usize,
+    /// Raw content of the page, whatever is left of it in the file.
+    pub bytes: Vec<u8>,
+    pub error: Error,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct OverflowNode {
     pub page: OverflowPage,
     pub page_num: usize,
 }
 
 impl BTreeNode {
-    pub fn new(page_num: usize, reader: &Reader) -> Result<Self, StdError> {
+    pub fn new(page_num: usize, reader: &Reader) -> Result<Self, Error> {
+        Self::with_ancestors(page_num, reader, &mut vec![])
+    }
+
+    /// Node of the page below the ancestor pages. Corrupt child pointer might lead
+    /// back to one of them, the tree would loop forever, so such a child is broken.
+    fn with_ancestors(
+        page_num: usize,
+        reader: &Reader,
+        ancestors: &mut Vec<usize>,
+    ) -> Result<Self, Error> {
+        if ancestors.contains(&page_num) {
+            return Err(Error::Malformed(format!(
+                "Page {} is its own ancestor in the b-tree.",
+                page_num
+            )));
+        }
         let page = reader.get_btree_page(page_num)?;
+        ancestors.push(page_num);
         let mut children = vec![];
         let mut overflow = vec![];
+        let mut corrupt = vec![];
 
-        let mut extend_overflow = |cell_overflow: &Option<CellOverflow>| {
+        // Broken child is kept aside, so the rest of the tree is still available.
+        let mut push_child = |children: &mut Vec<Option<BTreeNode>>, child_num: usize| {
+            match BTreeNode::with_ancestors(child_num, reader, ancestors) {
+                Ok(child) => children.push(Some(child)),
+                Err(e) => {
+                    children.push(None);
+                    corrupt.push(CorruptNode {
+                        page_num: child_num,
+                        bytes: reader.get_partial_page(child_num),
+                        error: e,
+                    })
+                }
+            }
+        };
+
+        let mut extend_overflow = |cell: usize,
+                                   cell_overflow: &Option<CellOverflow>,
+                                   record: &LazyRecord| {
             if let Some(o) = cell_overflow {
                 let res = Self::follow_overflow(vec![], o.units.to_vec(), o.page as usize, reader);
                 if let Ok(res) = res {
@@ -33,12 +83,30 @@ impl BTreeNode {
                     page_nums.insert(0, o.page as usize);
                     page_nums.pop();
 
+                    // Units of the values, which are still to come before each page,
+                    // are the tail of the record, the first one is the column on the page.
+                    let columns = record.header().datatypes.len();
+                    let mut units = o.units.to_vec();
                     let overflow_list = res
                         .into_iter()
-                        .zip(page_nums.into_iter())
-                        .map(|(o, n)| OverflowNode {
-                            page: o,
-                            page_num: n,
+                        .zip(page_nums)
+                        .map(|(mut o, n)| {
+                            let values = units
+                                .iter()
+                                .filter(|u| u.overflow_type != RecordCode::HEADER)
+                                .count();
+                            o.owner = Some(OverflowOwner {
+                                page_num,
+                                cell,
+                                cell_offset: page.cell_pointer.array[cell] as usize,
+                                column: (!o.data.is_empty())
+                                    .then_some(columns.saturating_sub(values)),
+                            });
+                            units = o.overflow_units.to_vec();
+                            OverflowNode {
+                                page: o,
+                                page_num: n,
+                            }
                         })
                         .collect::<Vec<OverflowNode>>();
                     overflow.extend(overflow_list);
@@ -46,50 +114,96 @@ impl BTreeNode {
             }
         };
 
-        for outer_cell in page.cells.iter() {
+        for (n, outer_cell) in page.cells.iter().enumerate() {
             match outer_cell {
                 Cell::TableInterior(cell) => {
-                    children.push(BTreeNode::new(cell.left_page_number as usize, reader)?);
+                    push_child(&mut children, cell.left_page_number as usize);
                 }
                 Cell::TableLeaf(cell) => {
-                    extend_overflow(&cell.overflow);
+                    extend_overflow(n, &cell.overflow, &cell.record);
                 }
                 Cell::IndexInterior(cell) => {
-                    children.push(BTreeNode::new(cell.left_page_number as usize, reader)?);
-                    extend_overflow(&cell.overflow);
+                    push_child(&mut children, cell.left_page_number as usize);
+                    extend_overflow(n, &cell.overflow, &cell.record);
                 }
                 Cell::IndexLeaf(cell) => {
-                    extend_overflow(&cell.overflow);
+                    extend_overflow(n, &cell.overflow, &cell.record);
                 }
             };
         }
         if page.page_header.page_type.is_interior() {
             // Don't forget the right-most pointer, which is in the page header.
             // If it's interior page, then page_num is Some by design.
-            children.push(BTreeNode::new(
-                page.page_header.page_num.unwrap() as usize,
-                reader,
-            )?);
+            push_child(&mut children, page.page_header.page_num.unwrap() as usize);
         };
+        ancestors.pop();
 
         Ok(Self {
             page,
             page_num,
             children: (!children.is_empty()).then_some(children),
             overflow: (!overflow.is_empty()).then_some(overflow),
+            corrupt,
         })
     }
 
+    /// Rows of the subtree in key order, payload spilled onto overflow pages is
+    /// joined back. For index b-trees these are index entries, interior pages included.
+    pub fn rows(&self, reader: &Reader) -> Result<Vec<Row>, Error> {
+        let mut rows = vec![];
+        let children = self.children.as_deref().unwrap_or_default();
+        for (n, outer_cell) in self.page.cells.iter().enumerate() {
+            if let Some(Some(child)) = children.get(n) {
+                rows.extend(child.rows(reader)?);
+            }
+            let cell_offset = self.page.cell_pointer.array[n] as usize;
+            let (rowid, payload, overflow) = match outer_cell {
+                Cell::TableInterior(_) => continue,
+                Cell::TableLeaf(cell) => (
+                    Some(cell.rowid_varint.value),
+                    cell.payload()?,
+                    &cell.overflow,
+                ),
+                Cell::IndexInterior(cell) => (None, cell.payload()?, &cell.overflow),
+                Cell::IndexLeaf(cell) => (None, cell.payload()?, &cell.overflow),
+            };
+            let values = match overflow {
+                Some(o) => BTree::follow_overflow(
+                    payload.values.to_vec(),
+                    o.units.to_vec(),
+                    o.page as usize,
+                    reader,
+                )?,
+                None => payload.values.to_vec(),
+            };
+            rows.push(Row {
+                rowid,
+                page_num: self.page_num,
+                cell_offset,
+                values,
+            });
+        }
+        // Right-most child follows all the cells.
+        if let Some(Some(child)) = children.get(self.page.cells.len()) {
+            rows.extend(child.rows(reader)?);
+        }
+        Ok(rows)
+    }
+
     fn follow_overflow(
         mut opages: Vec<OverflowPage>,
         overflow_units: Vec<OverflowUnit>,
         next_page: usize,
         reader: &Reader,
-    ) -> Result<Vec<OverflowPage>, StdError> {
+    ) -> Result<Vec<OverflowPage>, Error> {
         let opage = reader.get_overflow_page(overflow_units, next_page)?;
         let units = opage.overflow_units.to_vec();
         let next_page = opage.next_page;
         opages.push(opage);
+        // Corrupt chain might loop, it can't be longer than the file anyway.
+        if next_page != 0 && opages.len() >= reader.pages_total() {
+            return Err(Error::Malformed("Overflow chain loops.".into()));
+        }
         match next_page {
             0 => Ok(opages),
             n => Self::follow_overflow(opages, units, n as usize, reader),
@@ -97,14 +211,292 @@ impl BTreeNode {
     }
 }
 
+/// Logical row of a table or entry of an index.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Row {
+    /// Integer key of table rows, index entries keep it as the last value instead.
+    pub rowid: Option<i64>,
+    /// Page number and page offset of the cell, which holds the row.
+    pub page_num: usize,
+    pub cell_offset: usize,
+    pub values: Vec<RecordValue>,
+}
+
+impl Row {
+    /// Inclusive ranges of rowids missing between the first and the last row,
+    /// usually left by deleted rows. Rows are expected in key order, the ones of
+    /// a corrupt b-tree, which aren't, leave no gap.
+    pub fn rowid_gaps(rows: &[Row]) -> Vec<(i64, i64)> {
+        let rowids = rows.iter().filter_map(|r| r.rowid).collect::<Vec<_>>();
+        rowids
+            .windows(2)
+            // Rowids might be as far apart as i64::MIN and i64::MAX.
+            .filter(|w| w[0] < w[1] && w[1].abs_diff(w[0]) > 1)
+            .map(|w| (w[0] + 1, w[1] - 1))
+            .collect()
+    }
+}
+
+/// Piece of a cell payload kept on one page.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PayloadChunk {
+    pub page_num: usize,
+    /// Offset of the piece on the page and its size.
+    pub offset: usize,
+    pub size: usize,
+}
+
+/// Record value with the pieces of the pages it's made of.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ReassembledValue {
+    pub value: RecordValue,
+    pub chunks: Vec<PayloadChunk>,
+}
+
+/// Cell payload put back together from the b-tree page and its overflow pages.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Reassembled {
+    /// Pieces of the whole payload, in order: the b-tree page goes first.
+    pub chunks: Vec<PayloadChunk>,
+    pub header: Vec<PayloadChunk>,
+    pub values: Vec<ReassembledValue>,
+}
+
+impl Reassembled {
+    /// Concatenate payload of the cell at the page offset with its overflow
+    /// pages and decode the record as a whole.
+    pub fn new(page_num: usize, cell_offset: usize, reader: &Reader) -> Result<Self, Error> {
+        let page = reader.get_btree_page(page_num)?;
+        let n = page
+            .cell_pointer
+            .array
+            .iter()
+            .position(|&o| o as usize == cell_offset)
+            .ok_or_else(|| {
+                Error::Malformed(format!(
+                    "No cell at offset {} of page {}.",
+                    cell_offset, page_num
+                ))
+            })?;
+        let cell = &page.cells[n];
+        let (start, local) = cell
+            .local_payload(&reader.db_header)
+            .ok_or_else(|| Error::Malformed("Table interior cell has no payload.".into()))?;
+        let size = match cell {
+            Cell::TableLeaf(c) => c.payload_varint.value,
+            Cell::IndexLeaf(c) => c.payload_varint.value,
+            Cell::IndexInterior(c) => c.payload_varint.value,
+            Cell::TableInterior(_) => 0,
+        } as usize;
+
+        let db_header = &reader.db_header;
+        let usable =
+            payload_math::usable_size(db_header.page_size, db_header.reserved_page_space) as usize;
+        let buf = reader.get_raw_page(page_num)?;
+        let offset = cell_offset + start;
+        let mut bytes = buf
+            .get(offset..offset + local)
+            .ok_or_else(|| Error::Malformed("Cell payload is out of the page.".into()))?
+            .to_vec();
+        let mut chunks = vec![PayloadChunk {
+            page_num,
+            offset,
+            size: local,
+        }];
+        // Overflow page number follows the local part of the payload.
+        let mut next_page = match local < size {
+            true => read_u32(&buf[offset + local..])? as usize,
+            false => 0,
+        };
+        while bytes.len() < size {
+            if next_page == 0 {
+                return Err(Error::Malformed(
+                    "Overflow chain ends before the payload does.".into(),
+                ));
+            }
+            // Corrupt chain might loop back to a page, which is already read.
+            if chunks.iter().any(|c| c.page_num == next_page) {
+                return Err(Error::Malformed("Overflow chain loops.".into()));
+            }
+            let buf = reader.get_raw_page(next_page)?;
+            // Content of the overflow page ends where its reserved space starts.
+            let take = (size - bytes.len()).min(usable - 4);
+            bytes.extend_from_slice(&buf[4..4 + take]);
+            chunks.push(PayloadChunk {
+                page_num: next_page,
+                offset: 4,
+                size: take,
+            });
+            next_page = slc!(buf, 0, 4, u32) as usize;
+        }
+
+        let record = Record::try_from((reader.db_header.text_encoding, bytes.as_slice()))?;
+        let header_size = record.header.size.value as usize;
+        let header = Self::locate(&chunks, 0, header_size);
+        let mut position = header_size;
+        let mut values = vec![];
+        for value in record.values {
+            let size = value.bytes.as_ref().map_or(0, |b| b.len());
+            values.push(ReassembledValue {
+                chunks: Self::locate(&chunks, position, size),
+                value,
+            });
+            position += size;
+        }
+        Ok(Self {
+            chunks,
+            header,
+            values,
+        })
+    }
+
+    /// Pieces of the pages, which hold payload bytes from the start up to the size.
+    fn locate(chunks: &[PayloadChunk], start: usize, size: usize) -> Vec<PayloadChunk> {
+        let end = start + size;
+        let mut found = vec![];
+        let mut chunk_start = 0;
+        for chunk in chunks {
+            let chunk_end = chunk_start + chunk.size;
+            let (from, to) = (start.max(chunk_start), end.min(chunk_end));
+            if from < to {
+                found.push(PayloadChunk {
+                    page_num: chunk.page_num,
+                    offset: chunk.offset + from - chunk_start,
+                    size: to - from,
+                });
+            }
+            chunk_start = chunk_end;
+        }
+        found
+    }
+}
+
+/// Page visited while looking for a key.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SearchStep {
+    pub page_num: usize,
+    /// Offset of the cell, which points to the next page or holds the key.
+    /// None if the right-most pointer is followed or the key isn't on the leaf.
+    pub cell_offset: Option<usize>,
+    /// Offsets of the cells, which keys were compared with the one looked for,
+    /// and the keys, in the order they were compared.
+    pub compared: Vec<(usize, i64)>,
+}
+
+/// Path from the root page to the leaf, where the key is or would be.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct KeySearch {
+    pub rowid: i64,
+    pub path: Vec<SearchStep>,
+    pub found: bool,
+}
+
+impl KeySearch {
+    /// Walk table b-tree from the root page the same way SQLite does: binary search
+    /// the keys of the page for the first one not less than rowid, on interior pages
+    /// follow its left child, or the right-most pointer if there is none, until the
+    /// leaf page is reached.
+    pub fn rowid(root: usize, rowid: i64, reader: &Reader) -> Result<Self, Error> {
+        let mut path = vec![];
+        let mut page_num = root;
+        loop {
+            if path.len() > reader.pages_total() {
+                return Err(Error::Malformed(format!(
+                    "Loop in the b-tree at page {}",
+                    page_num
+                )));
+            }
+            let page = reader.get_btree_page(page_num)?;
+            let page_type = page.page_header.page_type;
+            if !matches!(
+                page_type,
+                PageHeaderType::InteriorTable | PageHeaderType::LeafTable
+            ) {
+                return Err(Error::Malformed(format!(
+                    "Page {} is {}, not a table b-tree page",
+                    page_num, page_type
+                )));
+            }
+            let keys = page
+                .cells
+                .iter()
+                .map(|cell| match cell {
+                    Cell::TableInterior(c) => c.rowid_varint.value,
+                    Cell::TableLeaf(c) => c.rowid_varint.value,
+                    _ => 0,
+                })
+                .collect::<Vec<_>>();
+            let offset = |n: usize| page.cell_pointer.array[n] as usize;
+            let (n, compared) = Self::bisect(&keys, rowid);
+            let compared = compared.into_iter().map(|n| (offset(n), keys[n])).collect();
+            if page_type == PageHeaderType::LeafTable {
+                let cell_offset = (keys.get(n) == Some(&rowid)).then(|| offset(n));
+                path.push(SearchStep {
+                    page_num,
+                    cell_offset,
+                    compared,
+                });
+                return Ok(Self {
+                    rowid,
+                    path,
+                    found: cell_offset.is_some(),
+                });
+            }
+            let (next_page, cell_offset) = match page.cells.get(n) {
+                Some(Cell::TableInterior(c)) => (c.left_page_number as usize, Some(offset(n))),
+                // If it's interior page, then page_num is Some by design.
+                _ => (page.page_header.page_num.unwrap_or_default() as usize, None),
+            };
+            path.push(SearchStep {
+                page_num,
+                cell_offset,
+                compared,
+            });
+            page_num = next_page;
+        }
+    }
+
+    /// Position of the first key not less than the one looked for, found by binary
+    /// search as SQLite does it, with the positions of the compared keys.
+    fn bisect(keys: &[i64], key: i64) -> (usize, Vec<usize>) {
+        let mut compared = vec![];
+        let (mut lower, mut upper) = (0, keys.len());
+        while lower < upper {
+            let n = (lower + upper - 1) / 2;
+            compared.push(n);
+            match keys[n].cmp(&key) {
+                std::cmp::Ordering::Less => lower = n + 1,
+                std::cmp::Ordering::Greater => upper = n,
+                std::cmp::Ordering::Equal => return (n, compared),
+            }
+        }
+        (lower, compared)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct BTree {
     pub ttype: String,
     pub name: String,
+    /// Table the b-tree belongs to: the table itself or the one the index is on.
+    pub tbl_name: String,
     pub root: BTreeNode,
+    /// Names of the record values, as they are defined by the CREATE statement.
+    pub columns: Vec<String>,
+    /// Number of PRIMARY KEY columns, which lead records of a WITHOUT ROWID table
+    /// stored in an index b-tree. None for rowid tables and indexes.
+    pub key_columns: Option<usize>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Schema {
     Type = 0,
     Name = 1,
@@ -114,27 +506,42 @@ pub enum Schema {
 }
 
 impl BTree {
-    pub fn new(cell: &TableLeafCell, reader: &Reader) -> Result<Self, StdError> {
+    pub fn new(cell: &TableLeafCell, reader: &Reader) -> Result<Self, Error> {
         match &cell.overflow {
             Some(overflow) => {
                 let payload = Self::follow_overflow(
-                    cell.payload.values.to_vec(),
+                    cell.payload()?.values.to_vec(),
                     overflow.units.to_vec(),
                     overflow.page as usize,
                     reader,
                 )?;
                 Self::parse_tree(&payload, reader)
             }
-            None => Self::parse_tree(&cell.payload.values, reader),
+            None => Self::parse_tree(&cell.payload()?.values, reader),
         }
     }
 
     fn follow_overflow(
+        payload: Vec<RecordValue>,
+        overflow_units: Vec<OverflowUnit>,
+        next_page: usize,
+        reader: &Reader,
+    ) -> Result<Vec<RecordValue>, Error> {
+        Self::follow_overflow_within(payload, overflow_units, next_page, reader, 1)
+    }
+
+    /// Continue the payload from the overflow page, which is the nth one of the chain.
+    fn follow_overflow_within(
         mut payload: Vec<RecordValue>,
         overflow_units: Vec<OverflowUnit>,
         next_page: usize,
         reader: &Reader,
-    ) -> Result<Vec<RecordValue>, StdError> {
+        nth: usize,
+    ) -> Result<Vec<RecordValue>, Error> {
+        // Corrupt chain might loop, it can't be longer than the file anyway.
+        if nth > reader.pages_total() {
+            return Err(Error::Malformed("Overflow chain loops.".into()));
+        }
         // We need to merge last of previous with the first of overflow value and
         // add values in between to payload.
         /*
@@ -146,43 +553,422 @@ impl BTree {
          *     +----------------+        ↓        +---------------+
          *            merge          as it is           merge
          */
+        // The first value continues the last one of the previous page, unless
+        // the split falls exactly between two values.
+        let continued = overflow_units
+            .first()
+            .is_some_and(|u| u.bytes_left < RecordCode::size(u.overflow_type));
         let opage = reader.get_overflow_page(overflow_units, next_page)?;
 
         let mut overflow = opage.data.to_vec();
-        let last_payload = payload.remove(payload.len() - 1);
-        let first_overflow = overflow.remove(0);
-        match last_payload.merge(first_overflow.value) {
-            Some(value) => payload.push(value),
-            None => unreachable!("Attempt to merge the unexpected Record types."),
-        };
+        if continued && !overflow.is_empty() {
+            let last_payload = payload
+                .pop()
+                .ok_or_else(|| Error::Malformed("Overflow continues an empty payload.".into()))?;
+            let first_overflow = overflow.remove(0);
+            let value = last_payload
+                .merge(first_overflow.value, reader.db_header.text_encoding)
+                .ok_or_else(|| {
+                    Error::Malformed(
+                        "Spilled value doesn't match its continuation on the overflow page.".into(),
+                    )
+                })?;
+            payload.push(value);
+        }
         payload.extend(overflow.into_iter().map(|v| v.value));
 
         match opage.next_page {
             0 => Ok(payload),
-            n => Self::follow_overflow(payload, opage.overflow_units, n as usize, reader),
+            n => Self::follow_overflow_within(
+                payload,
+                opage.overflow_units,
+                n as usize,
+                reader,
+                nth + 1,
+            ),
+        }
+    }
+
+    /// Root page number from the schema table record, None for entries without
+    /// a b-tree, like views and triggers, which have it set to 0.
+    pub fn root_page(values: &[RecordValue]) -> Option<usize> {
+        let tpage = match values.get(Schema::RootPage as usize)?.value {
+            RecordType::I8(v) => v as i64,
+            RecordType::I16(v) => v as i64,
+            RecordType::I24(v) | RecordType::I32(v) => v as i64,
+            RecordType::I48(v) | RecordType::I64(v) => v,
+            RecordType::One(v) => v as i64,
+            _ => return None,
+        };
+        (tpage > 0).then_some(tpage as usize)
+    }
+
+    /// SQL of the table, which the index belongs to, None for other entries.
+    fn table_sql(ttype: &str, tbl_name: &str, reader: &Reader) -> Option<String> {
+        if ttype != "index" {
+            return None;
         }
+        reader.get_schema_cells().into_iter().find_map(|cell| {
+            let payload = cell.payload().ok()?;
+            let text = |column: Schema| match &payload.values.get(column as usize)?.value {
+                RecordType::Text(Some(v)) => Some(v.to_string()),
+                _ => None,
+            };
+            (text(Schema::Type)? == "table" && text(Schema::Name)? == tbl_name)
+                .then(|| text(Schema::SQL))?
+        })
     }
 
-    fn parse_tree(values: &[RecordValue], reader: &Reader) -> Result<Self, StdError> {
-        let tname = match &values[Schema::Name as usize].value {
-            RecordType::Text(v) => v.as_ref().map_or("", |vv| vv),
-            _ => unreachable!("Unknown type for table schema name."),
+    fn parse_tree(values: &[RecordValue], reader: &Reader) -> Result<Self, Error> {
+        let text = |column: Schema| match values.get(column.clone() as usize).map(|v| &v.value) {
+            Some(RecordType::Text(v)) => Ok(v.as_deref().unwrap_or_default()),
+            _ => Err(Error::Malformed(format!(
+                "Unknown type for table schema {:?}.",
+                column
+            ))),
         };
-        let ttype = match &values[Schema::Type as usize].value {
-            RecordType::Text(v) => v.as_ref().map_or("", |vv| vv),
-            _ => unreachable!("Unknown type for table schema type."),
+        let tname = text(Schema::Name)?;
+        let ttype = text(Schema::Type)?;
+        let tbl_name = text(Schema::TableName).unwrap_or(tname);
+        let Some(tpage) = Self::root_page(values) else {
+            return Err(Error::Malformed(format!(
+                "Schema entry {} {} has no b-tree.",
+                ttype, tname
+            )));
         };
-        let tpage = match values[Schema::RootPage as usize].value {
-            RecordType::I8(v) => v as usize,
-            RecordType::I16(v) => v as usize,
-            RecordType::I24(v) | RecordType::I32(v) => v as usize,
-            RecordType::I48(v) | RecordType::I64(v) => v as usize,
-            _ => unreachable!("Unknown type for table schema root page."),
+        // Automatic indexes have no SQL, their columns are left unnamed.
+        let (columns, key_columns) = match &values.get(Schema::SQL as usize).map(|v| &v.value) {
+            Some(RecordType::Text(Some(sql))) => (
+                column_names(sql, Self::table_sql(ttype, tbl_name, reader).as_deref()),
+                without_rowid_key(sql).map(|key| key.len()),
+            ),
+            _ => (vec![], None),
         };
         Ok(Self {
             ttype: ttype.to_string(),
             name: tname.to_string(),
+            tbl_name: tbl_name.to_string(),
             root: BTreeNode::new(tpage, reader)?,
+            columns,
+            key_columns,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{corrupt, database, reserved_database};
+
+    #[test]
+    fn test_corrupt_loops() {
+        // Table of two levels at page 2, the last row spills onto 4 overflow pages.
+        let bytes = database(
+            "loops",
+            "PRAGMA page_size=512;
+            CREATE TABLE t(v);
+            WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 40)
+            INSERT INTO t SELECT printf('%.*c', 50, 'x') FROM n;
+            INSERT INTO t VALUES(zeroblob(2000));",
+        );
+        let reader = Reader::new(bytes).unwrap();
+        let root = BTreeNode::new(2, &reader).unwrap();
+        assert!(root.corrupt.is_empty());
+        assert_eq!(root.rows(&reader).unwrap().len(), 41);
+        let leaf = root
+            .children
+            .as_ref()
+            .unwrap()
+            .last()
+            .unwrap()
+            .as_ref()
+            .unwrap();
+        let overflow = leaf.overflow.as_ref().unwrap()[0].page_num;
+
+        // Right-most pointer of the root leads back to the root.
+        let reader = corrupt(bytes, 512 + 8, &2u32.to_be_bytes());
+        let root = BTreeNode::new(2, &reader).unwrap();
+        assert_eq!(root.corrupt[0].page_num, 2);
+        assert!(matches!(root.corrupt[0].error, Error::Malformed(_)));
+
+        // Overflow page points to itself.
+        let offset = (overflow - 1) * 512;
+        let reader = corrupt(bytes, offset, &(overflow as u32).to_be_bytes());
+        let root = BTreeNode::new(2, &reader).unwrap();
+        assert!(root.rows(&reader).is_err());
+        let leaf = root
+            .children
+            .as_ref()
+            .unwrap()
+            .last()
+            .unwrap()
+            .as_ref()
+            .unwrap();
+        let cell_offset = *leaf.page.cell_pointer.array.last().unwrap() as usize;
+        assert!(Reassembled::new(leaf.page_num, cell_offset, &reader).is_err());
+    }
+
+    #[test]
+    fn test_reassemble_reserved() {
+        // Blob of 2000 0xaa bytes spills onto overflow pages with 32 reserved bytes each.
+        let bytes = reserved_database(
+            "reassemble",
+            32,
+            "PRAGMA page_size=512;
+            CREATE TABLE t(v);
+            INSERT INTO t VALUES(unhex(printf('%.*c', 4000, 'a')));",
+        );
+        let reader = Reader::new(bytes).unwrap();
+        assert_eq!(reader.db_header.reserved_page_space, 32);
+        let page = reader.get_btree_page(2).unwrap();
+        let cell_offset = page.cell_pointer.array[0] as usize;
+        let reassembled = Reassembled::new(2, cell_offset, &reader).unwrap();
+        let overflow = &reassembled.chunks[1..];
+        assert!(overflow.iter().all(|c| c.size <= 512 - 32 - 4));
+        let RecordType::Blob(Some(blob)) = &reassembled.values[0].value.value else {
+            panic!("Blob is expected");
+        };
+        assert_eq!(blob, &vec![0xaa; 2000]);
+    }
+
+    #[test]
+    fn test_overflow_boundary() {
+        // Payload of 547 bytes keeps 39 on a 512-byte page: the header of 6 bytes,
+        // a blob of 25 or 22 bytes and an integer. The next integer starts the overflow
+        // page right at its first byte, or has its first 3 bytes left on the b-tree page.
+        for (blob, split) in [(25, 0), (22, 3)] {
+            let bytes = database(
+                &format!("boundary-{}", blob),
+                &format!(
+                    "PRAGMA page_size=512;
+                    CREATE TABLE t(a, b, c, d);
+                    INSERT INTO t VALUES(zeroblob({}), {}, {}, zeroblob({}));",
+                    blob,
+                    0x0102030405060708_i64,
+                    -0x0807060504030201_i64,
+                    547 - 6 - 16 - blob
+                ),
+            );
+            let reader = Reader::new(bytes).unwrap();
+            let page = reader.get_btree_page(2).unwrap();
+            let cell_offset = page.cell_pointer.array[0] as usize;
+            let reassembled = Reassembled::new(2, cell_offset, &reader).unwrap();
+            assert_eq!(reassembled.chunks[0].size, 39);
+            let c = &reassembled.values[2].chunks;
+            assert_eq!(
+                c.iter().filter(|c| c.page_num == 2).count(),
+                (split > 0) as usize
+            );
+
+            let rows = BTreeNode::new(2, &reader).unwrap().rows(&reader).unwrap();
+            let values = rows[0].values.iter().map(|v| &v.value).collect::<Vec<_>>();
+            assert_eq!(values[1], &RecordType::I64(0x0102030405060708));
+            assert_eq!(values[2], &RecordType::I64(-0x0807060504030201));
+            assert_eq!(
+                values[3],
+                &RecordType::Blob(Some(vec![0; 547 - 6 - 16 - blob]))
+            );
+        }
+    }
+
+    #[test]
+    fn test_rows() {
+        // Table of two levels at page 2 with a row spilled onto overflow pages,
+        // its index at page 3 has entries on the interior page too.
+        let bytes = database(
+            "rows",
+            "PRAGMA page_size=512;
+            CREATE TABLE t(k, v);
+            CREATE INDEX i ON t(k);
+            WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 40)
+            INSERT INTO t SELECT i, printf('%.*c', 50, 'x') FROM n;
+            INSERT INTO t VALUES(41, zeroblob(2000));
+            DELETE FROM t WHERE k = 20;",
+        );
+        let reader = Reader::new(bytes).unwrap();
+        let root = BTreeNode::new(2, &reader).unwrap();
+        assert!(root.children.is_some());
+        let rows = root.rows(&reader).unwrap();
+        let rowids = rows.iter().filter_map(|r| r.rowid).collect::<Vec<_>>();
+        assert_eq!(rowids, (1..=41).filter(|&n| n != 20).collect::<Vec<_>>());
+        // Rows point to the cells on the leaves.
+        assert!(rows.iter().all(|r| r.page_num != 2));
+        let page = reader.get_btree_page(rows[0].page_num).unwrap();
+        assert_eq!(rows[0].cell_offset, page.cell_pointer.array[0] as usize);
+        assert_eq!(
+            rows[0].values[1].value,
+            RecordType::Text(Some("x".repeat(50)))
+        );
+        // Spilled payload is joined back.
+        let last = rows.last().unwrap();
+        assert_eq!(last.values[1].value, RecordType::Blob(Some(vec![0; 2000])));
+
+        // Index entries are in key order with the rowid last, interior ones included.
+        let index = BTreeNode::new(3, &reader).unwrap();
+        let entries = index.rows(&reader).unwrap();
+        assert_eq!(entries.len(), 40);
+        assert!(entries.iter().all(|e| e.rowid.is_none()));
+        assert!(entries.iter().any(|e| e.page_num == 3));
+        let keys = entries
+            .iter()
+            .map(|e| {
+                assert_eq!(e.values[0].value, e.values[1].value);
+                match e.values[0].value {
+                    RecordType::One(_) => 1,
+                    RecordType::I8(k) => k as i64,
+                    ref v => panic!("Small integer key is expected, got {:?}", v),
+                }
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(keys, rowids);
+    }
+
+    #[test]
+    fn test_corrupt_child() {
+        // Index of two levels at page 3 with the same text first, ordered by the key,
+        // entries of the interior page separate the leaves.
+        let bytes = database(
+            "corrupt-child",
+            "PRAGMA page_size=512;
+            CREATE TABLE t(k, v);
+            CREATE INDEX i ON t(v, k);
+            WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 40)
+            INSERT INTO t SELECT i, printf('%.*c', 50, 'x') FROM n;",
+        );
+        let key = |e: &Row| match e.values[1].value {
+            RecordType::One(_) => 1,
+            RecordType::I8(k) => k as i64,
+            ref v => panic!("Small integer key is expected, got {:?}", v),
+        };
+        let reader = Reader::new(bytes).unwrap();
+        let page = reader.get_btree_page(3).unwrap();
+        let Cell::IndexInterior(cell) = &page.cells[0] else {
+            panic!("Index interior cell is expected");
+        };
+        let first = cell.left_page_number as usize;
+        let lost = BTreeNode::new(first, &reader)
+            .unwrap()
+            .rows(&reader)
+            .unwrap()
+            .iter()
+            .map(key)
+            .collect::<Vec<_>>();
+
+        // First leaf gets an unknown page type, the rest keep their place.
+        let reader = corrupt(bytes, (first - 1) * 512, &[0xff]);
+        let index = BTreeNode::new(3, &reader).unwrap();
+        let children = index.children.as_ref().unwrap();
+        assert_eq!(children.len(), page.cells.len() + 1);
+        assert!(children[0].is_none());
+        assert!(children[1..].iter().all(|c| c.is_some()));
+        assert_eq!(index.corrupt[0].page_num, first);
+        let keys = index
+            .rows(&reader)
+            .unwrap()
+            .iter()
+            .map(key)
+            .collect::<Vec<_>>();
+        let expected = (1..=40).filter(|k| !lost.contains(k)).collect::<Vec<_>>();
+        assert_eq!(keys, expected);
+    }
+
+    #[test]
+    fn test_key_search() {
+        // Table of two levels at page 2 without the row 20, its index is at page 3.
+        let bytes = database(
+            "key-search",
+            "PRAGMA page_size=512;
+            CREATE TABLE t(v);
+            CREATE INDEX i ON t(v);
+            WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 40)
+            INSERT INTO t SELECT printf('%.*c', 50, 'x') FROM n;
+            DELETE FROM t WHERE rowid = 20;",
+        );
+        let reader = Reader::new(bytes).unwrap();
+        let root = reader.get_btree_page(2).unwrap();
+        let right_most = root.page_header.page_num.unwrap() as usize;
+        let rowid_at = |step: &SearchStep| {
+            let page = reader.get_btree_page(step.page_num).unwrap();
+            let n = page
+                .cell_pointer
+                .array
+                .iter()
+                .position(|&o| Some(o as usize) == step.cell_offset)
+                .unwrap();
+            match &page.cells[n] {
+                Cell::TableLeaf(c) => c.rowid_varint.value,
+                Cell::TableInterior(c) => c.rowid_varint.value,
+                _ => panic!("Table cell is expected"),
+            }
+        };
+
+        // Found on a leaf through the left child of an interior cell, which key
+        // isn't less than the rowid.
+        let search = KeySearch::rowid(2, 5, &reader).unwrap();
+        assert!(search.found);
+        assert_eq!(search.path.len(), 2);
+        assert_eq!(search.path[0].page_num, 2);
+        assert!(rowid_at(&search.path[0]) >= 5);
+        assert_eq!(rowid_at(&search.path[1]), 5);
+
+        // Missing rowid stops on the leaf, where it would be.
+        let search = KeySearch::rowid(2, 20, &reader).unwrap();
+        assert!(!search.found);
+        assert_eq!(search.path.len(), 2);
+        assert_eq!(search.path[1].cell_offset, None);
+        assert!(!search.path[1].compared.is_empty());
+
+        // Keys past all of the interior ones follow the right-most pointer.
+        let search = KeySearch::rowid(2, 40, &reader).unwrap();
+        assert!(search.found);
+        assert_eq!(search.path[0].cell_offset, None);
+        assert_eq!(search.path[1].page_num, right_most);
+        let search = KeySearch::rowid(2, 1000, &reader).unwrap();
+        assert!(!search.found);
+        assert_eq!(search.path[1].page_num, right_most);
+
+        // Index b-tree isn't searched by rowid.
+        assert!(KeySearch::rowid(3, 5, &reader).is_err());
+    }
+
+    #[test]
+    fn test_rowid_gaps() {
+        let rows = |rowids: &[i64]| {
+            rowids
+                .iter()
+                .map(|&rowid| Row {
+                    rowid: Some(rowid),
+                    page_num: 2,
+                    cell_offset: 0,
+                    values: vec![],
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            Row::rowid_gaps(&rows(&[1, 2, 5, 7, 8])),
+            vec![(3, 4), (6, 6)]
+        );
+        assert_eq!(Row::rowid_gaps(&rows(&[1, 2, 3])), vec![]);
+        assert_eq!(
+            Row::rowid_gaps(&rows(&[i64::MIN, -1, i64::MAX])),
+            vec![(i64::MIN + 1, -2), (0, i64::MAX - 1)]
+        );
+        // Rows out of order or repeated leave no gap.
+        assert_eq!(
+            Row::rowid_gaps(&rows(&[i64::MAX, i64::MIN, i64::MIN])),
+            vec![]
+        );
+        assert_eq!(Row::rowid_gaps(&rows(&[])), vec![]);
+    }
+
+    #[test]
+    fn test_bisect() {
+        let keys = [2, 4, 6, 8, 10];
+        // Middle key goes first, as SQLite starts with it.
+        assert_eq!(KeySearch::bisect(&keys, 6), (2, vec![2]));
+        assert_eq!(KeySearch::bisect(&keys, 7), (3, vec![2, 3]));
+        assert_eq!(KeySearch::bisect(&keys, 1), (0, vec![2, 0]));
+        assert_eq!(KeySearch::bisect(&keys, 11), (5, vec![2, 3, 4]));
+        assert_eq!(KeySearch::bisect(&[], 1), (0, vec![]));
+    }
+}
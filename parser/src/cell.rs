@@ -9,6 +9,7 @@ use std::rc::Rc;
 use crate::*;
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Cell {
     TableLeaf(TableLeafCell),
     TableInterior(TableInteriorCell),
@@ -17,37 +18,45 @@ pub enum Cell {
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct CellOverflow {
     pub page: u32,
     pub units: Vec<OverflowUnit>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct TableLeafCell {
     pub payload_varint: Varint,
     pub rowid_varint: Varint,
-    pub payload: Record,
+    #[cfg_attr(feature = "serde", serde(rename = "payload"))]
+    pub record: LazyRecord,
     pub overflow: Option<CellOverflow>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct TableInteriorCell {
     pub left_page_number: u32,
     pub rowid_varint: Varint,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct IndexLeafCell {
     pub payload_varint: Varint,
-    pub payload: Record,
+    #[cfg_attr(feature = "serde", serde(rename = "payload"))]
+    pub record: LazyRecord,
     pub overflow: Option<CellOverflow>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct IndexInteriorCell {
     pub left_page_number: u32,
     pub payload_varint: Varint,
-    pub payload: Record,
+    #[cfg_attr(feature = "serde", serde(rename = "payload"))]
+    pub record: LazyRecord,
     pub overflow: Option<CellOverflow>,
 }
 
@@ -56,7 +65,7 @@ impl Cell {
         page_type: PageHeaderType,
         db_header: Rc<DBHeader>,
         buf: &[u8],
-    ) -> Result<Self, StdError> {
+    ) -> Result<Self, Error> {
         match page_type {
             PageHeaderType::LeafTable => {
                 let payload_varint = Varint::new(buf);
@@ -65,142 +74,200 @@ impl Cell {
                 let rowid_varint = Varint::new(&buf[offset..]);
                 offset += rowid_varint.bytes.len();
 
-                let max_payload = |u| u - 35;
-                let (payload, overflow) =
+                let max_payload = payload_math::table_max_local;
+                let (record, overflow) =
                     Self::parse_payload(db_header, &max_payload, &payload_varint, buf, offset)?;
 
                 Ok(Cell::TableLeaf(TableLeafCell {
                     payload_varint,
                     rowid_varint,
-                    payload,
+                    record,
                     overflow,
                 }))
             }
             PageHeaderType::InteriorTable => Ok(Cell::TableInterior(TableInteriorCell {
-                left_page_number: slc!(buf, 0, 4, u32),
+                left_page_number: read_u32(buf)?,
                 rowid_varint: Varint::new(&buf[4..]),
             })),
             PageHeaderType::LeafIndex => {
                 let payload_varint = Varint::new(buf);
                 let offset = payload_varint.bytes.len();
 
-                let max_payload = |u| ((u - 12) * 64 / 255) - 23;
-                let (payload, overflow) =
+                let max_payload = payload_math::index_max_local;
+                let (record, overflow) =
                     Self::parse_payload(db_header, &max_payload, &payload_varint, buf, offset)?;
 
                 Ok(Cell::IndexLeaf(IndexLeafCell {
                     payload_varint,
-                    payload,
+                    record,
                     overflow,
                 }))
             }
             PageHeaderType::InteriorIndex => {
-                let left_page_number = slc!(buf, 0, 4, u32);
+                let left_page_number = read_u32(buf)?;
                 let mut offset = 4;
 
                 let payload_varint = Varint::new(&buf[offset..]);
                 offset += payload_varint.bytes.len();
 
-                let max_payload = |u| ((u - 12) * 64 / 255) - 23;
-                let (payload, overflow) =
+                let max_payload = payload_math::index_max_local;
+                let (record, overflow) =
                     Self::parse_payload(db_header, &max_payload, &payload_varint, buf, offset)?;
 
                 Ok(Cell::IndexInterior(IndexInteriorCell {
                     left_page_number,
                     payload_varint,
-                    payload,
+                    record,
                     overflow,
                 }))
             }
         }
     }
 
+    /// Record of the payload, decoded on the first access. None for table interior
+    /// cells, which have no payload.
+    pub fn payload(&self) -> Option<Result<&Record, Error>> {
+        match self {
+            Cell::TableInterior(_) => None,
+            Cell::TableLeaf(c) => Some(c.payload()),
+            Cell::IndexLeaf(c) => Some(c.payload()),
+            Cell::IndexInterior(c) => Some(c.payload()),
+        }
+    }
+
+    /// Pages the payload spilled onto, None if it fits the b-tree page.
+    pub fn overflow(&self) -> Option<&CellOverflow> {
+        match self {
+            Cell::TableInterior(_) => None,
+            Cell::TableLeaf(c) => c.overflow.as_ref(),
+            Cell::IndexLeaf(c) => c.overflow.as_ref(),
+            Cell::IndexInterior(c) => c.overflow.as_ref(),
+        }
+    }
+
+    /// Offset of the payload from the start of the cell and the number of payload
+    /// bytes on the b-tree page, the rest of them is on overflow pages.
+    /// None for table interior cells, which have no payload.
+    pub fn local_payload(&self, db_header: &DBHeader) -> Option<(usize, usize)> {
+        let (offset, payload_varint, max_payload): (_, _, fn(u64) -> u64) = match self {
+            Cell::TableInterior(_) => return None,
+            Cell::TableLeaf(c) => (
+                c.payload_varint.bytes.len() + c.rowid_varint.bytes.len(),
+                &c.payload_varint,
+                payload_math::table_max_local,
+            ),
+            Cell::IndexLeaf(c) => (
+                c.payload_varint.bytes.len(),
+                &c.payload_varint,
+                payload_math::index_max_local,
+            ),
+            Cell::IndexInterior(c) => (
+                4 + c.payload_varint.bytes.len(),
+                &c.payload_varint,
+                payload_math::index_max_local,
+            ),
+        };
+        let u = payload_math::usable_size(db_header.page_size, db_header.reserved_page_space);
+        let p = payload_varint.value as u64;
+        Some((
+            offset,
+            PayloadSplit::new(u, max_payload(u), p).local as usize,
+        ))
+    }
+
+    /// Number of bytes the cell takes on the b-tree page, SQLite never allocates
+    /// less than 4 bytes for a cell.
+    pub fn size(&self, db_header: &DBHeader) -> usize {
+        let overflow = match self {
+            Cell::TableInterior(c) => return 4 + c.rowid_varint.bytes.len(),
+            Cell::TableLeaf(c) => &c.overflow,
+            Cell::IndexLeaf(c) => &c.overflow,
+            Cell::IndexInterior(c) => &c.overflow,
+        };
+        let (offset, local) = self.local_payload(db_header).unwrap_or_default();
+        // Spilled payload is followed by the page number of the first overflow page.
+        let pointer = if overflow.is_some() { 4 } else { 0 };
+        (offset + local + pointer).max(4)
+    }
+
     fn parse_payload(
         db_header: Rc<DBHeader>,
         max_payload: &dyn Fn(u64) -> u64,
         payload_varint: &Varint,
         buf: &[u8],
         offset: usize,
-    ) -> Result<(Record, Option<CellOverflow>), StdError> {
+    ) -> Result<(LazyRecord, Option<CellOverflow>), Error> {
         let text_encoding = db_header.text_encoding;
         let page_size = db_header.page_size;
         let reserved_size = db_header.reserved_page_space;
 
-        // -- Do the math to check for overflow.
-        // Let:
-        // - u: usable size of a database page,
-        // - p: payload size,
-        // - x: maximum amount of payload that can be stored directly on the page
-        //      without spilling onto the overflow page,
-        // - m: minimum amount of payload that must be stored on the btree page
-        //      before spilling is allowed,
-        //
-        // u = page size - reserved space
-        // - x = u - 35 (for leaf table page) OR
-        // - x = ((u-12)*64/255)-23 (for interior/leaf index pages)
-        //
-        // if p <= x {
-        //      entire payload stored on the btree leaf page
-        // } else {
-        //      m = ((u-12)*32/255)-23
-        //      k = m+((p-m)%(u-4))
-        //      if k <= x {
-        //          - first k-bytes of p are stored on the btree page,
-        //          - p-k bytes are stored on overflow page
-        //      } else {
-        //          - first m-bytes of p are stored on the btree page,
-        //          - p-m bytes are stored on overflow page
-        //      }
-        // }
-        let u = page_size - reserved_size as u64;
-        let x = max_payload(u);
+        // -- Do the math to check for overflow, see payload_math.
+        if payload_varint.value < 0 {
+            return Err(Error::BadVarint {
+                value: payload_varint.value,
+                what: "Payload size",
+            });
+        }
+        let u = payload_math::usable_size(page_size, reserved_size);
         let p = payload_varint.value as u64;
-        let (overflow_page, payload_size, overflow_size) = if p <= x {
-            (0, p as usize, 0_usize)
-        } else {
-            let m = ((u - 12) * 32 / 255) - 23;
-            let k = m + ((p - m) % (u - 4));
-            if k <= x {
-                (
-                    slc!(buf, offset + k as usize, 4, u32),
-                    k as usize,
-                    (p - k) as usize,
-                )
-            } else {
-                (
-                    slc!(buf, offset + m as usize, 4, u32),
-                    m as usize,
-                    (p - m) as usize,
-                )
-            }
+        let payload_size = PayloadSplit::new(u, max_payload(u), p).local as usize;
+        let overflow_size = p as usize - payload_size;
+        // Corrupted payload size might point past the end of the page.
+        let local = buf.get(offset..offset + payload_size).ok_or_else(|| {
+            Error::Malformed(format!(
+                "Cell payload of {} bytes on the page runs past the page end, there are only {}.",
+                payload_size,
+                buf.len().saturating_sub(offset)
+            ))
+        })?;
+        let overflow_page = match overflow_size {
+            0 => 0,
+            _ => read_u32(&buf[offset + payload_size..])?,
         };
 
-        // -- Parse cell payload.
-        let from_buf = (text_encoding, &buf[offset..offset + payload_size]);
-        let payload = Record::try_from(from_buf)?;
+        // -- Parse the record header, values are decoded once they're needed.
+        let payload = LazyRecord::new(text_encoding, local)?;
+        let header = payload.header();
 
         // -- Overflow check.
         if overflow_size == 0 {
             return Ok((payload, None));
         }
+        // Datatypes, which spilled with the header, aren't known until the header
+        // is completed from the overflow page.
+        if header.spilled > 0 {
+            let overflow = Some(CellOverflow {
+                page: overflow_page,
+                units: vec![OverflowUnit {
+                    overflow_type: RecordCode::HEADER,
+                    bytes_left: header.spilled,
+                }],
+            });
+            return Ok((payload, overflow));
+        }
+
         // If there is an overflow in one column, the rest of the columns after the
         // spilled one will be on the overflow pages as well, following it.
+        // Sizes of the values are known from the header, so are their local parts.
         let mut overflow_units = vec![];
-        for (n, datatype) in payload.header.datatypes.iter().enumerate() {
+        let mut value_offset = header.size.value as usize;
+        let mut spilled = false;
+        for datatype in &header.datatypes {
             let code = datatype.value;
             let specified_size = RecordCode::size(code);
-            let bytes_left = if n < payload.values.len() {
-                // Detect overflow comparing sizes.
-                let column = &payload.values[n];
-                let column_size = column.bytes.as_ref().map_or(0, |b| b.len());
+            let local_size = payload_size.saturating_sub(value_offset);
+            // Value, which starts past the page end, is spilled in full.
+            spilled |= local_size == 0 && specified_size != 0;
+            let bytes_left = if spilled {
+                specified_size
+            } else {
+                let column_size = specified_size.min(local_size);
+                value_offset += column_size;
                 if column_size == specified_size {
                     // No overflow for this column.
                     continue;
                 }
                 specified_size - column_size
-            } else {
-                // Means previous column was spilled, thus this one too.
-                specified_size
             };
             overflow_units.push(OverflowUnit {
                 overflow_type: code,
@@ -214,3 +281,57 @@ impl Cell {
         Ok((payload, overflow))
     }
 }
+
+impl TableLeafCell {
+    /// Record of the payload, decoded on the first access.
+    pub fn payload(&self) -> Result<&Record, Error> {
+        self.record.get()
+    }
+}
+
+impl IndexLeafCell {
+    /// Record of the payload, decoded on the first access.
+    pub fn payload(&self) -> Result<&Record, Error> {
+        self.record.get()
+    }
+}
+
+impl IndexInteriorCell {
+    /// Record of the payload, decoded on the first access.
+    pub fn payload(&self) -> Result<&Record, Error> {
+        self.record.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{db_header, LEAF_CELL};
+
+    #[test]
+    fn test_corrupt_cell() {
+        let db_header = db_header(4096, 0);
+        let cell = Cell::new(PageHeaderType::LeafTable, db_header.clone(), &LEAF_CELL).unwrap();
+        assert_eq!(cell.size(&db_header), 8);
+
+        // Payload size corrupted to 200 bytes, which run past the end of the page.
+        let mut buf = vec![0x81, 0x48];
+        buf.extend_from_slice(&LEAF_CELL[1..]);
+        let cell = Cell::new(PageHeaderType::LeafTable, db_header.clone(), &buf);
+        assert!(matches!(cell, Err(Error::Malformed(_))));
+        // Payload of 5008 bytes keeps 916 of them on the page, which ends right after,
+        // before the first overflow page number.
+        let mut buf = vec![0xa7, 0x10, 7, 2, 0];
+        buf.resize(3 + 916, 0);
+        let cell = Cell::new(PageHeaderType::LeafTable, db_header.clone(), &buf);
+        assert!(matches!(cell, Err(Error::Malformed(_))));
+        let cell = Cell::new(PageHeaderType::LeafTable, db_header.clone(), &[0xff; 10]);
+        assert!(matches!(cell, Err(Error::BadVarint { .. })));
+
+        // Interior cells cut before the left child page number ends.
+        let cell = Cell::new(PageHeaderType::InteriorTable, db_header.clone(), &[0, 0, 2]);
+        assert!(cell.is_err());
+        let cell = Cell::new(PageHeaderType::InteriorIndex, db_header, &[0, 0, 2]);
+        assert!(cell.is_err());
+    }
+}
@@ -0,0 +1,52 @@
+//! Whole parsed representation of the database file in one structure, meant to be
+//! serialized (see `serde` feature) for scripted analysis.
+use crate::{BTree, DBHeader, Error, PtrmapPage, Reader, TrunkFreelistPage};
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Dump {
+    pub header: DBHeader,
+    pub pages_total: usize,
+    pub btrees: Vec<BTree>,
+    /// Trunk pages in the list order, leaf pages hold no information.
+    pub freelist: Vec<FreelistTrunk>,
+    /// Pointer map pages of auto-vacuum databases, their entries know the pages they describe.
+    pub ptrmap: Vec<PtrmapPage>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FreelistTrunk {
+    pub page_num: usize,
+    pub page: TrunkFreelistPage,
+}
+
+impl Dump {
+    pub fn new(reader: &Reader) -> Result<Self, Error> {
+        Ok(Self {
+            header: reader.db_header.as_ref().clone(),
+            pages_total: reader.pages_total(),
+            btrees: reader.get_btrees()?,
+            freelist: Self::freelist(reader)?,
+            ptrmap: reader
+                .ptrmap_pages()
+                .into_iter()
+                .map(|page_num| reader.get_ptrmap_page(page_num))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Follow the chain of freelist trunk pages from the database header.
+    pub fn freelist(reader: &Reader) -> Result<Vec<FreelistTrunk>, Error> {
+        let mut freelist = vec![];
+        let mut page_num = reader.db_header.first_free_page_num as usize;
+        // Corrupt freelist might loop, it can't be longer than the file anyway.
+        while page_num != 0 && freelist.len() < reader.pages_total() {
+            let page = reader.get_trunk_freelist_page(page_num)?;
+            let next_page = page.next_page as usize;
+            freelist.push(FreelistTrunk { page_num, page });
+            page_num = next_page;
+        }
+        Ok(freelist)
+    }
+}
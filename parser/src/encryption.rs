@@ -0,0 +1,196 @@
+/// Recognize the databases, which are encrypted as a whole, by the file, which lacks
+/// the magic string, but still consists of whole pages of random-looking bytes.
+///
+/// SQLite Encryption Extension (SEE) encrypts everything but bytes 16..24 of the
+/// header, so the page size stays readable. SQLCipher encrypts the whole file and
+/// keeps the salt of the key in its first 16 bytes, the page size has to be guessed.
+use std::fmt;
+use std::rc::Rc;
+
+use crate::{DBHeader, Storage, TextEncoding, DB_HEADER_SIZE};
+
+/// First 16 bytes of every plain database file.
+pub const SQLITE_MAGIC: &[u8; 16] = b"SQLite format 3\0";
+/// Bits per byte of the content, from which it's considered random: plain pages
+/// stay well below, encrypted or compressed content comes close to 8.
+pub const ENCRYPTED_ENTROPY: f64 = 7.0;
+/// Bytes of the file, which entropy is measured.
+const SAMPLE_SIZE: usize = 4096;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Cipher {
+    /// SQLite Encryption Extension, the page size is read from the header.
+    See,
+    /// SQLCipher or another cipher, which encrypts the header too.
+    Unknown,
+}
+
+impl fmt::Display for Cipher {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Cipher::See => write!(f, "SQLite Encryption Extension"),
+            Cipher::Unknown => write!(f, "SQLCipher or a similar cipher"),
+        }
+    }
+}
+
+/// Why the file is likely an encrypted database.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Encryption {
+    pub cipher: Cipher,
+    /// Size of the pages, read from the header or guessed from the file size.
+    pub page_size: usize,
+    pub pages: usize,
+    /// Bits of information per byte of the file start, close to 8 for random bytes.
+    pub entropy: f64,
+}
+
+impl Encryption {
+    /// Encryption, which the file likely has, None if it's a plain database or
+    /// not a database at all.
+    pub fn detect(storage: &dyn Storage) -> Option<Self> {
+        let size = storage.size();
+        let mut sample = vec![0; SAMPLE_SIZE.min(size)];
+        let read = storage.read_at(0, &mut sample).ok()?;
+        sample.truncate(read);
+        if sample.len() < DB_HEADER_SIZE || sample.starts_with(SQLITE_MAGIC) {
+            return None;
+        }
+        let (cipher, page_size) = match see_page_size(&sample) {
+            Some(page_size) => (Cipher::See, page_size),
+            None => (Cipher::Unknown, guess_page_size(size)?),
+        };
+        if !size.is_multiple_of(page_size) {
+            return None;
+        }
+        // Plain header bytes of SEE aren't random.
+        let skip = match cipher {
+            Cipher::See => 24,
+            Cipher::Unknown => 0,
+        };
+        let entropy = entropy(&sample[skip..]);
+        (entropy >= ENCRYPTED_ENTROPY).then_some(Self {
+            cipher,
+            page_size,
+            pages: size / page_size,
+            entropy,
+        })
+    }
+
+    /// Header to lay out the file as pages, the rest of it is made up.
+    pub fn placeholder_header(&self) -> DBHeader {
+        DBHeader::new(
+            Rc::new(String::from_utf8_lossy(SQLITE_MAGIC).to_string()),
+            // 65536 is stored as 1.
+            self.page_size as u16 | (self.page_size >> 16) as u16,
+            1,
+            1,
+            0,
+            64,
+            32,
+            32,
+            0,
+            self.pages as u32,
+            0,
+            0,
+            0,
+            4,
+            0,
+            0,
+            TextEncoding::UTF8,
+            0,
+            0,
+            0,
+            &[0; 20],
+            0,
+            0,
+        )
+    }
+}
+
+impl fmt::Display for Encryption {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "The file is likely a database encrypted with {}: it doesn't start with the SQLite magic string, but it's {} pages of {} bytes and its content looks random, {:.2} bits per byte.",
+            self.cipher, self.pages, self.page_size, self.entropy
+        )
+    }
+}
+
+/// Page size from the plain bytes 16..24 of the SEE header, if they're valid.
+fn see_page_size(header: &[u8]) -> Option<usize> {
+    let page_size = match u16::from_be_bytes([header[16], header[17]]) {
+        1 => 65536,
+        n if n >= 512 && n.is_power_of_two() => n as usize,
+        _ => return None,
+    };
+    let versions = matches!(header[18], 1 | 2) && matches!(header[19], 1 | 2);
+    (versions && header[21..24] == [64, 32, 32]).then_some(page_size)
+}
+
+/// Default page size of SQLCipher 4 or 3, whichever the file consists of.
+fn guess_page_size(size: usize) -> Option<usize> {
+    [4096, 1024, 512].into_iter().find(|n| size.is_multiple_of(*n))
+}
+
+/// Shannon entropy of the bytes in bits per byte.
+pub fn entropy(bytes: &[u8]) -> f64 {
+    let mut counts = [0_usize; 256];
+    for b in bytes {
+        counts[*b as usize] += 1;
+    }
+    let total = bytes.len() as f64;
+    counts
+        .iter()
+        .filter(|n| **n > 0)
+        .map(|n| {
+            let p = *n as f64 / total;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Bytes of a simple generator, random enough for the entropy.
+    fn noise(size: usize) -> Vec<u8> {
+        let mut state = 0x2545f491_u32;
+        (0..size)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_detect_encryption() {
+        let file = noise(8 * 4096);
+        let encryption = Encryption::detect(&file).unwrap();
+        assert_eq!(encryption.cipher, Cipher::Unknown);
+        assert_eq!((encryption.page_size, encryption.pages), (4096, 8));
+        assert_eq!(encryption.placeholder_header().page_size, 4096);
+
+        // SEE keeps the page size and the payload fractions readable.
+        let mut file = noise(3 * 65536);
+        file[16..24].copy_from_slice(&[0, 1, 1, 1, 0, 64, 32, 32]);
+        let encryption = Encryption::detect(&file).unwrap();
+        assert_eq!(encryption.cipher, Cipher::See);
+        assert_eq!((encryption.page_size, encryption.pages), (65536, 3));
+        assert_eq!(encryption.placeholder_header().page_size, 65536);
+
+        // Plain databases, files of partial pages and files of plain bytes.
+        let mut file = noise(4096);
+        file[..16].copy_from_slice(SQLITE_MAGIC);
+        assert!(Encryption::detect(&file).is_none());
+        assert!(Encryption::detect(&noise(4000)).is_none());
+        assert!(Encryption::detect(&vec![7; 4096]).is_none());
+    }
+}
@@ -0,0 +1,85 @@
+/// Errors of reading the database file, by kind, so the consumers can tell
+/// a truncated file from a broken structure or a page out of the file.
+use std::fmt;
+
+use crate::Encryption;
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Error {
+    /// First byte of the b-tree page isn't one of the four b-tree page types.
+    UnexpectedPageType(u8),
+    /// File ends before the page does: expected and actually read number of bytes.
+    TruncatedPage {
+        page: usize,
+        expected: usize,
+        read: usize,
+    },
+    /// Varint, which value doesn't make sense for what it encodes.
+    BadVarint { value: i64, what: &'static str },
+    /// Serial type of a record value, which isn't defined by the format.
+    BadRecordCode(i64),
+    /// Page number, which isn't within the database.
+    OutOfBounds { page: usize, total: usize },
+    /// Text isn't valid in the text encoding of the database.
+    BadText(String),
+    /// Storage failed to read the bytes.
+    Io(String),
+    /// Structure contradicts the file format, with the explanation.
+    Malformed(String),
+    /// File isn't a plain database, it's likely encrypted.
+    Encrypted(Encryption),
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnexpectedPageType(byte) => write!(f, "Unexpected btree page type: {}", byte),
+            Self::TruncatedPage {
+                page,
+                expected,
+                read,
+            } => write!(
+                f,
+                "Incomplete read of page {}, expected to read {} bytes, got: {}",
+                page, expected, read
+            ),
+            Self::BadVarint { value, what } => write!(f, "{} {} is invalid.", what, value),
+            Self::BadRecordCode(code) => write!(f, "Unknown record serial type {}", code),
+            Self::OutOfBounds { page, total } => {
+                write!(f, "Out of bounds page access: {}/{}", page, total)
+            }
+            Self::BadText(e) => write!(f, "Invalid text: {}", e),
+            Self::Io(e) => write!(f, "Failed to read the database: {}", e),
+            Self::Malformed(e) => write!(f, "{}", e),
+            Self::Encrypted(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e.to_string())
+    }
+}
+
+impl From<std::str::Utf8Error> for Error {
+    fn from(e: std::str::Utf8Error) -> Self {
+        Self::BadText(e.to_string())
+    }
+}
+
+impl From<std::string::FromUtf16Error> for Error {
+    fn from(e: std::string::FromUtf16Error) -> Self {
+        Self::BadText(e.to_string())
+    }
+}
+
+/// Slices are taken of the exact size of the integer they are read into.
+impl From<std::array::TryFromSliceError> for Error {
+    fn from(e: std::array::TryFromSliceError) -> Self {
+        Self::Malformed(e.to_string())
+    }
+}
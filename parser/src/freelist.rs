@@ -1,10 +1,10 @@
 /// A database file might contain one or more pages that are not in active use, for example,
 /// when information is deleted from the database.
 /// Unused pages are stored on the freelist and are reused when additional pages are required.
-
+///
 /// The freelist is organized as a linked list of freelist trunk pages with each trunk page
 /// containing page numbers for zero or more freelist leaf pages.
-
+///
 /// A freelist trunk page consists of an array of 4-byte big-endian integers.
 /// The size of the array is as many integers as will fit in the usable space of a page.
 ///
@@ -13,26 +13,32 @@
 /// The second integer on a freelist trunk page is the number of leaf page pointers to follow.
 /// Call the second integer on a freelist trunk page L. If L >= 0 then integers with array
 /// indexes between 2 and L+1 inclusive contain page numbers for freelist leaf pages.
-
+///
 /// Freelist leaf pages contain no information.
 /// SQLite avoids reading or writing freelist leaf pages in order to reduce disk I/O.
-use crate::{slc, StdError};
+use crate::{slc, Error};
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct TrunkFreelistPage {
     pub next_page: u32,
     pub leaf_page_amount: u32,
     pub leaf_page_numbers: Option<Vec<u32>>,
     pub unallocated: Option<Vec<u8>>,
+    /// Reserved space at the end of the page, used by extensions.
+    pub reserved: Vec<u8>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct LeafFreelistPage {
     pub unallocated: Vec<u8>,
+    /// Reserved space at the end of the page, used by extensions.
+    pub reserved: Vec<u8>,
 }
 
 impl TryFrom<&[u8]> for TrunkFreelistPage {
-    type Error = StdError;
+    type Error = Error;
 
     fn try_from(buf: &[u8]) -> Result<Self, Self::Error> {
         let size = 4;
@@ -42,6 +48,13 @@ impl TryFrom<&[u8]> for TrunkFreelistPage {
 
         let leaf_page_amount = slc!(buf, offset, size, u32);
         offset += size;
+        if leaf_page_amount as usize > buf.len().saturating_sub(offset) / size {
+            return Err(Error::Malformed(format!(
+                "Freelist trunk page of {} bytes can't hold {} leaf page numbers.",
+                buf.len(),
+                leaf_page_amount
+            )));
+        }
 
         let leaf_page_numbers = if leaf_page_amount > 0 {
             let mut numbers = vec![];
@@ -65,16 +78,54 @@ impl TryFrom<&[u8]> for TrunkFreelistPage {
             leaf_page_amount,
             leaf_page_numbers,
             unallocated,
+            reserved: vec![],
         })
     }
 }
 
 impl TryFrom<&[u8]> for LeafFreelistPage {
-    type Error = StdError;
+    type Error = Error;
 
     fn try_from(buf: &[u8]) -> Result<Self, Self::Error> {
         Ok(Self {
             unallocated: buf[..].to_vec(),
+            reserved: vec![],
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{corrupt, database};
+    use crate::Reader;
+
+    #[test]
+    fn test_trunk_page() {
+        let bytes = database(
+            "freelist",
+            "PRAGMA page_size=512;
+            CREATE TABLE t(v);
+            INSERT INTO t VALUES(zeroblob(4000));
+            DELETE FROM t;",
+        );
+        let reader = Reader::new(bytes).unwrap();
+        let trunk_num = reader.db_header.first_free_page_num as usize;
+        let trunk = reader.get_trunk_freelist_page(trunk_num).unwrap();
+        let leaves = trunk.leaf_page_numbers.unwrap();
+        assert_eq!(trunk.leaf_page_amount as usize, leaves.len());
+        assert!(!leaves.is_empty());
+
+        // Count of leaf page numbers doesn't fit into the page.
+        let offset = (trunk_num - 1) * 512 + 4;
+        let reader = corrupt(bytes, offset, &0x7fffffffu32.to_be_bytes());
+        let result = reader.get_trunk_freelist_page(trunk_num);
+        assert!(matches!(result, Err(Error::Malformed(_))));
+
+        // Exactly as many as fit is still fine: (512 - 8) / 4.
+        let reader = corrupt(bytes, offset, &126u32.to_be_bytes());
+        let trunk = reader.get_trunk_freelist_page(trunk_num).unwrap();
+        assert_eq!(trunk.leaf_page_numbers.unwrap().len(), 126);
+        assert_eq!(trunk.unallocated, None);
+    }
+}
@@ -0,0 +1,606 @@
+//! FTS5 virtual table keeps its full-text index in ordinary shadow tables, named
+//! after the virtual table with a suffix: `%_data` holds the index itself, `%_idx`
+//! the interior of its segment b-trees, `%_content` a copy of the indexed text,
+//! `%_docsize` the number of tokens of every row and `%_config` the settings.
+//!
+//! The index is a set of segments, each of them is a b-tree of terms stored as blocks
+//! of the `%_data` table. Leaf pages hold the terms in order, each term followed by
+//! its doclist: the rowids, which contain the term, with the positions of the term
+//! in every column. Segments are merged into larger ones level by level, which
+//! segments make up the index is kept in the structure record.
+//!
+//! Format is described in the comments of ext/fts5/fts5_index.c.
+
+use crate::{virtual_table, Error, InternalTable, SchemaEntry, Varint};
+
+/// Rowid of the `%_data` block, which holds the total number of rows and tokens.
+pub const FTS5_AVERAGES_ROWID: i64 = 1;
+/// Rowid of the `%_data` block, which holds the structure record.
+pub const FTS5_STRUCTURE_ROWID: i64 = 10;
+/// Leaf page starts with the offset of its first rowid and the offset of the page index.
+pub const FTS5_LEAF_HEADER_SIZE: usize = 4;
+/// Structure record of a version 2 index has these bytes after the cookie.
+const STRUCTURE_V2: [u8; 4] = [0xff, 0x00, 0x00, 0x01];
+
+/// Bits of the `%_data` rowid, from the lowest: page number, height of the doclist
+/// index page, doclist index flag and segment id.
+const PAGE_BITS: u32 = 31;
+const HEIGHT_BITS: u32 = 5;
+const DLIDX_BITS: u32 = 1;
+const SEGID_BITS: u32 = 16;
+
+fn malformed(what: &str) -> Error {
+    Error::Malformed(format!("Invalid FTS5 record: {}.", what))
+}
+
+/// Shadow tables of an FTS5 table by their suffix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Fts5Table {
+    Data,
+    Idx,
+    Content,
+    Docsize,
+    Config,
+}
+
+impl Fts5Table {
+    pub const ALL: [Fts5Table; 5] = [
+        Fts5Table::Data,
+        Fts5Table::Idx,
+        Fts5Table::Content,
+        Fts5Table::Docsize,
+        Fts5Table::Config,
+    ];
+
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            Fts5Table::Data => "_data",
+            Fts5Table::Idx => "_idx",
+            Fts5Table::Content => "_content",
+            Fts5Table::Docsize => "_docsize",
+            Fts5Table::Config => "_config",
+        }
+    }
+
+    /// Meaning of the shadow table and its columns.
+    pub fn internal(&self) -> InternalTable {
+        match self {
+            Fts5Table::Data => InternalTable {
+                desc: "Shadow table of an FTS5 full-text index, it holds the index itself as blocks. Rowid 1 is the averages record, rowid 10 is the structure record, which lists the segments of the index by level. Other rowids pack the segment id, the doclist index flag, the height and the page number: these blocks are the leaf pages of the segment b-trees, with the terms in order and the doclist of every term, and the pages of the doclist indexes of the long doclists.",
+                columns: &[
+                    ("id", "Rowid of the block: segment id << 37 | doclist index flag << 36 | height << 31 | page number. 1 and 10 are the averages and the structure records."),
+                    ("block", "Content of the block. A leaf page starts with the 2-byte offset of its first rowid and the 2-byte offset of the page index, which follows the terms and lists their offsets as varints. The first term of the page is a varint length and the bytes of the term, the next ones share a prefix with the term before: varint length of the prefix, varint length of the rest and its bytes. The first byte of a term is '0' for the main index, or the number of a prefix index. A doclist follows every term: the rowid of the first row, the deltas of the next ones, each of them with the varint size of its position list * 2 + delete flag and the positions of the term: varint 1 switches to the column, which number follows, other values are the distance from the previous position + 2."),
+                ],
+            },
+            Fts5Table::Idx => InternalTable {
+                desc: "Shadow table of an FTS5 full-text index, it holds the interior of the segment b-trees: the first term of every leaf page of a segment after the first one, so a term is found by a lookup in this table, not by reading all the leaf pages.",
+                columns: &[
+                    ("segid", "Id of the segment, the same one the rowids of its blocks in the %_data table start with."),
+                    ("term", "Shortest prefix of the first term of the leaf page, which is still larger than the last term of the page before, with the byte of the index in front, empty for the first page."),
+                    ("pgno", "Number of the leaf page the term is on, shifted by 1 bit: the lowest bit tells, if the doclist of the term has a doclist index."),
+                ],
+            },
+            Fts5Table::Content => InternalTable {
+                desc: "Shadow table of an FTS5 full-text index, it holds a copy of the indexed text under the same rowid, so the columns of the FTS5 table are read from it. It's missing for contentless tables and tables with external content.",
+                columns: &[
+                    ("id", "Rowid of the row of the FTS5 table."),
+                    ("c*", "Value of a column of the FTS5 table: c0 is the first column, c1 the second one and so on."),
+                ],
+            },
+            Fts5Table::Docsize => InternalTable {
+                desc: "Shadow table of an FTS5 full-text index, it holds the number of tokens in every column of every row, so bm25() ranks the rows by the length of their text.",
+                columns: &[
+                    ("id", "Rowid of the row of the FTS5 table."),
+                    ("sz", "Varints, one per column: number of tokens in the column of the row."),
+                ],
+            },
+            Fts5Table::Config => InternalTable {
+                desc: "Shadow table of an FTS5 full-text index, it holds the settings changed with the special INSERT commands, like 'automerge' or 'rank', and the version of the index format.",
+                columns: &[
+                    ("k", "Name of the setting, 'version' for the version of the index format."),
+                    ("v", "Value of the setting."),
+                ],
+            },
+        }
+    }
+}
+
+/// Shadow table of an FTS5 table.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Fts5Shadow {
+    /// Name of the FTS5 virtual table.
+    pub vtab: String,
+    pub table: Fts5Table,
+    /// Doclists hold the positions of the terms, detail=full, the default.
+    pub full_detail: bool,
+}
+
+/// Shadow tables of the FTS5 tables of the schema with their names.
+pub fn fts5_shadows(entries: &[SchemaEntry]) -> Vec<(String, Fts5Shadow)> {
+    let mut shadows = vec![];
+    for entry in entries {
+        let Some((module, args)) = entry.sql.as_deref().and_then(virtual_table) else {
+            continue;
+        };
+        if module != "fts5" {
+            continue;
+        }
+        let options = args
+            .iter()
+            .map(|arg| arg.replace([' ', '\'', '"'], "").to_ascii_lowercase())
+            .collect::<Vec<_>>();
+        let full_detail = !options
+            .iter()
+            .any(|o| o == "detail=none" || o == "detail=column");
+        for table in Fts5Table::ALL {
+            let name = format!("{}{}", entry.name, table.suffix());
+            let Some(shadow) = entries.iter().find(|e| e.name.eq_ignore_ascii_case(&name)) else {
+                continue;
+            };
+            shadows.push((
+                shadow.name.clone(),
+                Fts5Shadow {
+                    vtab: entry.name.clone(),
+                    table,
+                    full_detail,
+                },
+            ));
+        }
+    }
+    shadows
+}
+
+/// Parts of the rowid of a `%_data` block.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Fts5BlockId {
+    pub segid: u32,
+    /// The block is a page of a doclist index, not a leaf page.
+    pub dlidx: bool,
+    /// Height of the doclist index page, 0 for leaves.
+    pub height: u8,
+    pub pgno: u32,
+}
+
+impl From<i64> for Fts5BlockId {
+    fn from(rowid: i64) -> Self {
+        let mask = |bits: u32| (1_i64 << bits) - 1;
+        Self {
+            segid: ((rowid >> (PAGE_BITS + HEIGHT_BITS + DLIDX_BITS)) & mask(SEGID_BITS + 1))
+                as u32,
+            dlidx: (rowid >> (PAGE_BITS + HEIGHT_BITS)) & 1 == 1,
+            height: ((rowid >> PAGE_BITS) & mask(HEIGHT_BITS)) as u8,
+            pgno: (rowid & mask(PAGE_BITS)) as u32,
+        }
+    }
+}
+
+impl Fts5BlockId {
+    /// Pages of the tombstone hash tables of the deleted rowids have the segment id
+    /// offset by 65536, they're only written by the version 2 index format.
+    pub fn is_tombstone(&self) -> bool {
+        self.segid >= 1 << SEGID_BITS
+    }
+}
+
+/// Total number of rows and of the tokens in each column.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Fts5Averages {
+    pub rows: u64,
+    pub tokens: Vec<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Fts5Segment {
+    pub id: u64,
+    pub first_page: u64,
+    pub last_page: u64,
+    /// Number of tombstone pages and of rows, only kept by the version 2 structure.
+    pub tombstone_pages: u64,
+    pub entries: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Fts5Level {
+    /// Number of segments of the level being merged into the next level.
+    pub merge: u64,
+    pub segments: Vec<Fts5Segment>,
+}
+
+/// Segments of the index, by level, the oldest and largest ones on the last level.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Fts5Structure {
+    /// Incremented on every change of the structure, so cached copies are dropped.
+    pub cookie: u32,
+    pub v2: bool,
+    /// Number of segments ever written, it schedules the automatic merges.
+    pub write_counter: u64,
+    pub levels: Vec<Fts5Level>,
+}
+
+/// Term of a leaf page and the part of its doclist on the same page.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Fts5Term {
+    /// Offset of the term in the block.
+    pub offset: usize,
+    /// 0 for the main index, the number of the prefix index otherwise.
+    pub index: u8,
+    pub term: Vec<u8>,
+    /// Offset and size of the doclist, which follows the term.
+    pub doclist: (usize, usize),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Fts5Leaf {
+    pub id: Fts5BlockId,
+    /// Offset of the first rowid on the page, 0 if no doclist starts on it.
+    pub first_rowid: usize,
+    /// Offset of the page index, which lists the offsets of the terms.
+    pub pgidx: usize,
+    pub terms: Vec<Fts5Term>,
+}
+
+/// Row of a doclist: the rowid with the positions of the term.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Fts5Doc {
+    pub rowid: i64,
+    /// Delete marker, which cancels the row of an older segment.
+    pub deleted: bool,
+    /// Column and token number of every occurrence of the term.
+    pub positions: Vec<(u64, u64)>,
+}
+
+/// Block of the `%_data` table.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Fts5Block {
+    Averages(Fts5Averages),
+    Structure(Fts5Structure),
+    Leaf(Fts5Leaf),
+    /// Page of the doclist index, which skips through a long doclist by rowids.
+    DoclistIndex(Fts5BlockId),
+    Tombstone(Fts5BlockId),
+}
+
+impl Fts5Block {
+    /// Block by its rowid and content.
+    pub fn new(rowid: i64, buf: &[u8]) -> Result<Self, Error> {
+        match rowid {
+            FTS5_AVERAGES_ROWID => Ok(Self::Averages(Fts5Averages::try_from(buf)?)),
+            FTS5_STRUCTURE_ROWID => Ok(Self::Structure(Fts5Structure::try_from(buf)?)),
+            _ => {
+                let id = Fts5BlockId::from(rowid);
+                if id.is_tombstone() {
+                    Ok(Self::Tombstone(id))
+                } else if id.dlidx {
+                    Ok(Self::DoclistIndex(id))
+                } else {
+                    Ok(Self::Leaf(Fts5Leaf::new(id, buf)?))
+                }
+            }
+        }
+    }
+}
+
+/// Varints read one after another.
+struct Varints<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl Varints<'_> {
+    fn next(&mut self) -> Result<u64, Error> {
+        let rest = self.buf.get(self.pos..).unwrap_or_default();
+        let varint = Varint::new(rest);
+        match varint.bytes.last() {
+            Some(last) if last & 0x80 == 0 || varint.bytes.len() == 9 => {
+                self.pos += varint.bytes.len();
+                Ok(varint.value as u64)
+            }
+            _ => Err(malformed("it ends in the middle of a varint")),
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.pos >= self.buf.len()
+    }
+}
+
+impl TryFrom<&[u8]> for Fts5Averages {
+    type Error = Error;
+
+    fn try_from(buf: &[u8]) -> Result<Self, Self::Error> {
+        let mut varints = Varints { buf, pos: 0 };
+        let rows = varints.next()?;
+        let mut tokens = vec![];
+        while !varints.is_done() {
+            tokens.push(varints.next()?);
+        }
+        Ok(Self { rows, tokens })
+    }
+}
+
+impl TryFrom<&[u8]> for Fts5Structure {
+    type Error = Error;
+
+    fn try_from(buf: &[u8]) -> Result<Self, Self::Error> {
+        let cookie = buf
+            .get(..4)
+            .ok_or_else(|| malformed("the structure record is too short"))?;
+        let cookie = u32::from_be_bytes(cookie.try_into()?);
+        let v2 = buf.get(4..8) == Some(&STRUCTURE_V2);
+        let mut varints = Varints {
+            buf,
+            pos: if v2 { 8 } else { 4 },
+        };
+        let level_count = varints.next()?;
+        let segment_count = varints.next()?;
+        let write_counter = varints.next()?;
+        let mut levels = vec![];
+        for _ in 0..level_count {
+            let merge = varints.next()?;
+            let count = varints.next()?;
+            let mut segments = vec![];
+            for _ in 0..count {
+                let mut segment = Fts5Segment {
+                    id: varints.next()?,
+                    first_page: varints.next()?,
+                    last_page: varints.next()?,
+                    tombstone_pages: 0,
+                    entries: 0,
+                };
+                if v2 {
+                    // Origin of the segment: first and last write counters it was made of.
+                    varints.next()?;
+                    varints.next()?;
+                    segment.tombstone_pages = varints.next()?;
+                    // Number of the tombstone entries.
+                    varints.next()?;
+                    segment.entries = varints.next()?;
+                }
+                segments.push(segment);
+            }
+            levels.push(Fts5Level { merge, segments });
+        }
+        let counted = levels.iter().map(|l| l.segments.len()).sum::<usize>();
+        if counted as u64 != segment_count {
+            return Err(malformed("segments don't add up to the total"));
+        }
+        Ok(Self {
+            cookie,
+            v2,
+            write_counter,
+            levels,
+        })
+    }
+}
+
+impl Fts5Leaf {
+    pub fn new(id: Fts5BlockId, buf: &[u8]) -> Result<Self, Error> {
+        let header = buf
+            .get(..FTS5_LEAF_HEADER_SIZE)
+            .ok_or_else(|| malformed("the leaf page is too short"))?;
+        let first_rowid = u16::from_be_bytes([header[0], header[1]]) as usize;
+        let pgidx = u16::from_be_bytes([header[2], header[3]]) as usize;
+        if pgidx < FTS5_LEAF_HEADER_SIZE || pgidx > buf.len() {
+            return Err(malformed("the page index is out of the page"));
+        }
+
+        // Page index lists the offset of the first term and the deltas to the next ones.
+        let mut offsets: Vec<usize> = vec![];
+        let mut index = Varints { buf, pos: pgidx };
+        while !index.is_done() {
+            let delta = index.next()? as usize;
+            let offset = offsets
+                .last()
+                .copied()
+                .unwrap_or_default()
+                .checked_add(delta)
+                .ok_or_else(|| malformed("a term offset overflows"))?;
+            offsets.push(offset);
+        }
+
+        let mut terms: Vec<Fts5Term> = vec![];
+        let mut key: Vec<u8> = vec![];
+        for (n, &offset) in offsets.iter().enumerate() {
+            let end = offsets.get(n + 1).copied().unwrap_or(pgidx);
+            if offset < FTS5_LEAF_HEADER_SIZE || end > pgidx || offset >= end {
+                return Err(malformed("a term is out of the page"));
+            }
+            let mut varints = Varints {
+                buf: &buf[..end],
+                pos: offset,
+            };
+            let prefix = match n {
+                0 => 0,
+                _ => varints.next()? as usize,
+            };
+            let size = varints.next()? as usize;
+            let start = varints
+                .pos
+                .checked_add(size)
+                .ok_or_else(|| malformed("a term size overflows"))?;
+            let suffix = buf
+                .get(varints.pos..start)
+                .filter(|_| start <= end && prefix <= key.len())
+                .ok_or_else(|| malformed("a term is out of the page"))?;
+            key.truncate(prefix);
+            key.extend_from_slice(suffix);
+            terms.push(Fts5Term {
+                offset,
+                index: key.first().map_or(0, |b| b.wrapping_sub(b'0')),
+                term: key.get(1..).unwrap_or_default().to_vec(),
+                doclist: (start, end - start),
+            });
+        }
+        Ok(Self {
+            id,
+            first_rowid,
+            pgidx,
+            terms,
+        })
+    }
+}
+
+/// Rows of the doclist of an index with the positions of the terms, detail=full.
+/// The doclist might go on to the next page, even in the middle of a varint, its
+/// last row is kept as far as it goes. Sizes or positions, which overflow, cut the
+/// doclist there.
+pub fn fts5_doclist(buf: &[u8]) -> Vec<Fts5Doc> {
+    let mut docs: Vec<Fts5Doc> = vec![];
+    let mut varints = Varints { buf, pos: 0 };
+    while let (Ok(delta), Ok(size)) = (varints.next(), varints.next()) {
+        let rowid = docs
+            .last()
+            .map_or(delta as i64, |d| d.rowid.wrapping_add(delta as i64));
+        let Some(end) = varints.pos.checked_add((size >> 1) as usize) else {
+            break;
+        };
+        let end = end.min(buf.len());
+        let mut positions = vec![];
+        let mut poslist = Varints {
+            buf: &buf[..end],
+            pos: varints.pos,
+        };
+        let (mut column, mut token) = (0, 0_u64);
+        let mut overflow = false;
+        while let Ok(v) = poslist.next() {
+            match v {
+                1 => match poslist.next() {
+                    Ok(next) => {
+                        column = next;
+                        token = 0;
+                    }
+                    Err(_) => break,
+                },
+                v => match token.checked_add(v.saturating_sub(2)) {
+                    Some(next) => {
+                        token = next;
+                        positions.push((column, token));
+                    }
+                    None => {
+                        overflow = true;
+                        break;
+                    }
+                },
+            }
+        }
+        varints.pos = end;
+        docs.push(Fts5Doc {
+            rowid,
+            deleted: size & 1 == 1,
+            positions,
+        });
+        if overflow {
+            break;
+        }
+    }
+    docs
+}
+
+/// Number of tokens in each column of the `%_docsize` row.
+pub fn fts5_docsize(buf: &[u8]) -> Result<Vec<u64>, Error> {
+    let mut varints = Varints { buf, pos: 0 };
+    let mut sizes = vec![];
+    while !varints.is_done() {
+        sizes.push(varints.next()?);
+    }
+    Ok(sizes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::unhex;
+
+    #[test]
+    fn test_fts5_blocks() {
+        // Blocks of an FTS5 table (a, b) after three inserts of
+        // ('hello world N', 'sqlite fts five').
+        let averages = Fts5Block::new(FTS5_AVERAGES_ROWID, &unhex("030909")).unwrap();
+        assert_eq!(
+            averages,
+            Fts5Block::Averages(Fts5Averages {
+                rows: 3,
+                tokens: vec![9, 9]
+            })
+        );
+
+        let structure = unhex("000000000101010001010101");
+        let Fts5Block::Structure(structure) =
+            Fts5Block::new(FTS5_STRUCTURE_ROWID, &structure).unwrap()
+        else {
+            panic!("not a structure record");
+        };
+        assert_eq!(structure.write_counter, 1);
+        assert_eq!(structure.levels.len(), 1);
+        assert_eq!(
+            (
+                structure.levels[0].segments[0].id,
+                structure.levels[0].segments[0].last_page
+            ),
+            (1, 1)
+        );
+
+        let bytes = unhex(concat!(
+            "0000007502303001020401013102020401013203020401046669766501060101040106010104",
+            "0106010104020274730106010103010601010301060101030105",
+            "68656c6c6f010202010202010202010673716c6974650106010102010601010201060101020105",
+            "776f726c640102030102030102030406060615131017",
+        ));
+        let rowid = (1 << 37) | 1;
+        let Fts5Block::Leaf(leaf) = Fts5Block::new(rowid, &bytes).unwrap() else {
+            panic!("not a leaf page");
+        };
+        assert_eq!((leaf.id.segid, leaf.id.pgno), (1, 1));
+        assert_eq!(leaf.pgidx, 117);
+        let terms = leaf
+            .terms
+            .iter()
+            .map(|t| String::from_utf8_lossy(&t.term).to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            terms,
+            ["0", "1", "2", "five", "fts", "hello", "sqlite", "world"]
+        );
+
+        // 'five' is the third token of the second column of every row.
+        let (start, size) = leaf.terms[3].doclist;
+        let docs = fts5_doclist(&bytes[start..start + size]);
+        assert_eq!(docs.iter().map(|d| d.rowid).collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(docs[0].positions, [(1, 2)]);
+    }
+
+    #[test]
+    fn test_fts5_overflow() {
+        let max = "ffffffffffffffffff";
+        let id = Fts5BlockId::from((1 << 37) | 1);
+
+        // Delta to the second term offset in the page index overflows.
+        let bytes = unhex(&format!("0000000404{}", max));
+        let leaf = Fts5Leaf::new(id, &bytes);
+        assert!(matches!(leaf, Err(Error::Malformed(_))));
+
+        // Size of the first term overflows.
+        let bytes = unhex(&format!("0000000e{}0004", max));
+        let leaf = Fts5Leaf::new(id, &bytes);
+        assert!(matches!(leaf, Err(Error::Malformed(_))));
+
+        // Token position overflows, the doclist is cut there.
+        let bytes = unhex(&format!("0114{}05", max));
+        let docs = fts5_doclist(&bytes);
+        assert_eq!(docs.len(), 1);
+        assert_eq!(docs[0].positions, [(0, u64::MAX - 2)]);
+        let bytes = unhex(&format!("0114{}05{}", max, "0104020203"));
+        assert_eq!(fts5_doclist(&bytes).len(), 1);
+    }
+}
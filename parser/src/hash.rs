@@ -0,0 +1,69 @@
+/// Checksums and digests of the raw page content, to tell whether a page changed
+/// between two files or snapshots without comparing them byte by byte.
+use std::fmt;
+
+use sha2::{Digest, Sha256};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum PageHash {
+    /// CRC-32 of zlib and gzip, quick, 8 hex digits.
+    Crc32,
+    /// SHA-256, slower, but collisions aren't a concern.
+    Sha256,
+}
+
+impl PageHash {
+    pub const ALL: [PageHash; 2] = [PageHash::Crc32, PageHash::Sha256];
+
+    /// Lowercase hex of the checksum or digest of the bytes.
+    pub fn hex(&self, bytes: &[u8]) -> String {
+        match self {
+            PageHash::Crc32 => format!("{:08x}", crc32(bytes)),
+            PageHash::Sha256 => sha256(bytes).iter().map(|b| format!("{:02x}", b)).collect(),
+        }
+    }
+}
+
+impl fmt::Display for PageHash {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PageHash::Crc32 => write!(f, "CRC32"),
+            PageHash::Sha256 => write!(f, "SHA-256"),
+        }
+    }
+}
+
+/// CRC-32 with the reflected polynomial 0xedb88320.
+pub fn crc32(bytes: &[u8]) -> u32 {
+    crc32fast::hash(bytes)
+}
+
+/// SHA-256 of FIPS 180-4.
+pub fn sha256(bytes: &[u8]) -> [u8; 32] {
+    Sha256::digest(bytes).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_hash() {
+        assert_eq!(PageHash::Crc32.hex(b""), "00000000");
+        assert_eq!(PageHash::Crc32.hex(b"123456789"), "cbf43926");
+        assert_eq!(
+            PageHash::Sha256.hex(b""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            PageHash::Sha256.hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        // Padding takes a whole block of its own.
+        assert_eq!(
+            PageHash::Sha256.hex(&[0; 4096]),
+            "ad7facb2586fc6e966c004d7d1d16b024f5805ff7cb47c7a85dabd8b48892ca7"
+        );
+    }
+}
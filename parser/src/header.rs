@@ -5,9 +5,10 @@
 
 use std::rc::Rc;
 
-use crate::{slc, StdError};
+use crate::{slc, Error};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum TextEncoding {
     UTF8,
     UTF16le,
@@ -15,14 +16,17 @@ pub enum TextEncoding {
 }
 
 impl TryFrom<u32> for TextEncoding {
-    type Error = String;
+    type Error = Error;
 
     fn try_from(val: u32) -> Result<Self, Self::Error> {
         match val {
             1 => Ok(Self::UTF8),
             2 => Ok(Self::UTF16le),
             3 => Ok(Self::UTF16be),
-            _ => Err(format!("Wrong db encoding value: {}", val)),
+            _ => Err(Error::Malformed(format!(
+                "Wrong db encoding value: {}",
+                val
+            ))),
         }
     }
 }
@@ -45,9 +49,32 @@ impl TextEncoding {
             Self::UTF16be => 3_u32.to_be_bytes(),
         }
     }
+
+    /// Encode text the way it's stored in the database.
+    pub fn encode(&self, text: &str) -> Vec<u8> {
+        match self {
+            Self::UTF8 => text.as_bytes().to_vec(),
+            Self::UTF16le => text.encode_utf16().flat_map(u16::to_le_bytes).collect(),
+            Self::UTF16be => text.encode_utf16().flat_map(u16::to_be_bytes).collect(),
+        }
+    }
+
+    /// Decode text of the encoding. Part of a value spilled over onto overflow pages
+    /// might end in the middle of a character, so it's decoded lossy until merged.
+    pub fn decode(&self, bytes: &[u8], partial: bool) -> Result<String, Error> {
+        Ok(match (self, partial) {
+            (Self::UTF8, false) => std::str::from_utf8(bytes)?.to_string(),
+            (Self::UTF8, true) => String::from_utf8_lossy(bytes).into_owned(),
+            (Self::UTF16le, false) => String::from_utf16le(bytes)?,
+            (Self::UTF16le, true) => String::from_utf16le_lossy(bytes),
+            (Self::UTF16be, false) => String::from_utf16be(bytes)?,
+            (Self::UTF16be, true) => String::from_utf16be_lossy(bytes),
+        })
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct DBHeader {
     /// should be 'SQLite format 3\0'
     /// offset: 0, size: 16
@@ -126,10 +153,10 @@ pub struct DBHeader {
 }
 
 impl TryFrom<&[u8; 100]> for DBHeader {
-    type Error = StdError;
+    type Error = Error;
 
     fn try_from(buf: &[u8; 100]) -> Result<Self, Self::Error> {
-        Ok(Self::new(
+        let header = Self::new(
             // header
             Rc::new(std::str::from_utf8(&slc!(buf, 0, 16))?.to_string()),
             // page_size
@@ -176,7 +203,9 @@ impl TryFrom<&[u8; 100]> for DBHeader {
             slc!(buf, 92, 4, u32),
             // version
             slc!(buf, 96, 4, u32),
-        ))
+        );
+        Self::check_page_size(header.page_size)?;
+        Ok(header)
     }
 }
 
@@ -235,6 +264,18 @@ impl DBHeader {
         }
     }
 
+    /// Every offset in the file is computed from the page size, there is no way
+    /// to read the rest of it with an invalid one.
+    fn check_page_size(page_size: u64) -> Result<(), Error> {
+        if !page_size.is_power_of_two() || !(512..=65536).contains(&page_size) {
+            return Err(Error::Malformed(format!(
+                "Unexpected page size: {}",
+                page_size
+            )));
+        }
+        Ok(())
+    }
+
     /// Field stores only 2 bytes, to max value to represent is 65535
     /// To specify page size of value 65536 - 0x0001 value is used
     fn to_page_size(value: u16) -> u64 {
@@ -244,3 +285,33 @@ impl DBHeader {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::database;
+
+    #[test]
+    fn test_page_size() {
+        let bytes = database("page-size", "PRAGMA page_size=65536; CREATE TABLE t(v);");
+        let mut buf: [u8; 100] = bytes[..100].try_into().unwrap();
+        // 65536 doesn't fit into 2 bytes, it's stored as 1.
+        assert_eq!(buf[16..18], [0, 1]);
+        assert_eq!(DBHeader::try_from(&buf).unwrap().page_size, 65536);
+
+        for size in [512_u16, 1024, 4096, 32768] {
+            buf[16..18].copy_from_slice(&size.to_be_bytes());
+            assert_eq!(DBHeader::try_from(&buf).unwrap().page_size, size as u64);
+        }
+        for size in [0_u16, 256, 1000] {
+            buf[16..18].copy_from_slice(&size.to_be_bytes());
+            let header = DBHeader::try_from(&buf);
+            assert!(matches!(header, Err(Error::Malformed(_))));
+        }
+        // Values only reachable past the 2 bytes of the field.
+        for size in [0, 256, 1000, 131072] {
+            assert!(DBHeader::check_page_size(size).is_err());
+        }
+        assert!(DBHeader::check_page_size(65536).is_ok());
+    }
+}
@@ -0,0 +1,321 @@
+/// Decompression of zlib streams (RFC 1950) of DEFLATE data (RFC 1951), the way
+/// SQLite Archives keep compressed files. Everything is decompressed at once into
+/// memory, there's no streaming.
+use crate::Error;
+
+/// Base lengths and extra bits of the length symbols 257..285.
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+/// Base distances and extra bits of the distance symbols 0..29.
+const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DIST_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+/// Order of the code length code lengths of a dynamic block.
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+const MAX_BITS: usize = 15;
+
+fn malformed(what: &str) -> Error {
+    Error::Malformed(format!("Invalid compressed data: {}.", what))
+}
+
+/// Decompress the zlib stream, which is expected to hold at most `limit` bytes.
+pub fn zlib_decompress(buf: &[u8], limit: usize) -> Result<Vec<u8>, Error> {
+    let [cmf, flg, ..] = *buf else {
+        return Err(malformed("zlib header is missing"));
+    };
+    if cmf & 0x0f != 8 || (u16::from(cmf) << 8 | u16::from(flg)) % 31 != 0 {
+        return Err(malformed("not a zlib stream of DEFLATE data"));
+    }
+    if flg & 0x20 != 0 {
+        return Err(malformed("preset dictionaries aren't supported"));
+    }
+    let mut bits = Bits::new(&buf[2..]);
+    let out = inflate(&mut bits, limit)?;
+    // Adler-32 checksum of the data follows the last block.
+    bits.align();
+    if let Some(checksum) = bits.buf.get(bits.pos..bits.pos + 4) {
+        if u32::from_be_bytes(checksum.try_into()?) != adler32(&out) {
+            return Err(malformed("checksum doesn't match"));
+        }
+    }
+    Ok(out)
+}
+
+pub fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1_u32, 0_u32);
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    b << 16 | a
+}
+
+/// Bits are read from the least significant one of each byte.
+struct Bits<'a> {
+    buf: &'a [u8],
+    pos: usize,
+    value: u32,
+    count: u32,
+}
+
+impl<'a> Bits<'a> {
+    fn new(buf: &'a [u8]) -> Self {
+        Self {
+            buf,
+            pos: 0,
+            value: 0,
+            count: 0,
+        }
+    }
+
+    fn take(&mut self, n: u32) -> Result<u32, Error> {
+        while self.count < n {
+            let byte = *self
+                .buf
+                .get(self.pos)
+                .ok_or_else(|| malformed("it ends unexpectedly"))?;
+            self.pos += 1;
+            self.value |= (byte as u32) << self.count;
+            self.count += 8;
+        }
+        let value = self.value & ((1 << n) - 1);
+        self.value >>= n;
+        self.count -= n;
+        Ok(value)
+    }
+
+    /// Drop the rest of the bits of the current byte.
+    fn align(&mut self) {
+        self.value = 0;
+        self.count = 0;
+    }
+}
+
+/// Canonical Huffman code, symbols are sorted by the length of their codes.
+struct Huffman {
+    counts: [u16; MAX_BITS + 1],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Result<Self, Error> {
+        let mut counts = [0_u16; MAX_BITS + 1];
+        for &len in lengths {
+            counts[len as usize] += 1;
+        }
+        counts[0] = 0;
+        let mut left = 1_i32;
+        for &count in &counts[1..] {
+            left = (left << 1) - count as i32;
+            if left < 0 {
+                return Err(malformed("Huffman code is over-subscribed"));
+            }
+        }
+        let mut offsets = [0_u16; MAX_BITS + 2];
+        for len in 1..=MAX_BITS {
+            offsets[len + 1] = offsets[len] + counts[len];
+        }
+        let mut symbols = vec![0; offsets[MAX_BITS + 1] as usize];
+        for (symbol, &len) in lengths.iter().enumerate() {
+            if len != 0 {
+                symbols[offsets[len as usize] as usize] = symbol as u16;
+                offsets[len as usize] += 1;
+            }
+        }
+        Ok(Self { counts, symbols })
+    }
+
+    fn decode(&self, bits: &mut Bits) -> Result<u16, Error> {
+        let (mut code, mut first, mut index) = (0_i32, 0_i32, 0_i32);
+        for len in 1..=MAX_BITS {
+            code |= bits.take(1)? as i32;
+            let count = self.counts[len] as i32;
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(malformed("Huffman code is incomplete"))
+    }
+}
+
+fn inflate(bits: &mut Bits, limit: usize) -> Result<Vec<u8>, Error> {
+    let mut out = vec![];
+    loop {
+        let last = bits.take(1)? == 1;
+        match bits.take(2)? {
+            0 => stored(bits, &mut out)?,
+            1 => {
+                let (lengths, distances) = fixed()?;
+                codes(bits, &mut out, &lengths, &distances, limit)?
+            }
+            2 => {
+                let (lengths, distances) = dynamic(bits)?;
+                codes(bits, &mut out, &lengths, &distances, limit)?
+            }
+            _ => return Err(malformed("block type is reserved")),
+        }
+        if out.len() > limit {
+            return Err(malformed("it's larger than expected"));
+        }
+        if last {
+            return Ok(out);
+        }
+    }
+}
+
+fn stored(bits: &mut Bits, out: &mut Vec<u8>) -> Result<(), Error> {
+    bits.align();
+    let header = bits
+        .buf
+        .get(bits.pos..bits.pos + 4)
+        .ok_or_else(|| malformed("it ends unexpectedly"))?;
+    let len = u16::from_le_bytes([header[0], header[1]]);
+    if len != !u16::from_le_bytes([header[2], header[3]]) {
+        return Err(malformed(
+            "stored block length doesn't match its complement",
+        ));
+    }
+    let start = bits.pos + 4;
+    let data = bits
+        .buf
+        .get(start..start + len as usize)
+        .ok_or_else(|| malformed("it ends unexpectedly"))?;
+    out.extend_from_slice(data);
+    bits.pos = start + len as usize;
+    Ok(())
+}
+
+fn fixed() -> Result<(Huffman, Huffman), Error> {
+    let mut lengths = [8_u8; 288];
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    Ok((Huffman::new(&lengths)?, Huffman::new(&[5; 30])?))
+}
+
+fn dynamic(bits: &mut Bits) -> Result<(Huffman, Huffman), Error> {
+    let nlen = bits.take(5)? as usize + 257;
+    let ndist = bits.take(5)? as usize + 1;
+    let ncode = bits.take(4)? as usize + 4;
+    let mut code_lengths = [0_u8; 19];
+    for &n in &CODE_LENGTH_ORDER[..ncode] {
+        code_lengths[n] = bits.take(3)? as u8;
+    }
+    let code = Huffman::new(&code_lengths)?;
+
+    let mut lengths = Vec::with_capacity(nlen + ndist);
+    while lengths.len() < nlen + ndist {
+        let (len, repeat) = match code.decode(bits)? {
+            len @ 0..=15 => (len as u8, 1),
+            16 => {
+                let previous = *lengths
+                    .last()
+                    .ok_or_else(|| malformed("repeated length has nothing to repeat"))?;
+                (previous, 3 + bits.take(2)?)
+            }
+            17 => (0, 3 + bits.take(3)?),
+            _ => (0, 11 + bits.take(7)?),
+        };
+        if lengths.len() + repeat as usize > nlen + ndist {
+            return Err(malformed("too many code lengths"));
+        }
+        lengths.extend(std::iter::repeat_n(len, repeat as usize));
+    }
+    if lengths[256] == 0 {
+        return Err(malformed("end of block code is missing"));
+    }
+    Ok((
+        Huffman::new(&lengths[..nlen])?,
+        Huffman::new(&lengths[nlen..])?,
+    ))
+}
+
+/// Literals and back references up to the end of the block.
+fn codes(
+    bits: &mut Bits,
+    out: &mut Vec<u8>,
+    lengths: &Huffman,
+    distances: &Huffman,
+    limit: usize,
+) -> Result<(), Error> {
+    loop {
+        let symbol = lengths.decode(bits)? as usize;
+        match symbol {
+            0..=255 => out.push(symbol as u8),
+            256 => return Ok(()),
+            _ => {
+                let n = symbol - 257;
+                if n >= LENGTH_BASE.len() {
+                    return Err(malformed("length symbol is invalid"));
+                }
+                let len = LENGTH_BASE[n] as usize + bits.take(LENGTH_EXTRA[n] as u32)? as usize;
+                let n = distances.decode(bits)? as usize;
+                if n >= DIST_BASE.len() {
+                    return Err(malformed("distance symbol is invalid"));
+                }
+                let dist = DIST_BASE[n] as usize + bits.take(DIST_EXTRA[n] as u32)? as usize;
+                if dist > out.len() {
+                    return Err(malformed("distance is too far back"));
+                }
+                for _ in 0..len {
+                    out.push(out[out.len() - dist]);
+                }
+            }
+        }
+        if out.len() > limit {
+            return Err(malformed("it's larger than expected"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::unhex;
+
+    #[test]
+    fn test_zlib_decompress() {
+        // Stored, fixed and dynamic Huffman blocks, as Python's zlib writes them.
+        let stored = unhex("7801010600f9ff73746f726564093c0292");
+        assert_eq!(zlib_decompress(&stored, 100).unwrap(), b"stored");
+
+        let fixed = unhex("78dacb48cdc9c9d751c840a2148a0b73128b0074610918");
+        assert_eq!(
+            zlib_decompress(&fixed, 100).unwrap(),
+            b"hello, hello, hello sqlar"
+        );
+
+        let dynamic = unhex(concat!(
+            "78da75cfbb0980401004d056a60003c7bfe588ec7207822062fd62ec4cfab297f508b43813",
+            "77096cd75eea130df2636aee34f79a07cda3e649f3ac79d1bc9a8e6b9a274d94664a53a5b9",
+            "d26469b6345dfebf2f227299d5",
+        ));
+        let expected = (0..20)
+            .map(|i| format!("file {} of the archive, ", i))
+            .collect::<String>();
+        assert_eq!(
+            zlib_decompress(&dynamic, 1000).unwrap(),
+            expected.as_bytes()
+        );
+        // Data larger than expected isn't decompressed.
+        assert!(zlib_decompress(&dynamic, 100).is_err());
+    }
+}
@@ -0,0 +1,145 @@
+//! What SQLite would do to the b-tree page to insert one more cell into it, following
+//! insertCell, allocateSpace and pageFindSlot of btree.c. It's only computed from the
+//! parsed page, nothing is written.
+use crate::*;
+
+/// Fragmented bytes of the page, past which SQLite doesn't leave another fragment
+/// behind, it takes the space from the cell content area instead, see pageFindSlot.
+pub const MAX_FRAGMENTED: usize = 57;
+
+/// Where the new cell goes, offsets are within the page.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Placement {
+    /// Cut from the end of the first freeblock, which is big enough. What's left of it
+    /// stays a freeblock, or becomes fragmented bytes if it's less than 4 bytes.
+    Freeblock {
+        offset: usize,
+        block_offset: usize,
+        left: usize,
+    },
+    /// Taken from the unallocated space right before the cell content area.
+    Unallocated { offset: usize },
+    /// Free bytes are enough, but they are scattered: the page is defragmented first,
+    /// all the cells are moved to the end of the page and the new one goes before them.
+    Defragment { offset: usize },
+    /// Page is too full, SQLite balances it with its siblings, the cells are split
+    /// between the pages.
+    Split,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Insertion {
+    /// Payload of the new cell split between the page and overflow pages, None for
+    /// table interior cells, which have no payload.
+    pub split: Option<PayloadSplit>,
+    /// Bytes the new cell takes on the page, never less than 4.
+    pub cell_size: usize,
+    /// Free bytes of the page: unallocated space, freeblocks and fragments.
+    pub free: usize,
+    pub placement: Placement,
+}
+
+impl Insertion {
+    /// Insert a cell with the payload of the size into the page, the rowid only
+    /// matters for table pages, which keep it in the cell.
+    pub fn new(page: &Page, payload_size: u64, rowid: i64) -> Self {
+        let db_header = &page.db_header;
+        let u = payload_math::usable_size(db_header.page_size, db_header.reserved_page_space);
+        let rowid = Varint::encode(rowid).bytes.len();
+        let payload = Varint::encode(payload_size as i64).bytes.len();
+        let (prefix, max_local) = match page.page_header.page_type {
+            PageHeaderType::InteriorTable => (4 + rowid, None),
+            PageHeaderType::LeafTable => (payload + rowid, Some(payload_math::table_max_local(u))),
+            PageHeaderType::LeafIndex => (payload, Some(payload_math::index_max_local(u))),
+            PageHeaderType::InteriorIndex => (4 + payload, Some(payload_math::index_max_local(u))),
+        };
+        let split = max_local.map(|x| PayloadSplit::new(u, x, payload_size));
+        // Spilled payload is followed by the page number of the first overflow page.
+        let local = split.as_ref().map_or(0, |s| match s.overflow {
+            0 => s.local as usize,
+            _ => s.local as usize + 4,
+        });
+        let cell_size = (prefix + local).max(4);
+        let stats = PageStats::new(page);
+        Self {
+            split,
+            cell_size,
+            free: stats.free(),
+            placement: Self::place(page, &stats, u as usize, cell_size),
+        }
+    }
+
+    fn place(page: &Page, stats: &PageStats, usable: usize, size: usize) -> Placement {
+        // Cell comes with its pointer, both have to fit.
+        if stats.free() < size + CELL_PTR_SIZE {
+            return Placement::Split;
+        }
+        let header_start = if page.id == 1 { DB_HEADER_SIZE } else { 0 };
+        let gap =
+            header_start + page.page_header.size + page.cell_pointer.array.len() * CELL_PTR_SIZE;
+        let top = page.page_header.cell_start_offset as usize;
+        // Freeblocks are searched only if the new pointer doesn't run into the content area.
+        if gap + CELL_PTR_SIZE <= top {
+            for block in &page.free_blocks {
+                let Some(left) = (block.size as usize).checked_sub(size) else {
+                    continue;
+                };
+                if left < 4 && page.page_header.fragmented_free_bytes as usize > MAX_FRAGMENTED {
+                    break;
+                }
+                return Placement::Freeblock {
+                    offset: block.offset + left,
+                    block_offset: block.offset,
+                    left,
+                };
+            }
+        }
+        if gap + CELL_PTR_SIZE + size > top {
+            // Defragmented cells take the end of the usable space, one after another.
+            return Placement::Defragment {
+                offset: usable - stats.cells - size,
+            };
+        }
+        Placement::Unallocated { offset: top - size }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::leaf_page;
+
+    #[test]
+    fn test_insertion() {
+        // 1-byte payload size and rowid varints with the payload of 10 bytes.
+        let insertion = Insertion::new(&leaf_page(4088, 0), 10, 8);
+        assert_eq!(insertion.cell_size, 12);
+        assert_eq!(insertion.placement, Placement::Unallocated { offset: 4076 });
+
+        let insertion = Insertion::new(&leaf_page(2000, 2088), 10, 8);
+        assert_eq!(
+            insertion.placement,
+            Placement::Freeblock {
+                offset: 4076,
+                block_offset: 2000,
+                left: 2076
+            }
+        );
+        // Biggest payload, which doesn't spill, fits only into the defragmented page.
+        let insertion = Insertion::new(&leaf_page(2000, 2088), 4061, 8);
+        assert_eq!(insertion.split.as_ref().unwrap().overflow, 0);
+        assert_eq!(insertion.placement, Placement::Defragment { offset: 24 });
+
+        let insertion = Insertion::new(&leaf_page(20, 0), 10, 8);
+        assert_eq!(insertion.free, 10);
+        assert_eq!(insertion.placement, Placement::Split);
+
+        // Spilled payload keeps the first overflow page number on the page.
+        let insertion = Insertion::new(&leaf_page(4088, 0), 10000, 8);
+        let split = insertion.split.unwrap();
+        assert!(split.overflow > 0);
+        assert_eq!(insertion.cell_size, 2 + 1 + split.local as usize + 4);
+    }
+}
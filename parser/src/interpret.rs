@@ -0,0 +1,163 @@
+/// Stateless entry points to read bytes at any place as one of the structures of
+/// the format, for the bytes the page parsers don't place themselves. Unlike the
+/// page parsers, they check first that the bytes are enough, so a wrong guess is
+/// an error, not a panic.
+use std::fmt;
+use std::rc::Rc;
+
+use crate::*;
+
+/// Structure the bytes are read as.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Interpretation {
+    Varint,
+    /// Big-endian 4-byte integer, like page numbers and most header fields.
+    U32,
+    RecordHeader,
+    /// Cell of the b-tree page of the type.
+    Cell(PageHeaderType),
+}
+
+impl Interpretation {
+    pub const ALL: [Interpretation; 7] = [
+        Interpretation::Varint,
+        Interpretation::U32,
+        Interpretation::RecordHeader,
+        Interpretation::Cell(PageHeaderType::LeafTable),
+        Interpretation::Cell(PageHeaderType::InteriorTable),
+        Interpretation::Cell(PageHeaderType::LeafIndex),
+        Interpretation::Cell(PageHeaderType::InteriorIndex),
+    ];
+}
+
+impl fmt::Display for Interpretation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Varint => write!(f, "Varint"),
+            Self::U32 => write!(f, "u32"),
+            Self::RecordHeader => write!(f, "Record header"),
+            Self::Cell(page_type) => write!(f, "{} cell", page_type),
+        }
+    }
+}
+
+/// Varint at the start of the bytes, which have to hold all of it.
+pub fn read_varint(buf: &[u8]) -> Result<Varint, Error> {
+    let varint = Varint::new(buf);
+    match varint.bytes.last() {
+        Some(last) if varint.bytes.len() == 9 || last & 0x80 == 0 => Ok(varint),
+        _ => Err(Error::Malformed(format!(
+            "Varint isn't complete in {} byte(s): every byte but the last one has the high bit set.",
+            buf.len()
+        ))),
+    }
+}
+
+/// Big-endian 4-byte integer at the start of the bytes.
+pub fn read_u32(buf: &[u8]) -> Result<u32, Error> {
+    let bytes = need(buf, 4, "u32")?;
+    Ok(slc!(bytes, 0, 4, u32))
+}
+
+/// Record header at the start of the bytes, the datatypes past them are counted
+/// as spilled, like the ones on the overflow page.
+pub fn read_record_header(buf: &[u8]) -> Result<RecordHeader, Error> {
+    let size = read_varint(buf)?;
+    if size.value < size.bytes.len() as i64 {
+        return Err(Error::BadVarint {
+            value: size.value,
+            what: "Record header size",
+        });
+    }
+    let header = RecordHeader::try_from(buf)?;
+    if let Some(d) = header.datatypes.iter().find(|d| d.value < 0) {
+        return Err(Error::BadRecordCode(d.value));
+    }
+    Ok(header)
+}
+
+/// Cell of the b-tree page of the type at the start of the bytes. The payload has
+/// to fit the bytes up to the pointer to the first overflow page, if it spills.
+pub fn read_cell(
+    page_type: PageHeaderType,
+    db_header: Rc<DBHeader>,
+    buf: &[u8],
+) -> Result<Cell, Error> {
+    let mut offset = 0;
+    if page_type.is_interior() {
+        read_u32(buf)?;
+        offset += 4;
+    }
+    let first = read_varint(&buf[offset..])?;
+    offset += first.bytes.len();
+    let payload_size = match page_type {
+        PageHeaderType::InteriorTable => return Cell::new(page_type, db_header, buf),
+        PageHeaderType::LeafTable => {
+            offset += read_varint(&buf[offset..])?.bytes.len();
+            first.value
+        }
+        PageHeaderType::LeafIndex | PageHeaderType::InteriorIndex => first.value,
+    };
+    if payload_size < 0 {
+        return Err(Error::BadVarint {
+            value: payload_size,
+            what: "Payload size",
+        });
+    }
+    let u = payload_math::usable_size(db_header.page_size, db_header.reserved_page_space);
+    let max_local = match page_type {
+        PageHeaderType::LeafTable => payload_math::table_max_local(u),
+        _ => payload_math::index_max_local(u),
+    };
+    let split = PayloadSplit::new(u, max_local, payload_size as u64);
+    let pointer = if split.overflow > 0 { 4 } else { 0 };
+    need(buf, offset + split.local as usize + pointer, "Cell")?;
+    Cell::new(page_type, db_header, buf)
+}
+
+fn need<'a>(buf: &'a [u8], size: usize, what: &str) -> Result<&'a [u8], Error> {
+    buf.get(..size).ok_or_else(|| {
+        Error::Malformed(format!(
+            "{} takes {} bytes, there are only {}.",
+            what,
+            size,
+            buf.len()
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{db_header, LEAF_CELL};
+
+    #[test]
+    fn test_interpret() {
+        let varint = read_varint(&[0x81, 0x00, 0xff]).unwrap();
+        assert_eq!((varint.value, varint.bytes.len()), (128, 2));
+        assert!(read_varint(&[0x81, 0x82]).is_err());
+        assert!(read_varint(&[]).is_err());
+
+        assert_eq!(read_u32(&[0, 0, 1, 2, 3]).unwrap(), 258);
+        assert!(read_u32(&[0, 1]).is_err());
+
+        // Header of 3 bytes with an integer and a 2-byte text, one more datatype is cut.
+        let header = read_record_header(&[3, 1, 17, 9]).unwrap();
+        assert_eq!(header.datatypes.len(), 2);
+        let header = read_record_header(&[4, 1, 17]).unwrap();
+        assert_eq!(header.spilled, 1);
+        assert!(read_record_header(&[0]).is_err());
+
+        let db_header = db_header(4096, 0);
+        let truncated = read_cell(
+            PageHeaderType::LeafTable,
+            db_header.clone(),
+            &LEAF_CELL[..7],
+        );
+        assert!(truncated.is_err());
+        let cell = read_cell(PageHeaderType::LeafTable, db_header.clone(), &LEAF_CELL).unwrap();
+        assert_eq!(cell.payload().unwrap().unwrap().values.len(), 2);
+        assert!(read_cell(PageHeaderType::InteriorTable, db_header, &[0, 0, 1]).is_err());
+    }
+}
@@ -0,0 +1,375 @@
+/// A rollback journal is a file associated with each SQLite database file that holds
+/// information used to restore the database file to its initial state during
+/// the course of a transaction. The rollback journal file is always located in the
+/// same directory as the database file and has the same name as the database file
+/// but with the string "-journal" appended.
+///
+/// The rollback journal is usually created when a transaction is first started and
+/// is usually deleted when a transaction commits or rolls back. If a rollback journal
+/// exists without a corresponding lock, it's a "hot journal", which must be played
+/// back to restore the database to a sane state.
+///
+/// A journal consists of one or more segments, each segment starts with a header
+/// padded to the sector size and followed by zero or more page records.
+///
+/// A transaction, which changes several attached databases, has a super-journal
+/// besides the journals of each database. It lists the names of these child
+/// journals, and each of them ends with a pointer to the super-journal.
+use crate::{slc, Error};
+
+pub const JOURNAL_MAGIC: [u8; 8] = [0xd9, 0xd5, 0x05, 0xf9, 0x20, 0xa1, 0x63, 0xd7];
+pub const JOURNAL_HEADER_SIZE: usize = 28;
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct JournalHeader {
+    /// Header string: 0xd9, 0xd5, 0x05, 0xf9, 0x20, 0xa1, 0x63, 0xd7
+    /// offset: 0, size: 8
+    pub magic: [u8; 8],
+    /// The "Page Count" - number of pages in the next segment of the journal,
+    /// or -1 to mean all content to the end of the file.
+    /// offset: 8, size: 4
+    pub page_count: u32,
+    /// A random nonce for the checksum
+    /// offset: 12, size: 4
+    pub nonce: u32,
+    /// Initial size of the database in pages
+    /// offset: 16, size: 4
+    pub initial_db_size: u32,
+    /// Size of a disk sector assumed by the process that wrote this journal.
+    /// offset: 20, size: 4
+    pub sector_size: u32,
+    /// Size of pages in this journal.
+    /// offset: 24, size: 4
+    pub page_size: u32,
+}
+
+impl TryFrom<&[u8]> for JournalHeader {
+    type Error = Error;
+
+    fn try_from(buf: &[u8]) -> Result<Self, Self::Error> {
+        if buf.len() < JOURNAL_HEADER_SIZE {
+            return Err(Error::Malformed(format!(
+                "Incomplete journal header, expected {} bytes, got: {}",
+                JOURNAL_HEADER_SIZE,
+                buf.len()
+            )));
+        }
+        let magic: [u8; 8] = slc!(buf, 0, 8).try_into()?;
+        if magic != JOURNAL_MAGIC {
+            return Err(Error::Malformed(
+                "Journal header magic doesn't match.".into(),
+            ));
+        }
+        Ok(Self {
+            magic,
+            page_count: slc!(buf, 8, 4, u32),
+            nonce: slc!(buf, 12, 4, u32),
+            initial_db_size: slc!(buf, 16, 4, u32),
+            sector_size: slc!(buf, 20, 4, u32),
+            page_size: slc!(buf, 24, 4, u32),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct JournalRecord {
+    /// Offset of the record within the journal file.
+    pub offset: usize,
+    /// The page number in the database file
+    /// offset: 0, size: 4
+    pub page_num: u32,
+    /// Original content of the page prior to the start of the transaction
+    /// offset: 4, size: N
+    pub data: Vec<u8>,
+    /// Checksum
+    /// offset: N+4, size: 4
+    pub checksum: u32,
+    /// Whether checksum matches the one computed from data and segment nonce.
+    pub valid: bool,
+}
+
+impl JournalRecord {
+    /// The checksum is an unsigned 32-bit integer computed as the nonce plus
+    /// every 200th byte of the page data, starting with the byte at offset
+    /// page_size - 200 and moving towards the beginning of the page.
+    pub fn checksum(nonce: u32, data: &[u8]) -> u32 {
+        let mut checksum = nonce;
+        let mut i = data.len() as isize - 200;
+        while i > 0 {
+            checksum = checksum.wrapping_add(data[i as usize] as u32);
+            i -= 200;
+        }
+        checksum
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct JournalSegment {
+    /// Offset of the segment header within the journal file.
+    pub offset: usize,
+    pub header: JournalHeader,
+    pub records: Vec<JournalRecord>,
+}
+
+/// Pointer to the super-journal at the very end of the journal file.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SuperJournalPointer {
+    /// Offset of the pointer within the journal file.
+    pub offset: usize,
+    /// Number of the lock-byte page, which is never journaled, marks the pointer.
+    /// offset: 0, size: 4
+    pub page_num: u32,
+    /// Name of the super-journal file.
+    /// offset: 4, size: N
+    pub name: String,
+    /// Length of the name.
+    /// offset: N+4, size: 4
+    pub length: u32,
+    /// Checksum of the name.
+    /// offset: N+8, size: 4
+    pub checksum: u32,
+    /// Whether the checksum matches the name, otherwise the pointer is ignored.
+    pub valid: bool,
+    /// Header string, the same as the one of the journal header.
+    /// offset: N+12, size: 8
+    pub magic: [u8; 8],
+}
+
+impl SuperJournalPointer {
+    /// Size of the pointer without the name.
+    pub const SIZE: usize = 20;
+
+    /// The checksum is the sum of the name bytes, taken as signed chars the way
+    /// SQLite does on the common platforms.
+    pub fn checksum(name: &[u8]) -> u32 {
+        name.iter()
+            .fold(0_u32, |sum, &b| sum.wrapping_add(b as i8 as u32))
+    }
+}
+
+impl TryFrom<&[u8]> for SuperJournalPointer {
+    type Error = Error;
+
+    /// Pointer is read from the end of the journal.
+    fn try_from(buf: &[u8]) -> Result<Self, Self::Error> {
+        let end = buf.len();
+        if end < SuperJournalPointer::SIZE {
+            return Err(Error::Malformed(
+                "Journal is too short for a super-journal pointer.".into(),
+            ));
+        }
+        let magic: [u8; 8] = slc!(buf, end - 8, 8).try_into()?;
+        if magic != JOURNAL_MAGIC {
+            return Err(Error::Malformed(
+                "Journal has no super-journal pointer.".into(),
+            ));
+        }
+        let length = slc!(buf, end - 16, 4, u32);
+        let checksum = slc!(buf, end - 12, 4, u32);
+        let name_len = length as usize;
+        if name_len == 0 || name_len > end - SuperJournalPointer::SIZE {
+            return Err(Error::Malformed(format!(
+                "Invalid super-journal name length {}",
+                length
+            )));
+        }
+        let offset = end - SuperJournalPointer::SIZE - name_len;
+        let name = &buf[offset + 4..offset + 4 + name_len];
+        Ok(Self {
+            offset,
+            page_num: slc!(buf, offset, 4, u32),
+            // Name is NUL-terminated, if it's shorter than its length.
+            name: String::from_utf8_lossy(name.split(|&b| b == 0).next().unwrap_or_default())
+                .into_owned(),
+            length,
+            checksum,
+            valid: checksum == SuperJournalPointer::checksum(name),
+            magic,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Journal {
+    pub segments: Vec<JournalSegment>,
+    /// The journal is a child of a multi-database transaction.
+    pub super_journal: Option<SuperJournalPointer>,
+}
+
+impl Journal {
+    /// All page records across segments.
+    pub fn records(&self) -> impl Iterator<Item = &JournalRecord> {
+        self.segments.iter().flat_map(|s| s.records.iter())
+    }
+
+    /// Journaled original content of the database page, if any.
+    pub fn record_for_page(&self, page_num: usize) -> Option<&JournalRecord> {
+        self.records().find(|r| r.page_num as usize == page_num)
+    }
+}
+
+impl TryFrom<&[u8]> for Journal {
+    type Error = Error;
+
+    fn try_from(buf: &[u8]) -> Result<Self, Self::Error> {
+        // Segments end where the pointer starts.
+        let super_journal = SuperJournalPointer::try_from(buf).ok();
+        let buf = &buf[..super_journal.as_ref().map_or(buf.len(), |p| p.offset)];
+
+        // The first header is mandatory, the rest of the segments are
+        // followed while there is a valid header on the sector boundary.
+        let mut segments = vec![];
+        let mut offset = 0;
+        while let Ok(header) = JournalHeader::try_from(&buf[offset..]) {
+            let sector_size = header.sector_size as usize;
+            let page_size = header.page_size as usize;
+            if sector_size < JOURNAL_HEADER_SIZE || page_size == 0 {
+                return Err(Error::Malformed(format!(
+                    "Invalid journal header: sector size {}, page size {}",
+                    sector_size, page_size
+                )));
+            }
+
+            let record_size = page_size + 8;
+            let mut record_offset = offset + sector_size;
+            let page_count = match header.page_count {
+                u32::MAX => buf.len().saturating_sub(record_offset) / record_size,
+                n => n as usize,
+            };
+
+            let mut records = vec![];
+            for _ in 0..page_count {
+                if record_offset + record_size > buf.len() {
+                    break;
+                }
+                let page_num = slc!(buf, record_offset, 4, u32);
+                let data = buf[record_offset + 4..record_offset + 4 + page_size].to_vec();
+                let checksum = slc!(buf, record_offset + 4 + page_size, 4, u32);
+                let valid = checksum == JournalRecord::checksum(header.nonce, &data);
+                records.push(JournalRecord {
+                    offset: record_offset,
+                    page_num,
+                    data,
+                    checksum,
+                    valid,
+                });
+                record_offset += record_size;
+            }
+            segments.push(JournalSegment {
+                offset,
+                header,
+                records,
+            });
+
+            // Next segment header starts at the next sector boundary.
+            offset = record_offset.div_ceil(sector_size) * sector_size;
+            if offset >= buf.len() {
+                break;
+            }
+        }
+
+        if segments.is_empty() {
+            return Err(Error::Malformed(
+                "Not a rollback journal, header is missing.".into(),
+            ));
+        }
+        Ok(Self {
+            segments,
+            super_journal,
+        })
+    }
+}
+
+/// Super-journal of a multi-database transaction, which lists the child journals.
+/// Once it's deleted the transaction is committed, even if the child journals remain.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SuperJournal {
+    /// Names of the child journals with their offsets in the file.
+    pub children: Vec<(usize, String)>,
+}
+
+impl TryFrom<&[u8]> for SuperJournal {
+    type Error = Error;
+
+    /// The file has no header, it's just the names, each one is NUL-terminated.
+    fn try_from(buf: &[u8]) -> Result<Self, Self::Error> {
+        let mut children = vec![];
+        let mut offset = 0;
+        for name in buf.split(|&b| b == 0) {
+            if !name.is_empty() {
+                children.push((offset, std::str::from_utf8(name)?.to_string()));
+            }
+            offset += name.len() + 1;
+        }
+        if children.is_empty() {
+            return Err(Error::Malformed(
+                "Super-journal lists no child journals.".into(),
+            ));
+        }
+        Ok(Self { children })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_journal() {
+        let (sector_size, page_size, nonce) = (512_u32, 512_u32, 7_u32);
+        let mut buf = JOURNAL_MAGIC.to_vec();
+        for v in [1, nonce, 3, sector_size, page_size] {
+            buf.extend(v.to_be_bytes());
+        }
+        buf.resize(sector_size as usize, 0);
+
+        let data = vec![2_u8; page_size as usize];
+        buf.extend(2_u32.to_be_bytes());
+        buf.extend(&data);
+        // Bytes at offsets 312 and 112 are summed up to the nonce.
+        buf.extend((nonce + 4).to_be_bytes());
+
+        let journal = Journal::try_from(buf.as_slice()).unwrap();
+        assert_eq!(journal.segments.len(), 1);
+        assert_eq!(journal.segments[0].header.initial_db_size, 3);
+        let record = journal.record_for_page(2).unwrap();
+        assert_eq!((record.offset, record.valid), (512, true));
+        assert_eq!(journal.super_journal, None);
+    }
+
+    #[test]
+    fn test_super_journal_pointer() {
+        let mut buf = JOURNAL_MAGIC.to_vec();
+        for v in [0_u32, 7, 3, 512, 512] {
+            buf.extend(v.to_be_bytes());
+        }
+        buf.resize(512, 0);
+
+        let name = b"/tmp/main.db-mj0A1B2C3D";
+        buf.extend(262145_u32.to_be_bytes());
+        buf.extend(name);
+        buf.extend((name.len() as u32).to_be_bytes());
+        buf.extend(SuperJournalPointer::checksum(name).to_be_bytes());
+        buf.extend(JOURNAL_MAGIC);
+
+        let journal = Journal::try_from(buf.as_slice()).unwrap();
+        assert_eq!(journal.segments.len(), 1);
+        let pointer = journal.super_journal.unwrap();
+        assert_eq!((pointer.offset, pointer.page_num), (512, 262145));
+        assert_eq!(pointer.name, "/tmp/main.db-mj0A1B2C3D");
+        assert!(pointer.valid);
+
+        let children = b"main.db-journal\0aux.db-journal\0";
+        let super_journal = SuperJournal::try_from(&children[..]).unwrap();
+        assert_eq!(
+            super_journal.children,
+            vec![(0, "main.db-journal".into()), (16, "aux.db-journal".into())]
+        );
+    }
+}
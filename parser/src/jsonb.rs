@@ -0,0 +1,372 @@
+//! JSONB, the binary JSON of SQLite, https://sqlite.org/jsonb.html
+//!
+//! Every element starts with a header: the low 4 bits of the first byte are the type
+//! of the element, the high 4 bits are the size of the payload, if it's below 12,
+//! otherwise the payload size follows in the next 1, 2, 4 or 8 bytes, big-endian.
+//! Numbers and strings keep their text in the payload, arrays and objects keep
+//! their elements, an object alternates labels and values.
+use crate::Error;
+
+/// Deepest nesting of arrays and objects, the same as SQLite allows.
+pub const JSONB_MAX_DEPTH: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum JsonbType {
+    Null,
+    True,
+    False,
+    /// Integer in the canonical JSON text.
+    Int,
+    /// Integer in JSON5 text, like hexadecimal.
+    Int5,
+    /// Floating point number in the canonical JSON text.
+    Float,
+    /// Floating point number in JSON5 text, like .5 or Infinity.
+    Float5,
+    /// String without escapes.
+    Text,
+    /// String with JSON escapes.
+    TextJ,
+    /// String with JSON5 escapes.
+    Text5,
+    /// String without escapes, which may have characters JSON needs to escape.
+    TextRaw,
+    Array,
+    Object,
+}
+
+impl JsonbType {
+    pub fn new(code: u8) -> Result<Self, Error> {
+        Ok(match code {
+            0 => JsonbType::Null,
+            1 => JsonbType::True,
+            2 => JsonbType::False,
+            3 => JsonbType::Int,
+            4 => JsonbType::Int5,
+            5 => JsonbType::Float,
+            6 => JsonbType::Float5,
+            7 => JsonbType::Text,
+            8 => JsonbType::TextJ,
+            9 => JsonbType::Text5,
+            10 => JsonbType::TextRaw,
+            11 => JsonbType::Array,
+            12 => JsonbType::Object,
+            _ => return Err(malformed(&format!("reserved element type {}", code))),
+        })
+    }
+
+    pub fn is_text(&self) -> bool {
+        matches!(
+            self,
+            JsonbType::Text | JsonbType::TextJ | JsonbType::Text5 | JsonbType::TextRaw
+        )
+    }
+}
+
+/// Header of an element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct JsonbHeader {
+    pub kind: JsonbType,
+    /// Size of the header itself: 1, 2, 3, 5 or 9 bytes.
+    pub size: usize,
+    pub payload_size: usize,
+}
+
+impl JsonbHeader {
+    pub fn new(buf: &[u8]) -> Result<Self, Error> {
+        let first = *buf.first().ok_or_else(|| malformed("element is empty"))?;
+        let kind = JsonbType::new(first & 0x0f)?;
+        let extra = match first >> 4 {
+            n @ 0..=11 => {
+                return Ok(Self {
+                    kind,
+                    size: 1,
+                    payload_size: n as usize,
+                })
+            }
+            12 => 1,
+            13 => 2,
+            14 => 4,
+            _ => 8,
+        };
+        let bytes = buf
+            .get(1..1 + extra)
+            .ok_or_else(|| malformed("payload size is cut"))?;
+        let payload_size = bytes.iter().fold(0_u64, |n, b| n << 8 | *b as u64);
+        Ok(Self {
+            kind,
+            size: 1 + extra,
+            payload_size: usize::try_from(payload_size)
+                .map_err(|_| malformed("payload size is too large"))?,
+        })
+    }
+}
+
+fn malformed(what: &str) -> Error {
+    Error::Malformed(format!("Invalid JSONB: {}.", what))
+}
+
+/// Blob holds exactly one JSONB element, which decodes to valid JSON.
+pub fn is_jsonb(buf: &[u8]) -> bool {
+    !buf.is_empty() && jsonb_to_json(buf, false).is_ok()
+}
+
+/// Text of the JSONB blob as canonical JSON, the way json() of SQLite renders it,
+/// indented by 4 spaces if `pretty`, like json_pretty().
+pub fn jsonb_to_json(buf: &[u8], pretty: bool) -> Result<String, Error> {
+    let mut out = String::new();
+    let size = element(buf, 0, pretty, &mut out)?;
+    if size != buf.len() {
+        return Err(malformed("bytes follow the element"));
+    }
+    Ok(out)
+}
+
+/// Writes the element at the start of the buffer, returns its size.
+fn element(buf: &[u8], depth: usize, pretty: bool, out: &mut String) -> Result<usize, Error> {
+    let header = JsonbHeader::new(buf)?;
+    let end = header
+        .size
+        .checked_add(header.payload_size)
+        .filter(|end| *end <= buf.len())
+        .ok_or_else(|| malformed("payload is cut"))?;
+    let payload = &buf[header.size..end];
+    let text = || std::str::from_utf8(payload).map_err(|_| malformed("text isn't UTF-8"));
+    match header.kind {
+        JsonbType::Null | JsonbType::True | JsonbType::False if !payload.is_empty() => {
+            return Err(malformed("literal has a payload"));
+        }
+        JsonbType::Null => out.push_str("null"),
+        JsonbType::True => out.push_str("true"),
+        JsonbType::False => out.push_str("false"),
+        JsonbType::Int => out.push_str(&int(text()?)?),
+        JsonbType::Int5 => out.push_str(&int5(text()?)?),
+        JsonbType::Float => out.push_str(&float(text()?)?),
+        JsonbType::Float5 => out.push_str(&float5(text()?)?),
+        JsonbType::Text | JsonbType::TextRaw => quote(text()?, out),
+        JsonbType::TextJ => {
+            out.push('"');
+            out.push_str(text()?);
+            out.push('"');
+        }
+        JsonbType::Text5 => text5(text()?, out)?,
+        JsonbType::Array | JsonbType::Object => {
+            if depth >= JSONB_MAX_DEPTH {
+                return Err(malformed("nesting is too deep"));
+            }
+            let object = header.kind == JsonbType::Object;
+            let (open, close) = if object { ('{', '}') } else { ('[', ']') };
+            out.push(open);
+            let mut pos = 0;
+            let mut count = 0;
+            while pos < payload.len() {
+                let label = object && count % 2 == 0;
+                if object && !label {
+                    out.push_str(if pretty { ": " } else { ":" });
+                } else {
+                    if count > 0 {
+                        out.push(',');
+                    }
+                    if pretty {
+                        newline(depth + 1, out);
+                    }
+                }
+                if label && !JsonbHeader::new(&payload[pos..])?.kind.is_text() {
+                    return Err(malformed("object label isn't text"));
+                }
+                pos += element(&payload[pos..], depth + 1, pretty, out)?;
+                count += 1;
+            }
+            if object && count % 2 != 0 {
+                return Err(malformed("object label has no value"));
+            }
+            if pretty && count > 0 {
+                newline(depth, out);
+            }
+            out.push(close);
+        }
+    }
+    Ok(end)
+}
+
+fn newline(depth: usize, out: &mut String) {
+    out.push('\n');
+    out.push_str(&"    ".repeat(depth));
+}
+
+fn int(text: &str) -> Result<String, Error> {
+    let digits = text.strip_prefix('-').unwrap_or(text);
+    match !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) {
+        true => Ok(text.to_string()),
+        false => Err(malformed("integer isn't a number")),
+    }
+}
+
+/// Hexadecimal integer as decimal, the too large ones as 9e999 like SQLite does.
+fn int5(text: &str) -> Result<String, Error> {
+    let (sign, unsigned) = match text.as_bytes().first() {
+        Some(b'-') => ("-", &text[1..]),
+        Some(b'+') => ("", &text[1..]),
+        _ => ("", text),
+    };
+    let hex = unsigned
+        .strip_prefix("0x")
+        .or_else(|| unsigned.strip_prefix("0X"));
+    match hex {
+        Some(hex) if !hex.is_empty() && hex.bytes().all(|b| b.is_ascii_hexdigit()) => {
+            Ok(match u64::from_str_radix(hex, 16) {
+                Ok(value) => format!("{}{}", sign, value),
+                Err(_) => format!("{}9e999", sign),
+            })
+        }
+        // JSON5 allows a leading '+' on decimal integers too.
+        _ if hex.is_none() && !unsigned.is_empty() => int(&format!("{}{}", sign, unsigned)),
+        _ => Err(malformed("integer isn't a number")),
+    }
+}
+
+fn float(text: &str) -> Result<String, Error> {
+    match text.parse::<f64>() {
+        Ok(_) if text.bytes().all(|b| b"0123456789+-.eE".contains(&b)) => Ok(text.to_string()),
+        _ => Err(malformed("float isn't a number")),
+    }
+}
+
+/// JSON5 float as canonical JSON: no leading '+', no bare leading or trailing
+/// dot, Infinity as 9e999 and NaN as null.
+fn float5(text: &str) -> Result<String, Error> {
+    let (sign, unsigned) = match text.as_bytes().first() {
+        Some(b'-') => ("-", &text[1..]),
+        Some(b'+') => ("", &text[1..]),
+        _ => ("", text),
+    };
+    if unsigned == "Infinity" {
+        return Ok(format!("{}9e999", sign));
+    }
+    if unsigned == "NaN" {
+        return Ok("null".to_string());
+    }
+    let mut canonical = sign.to_string();
+    if unsigned.starts_with('.') {
+        canonical.push('0');
+    }
+    match unsigned.find('.') {
+        Some(dot) if !unsigned[dot + 1..].starts_with(|c: char| c.is_ascii_digit()) => {
+            canonical.push_str(&unsigned[..=dot]);
+            canonical.push('0');
+            canonical.push_str(&unsigned[dot + 1..]);
+        }
+        _ => canonical.push_str(unsigned),
+    }
+    float(&canonical)
+}
+
+/// String, which has no escapes, with the characters JSON needs escaped.
+fn quote(text: &str, out: &mut String) {
+    out.push('"');
+    for c in text.chars() {
+        escape(c, out);
+    }
+    out.push('"');
+}
+
+fn escape(c: char, out: &mut String) {
+    match c {
+        '"' => out.push_str("\\\""),
+        '\\' => out.push_str("\\\\"),
+        '\n' => out.push_str("\\n"),
+        '\r' => out.push_str("\\r"),
+        '\t' => out.push_str("\\t"),
+        '\u{8}' => out.push_str("\\b"),
+        '\u{c}' => out.push_str("\\f"),
+        c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+        c => out.push(c),
+    }
+}
+
+/// String with JSON5 escapes, with the ones JSON doesn't know rewritten: \' and
+/// \x, \v, \0 and escaped line breaks, which continue the string.
+fn text5(text: &str, out: &mut String) -> Result<(), Error> {
+    out.push('"');
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            // Unescaped quotes and control characters are valid in JSON5 strings.
+            escape(c, out);
+            continue;
+        }
+        let escaped = chars
+            .next()
+            .ok_or_else(|| malformed("string ends with \\"))?;
+        match escaped {
+            '\'' => out.push('\''),
+            'v' => out.push_str("\\u000b"),
+            '0' => out.push_str("\\u0000"),
+            'x' => {
+                let hex = chars.by_ref().take(2).collect::<String>();
+                let code = u8::from_str_radix(&hex, 16)
+                    .ok()
+                    .filter(|_| hex.len() == 2)
+                    .ok_or_else(|| malformed("\\x escape isn't 2 hex digits"))?;
+                escape(code as char, out);
+            }
+            '\r' => {
+                chars.next_if_eq(&'\n');
+            }
+            '\n' | '\u{2028}' | '\u{2029}' => {}
+            '"' | '\\' | '/' | 'b' | 'f' | 'n' | 'r' | 't' | 'u' => {
+                out.push('\\');
+                out.push(escaped);
+            }
+            c => return Err(malformed(&format!("unknown escape \\{}", c))),
+        }
+    }
+    out.push('"');
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::unhex;
+
+    #[test]
+    fn test_jsonb_to_json() {
+        // Blobs of jsonb() of SQLite 3.51.
+        // {"a":[1,2.5,true,null,"x\ny"],"b":{}}
+        let buf = unhex("CC141761CB0D133135322E35010048785C6E7917620C");
+        assert_eq!(
+            jsonb_to_json(&buf, false).unwrap(),
+            r#"{"a":[1,2.5,true,null,"x\ny"],"b":{}}"#
+        );
+        assert_eq!(
+            jsonb_to_json(&buf, true).unwrap(),
+            "{\n    \"a\": [\n        1,\n        2.5,\n        true,\n        null,\n        \"x\\ny\"\n    ],\n    \"b\": {}\n}"
+        );
+        assert!(is_jsonb(&buf));
+
+        // [0x1F, Infinity, .5, "é", 'q\'x']
+        let buf = unhex("CB164430783146553965393939262E3527C3A949715C2778");
+        assert_eq!(
+            jsonb_to_json(&buf, false).unwrap(),
+            r#"[31,9e999,0.5,"é","q'x"]"#
+        );
+
+        // Payload size in the byte after the header.
+        let buf = unhex("C7146161616161616161616161616161616161616161");
+        assert_eq!(JsonbHeader::new(&buf).unwrap().size, 2);
+        assert_eq!(
+            jsonb_to_json(&buf, false).unwrap(),
+            format!("\"{}\"", "a".repeat(20))
+        );
+
+        // Cut payload, trailing bytes, reserved type and a label, which isn't text.
+        assert!(!is_jsonb(&unhex("CB0D1331")));
+        assert!(!is_jsonb(&unhex("0000")));
+        assert!(!is_jsonb(&unhex("0D")));
+        assert!(!is_jsonb(&unhex("2C1313")));
+        assert!(!is_jsonb(&[]));
+    }
+}
@@ -3,28 +3,92 @@
 
 pub mod btree;
 pub mod cell;
+pub mod dump;
+pub mod encryption;
+pub mod error;
 pub mod freelist;
+pub mod fts5;
+pub mod hash;
 pub mod header;
+pub mod inflate;
+pub mod insertion;
+pub mod interpret;
+pub mod journal;
+pub mod jsonb;
+pub mod lock_byte;
 pub mod overflow;
 pub mod page;
+pub mod payload_math;
+pub mod ptrmap;
 pub mod reader;
 pub mod record;
+pub mod rtree;
+pub mod schema;
+pub mod sqlar;
+#[cfg(feature = "sqlcipher")]
+pub mod sqlcipher;
+pub mod stats;
+pub mod storage;
+#[cfg(test)]
+mod test_util;
 pub mod varint;
+pub mod wal_index;
 
-pub use btree::{BTree, BTreeNode};
+pub use btree::{
+    BTree, BTreeNode, CorruptNode, KeySearch, PayloadChunk, Reassembled, ReassembledValue, Row,
+    SearchStep,
+};
 pub use cell::{
     Cell, CellOverflow, IndexInteriorCell, IndexLeafCell, TableInteriorCell, TableLeafCell,
 };
+pub use dump::{Dump, FreelistTrunk};
+pub use encryption::{entropy, Cipher, Encryption, ENCRYPTED_ENTROPY, SQLITE_MAGIC};
+pub use error::Error;
 pub use freelist::{LeafFreelistPage, TrunkFreelistPage};
+pub use fts5::{
+    fts5_doclist, fts5_docsize, fts5_shadows, Fts5Averages, Fts5Block, Fts5BlockId, Fts5Doc,
+    Fts5Leaf, Fts5Level, Fts5Segment, Fts5Shadow, Fts5Structure, Fts5Table, Fts5Term,
+    FTS5_AVERAGES_ROWID, FTS5_LEAF_HEADER_SIZE, FTS5_STRUCTURE_ROWID,
+};
+pub use hash::{crc32, sha256, PageHash};
 pub use header::{DBHeader, TextEncoding};
-pub use overflow::{OverflowData, OverflowPage, OverflowUnit};
-pub use page::{CellPointer, Page, PageHeader, PageHeaderType, CELL_PTR_SIZE};
+pub use insertion::{Insertion, Placement, MAX_FRAGMENTED};
+pub use interpret::{read_cell, read_record_header, read_u32, read_varint, Interpretation};
+pub use journal::{
+    Journal, JournalHeader, JournalRecord, JournalSegment, SuperJournal, SuperJournalPointer,
+};
+pub use jsonb::{is_jsonb, jsonb_to_json, JsonbHeader, JsonbType, JSONB_MAX_DEPTH};
+pub use lock_byte::{LockBytePage, LOCK_BYTE_OFFSET};
+pub use overflow::{OverflowData, OverflowOwner, OverflowPage, OverflowUnit};
+pub use page::{
+    CellPointer, Fragment, FreeBlock, Page, PageHeader, PageHeaderType, CELL_PTR_SIZE,
+    FREE_BLOCK_HEADER_SIZE,
+};
+pub use payload_math::PayloadSplit;
+pub use ptrmap::{PtrmapEntry, PtrmapPage, PtrmapType, PTRMAP_ENTRY_SIZE};
 pub use reader::{Reader, DB_HEADER_SIZE};
-pub use record::{Record, RecordCode, RecordType, RecordValue};
+pub use record::{LazyRecord, Record, RecordCode, RecordHeader, RecordType, RecordValue};
+pub use rtree::{
+    rtree_shadows, RtreeCell, RtreeCoord, RtreeNode, RtreeShadow, RtreeTable, RTREE_CELL_ID_SIZE,
+    RTREE_COORD_SIZE, RTREE_NODE_HEADER_SIZE, RTREE_ROOT_NODE,
+};
+pub use schema::{
+    column_names, highlight, internal_table, shadow_tables, virtual_table, without_rowid_key,
+    InternalTable, SchemaEntry, ShadowTable, SqlSpan, SCHEMA_COLUMNS,
+};
+pub use sqlar::{SqlarEntry, SQLAR_DATA, SQLAR_TABLE};
+#[cfg(feature = "sqlcipher")]
+pub use sqlcipher::{
+    sqlcipher_decrypt, SqlCipher, SqlCipherHmac, SqlCipherSettings, SQLCIPHER_IV_SIZE,
+    SQLCIPHER_SALT_SIZE,
+};
+pub use stats::PageStats;
+pub use storage::{SeekStorage, Storage};
 pub use varint::Varint;
+pub use wal_index::{WalCheckpointInfo, WalIndex, WalIndexBlock, WalIndexHeader};
 
 pub type StdError = Box<dyn std::error::Error + Sync + Send + 'static>;
-pub type Result<T, E = StdError> = std::result::Result<T, E>;
+pub type Result<T, E = Error> = std::result::Result<T, E>;
 
 #[macro_export]
 macro_rules! slc {
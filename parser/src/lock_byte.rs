@@ -0,0 +1,34 @@
+/// The lock-byte page is the single page of the database file that contains the bytes
+/// at offsets between 1073741824 and 1073742335, inclusive. A database file that is less
+/// than or equal to 1073741824 bytes in size contains no lock-byte page.
+///
+/// The lock-byte page is set aside for use by the operating-system specific VFS
+/// implementation in implementing the database file locking primitives.
+/// SQLite does not use the lock-byte page, it's never read or written by the core.
+use crate::Error;
+
+/// File offset of the first lock byte, 1GB.
+pub const LOCK_BYTE_OFFSET: usize = 0x40000000;
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct LockBytePage {
+    pub unallocated: Vec<u8>,
+}
+
+impl LockBytePage {
+    /// Number of the page, which contains the lock bytes.
+    pub fn page_num(page_size: usize) -> usize {
+        LOCK_BYTE_OFFSET / page_size + 1
+    }
+}
+
+impl TryFrom<&[u8]> for LockBytePage {
+    type Error = Error;
+
+    fn try_from(buf: &[u8]) -> Result<Self, Self::Error> {
+        Ok(Self {
+            unallocated: buf.to_vec(),
+        })
+    }
+}
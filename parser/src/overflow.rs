@@ -7,23 +7,46 @@
 /// the chain.
 ///
 /// The fifth byte through the last usable byte are used to hold overflow content.
-use crate::{slc, RecordValue, StdError, TextEncoding};
+use crate::{slc, Error, RecordCode, RecordValue, TextEncoding};
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct OverflowPage {
     pub overflow_units: Vec<OverflowUnit>,
     pub next_page: u32,
+    /// Record header bytes, which spilled from the b-tree page, they precede the data.
+    pub header: Option<Vec<u8>>,
     pub data: Vec<OverflowData>,
     pub unallocated: Option<Vec<u8>>,
+    /// Reserved space at the end of the page, used by extensions.
+    pub reserved: Vec<u8>,
+    /// B-tree cell, which payload spilled onto the page, known once the chain
+    /// is followed from the cell.
+    pub owner: Option<OverflowOwner>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct OverflowOwner {
+    /// B-tree page of the cell.
+    pub page_num: usize,
+    /// Index of the cell in the cell pointer array and its offset on the page.
+    pub cell: usize,
+    pub cell_offset: usize,
+    /// Index of the first record value with bytes on the page, none if the page
+    /// holds only the rest of the record header.
+    pub column: Option<usize>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct OverflowUnit {
     pub bytes_left: usize,
     pub overflow_type: i64,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct OverflowData {
     pub bytes: Vec<u8>,
     pub value: RecordValue,
@@ -33,20 +56,25 @@ impl OverflowPage {
     pub fn new(
         overflow_units: Vec<OverflowUnit>,
         next_page: u32,
+        header: Option<Vec<u8>>,
         data: Vec<OverflowData>,
         unallocated: Option<Vec<u8>>,
+        reserved: Vec<u8>,
     ) -> Self {
         Self {
             overflow_units,
             next_page,
+            header,
             data,
             unallocated,
+            reserved,
+            owner: None,
         }
     }
 }
 
 impl TryFrom<(TextEncoding, Vec<OverflowUnit>, &[u8])> for OverflowPage {
-    type Error = StdError;
+    type Error = Error;
 
     fn try_from(value: (TextEncoding, Vec<OverflowUnit>, &[u8])) -> Result<Self, Self::Error> {
         let (text_encoding, mut overflow_units, buf) = value;
@@ -57,14 +85,19 @@ impl TryFrom<(TextEncoding, Vec<OverflowUnit>, &[u8])> for OverflowPage {
 
         // Overflow content goes from the fifth byte to the last usable byte
         // of the page. All overflow units follow each other sequentially.
+        let mut header: Option<Vec<u8>> = None;
         let mut data = vec![];
         let mut usable_size = buf.len() - next_page_size;
         while usable_size > 0 && !overflow_units.is_empty() {
             let unit = overflow_units.remove(0);
             let content_size = unit.bytes_left.min(usable_size);
             let bytes = buf[offset..offset + content_size].to_vec();
-            let value = RecordValue::new(unit.overflow_type, text_encoding, &bytes)?;
-            data.push(OverflowData { bytes, value });
+            if unit.overflow_type == RecordCode::HEADER {
+                header.get_or_insert_with(Vec::new).extend(bytes);
+            } else {
+                let value = RecordValue::new(unit.overflow_type, text_encoding, &bytes)?;
+                data.push(OverflowData { bytes, value });
+            }
 
             usable_size -= content_size;
             offset += content_size;
@@ -83,8 +116,11 @@ impl TryFrom<(TextEncoding, Vec<OverflowUnit>, &[u8])> for OverflowPage {
         Ok(Self {
             overflow_units: overflow_units.to_vec(),
             next_page,
+            header,
             data,
             unallocated,
+            reserved: vec![],
+            owner: None,
         })
     }
 }
@@ -6,8 +6,10 @@ use crate::*;
 const PAGE_HEADER_SIZE: usize = 12;
 const PAGE_RIGHT_PTR_SIZE: usize = 4;
 pub const CELL_PTR_SIZE: usize = 2;
+pub const FREE_BLOCK_HEADER_SIZE: usize = 4;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum PageHeaderType {
     InteriorIndex = 2,
     LeafIndex = 10,
@@ -22,7 +24,7 @@ impl PageHeaderType {
 }
 
 impl TryFrom<u8> for PageHeaderType {
-    type Error = StdError;
+    type Error = Error;
 
     fn try_from(byte: u8) -> Result<Self, Self::Error> {
         match byte {
@@ -30,7 +32,7 @@ impl TryFrom<u8> for PageHeaderType {
             5 => Ok(PageHeaderType::InteriorTable),
             10 => Ok(PageHeaderType::LeafIndex),
             13 => Ok(PageHeaderType::LeafTable),
-            _ => Err(format!("Unexpected btree page type: {}", byte))?,
+            _ => Err(Error::UnexpectedPageType(byte)),
         }
     }
 }
@@ -58,6 +60,7 @@ impl PageHeaderType {
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct PageHeader {
     /// B-tree page type
     /// offset: 0, size: 1
@@ -109,7 +112,7 @@ impl PageHeader {
 }
 
 impl TryFrom<&[u8]> for PageHeader {
-    type Error = StdError;
+    type Error = Error;
 
     fn try_from(buf: &[u8]) -> Result<Self, Self::Error> {
         let page_type = PageHeaderType::try_from(slc!(buf, 0, 1, u8))?;
@@ -132,6 +135,7 @@ impl TryFrom<&[u8]> for PageHeader {
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct CellPointer {
     /// Let K be the number of the cells on the btree, then
     /// cell array are K*2 bytes integer to the cell contents.
@@ -146,7 +150,7 @@ impl CellPointer {
 }
 
 impl TryFrom<&[u8]> for CellPointer {
-    type Error = StdError;
+    type Error = Error;
 
     fn try_from(buf: &[u8]) -> Result<Self, Self::Error> {
         let mut array = vec![];
@@ -160,17 +164,108 @@ impl TryFrom<&[u8]> for CellPointer {
     }
 }
 
+/// A freeblock is a structure used to identify unallocated space within
+/// the cell content area of a b-tree page. Freeblocks are organized as a chain
+/// in order of increasing offset.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FreeBlock {
+    /// Offset of the freeblock within the page.
+    pub offset: usize,
+    /// Offset of the next freeblock in the chain or zero if it's the last one.
+    /// offset: 0, size: 2
+    pub next: u16,
+    /// Size of the freeblock in bytes, including the 4-byte header.
+    /// offset: 2, size: 2
+    pub size: u16,
+    /// Leftovers of the previous content.
+    /// offset: 4, size: size - 4
+    pub unallocated: Vec<u8>,
+}
+
+impl FreeBlock {
+    /// Follow freeblock chain, which starts at the offset from the page header.
+    /// Corrupt chain is cut at the first link, which is out of the page bounds, has
+    /// an invalid size or doesn't go past the previous block, so it can't loop either.
+    /// The blocks before it are still there.
+    pub fn chain(buf: &[u8], first: Option<u16>) -> Vec<Self> {
+        let mut blocks: Vec<FreeBlock> = vec![];
+        let mut next = first.map_or(0, |o| o as usize);
+        while next != 0 {
+            if let Some(prev) = blocks.last() {
+                if next < prev.offset + prev.size as usize {
+                    break;
+                }
+            }
+            let Some(header) = buf.get(next..next + FREE_BLOCK_HEADER_SIZE) else {
+                break;
+            };
+            let size = u16::from_be_bytes([header[2], header[3]]);
+            if (size as usize) < FREE_BLOCK_HEADER_SIZE || next + size as usize > buf.len() {
+                break;
+            }
+            let block = FreeBlock {
+                offset: next,
+                next: u16::from_be_bytes([header[0], header[1]]),
+                size,
+                unallocated: buf[next + FREE_BLOCK_HEADER_SIZE..next + size as usize].to_vec(),
+            };
+            next = block.next as usize;
+            blocks.push(block);
+        }
+        blocks
+    }
+}
+
+/// Isolated group of unused bytes within the cell content area, which is too
+/// small to become a freeblock. Its size is counted in the page header only.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Fragment {
+    /// Offset of the fragment within the page.
+    pub offset: usize,
+    pub unallocated: Vec<u8>,
+}
+
+impl Fragment {
+    /// Gaps in between cells and freeblocks of the cell content area, which spans
+    /// from its start offset to the end of the usable page.
+    pub fn find(buf: &[u8], cell_start: usize, mut used: Vec<(usize, usize)>) -> Vec<Self> {
+        used.sort();
+        let mut fragments = vec![];
+        let mut offset = cell_start;
+        for (start, size) in used.into_iter().chain([(buf.len(), 0)]) {
+            if start > offset {
+                fragments.push(Fragment {
+                    offset,
+                    unallocated: buf[offset..start].to_vec(),
+                });
+            }
+            offset = offset.max(start + size);
+        }
+        fragments
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Page {
     pub id: usize,
+    /// Shared by all pages, serialized once as a part of the dump.
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub db_header: Rc<DBHeader>,
     pub page_header: PageHeader,
     pub cell_pointer: CellPointer,
     pub unallocated: Vec<u8>,
     pub cells: Vec<Cell>,
+    pub free_blocks: Vec<FreeBlock>,
+    pub fragments: Vec<Fragment>,
+    /// Reserved space at the end of the page, used by extensions.
+    pub reserved: Vec<u8>,
 }
 
 impl Page {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         id: usize,
         db_header: Rc<DBHeader>,
@@ -178,6 +273,9 @@ impl Page {
         cell_pointer: CellPointer,
         unallocated: Vec<u8>,
         cells: Vec<Cell>,
+        free_blocks: Vec<FreeBlock>,
+        fragments: Vec<Fragment>,
+        reserved: Vec<u8>,
     ) -> Self {
         Self {
             id,
@@ -186,15 +284,23 @@ impl Page {
             cell_pointer,
             unallocated,
             cells,
+            free_blocks,
+            fragments,
+            reserved,
         }
     }
 }
 
 impl TryFrom<(Rc<DBHeader>, usize, &[u8])> for Page {
-    type Error = StdError;
+    type Error = Error;
 
     fn try_from(value: (Rc<DBHeader>, usize, &[u8])) -> Result<Self, Self::Error> {
         let (db_header, page_num, buf) = value;
+        // Cells and freeblocks live within the usable size only.
+        let usable = buf
+            .len()
+            .saturating_sub(db_header.reserved_page_space as usize);
+        let (buf, reserved) = buf.split_at(usable);
 
         // -- Create page header.
         let mut offset = match page_num {
@@ -206,11 +312,28 @@ impl TryFrom<(Rc<DBHeader>, usize, &[u8])> for Page {
 
         // -- Create cell pointer array.
         let ptrs_size = page_header.cell_num as usize * CELL_PTR_SIZE;
+        let cell_start = page_header.cell_start_offset as usize;
+        if offset + ptrs_size > cell_start || cell_start > buf.len() {
+            return Err(Error::Malformed(format!(
+                "Cell pointer array of {} cells overlaps cell content area at {}",
+                page_header.cell_num, cell_start
+            )));
+        }
         let cell_pointer = CellPointer::try_from(&buf[offset..offset + ptrs_size])?;
         offset += ptrs_size;
+        if let Some(ptr) = cell_pointer
+            .array
+            .iter()
+            .find(|ptr| (**ptr as usize) < cell_start || **ptr as usize >= buf.len())
+        {
+            return Err(Error::Malformed(format!(
+                "Cell pointer {} is outside of cell content area",
+                ptr
+            )));
+        }
 
         // -- Make an unallocated space.
-        let unallocated_size = page_header.cell_start_offset as usize - offset;
+        let unallocated_size = cell_start - offset;
         let unallocated = buf[offset..offset + unallocated_size]
             .iter()
             .map(|b| u8::from_be_bytes([*b; 1]))
@@ -227,6 +350,19 @@ impl TryFrom<(Rc<DBHeader>, usize, &[u8])> for Page {
             cells.push(cell)
         }
 
+        // -- Follow freeblocks.
+        let free_blocks = FreeBlock::chain(buf, page_header.free_block_offset);
+
+        // -- Find fragmented bytes left in between.
+        let used = cell_pointer
+            .array
+            .iter()
+            .zip(&cells)
+            .map(|(ptr, cell)| (*ptr as usize, cell.size(&db_header)))
+            .chain(free_blocks.iter().map(|b| (b.offset, b.size as usize)))
+            .collect();
+        let fragments = Fragment::find(buf, cell_start, used);
+
         Ok(Page::new(
             page_num,
             db_header,
@@ -234,6 +370,60 @@ impl TryFrom<(Rc<DBHeader>, usize, &[u8])> for Page {
             cell_pointer,
             unallocated,
             cells,
+            free_blocks,
+            fragments,
+            reserved.to_vec(),
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Page bytes with freeblocks of (offset, next, size).
+    fn page_with(blocks: &[(u16, u16, u16)]) -> Vec<u8> {
+        let mut buf = vec![0; 64];
+        for &(offset, next, size) in blocks {
+            let offset = offset as usize;
+            buf[offset..offset + 2].copy_from_slice(&next.to_be_bytes());
+            buf[offset + 2..offset + 4].copy_from_slice(&size.to_be_bytes());
+        }
+        buf
+    }
+
+    fn offsets(blocks: Vec<FreeBlock>) -> Vec<usize> {
+        blocks.iter().map(|b| b.offset).collect()
+    }
+
+    #[test]
+    fn test_freeblock_chain() {
+        let buf = page_with(&[(16, 32, 8), (32, 48, 4), (48, 0, 16)]);
+        let blocks = FreeBlock::chain(&buf, Some(16));
+        assert_eq!(offsets(blocks.clone()), vec![16, 32, 48]);
+        assert_eq!(blocks[0].unallocated.len(), 4);
+        assert!(FreeBlock::chain(&buf, None).is_empty());
+
+        // Loop back to the first block.
+        let buf = page_with(&[(16, 32, 8), (32, 16, 8)]);
+        assert_eq!(offsets(FreeBlock::chain(&buf, Some(16))), vec![16, 32]);
+        // Block points to itself.
+        let buf = page_with(&[(16, 16, 8)]);
+        assert_eq!(offsets(FreeBlock::chain(&buf, Some(16))), vec![16]);
+        // Next block starts within the previous one.
+        let buf = page_with(&[(16, 20, 8), (20, 0, 8)]);
+        assert_eq!(offsets(FreeBlock::chain(&buf, Some(16))), vec![16]);
+
+        // Next block is past the page end, or its header is cut by it.
+        let buf = page_with(&[(16, 100, 8)]);
+        assert_eq!(offsets(FreeBlock::chain(&buf, Some(16))), vec![16]);
+        let buf = page_with(&[(16, 62, 8)]);
+        assert_eq!(offsets(FreeBlock::chain(&buf, Some(16))), vec![16]);
+        // Block runs past the page end or is smaller than its own header.
+        let buf = page_with(&[(16, 48, 8), (48, 0, 32)]);
+        assert_eq!(offsets(FreeBlock::chain(&buf, Some(16))), vec![16]);
+        let buf = page_with(&[(16, 0, 2)]);
+        assert!(FreeBlock::chain(&buf, Some(16)).is_empty());
+        assert!(FreeBlock::chain(&buf, Some(200)).is_empty());
+    }
+}
@@ -0,0 +1,79 @@
+//! How much of a cell payload is stored on the b-tree page and how much
+//! spills onto overflow pages, the math is given in
+//! https://www.sqlite.org/fileformat2.html#b_tree_pages
+//!
+//! Let:
+//! - u: usable size of a database page, page size less the reserved space,
+//! - p: payload size,
+//! - x: maximum amount of payload that can be stored directly on the page
+//!   without spilling onto the overflow page,
+//! - m: minimum amount of payload that must be stored on the b-tree page
+//!   before spilling is allowed.
+//!
+//! If p <= x, the entire payload is stored on the b-tree page. Otherwise
+//! k = m + ((p - m) % (u - 4)): if k <= x, the first k bytes are stored on the
+//! b-tree page, else the first m bytes are, the rest goes onto overflow pages.
+
+/// Usable size of a database page, u.
+pub fn usable_size(page_size: u64, reserved_space: u8) -> u64 {
+    page_size - reserved_space as u64
+}
+
+/// Maximum payload kept on a table leaf page, x = u - 35.
+pub fn table_max_local(u: u64) -> u64 {
+    u - 35
+}
+
+/// Maximum payload kept on an index page, x = ((u - 12) * 64 / 255) - 23.
+pub fn index_max_local(u: u64) -> u64 {
+    ((u - 12) * 64 / 255) - 23
+}
+
+/// Minimum payload kept on any b-tree page once it spills, m = ((u - 12) * 32 / 255) - 23.
+pub fn min_local(u: u64) -> u64 {
+    ((u - 12) * 32 / 255) - 23
+}
+
+/// Content bytes of an overflow page, the first 4 bytes are the next page number.
+pub fn overflow_page_content(u: u64) -> u64 {
+    u - 4
+}
+
+/// Where the payload of a cell ends up.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PayloadSplit {
+    pub usable: u64,
+    pub max_local: u64,
+    pub min_local: u64,
+    /// k, only computed when the payload spills.
+    pub k: Option<u64>,
+    /// Bytes on the b-tree page and on the overflow pages.
+    pub local: u64,
+    pub overflow: u64,
+    pub overflow_pages: u64,
+}
+
+impl PayloadSplit {
+    /// Split of the payload of size p on a page of usable size u with the
+    /// maximum local payload x, which depends on the page type.
+    pub fn new(u: u64, x: u64, p: u64) -> Self {
+        let m = min_local(u);
+        let k = (p > x).then(|| m + ((p - m) % overflow_page_content(u)));
+        let local = match k {
+            None => p,
+            Some(k) if k <= x => k,
+            Some(_) => m,
+        };
+        let overflow = p - local;
+        Self {
+            usable: u,
+            max_local: x,
+            min_local: m,
+            k,
+            local,
+            overflow,
+            overflow_pages: overflow.div_ceil(overflow_page_content(u)),
+        }
+    }
+}
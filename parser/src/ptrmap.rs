@@ -0,0 +1,113 @@
+/// Pointer map or ptrmap pages are extra pages inserted into the database to make
+/// the operation of auto_vacuum and incremental_vacuum modes more efficient.
+///
+/// Ptrmap pages exist only when the largest root b-tree page number in the database
+/// header is non-zero. The first ptrmap page is page 2, each one holds 5-byte entries
+/// for as many pages following it as fit into its usable space, the next ptrmap page
+/// comes right after the last of them.
+///
+/// The first byte of the entry is the type of the page, the other four are
+/// the big-endian page number of its parent, which is zero for the types without one.
+use crate::{slc, Error};
+
+pub const PTRMAP_ENTRY_SIZE: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum PtrmapType {
+    /// B-tree root page, the parent page number is zero.
+    RootPage = 1,
+    /// Free page, the parent page number is zero.
+    FreePage = 2,
+    /// First page of an overflow chain, the parent is the b-tree page of the cell.
+    Overflow1 = 3,
+    /// Page of an overflow chain after the first, the parent is the previous one.
+    Overflow2 = 4,
+    /// Non-root b-tree page, the parent is the b-tree page pointing to it.
+    Btree = 5,
+}
+
+impl TryFrom<u8> for PtrmapType {
+    type Error = Error;
+
+    fn try_from(byte: u8) -> Result<Self, Self::Error> {
+        match byte {
+            1 => Ok(Self::RootPage),
+            2 => Ok(Self::FreePage),
+            3 => Ok(Self::Overflow1),
+            4 => Ok(Self::Overflow2),
+            5 => Ok(Self::Btree),
+            _ => Err(Error::Malformed(format!(
+                "Unexpected ptrmap page type: {}",
+                byte
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for PtrmapType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::RootPage => write!(f, "B-tree root page"),
+            Self::FreePage => write!(f, "Free page"),
+            Self::Overflow1 => write!(f, "First overflow page"),
+            Self::Overflow2 => write!(f, "Overflow page"),
+            Self::Btree => write!(f, "Non-root b-tree page"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PtrmapEntry {
+    /// Page, which the entry describes.
+    pub page_num: usize,
+    /// offset: 0, size: 1
+    pub ptype: PtrmapType,
+    /// offset: 1, size: 4
+    pub parent: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PtrmapPage {
+    pub entries: Vec<PtrmapEntry>,
+    /// Space after the entries of the last ptrmap page, which has fewer pages to follow.
+    pub unallocated: Vec<u8>,
+    /// Reserved space at the end of the page, used by extensions.
+    pub reserved: Vec<u8>,
+}
+
+impl PtrmapPage {
+    /// Numbers of the ptrmap pages among the pages of the database.
+    pub fn page_nums(usable_size: usize, pages_total: usize) -> Vec<usize> {
+        let entries = usable_size / PTRMAP_ENTRY_SIZE;
+        (2..=pages_total).step_by(entries + 1).collect()
+    }
+}
+
+/// Page number of the ptrmap page, number of the pages in the database and
+/// the usable bytes of the ptrmap page.
+impl TryFrom<(usize, usize, &[u8])> for PtrmapPage {
+    type Error = Error;
+
+    fn try_from(value: (usize, usize, &[u8])) -> Result<Self, Self::Error> {
+        let (page_num, pages_total, buf) = value;
+        // Last ptrmap page might be followed by fewer pages than it has room for.
+        let count = (buf.len() / PTRMAP_ENTRY_SIZE).min(pages_total.saturating_sub(page_num));
+        let mut entries = vec![];
+        for n in 0..count {
+            let offset = n * PTRMAP_ENTRY_SIZE;
+            entries.push(PtrmapEntry {
+                page_num: page_num + n + 1,
+                ptype: PtrmapType::try_from(buf[offset])?,
+                parent: slc!(buf, offset + 1, 4, u32),
+            });
+        }
+        Ok(Self {
+            entries,
+            unallocated: buf[count * PTRMAP_ENTRY_SIZE..].to_vec(),
+            reserved: vec![],
+        })
+    }
+}
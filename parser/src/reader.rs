@@ -5,75 +5,242 @@ pub const DB_HEADER_SIZE: usize = 100;
 
 #[derive(Debug)]
 pub struct Reader {
-    pub bytes: &'static [u8],
+    storage: Box<dyn Storage>,
     pub db_header: Rc<DBHeader>,
 }
 
 impl Reader {
-    pub fn new(bytes: &'static [u8]) -> Result<Self, StdError> {
-        if bytes.len() < DB_HEADER_SIZE {
-            return Err(Self::incomplete(
-                "read",
-                "database header",
-                DB_HEADER_SIZE,
-                bytes.len(),
-            ));
-        }
+    pub fn new(bytes: &'static [u8]) -> Result<Self, Error> {
+        Self::with_storage(bytes)
+    }
 
+    /// Reader over any storage backend, only the database header is read upfront.
+    pub fn with_storage(storage: impl Storage + 'static) -> Result<Self, Error> {
         let mut bheader = [0; DB_HEADER_SIZE];
-        bheader.clone_from_slice(&bytes[..DB_HEADER_SIZE]);
+        let read = storage.read_at(0, &mut bheader)?;
+        if read < DB_HEADER_SIZE {
+            return Err(Error::TruncatedPage {
+                page: 1,
+                expected: DB_HEADER_SIZE,
+                read,
+            });
+        }
+        // Header of an encrypted database is garbage, don't pretend to parse it.
+        if !bheader.starts_with(SQLITE_MAGIC) {
+            if let Some(encryption) = Encryption::detect(&storage) {
+                return Err(Error::Encrypted(encryption));
+            }
+        }
         let db_header = Rc::new(DBHeader::try_from(&bheader)?);
 
-        Ok(Self { bytes, db_header })
+        Ok(Self {
+            storage: Box::new(storage),
+            db_header,
+        })
+    }
+
+    /// Reader over the plain content of the SQLCipher database, all of its pages
+    /// are decrypted upfront.
+    #[cfg(feature = "sqlcipher")]
+    pub fn with_sqlcipher(
+        storage: impl Storage + 'static,
+        passphrase: &str,
+        settings: SqlCipherSettings,
+    ) -> Result<Self, Error> {
+        Self::with_storage(sqlcipher_decrypt(&storage, passphrase, settings)?)
+    }
+
+    /// Size of the database file in bytes.
+    pub fn size(&self) -> usize {
+        self.storage.size()
     }
 
     /// Get parsed Btree Page.
     pub fn get_btree_page(&self, page_num: usize) -> Result<Page> {
+        if self.lock_byte_page() == Some(page_num) {
+            return Err(Error::Malformed(format!(
+                "Page {} is the lock-byte page, not a btree page",
+                page_num
+            )));
+        }
         let buf = self.page_slice(page_num)?;
-        let page = Page::try_from((self.db_header.clone(), page_num, buf.as_slice()))?;
+        let mut page = Page::try_from((self.db_header.clone(), page_num, buf.as_slice()))?;
+        for cell in page.cells.iter_mut() {
+            let (record, overflow) = match cell {
+                Cell::TableLeaf(c) => (&mut c.record, &mut c.overflow),
+                Cell::IndexLeaf(c) => (&mut c.record, &mut c.overflow),
+                Cell::IndexInterior(c) => (&mut c.record, &mut c.overflow),
+                Cell::TableInterior(_) => continue,
+            };
+            if let Some(overflow) = overflow {
+                self.complete_header(record, overflow)?;
+            }
+        }
         Ok(page)
     }
 
+    /// Record header might not fit onto the b-tree page, when there are many
+    /// columns. Its rest is read from the overflow chain, so all the datatypes
+    /// are known and so are the values to follow on the overflow pages.
+    fn complete_header(&self, record: &mut LazyRecord, overflow: &mut CellOverflow) -> Result<()> {
+        let spilled = record.header().spilled;
+        if spilled == 0 {
+            return Ok(());
+        }
+        let mut bytes = vec![];
+        let mut pages = vec![];
+        let mut next_page = overflow.page as usize;
+        while bytes.len() < spilled {
+            if next_page == 0 {
+                return Err(Error::Malformed(
+                    "Overflow chain ends within the record header.".into(),
+                ));
+            }
+            if pages.contains(&next_page) {
+                return Err(Error::Malformed("Overflow chain loops.".into()));
+            }
+            pages.push(next_page);
+            // Content of the overflow page ends where its reserved space starts.
+            let (buf, _) = self.usable_page_slice(next_page)?;
+            next_page = read_u32(&buf)? as usize;
+            bytes.extend_from_slice(&buf[4..]);
+        }
+        bytes.truncate(spilled);
+
+        record.complete_header(&bytes)?;
+        let header = std::iter::once(OverflowUnit {
+            overflow_type: RecordCode::HEADER,
+            bytes_left: spilled,
+        });
+        let values = record.header().datatypes.iter().map(|d| OverflowUnit {
+            overflow_type: d.value,
+            bytes_left: RecordCode::size(d.value),
+        });
+        overflow.units = header.chain(values).collect();
+        Ok(())
+    }
+
     /// Get parsed Overflow Page.
     pub fn get_overflow_page(
         &self,
         overflow: Vec<OverflowUnit>,
         page_num: usize,
     ) -> Result<OverflowPage> {
-        let buf = self.page_slice(page_num)?;
-        let page =
+        let (buf, reserved) = self.usable_page_slice(page_num)?;
+        let mut page =
             OverflowPage::try_from((self.db_header.text_encoding, overflow, buf.as_slice()))?;
+        page.reserved = reserved;
         Ok(page)
     }
 
     /// Get parsed Trunk Freelist Page.
     pub fn get_trunk_freelist_page(&self, page_num: usize) -> Result<TrunkFreelistPage> {
-        let buf = self.page_slice(page_num)?;
-        let page = TrunkFreelistPage::try_from(buf.as_slice())?;
+        let (buf, reserved) = self.usable_page_slice(page_num)?;
+        let mut page = TrunkFreelistPage::try_from(buf.as_slice())?;
+        page.reserved = reserved;
         Ok(page)
     }
 
     /// Get Leaf Freelist Page.
     pub fn get_leaf_freelist_page(&self, page_num: usize) -> Result<LeafFreelistPage> {
+        let (buf, reserved) = self.usable_page_slice(page_num)?;
+        let mut page = LeafFreelistPage::try_from(buf.as_slice())?;
+        page.reserved = reserved;
+        Ok(page)
+    }
+
+    /// Get Lock-byte Page.
+    pub fn get_lock_byte_page(&self, page_num: usize) -> Result<LockBytePage> {
         let buf = self.page_slice(page_num)?;
-        let page = LeafFreelistPage::try_from(buf.as_slice())?;
+        let page = LockBytePage::try_from(buf.as_slice())?;
+        Ok(page)
+    }
+
+    /// Get parsed Pointer Map Page.
+    pub fn get_ptrmap_page(&self, page_num: usize) -> Result<PtrmapPage> {
+        let (buf, reserved) = self.usable_page_slice(page_num)?;
+        let mut page = PtrmapPage::try_from((page_num, self.pages_total(), buf.as_slice()))?;
+        page.reserved = reserved;
         Ok(page)
     }
 
+    /// Get raw content of the page, whatever it holds.
+    pub fn get_raw_page(&self, page_num: usize) -> Result<Vec<u8>> {
+        self.page_slice(page_num)
+    }
+
+    /// Checksum or digest of the raw content of the page as hex, to compare it with
+    /// the same page of another file.
+    pub fn page_hash(&self, page_num: usize, hash: PageHash) -> Result<String> {
+        Ok(hash.hex(&self.page_slice(page_num)?))
+    }
+
+    /// Whatever is left of the page in the file, empty if it's beyond the end.
+    /// Unlike the other getters, doesn't fail on truncated files.
+    pub fn get_partial_page(&self, page_num: usize) -> Vec<u8> {
+        if page_num == 0 {
+            return vec![];
+        }
+        let page_size = self.db_header.page_size as usize;
+        // Page number comes from a corrupt pointer, it might be arbitrary large.
+        let start = (page_num - 1).saturating_mul(page_size);
+        let mut buf = vec![0; page_size];
+        let read = self.storage.read_at(start, &mut buf).unwrap_or(0);
+        buf.truncate(read);
+        buf
+    }
+
+    /// Number of the lock-byte page, only databases larger than 1GB have one.
+    pub fn lock_byte_page(&self) -> Option<usize> {
+        let page_num = LockBytePage::page_num(self.db_header.page_size as usize);
+        (page_num <= self.pages_total()).then_some(page_num)
+    }
+
+    /// Numbers of the pointer map pages, only auto-vacuum databases have them.
+    pub fn ptrmap_pages(&self) -> Vec<usize> {
+        if self.db_header.largest_root == 0 {
+            return vec![];
+        }
+        let usable =
+            payload_math::usable_size(self.db_header.page_size, self.db_header.reserved_page_space);
+        PtrmapPage::page_nums(usable as usize, self.pages_total())
+    }
+
     /// Create btrees.
-    pub fn get_btrees(&self) -> Result<Vec<BTree>, StdError> {
+    pub fn get_btrees(&self) -> Result<Vec<BTree>, Error> {
+        let mut trees = vec![self.get_schema_btree()?];
+        for cell in self.get_schema_cells() {
+            // Views and triggers have no b-tree.
+            let values = cell
+                .payload()
+                .map(|p| p.values.as_slice())
+                .unwrap_or_default();
+            if BTree::root_page(values).is_none() && cell.overflow.is_none() {
+                continue;
+            }
+            trees.push(BTree::new(&cell, self)?);
+        }
+        Ok(trees)
+    }
+
+    /// Create schema btree.
+    pub fn get_schema_btree(&self) -> Result<BTree, Error> {
         // Schema page is always a table b-tree and always has a root page of 1.
-        let mut cells = vec![];
-        let _ = self.collect_cells(1, &mut cells);
-        let mut trees = vec![BTree {
+        Ok(BTree {
             ttype: "table".to_string(),
             name: "master schema".to_string(),
+            tbl_name: "master schema".to_string(),
             root: BTreeNode::new(1, self)?,
-        }];
-        for cell in cells {
-            trees.push(BTree::new(&cell, self)?);
-        }
-        Ok(trees)
+            columns: SCHEMA_COLUMNS.iter().map(|c| c.to_string()).collect(),
+            key_columns: None,
+        })
+    }
+
+    /// Get schema table leaf cells, each of them describes one more btree.
+    pub fn get_schema_cells(&self) -> Vec<TableLeafCell> {
+        let mut cells = vec![];
+        let _ = self.collect_cells(1, &mut cells, &mut vec![]);
+        cells
     }
 
     /// Get an actual number of total pages per database file.
@@ -89,21 +256,31 @@ impl Reader {
         {
             self.db_header.db_size as usize
         } else {
-            self.bytes.len() / self.db_header.page_size as usize
+            self.size() / self.db_header.page_size as usize
         }
     }
 
+    /// Corrupt child pointer might lead to a page, which was already visited, the
+    /// walk would never end, so such a schema is broken.
     fn collect_cells(
         &self,
         page_num: usize,
         cells: &mut Vec<TableLeafCell>,
-    ) -> Result<(), StdError> {
+        visited: &mut Vec<usize>,
+    ) -> Result<(), Error> {
+        if visited.contains(&page_num) {
+            return Err(Error::Malformed(format!(
+                "Page {} is visited twice in the schema b-tree.",
+                page_num
+            )));
+        }
+        visited.push(page_num);
         let page = self.get_btree_page(page_num)?;
         for outer_cell in page.cells.iter() {
             match outer_cell {
                 Cell::TableInterior(cell) => {
                     // No overflow, but we need to follow references to the leaves.
-                    self.collect_cells(cell.left_page_number as usize, cells)?;
+                    self.collect_cells(cell.left_page_number as usize, cells, visited)?;
                 }
                 Cell::TableLeaf(cell) => {
                     cells.push(cell.clone());
@@ -114,30 +291,54 @@ impl Reader {
         if page.page_header.page_type.is_interior() {
             // Don't forget the right-most pointer, which is in the page header.
             // If it's interior page, then page_num is Some by design.
-            self.collect_cells(page.page_header.page_num.unwrap() as usize, cells)?;
+            self.collect_cells(page.page_header.page_num.unwrap() as usize, cells, visited)?;
         }
         Ok(())
     }
 
-    fn page_slice(&self, page_num: usize) -> Result<Vec<u8>, StdError> {
+    fn page_slice(&self, page_num: usize) -> Result<Vec<u8>, Error> {
         self.validate_page_bounds(page_num)?;
         let page_offset = self.page_offset(page_num);
         let page_size = self.db_header.page_size as usize;
         let mut b_page = vec![0; page_size];
-        b_page.clone_from_slice(&self.bytes[page_offset..page_offset + page_size]);
+        let read = self.storage.read_at(page_offset, &mut b_page)?;
+        if read < page_size {
+            return Err(Error::TruncatedPage {
+                page: page_num,
+                expected: page_size,
+                read,
+            });
+        }
         Ok(b_page)
     }
 
+    /// Page bytes split into the usable ones and the reserved space at the end.
+    fn usable_page_slice(&self, page_num: usize) -> Result<(Vec<u8>, Vec<u8>), Error> {
+        let mut usable = self.page_slice(page_num)?;
+        let size = usable
+            .len()
+            .saturating_sub(self.db_header.reserved_page_space as usize);
+        let reserved = usable.split_off(size);
+        Ok((usable, reserved))
+    }
+
     fn validate_page_bounds(&self, page_num: usize) -> Result<()> {
         let pages_total = self.pages_total();
         // SQLite pages are started from 1
         if page_num > pages_total || page_num == 0 {
-            return Err(format!("Out of bounds page access: {}/{}", page_num, pages_total).into());
+            return Err(Error::OutOfBounds {
+                page: page_num,
+                total: pages_total,
+            });
         }
 
         let page_end = self.page_offset(page_num) + self.db_header.page_size as usize;
-        if self.bytes.len() < page_end {
-            return Err(Self::incomplete("read", "page", page_end, self.bytes.len()));
+        if self.size() < page_end {
+            return Err(Error::TruncatedPage {
+                page: page_num,
+                expected: page_end,
+                read: self.size(),
+            });
         }
         Ok(())
     }
@@ -147,12 +348,107 @@ impl Reader {
         //((page_num - 1) * self.db_header.page_size as usize).max(DB_HEADER_SIZE)
         (page_num - 1) * self.db_header.page_size as usize
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{corrupt, database, reserved_database};
+
+    #[test]
+    fn test_spilled_record_header() {
+        // Record header of 1502 bytes, every value is the constant 1 of type 9, spills
+        // onto overflow pages with 32 reserved bytes each.
+        let columns = (0..1500).map(|i| format!("c{}", i)).collect::<Vec<_>>();
+        let values = vec!["1"; 1500];
+        let bytes = reserved_database(
+            "spilled-header",
+            32,
+            &format!(
+                "PRAGMA page_size=512; CREATE TABLE t({}); INSERT INTO t VALUES({});",
+                columns.join(", "),
+                values.join(", ")
+            ),
+        );
+        let reader = Reader::new(bytes).unwrap();
+        let page = reader.get_btree_page(2).unwrap();
+        let Cell::TableLeaf(cell) = &page.cells[0] else {
+            panic!("Table leaf cell is expected");
+        };
+        let header = cell.record.header();
+        assert_eq!(header.size.value, 1502);
+        assert_eq!(header.datatypes.len(), 1500);
+        assert!(header.datatypes.iter().all(|d| d.value == 9));
+        let units = &cell.overflow.as_ref().unwrap().units;
+        assert_eq!(units[0].overflow_type, RecordCode::HEADER);
+    }
+
+    #[test]
+    fn test_schema_loop() {
+        let bytes = database("schema-loop", "CREATE TABLE t(v);");
+        let reader = Reader::new(bytes).unwrap();
+        assert_eq!(reader.get_schema_cells().len(), 1);
+
+        // Schema root turns into an interior page without cells, whose right-most
+        // pointer leads back to the root.
+        let mut patch = bytes[100..112].to_vec();
+        patch[0] = 5;
+        patch[3..5].copy_from_slice(&[0, 0]);
+        patch[8..].copy_from_slice(&1u32.to_be_bytes());
+        let reader = corrupt(bytes, 100, &patch);
+        let mut cells = vec![];
+        let result = reader.collect_cells(1, &mut cells, &mut vec![]);
+        assert!(matches!(result, Err(Error::Malformed(_))));
+        assert!(reader.get_schema_cells().is_empty());
+        assert_eq!(reader.get_btrees().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_64k_pages() {
+        // Page 2 is an empty table, page 3 has a row of 100004 bytes of payload,
+        // which spills onto exactly one overflow page 4.
+        let bytes = database(
+            "64k-pages",
+            "PRAGMA page_size=65536;
+            CREATE TABLE e(v);
+            CREATE TABLE t(v);
+            INSERT INTO t VALUES(zeroblob(100000));",
+        );
+        let reader = Reader::new(bytes).unwrap();
+        assert_eq!(reader.db_header.page_size, 65536);
+        assert_eq!(reader.pages_total(), 4);
+        assert_eq!(reader.size(), 4 * 65536);
+        assert_eq!(reader.page_offset(4), 3 * 65536);
+        assert_eq!(reader.get_raw_page(3).unwrap(), bytes[2 * 65536..3 * 65536]);
+
+        // Cell content area of the page without cells starts at 0, which is 65536.
+        let empty = reader.get_btree_page(2).unwrap();
+        assert_eq!(bytes[65536 + 5..65536 + 7], [0, 0]);
+        assert_eq!(empty.page_header.cell_start_offset, 65536);
+        assert!(empty.cells.is_empty());
+
+        let usable = payload_math::usable_size(65536, 0);
+        let split =
+            payload_math::PayloadSplit::new(usable, payload_math::table_max_local(usable), 100004);
+        assert_eq!((split.max_local, split.min_local), (65501, 8199));
+        assert_eq!(
+            (split.local, split.overflow, split.overflow_pages),
+            (34472, 65532, 1)
+        );
 
-    fn incomplete(op: &str, what: &str, expected: usize, got: usize) -> StdError {
-        format!(
-            "Incomplete {} of {}, expected to read {} bytes, got: {}",
-            what, op, expected, got
-        )
-        .into()
+        let page = reader.get_btree_page(3).unwrap();
+        let cell_offset = page.cell_pointer.array[0] as usize;
+        let reassembled = Reassembled::new(3, cell_offset, &reader).unwrap();
+        let chunks = reassembled
+            .chunks
+            .iter()
+            .map(|c| (c.page_num, c.size))
+            .collect::<Vec<_>>();
+        assert_eq!(chunks, [(3, 34472), (4, 65532)]);
+        let rows = BTreeNode::new(3, &reader).unwrap().rows(&reader).unwrap();
+        assert_eq!(
+            rows[0].values[0].value,
+            RecordType::Blob(Some(vec![0; 100000]))
+        );
     }
 }
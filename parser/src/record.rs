@@ -4,23 +4,32 @@
 /// The record format specifies the number of columns, the datatype of each column, and
 /// the content of each column.
 /// A record contains a header and a body, in that order.
-use crate::{StdError, TextEncoding, Varint};
+use std::cell::OnceCell;
+
+use crate::{Error, TextEncoding, Varint};
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Record {
     pub header: RecordHeader,
     pub values: Vec<RecordValue>,
 }
 
 impl TryFrom<(TextEncoding, &[u8])> for Record {
-    type Error = StdError;
+    type Error = Error;
 
     fn try_from(value: (TextEncoding, &[u8])) -> Result<Self, Self::Error> {
         let (text_encoding, buf) = value;
 
-        // Record header usually accessible without consulting an overflow page.
-        // TODO: an example, which will cover for header spillover.
+        // Record header usually accessible without consulting an overflow page,
+        // otherwise values are all on the overflow pages, see Reader::complete_header.
         let header = RecordHeader::try_from(buf)?;
+        if header.spilled > 0 {
+            return Ok(Self {
+                header,
+                values: vec![],
+            });
+        }
 
         let mut values = vec![];
         let mut offset = header.size.value as usize;
@@ -45,19 +54,139 @@ impl TryFrom<(TextEncoding, &[u8])> for Record {
     }
 }
 
+/// Record of the cell payload, which values are decoded only once they're asked for.
+/// Header is parsed upfront: it tells the size of every value, so the overflow of
+/// the cell is known without decoding any of them. Pages with hundreds of cells are
+/// parsed quickly this way, the decoded record is kept for the next access.
+#[derive(Debug, Clone)]
+pub struct LazyRecord {
+    text_encoding: TextEncoding,
+    /// Payload bytes on the b-tree page.
+    bytes: Vec<u8>,
+    header: RecordHeader,
+    record: OnceCell<Result<Record, Error>>,
+}
+
+impl LazyRecord {
+    pub fn new(text_encoding: TextEncoding, buf: &[u8]) -> Result<Self, Error> {
+        let header = RecordHeader::try_from(buf)?;
+        header.validate()?;
+        Ok(Self {
+            text_encoding,
+            bytes: buf.to_vec(),
+            header,
+            record: OnceCell::new(),
+        })
+    }
+
+    pub fn header(&self) -> &RecordHeader {
+        &self.header
+    }
+
+    /// Payload bytes on the b-tree page.
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Decoded record, values are decoded on the first call.
+    pub fn get(&self) -> Result<&Record, Error> {
+        self.record
+            .get_or_init(|| match self.header.spilled {
+                // Values are all on the overflow pages, see Reader::complete_header.
+                0 => Record::try_from((self.text_encoding, self.bytes.as_slice())),
+                _ => Ok(Record {
+                    header: self.header.clone(),
+                    values: vec![],
+                }),
+            })
+            .as_ref()
+            .map_err(Clone::clone)
+    }
+
+    /// Complete the header with its bytes spilled onto the overflow page.
+    pub fn complete_header(&mut self, spilled_bytes: &[u8]) -> Result<(), Error> {
+        let header = self.header.complete(spilled_bytes)?;
+        header.validate()?;
+        self.header = header;
+        self.record = OnceCell::new();
+        Ok(())
+    }
+}
+
+/// Records are the same if their bytes are, decoded or not.
+impl PartialEq for LazyRecord {
+    fn eq(&self, other: &Self) -> bool {
+        self.text_encoding == other.text_encoding
+            && self.bytes == other.bytes
+            && self.header == other.header
+    }
+}
+
+/// Serialized as the decoded record, so the dump doesn't depend on what was accessed.
+#[cfg(feature = "serde")]
+impl serde::Serialize for LazyRecord {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.get()
+            .map_err(serde::ser::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct RecordHeader {
     pub size: Varint,
     pub datatypes: Vec<Varint>,
+    /// Number of header bytes, which didn't fit onto the b-tree page and
+    /// continue on the overflow page.
+    pub spilled: usize,
+}
+
+impl RecordHeader {
+    /// Raw bytes of the header, as far as they are known.
+    pub fn bytes(&self) -> Vec<u8> {
+        let datatypes = self.datatypes.iter().flat_map(|d| d.bytes.iter());
+        self.size.bytes.iter().chain(datatypes).copied().collect()
+    }
+
+    /// Serial types, which aren't defined by the format, are rejected before
+    /// any value is decoded.
+    fn validate(&self) -> Result<(), Error> {
+        match self.datatypes.iter().find(|d| d.value < 0) {
+            Some(d) => Err(Error::BadRecordCode(d.value)),
+            None => Ok(()),
+        }
+    }
+
+    /// Header bytes from the b-tree page are followed by the spilled ones,
+    /// so the datatypes past the page, if any, are parsed in full.
+    pub fn complete(&self, spilled_bytes: &[u8]) -> Result<Self, Error> {
+        let mut bytes = self.bytes();
+        // Trailing datatype might be cut by the page end.
+        bytes.truncate(self.size.value as usize - self.spilled);
+        bytes.extend_from_slice(spilled_bytes);
+        Ok(Self {
+            spilled: self.spilled,
+            ..Self::try_from(bytes.as_slice())?
+        })
+    }
 }
 
 impl TryFrom<&[u8]> for RecordHeader {
-    type Error = StdError;
+    type Error = Error;
 
     fn try_from(buf: &[u8]) -> Result<Self, Self::Error> {
         let size = Varint::new(buf);
-        let datatype_size = size.value as usize - size.bytes.len() + 1;
-        let datatype_buf = &buf[size.bytes.len()..datatype_size];
+        let header_size = size.value as usize;
+        if header_size < size.bytes.len() {
+            return Err(Error::BadVarint {
+                value: size.value,
+                what: "Record header size",
+            });
+        }
+        // Datatypes past the end of the buffer are on the overflow page.
+        let end = header_size.min(buf.len());
+        let datatype_buf = &buf[size.bytes.len()..end];
 
         let mut offset = 0;
         let mut datatypes = vec![];
@@ -66,11 +195,16 @@ impl TryFrom<&[u8]> for RecordHeader {
             offset += datatype.bytes.len();
             datatypes.push(datatype);
         }
-        Ok(Self { size, datatypes })
+        Ok(Self {
+            size,
+            datatypes,
+            spilled: header_size - end,
+        })
     }
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum RecordType {
     Null,
     I8(i8),
@@ -89,12 +223,54 @@ pub enum RecordType {
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct RecordCode;
 
 impl RecordCode {
+    /// Pseudo serial type of the record header bytes spilled onto the overflow page.
+    pub const HEADER: i64 = -1;
+
+    /// What the serial type stands for, None for the invalid negative ones.
+    pub fn describe(code: i64) -> Option<String> {
+        let text = match code {
+            0 => "NULL",
+            1 => "8-bit twos-complement integer",
+            2 => "Big-endian 16-bit twos-complement integer",
+            3 => "Big-endian 24-bit twos-complement integer",
+            4 => "Big-endian 32-bit twos-complement integer",
+            5 => "Big-endian 48-bit twos-complement integer",
+            6 => "Big-endian 64-bit twos-complement integer",
+            7 => "Big-endian IEEE 754-2008 64-bit floating point number",
+            8 => "Integer 0, only in schema format 4 and higher",
+            9 => "Integer 1, only in schema format 4 and higher",
+            10 | 11 => "Reserved for internal use, never in a well-formed database",
+            n if n >= 12 && n % 2 == 0 => {
+                return Some(format!("BLOB of (N-12)/2 = {} byte(s)", Self::size(n)))
+            }
+            n if n >= 13 => {
+                return Some(format!(
+                    "Text of (N-13)/2 = {} byte(s) in the database encoding",
+                    Self::size(n)
+                ))
+            }
+            _ => return None,
+        };
+        Some(text.to_string())
+    }
+
+    /// Serial type of a BLOB of the size in bytes.
+    pub fn blob(size: usize) -> i64 {
+        size as i64 * 2 + 12
+    }
+
+    /// Serial type of a text of the size in bytes.
+    pub fn text(size: usize) -> i64 {
+        size as i64 * 2 + 13
+    }
+
     pub fn size(code: i64) -> usize {
         match code {
-            0 | 8 | 9 | 12 | 13 => 0,
+            0 | 8..=13 => 0,
             1 => 1,
             2 => 2,
             3 => 3,
@@ -104,83 +280,34 @@ impl RecordCode {
             7 => 8,
             n if n >= 12 && n % 2 == 0 => ((n - 12) / 2) as usize,
             n if n >= 13 && n % 2 != 0 => ((n - 13) / 2) as usize,
-            _ => unreachable!("Record Value of unknown serial type."),
+            // Negative serial types are invalid, RecordValue::new rejects them.
+            _ => 0,
         }
     }
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct RecordValue {
     pub value: RecordType,
     pub bytes: Option<Vec<u8>>,
 }
 
 impl RecordValue {
-    pub fn new(code: i64, text_encoding: TextEncoding, buf: &[u8]) -> Result<Self, StdError> {
+    pub fn new(code: i64, text_encoding: TextEncoding, buf: &[u8]) -> Result<Self, Error> {
         let size = RecordCode::size(code);
         match code {
             0 => Ok(Self {
                 value: RecordType::Null,
                 bytes: None,
             }),
-            1 => {
-                let bytes = &buf[..size];
-                let value = RecordType::I8(i8::from_be_bytes(bytes.try_into()?));
+            1..=7 => {
+                // Number might be split between the b-tree page and the overflow page,
+                // the rest of its bytes are merged in when the overflow is followed.
+                let bytes = &buf[..size.min(buf.len())];
                 Ok(Self {
+                    value: Self::number(code, bytes),
                     bytes: Some(bytes.to_vec()),
-                    value,
-                })
-            }
-            2 => {
-                let bytes = &buf[..size];
-                let value = RecordType::I16(i16::from_be_bytes(bytes.try_into()?));
-                Ok(Self {
-                    bytes: Some(bytes.to_vec()),
-                    value,
-                })
-            }
-            3 => {
-                let mut bytes: [u8; 4] = [0; 4];
-                let bytes_ref = &mut bytes.as_mut_slice()[1..];
-                bytes_ref.copy_from_slice(&buf[..size]);
-                let value = RecordType::I24(i32::from_be_bytes(bytes));
-                Ok(Self {
-                    bytes: Some(buf[..size].to_vec()),
-                    value,
-                })
-            }
-            4 => {
-                let bytes = &buf[..size];
-                let value = RecordType::I32(i32::from_be_bytes(bytes.try_into()?));
-                Ok(Self {
-                    bytes: Some(bytes.to_vec()),
-                    value,
-                })
-            }
-            5 => {
-                let mut bytes: [u8; 8] = [0; 8];
-                let bytes_ref = &mut bytes.as_mut_slice()[2..];
-                bytes_ref.copy_from_slice(&buf[..size]);
-                let value = RecordType::I48(i64::from_be_bytes(bytes));
-                Ok(Self {
-                    bytes: Some(buf[..size].to_vec()),
-                    value,
-                })
-            }
-            6 => {
-                let bytes = &buf[..size];
-                let value = RecordType::I64(i64::from_be_bytes(bytes.try_into()?));
-                Ok(Self {
-                    bytes: Some(bytes.to_vec()),
-                    value,
-                })
-            }
-            7 => {
-                let bytes = &buf[..size];
-                let value = RecordType::F64(f64::from_be_bytes(bytes.try_into()?));
-                Ok(Self {
-                    bytes: Some(bytes.to_vec()),
-                    value,
                 })
             }
             8 => Ok(Self {
@@ -219,17 +346,8 @@ impl RecordValue {
                 let max_size = size.min(buf.len());
                 if max_size > 0 {
                     let bytes = &buf[..max_size].to_vec();
-                    let value = match text_encoding {
-                        TextEncoding::UTF8 => {
-                            RecordType::Text(Some(std::str::from_utf8(bytes)?.to_string()))
-                        }
-                        TextEncoding::UTF16le => {
-                            RecordType::Text(Some(String::from_utf16le(bytes)?))
-                        }
-                        TextEncoding::UTF16be => {
-                            RecordType::Text(Some(String::from_utf16be(bytes)?))
-                        }
-                    };
+                    let text = text_encoding.decode(bytes, max_size < size)?;
+                    let value = RecordType::Text(Some(text));
                     Ok(Self {
                         bytes: Some(bytes.clone()),
                         value,
@@ -239,20 +357,75 @@ impl RecordValue {
                     Ok(Self { bytes: None, value })
                 }
             }
-            _ => unreachable!("Record Value of unknown serial type."),
+            _ => Err(Error::BadRecordCode(code)),
+        }
+    }
+
+    /// Big-endian signed integer or float of the serial type size, missing
+    /// trailing bytes of a split number are taken as zeros.
+    fn number(code: i64, bytes: &[u8]) -> RecordType {
+        let size = RecordCode::size(code);
+        let mut padded = [0; 8];
+        padded[8 - size..8 - size + bytes.len()].copy_from_slice(bytes);
+        // Shift back and forth to extend the sign of 3 and 6 byte integers.
+        let shift = 64 - size as u32 * 8;
+        let value = (i64::from_be_bytes(padded) << shift) >> shift;
+        match code {
+            1 => RecordType::I8(value as i8),
+            2 => RecordType::I16(value as i16),
+            3 => RecordType::I24(value as i32),
+            4 => RecordType::I32(value as i32),
+            5 => RecordType::I48(value),
+            6 => RecordType::I64(value),
+            _ => RecordType::F64(f64::from_be_bytes(padded)),
+        }
+    }
+
+    /// Serial type of a number value.
+    fn number_code(value: &RecordType) -> Option<i64> {
+        match value {
+            RecordType::I8(_) => Some(1),
+            RecordType::I16(_) => Some(2),
+            RecordType::I24(_) => Some(3),
+            RecordType::I32(_) => Some(4),
+            RecordType::I48(_) => Some(5),
+            RecordType::I64(_) => Some(6),
+            RecordType::F64(_) => Some(7),
+            _ => None,
         }
     }
 
     /// Merging RecordValues is helpful to create full payload when spilled over.
-    pub fn merge(self, rhs: RecordValue) -> Option<RecordValue> {
-        // Only Text & Blob types can be meaningfully merged together.
+    pub fn merge(self, rhs: RecordValue, text_encoding: TextEncoding) -> Option<RecordValue> {
+        // Only Text & Blob types and numbers split between pages can be
+        // meaningfully merged together.
         match (&self.value, &rhs.value) {
+            (lval, rval)
+                if Self::number_code(lval).is_some()
+                    && Self::number_code(lval) == Self::number_code(rval) =>
+            {
+                let code = Self::number_code(lval)?;
+                let bytes = [self.bytes?, rhs.bytes?].concat();
+                if bytes.len() > RecordCode::size(code) {
+                    return None;
+                }
+                Some(RecordValue {
+                    value: Self::number(code, &bytes),
+                    bytes: Some(bytes),
+                })
+            }
             (RecordType::Text(lval), RecordType::Text(rval)) => match (lval, rval) {
-                (Some(l), Some(r)) => {
-                    let value = RecordType::Text(Some(format!("{}{}", l, r)));
+                (Some(_), Some(_)) => {
                     // Bytes are guaranteed to be Some if value is Some by design.
-                    let bytes = Some([self.bytes.unwrap(), rhs.bytes.unwrap()].concat());
-                    Some(RecordValue { bytes, value })
+                    let bytes = [self.bytes.unwrap(), rhs.bytes.unwrap()].concat();
+                    // A character might be split between the pages, so the text is
+                    // decoded again from the joined bytes rather than concatenated.
+                    let text = text_encoding.decode(&bytes, true).ok()?;
+                    let value = RecordType::Text(Some(text));
+                    Some(RecordValue {
+                        bytes: Some(bytes),
+                        value,
+                    })
                 }
                 (None, Some(_)) => Some(rhs),
                 (Some(_), None) => Some(self),
@@ -273,3 +446,42 @@ impl RecordValue {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_number() {
+        let numbers = [
+            (
+                3,
+                RecordType::I24(-0x123456),
+                &(-0x123456_i32).to_be_bytes()[1..],
+            ),
+            (
+                5,
+                RecordType::I48(0x123456789abc),
+                &0x123456789abc_i64.to_be_bytes()[2..],
+            ),
+            (
+                7,
+                RecordType::F64(-1.5e300),
+                &(-1.5e300_f64).to_be_bytes()[..],
+            ),
+        ];
+        for (code, value, bytes) in numbers {
+            let whole = RecordValue::new(code, TextEncoding::UTF8, bytes).unwrap();
+            assert_eq!(whole.value, value);
+            // Every split between the b-tree page and the overflow page joins back.
+            for at in 1..bytes.len() {
+                let head = RecordValue::new(code, TextEncoding::UTF8, &bytes[..at]).unwrap();
+                let tail = RecordValue::new(code, TextEncoding::UTF8, &bytes[at..]).unwrap();
+                let merged = head.merge(tail, TextEncoding::UTF8).unwrap();
+                assert_eq!(merged, whole, "{:?} split at {}", value, at);
+            }
+            // Two whole numbers are never one.
+            assert!(whole.clone().merge(whole, TextEncoding::UTF8).is_none());
+        }
+    }
+}
@@ -0,0 +1,253 @@
+//! R*Tree virtual table keeps its spatial index in three shadow tables, named after
+//! the virtual table: `%_node` holds the nodes of the tree, `%_rowid` maps the
+//! entries to their leaf nodes and `%_parent` maps the nodes to their parents.
+//!
+//! Every node is a blob of the same size: 2-byte depth of the tree, only meaningful
+//! in the root node 1, 2-byte number of cells and the cells. A cell is the 8-byte
+//! rowid of an entry on a leaf node, or the number of a child node on an interior
+//! one, followed by the minimum and the maximum of every dimension of its bounding
+//! box: 4-byte floats, or 4-byte integers for rtree_i32. All of it is big-endian.
+
+use std::fmt;
+
+use crate::{virtual_table, Error, InternalTable, SchemaEntry};
+
+/// Depth and the number of cells.
+pub const RTREE_NODE_HEADER_SIZE: usize = 4;
+/// Rowid or child node number, which starts every cell.
+pub const RTREE_CELL_ID_SIZE: usize = 8;
+pub const RTREE_COORD_SIZE: usize = 4;
+/// Number of the root node.
+pub const RTREE_ROOT_NODE: i64 = 1;
+
+/// Shadow tables of an R*Tree table by their suffix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum RtreeTable {
+    Node,
+    Rowid,
+    Parent,
+}
+
+impl RtreeTable {
+    pub const ALL: [RtreeTable; 3] = [RtreeTable::Node, RtreeTable::Rowid, RtreeTable::Parent];
+
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            RtreeTable::Node => "_node",
+            RtreeTable::Rowid => "_rowid",
+            RtreeTable::Parent => "_parent",
+        }
+    }
+
+    /// Meaning of the shadow table and its columns.
+    pub fn internal(&self) -> InternalTable {
+        match self {
+            RtreeTable::Node => InternalTable {
+                desc: "Shadow table of an R*Tree index, it holds the nodes of the tree, one per row, node 1 is the root. Interior nodes hold the bounding boxes of their children, leaf nodes the bounding boxes of the entries with their rowids.",
+                columns: &[
+                    ("nodeno", "Number of the node, 1 for the root."),
+                    ("data", "Content of the node: 2-byte depth of the tree, only kept by the root node, 2-byte number of cells and the cells. A cell is the 8-byte rowid of an entry or the number of a child node, followed by the minimum and the maximum of every dimension as 4-byte floats, or integers for rtree_i32. The rest of the node is zeroed."),
+                ],
+            },
+            RtreeTable::Rowid => InternalTable {
+                desc: "Shadow table of an R*Tree index, it maps the rowid of every entry to the leaf node, which holds it, and keeps the auxiliary columns of the entries.",
+                columns: &[
+                    ("rowid", "Rowid of the entry of the R*Tree table."),
+                    ("nodeno", "Leaf node, which holds the entry."),
+                    ("a*", "Auxiliary column of the R*Tree table, declared with a leading '+': a0 is the first one. It isn't indexed, so it's kept here, not in the nodes."),
+                ],
+            },
+            RtreeTable::Parent => InternalTable {
+                desc: "Shadow table of an R*Tree index, it maps every node but the root to its parent node, so a change of a bounding box is carried up the tree.",
+                columns: &[
+                    ("nodeno", "Number of the node."),
+                    ("parentnode", "Interior node, which holds the cell of this node."),
+                ],
+            },
+        }
+    }
+}
+
+/// Shadow table of an R*Tree table.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RtreeShadow {
+    /// Name of the R*Tree virtual table.
+    pub vtab: String,
+    pub table: RtreeTable,
+    /// Names of the minimum and the maximum of every dimension.
+    pub coordinates: Vec<String>,
+    /// Coordinates are 4-byte integers of rtree_i32, not floats.
+    pub int32: bool,
+}
+
+impl RtreeShadow {
+    pub fn dimensions(&self) -> usize {
+        self.coordinates.len() / 2
+    }
+}
+
+/// Shadow tables of the R*Tree tables of the schema with their names.
+pub fn rtree_shadows(entries: &[SchemaEntry]) -> Vec<(String, RtreeShadow)> {
+    let mut shadows = vec![];
+    for entry in entries {
+        let Some((module, args)) = entry.sql.as_deref().and_then(virtual_table) else {
+            continue;
+        };
+        if module != "rtree" && module != "rtree_i32" {
+            continue;
+        }
+        // The first column is the rowid, auxiliary columns start with '+'.
+        let coordinates = args
+            .iter()
+            .skip(1)
+            .filter(|arg| !arg.starts_with('+'))
+            .filter_map(|arg| arg.split_whitespace().next().map(str::to_string))
+            .collect::<Vec<_>>();
+        for table in RtreeTable::ALL {
+            let name = format!("{}{}", entry.name, table.suffix());
+            let Some(shadow) = entries.iter().find(|e| e.name.eq_ignore_ascii_case(&name)) else {
+                continue;
+            };
+            shadows.push((
+                shadow.name.clone(),
+                RtreeShadow {
+                    vtab: entry.name.clone(),
+                    table,
+                    coordinates: coordinates.clone(),
+                    int32: module == "rtree_i32",
+                },
+            ));
+        }
+    }
+    shadows
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum RtreeCoord {
+    Real(f32),
+    Int(i32),
+}
+
+impl RtreeCoord {
+    pub fn to_be_bytes(&self) -> [u8; RTREE_COORD_SIZE] {
+        match self {
+            Self::Real(v) => v.to_be_bytes(),
+            Self::Int(v) => v.to_be_bytes(),
+        }
+    }
+}
+
+impl fmt::Display for RtreeCoord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Real(v) => write!(f, "{v}"),
+            Self::Int(v) => write!(f, "{v}"),
+        }
+    }
+}
+
+/// Cell of a node: rowid or child node number with its bounding box.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RtreeCell {
+    /// Offset of the cell in the node.
+    pub offset: usize,
+    pub id: i64,
+    /// Minimum and maximum of every dimension.
+    pub coords: Vec<RtreeCoord>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RtreeNode {
+    /// Depth of the tree, 0 if the root is a leaf. Only the root node keeps it.
+    pub depth: u16,
+    pub cells: Vec<RtreeCell>,
+    /// Size of the node blob, the cells are followed by zeros up to it.
+    pub size: usize,
+}
+
+impl RtreeNode {
+    pub fn new(buf: &[u8], dimensions: usize, int32: bool) -> Result<Self, Error> {
+        let header = buf.get(..RTREE_NODE_HEADER_SIZE).ok_or_else(|| {
+            Error::Malformed("R*Tree node is too short for its header.".to_string())
+        })?;
+        let depth = u16::from_be_bytes([header[0], header[1]]);
+        let count = u16::from_be_bytes([header[2], header[3]]) as usize;
+        let cell_size = RTREE_CELL_ID_SIZE + 2 * dimensions * RTREE_COORD_SIZE;
+        if RTREE_NODE_HEADER_SIZE + count * cell_size > buf.len() {
+            return Err(Error::Malformed(format!(
+                "R*Tree node of {} bytes can't hold {} cells of {} dimension(s).",
+                buf.len(),
+                count,
+                dimensions
+            )));
+        }
+        let cells = (0..count)
+            .map(|n| {
+                let offset = RTREE_NODE_HEADER_SIZE + n * cell_size;
+                let cell = &buf[offset..offset + cell_size];
+                let id = i64::from_be_bytes(cell[..RTREE_CELL_ID_SIZE].try_into()?);
+                let coords = cell[RTREE_CELL_ID_SIZE..]
+                    .chunks_exact(RTREE_COORD_SIZE)
+                    .map(|c| {
+                        let bytes = [c[0], c[1], c[2], c[3]];
+                        match int32 {
+                            true => RtreeCoord::Int(i32::from_be_bytes(bytes)),
+                            false => RtreeCoord::Real(f32::from_be_bytes(bytes)),
+                        }
+                    })
+                    .collect();
+                Ok(RtreeCell { offset, id, coords })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(Self {
+            depth,
+            cells,
+            size: buf.len(),
+        })
+    }
+
+    /// Offset of the zeroed space after the last cell.
+    pub fn cells_end(&self) -> usize {
+        self.cells.last().map_or(RTREE_NODE_HEADER_SIZE, |c| {
+            c.offset + RTREE_CELL_ID_SIZE + c.coords.len() * RTREE_COORD_SIZE
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::unhex;
+
+    #[test]
+    fn test_rtree_node() {
+        // Root of rtree(id, minx, maxx, miny, maxy) with the entry (1, 0.5, 1.5, -2, 2).
+        let mut buf = unhex("0000000100000000000000013f0000003fc00000c000000040000000");
+        buf.resize(64, 0);
+        let node = RtreeNode::new(&buf, 2, false).unwrap();
+        assert_eq!(node.depth, 0);
+        assert_eq!(node.cells.len(), 1);
+        assert_eq!(node.cells[0].id, 1);
+        assert_eq!(
+            node.cells[0].coords,
+            [0.5, 1.5, -2.0, 2.0].map(RtreeCoord::Real)
+        );
+        assert_eq!(node.cells_end(), 28);
+
+        // Root of rtree_i32(id, a, b) with the entry (7, -3, 4).
+        let buf = unhex("000000010000000000000007fffffffd00000004");
+        let node = RtreeNode::new(&buf, 1, true).unwrap();
+        assert_eq!(
+            node.cells[0].coords,
+            [RtreeCoord::Int(-3), RtreeCoord::Int(4)]
+        );
+
+        // Header, which counts more cells than the node holds.
+        assert!(RtreeNode::new(&buf, 2, true).is_err());
+    }
+}
@@ -0,0 +1,781 @@
+//! The sqlite_schema table contains the CREATE statement of every table and index,
+//! which is the only place where column names are stored. Records themselves
+//! keep values in the order of columns, but not their names.
+//!
+//! This is a minimal SQL DDL tokenizer, just enough to find column definitions
+//! in CREATE TABLE and indexed columns in CREATE INDEX statements.
+
+use std::ops::Range;
+
+use crate::{fts5_shadows, rtree_shadows, Fts5Shadow, RecordType, RecordValue, RtreeShadow};
+
+/// Columns of the sqlite_schema table itself.
+pub const SCHEMA_COLUMNS: [&str; 5] = ["type", "name", "tbl_name", "rootpage", "sql"];
+
+/// Row of the sqlite_schema table.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SchemaEntry {
+    pub etype: String,
+    pub name: String,
+    pub tbl_name: String,
+    /// Root page of the b-tree, None for views and triggers.
+    pub rootpage: Option<usize>,
+    /// Automatic indexes have no SQL.
+    pub sql: Option<String>,
+}
+
+impl SchemaEntry {
+    /// Entry from the values of a schema record, missing or mistyped values are left empty.
+    pub fn new(values: &[RecordValue]) -> Self {
+        let text = |n: usize| match values.get(n).map(|v| &v.value) {
+            Some(RecordType::Text(Some(v))) => Some(v.to_string()),
+            _ => None,
+        };
+        let rootpage = match values.get(3).map(|v| &v.value) {
+            Some(RecordType::I8(v)) => *v as i64,
+            Some(RecordType::I16(v)) => *v as i64,
+            Some(RecordType::I24(v) | RecordType::I32(v)) => *v as i64,
+            Some(RecordType::I48(v) | RecordType::I64(v)) => *v,
+            Some(RecordType::One(v)) => *v as i64,
+            _ => 0,
+        };
+        Self {
+            etype: text(0).unwrap_or_default(),
+            name: text(1).unwrap_or_default(),
+            tbl_name: text(2).unwrap_or_default(),
+            rootpage: (rootpage > 0).then_some(rootpage as usize),
+            sql: text(4),
+        }
+    }
+}
+
+/// Table or index, which SQLite creates and maintains itself.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InternalTable {
+    pub desc: &'static str,
+    /// Meaning of the columns by their names, a name ending with '*' matches
+    /// the columns it's a prefix of.
+    pub columns: &'static [(&'static str, &'static str)],
+}
+
+impl InternalTable {
+    pub fn column_desc(&self, column: &str) -> Option<&'static str> {
+        let column = column.to_ascii_lowercase();
+        self.columns
+            .iter()
+            .find(|(name, _)| match name.strip_suffix('*') {
+                Some(prefix) => column.starts_with(prefix),
+                None => column == *name,
+            })
+            .map(|(_, desc)| *desc)
+    }
+}
+
+const STAT_TBL: (&str, &str) = ("tbl", "Name of the analyzed table.");
+const STAT_IDX: (&str, &str) = (
+    "idx",
+    "Name of the index the statistics are for, NULL in the row of the table itself.",
+);
+
+/// Internal table or index by its name, None for the ones created by users.
+pub fn internal_table(name: &str) -> Option<InternalTable> {
+    let table = match name.to_ascii_lowercase().as_str() {
+        "sqlite_sequence" => InternalTable {
+            desc: "Internal table, created along with the first table with AUTOINCREMENT. It keeps the largest rowid ever used by every AUTOINCREMENT table, so rowids of deleted rows are never reused.",
+            columns: &[
+                ("name", "Name of the AUTOINCREMENT table."),
+                ("seq", "Largest rowid ever used in the table, the next row gets a bigger one, even if the row with this rowid was deleted."),
+            ],
+        },
+        "sqlite_stat1" => InternalTable {
+            desc: "Internal table, created by ANALYZE. The query planner reads its statistics of tables and indexes to choose between indexes.",
+            columns: &[
+                STAT_TBL,
+                STAT_IDX,
+                ("stat", "Space separated integers: number of rows in the table or the index, followed by the average number of rows, which share the same values of the first one, two and more indexed columns. Keywords like 'unordered' or 'sz=N' might follow."),
+            ],
+        },
+        "sqlite_stat4" => InternalTable {
+            desc: "Internal table, created by ANALYZE in builds with SQLITE_ENABLE_STAT4. It samples index entries and how the rest of the entries are distributed around them, so the query planner can estimate range lookups.",
+            columns: &[
+                STAT_TBL,
+                STAT_IDX,
+                ("neq", "Space separated integers: approximate number of entries equal to the sample in the first one, two and more indexed columns."),
+                ("nlt", "Space separated integers: approximate number of entries less than the sample in the first one, two and more indexed columns."),
+                ("ndlt", "Space separated integers: approximate number of distinct entries less than the sample in the first one, two and more indexed columns."),
+                ("sample", "Index record of the sampled entry, a BLOB in the record format."),
+            ],
+        },
+        n if n.starts_with("sqlite_autoindex_") => InternalTable {
+            desc: "Internal index, created for a UNIQUE or PRIMARY KEY constraint of the table, it has no SQL in sqlite_schema. Its name is sqlite_autoindex_<table>_<N>, N counts the constraints from 1.",
+            columns: &[],
+        },
+        _ => return None,
+    };
+    Some(table)
+}
+
+/// Shadow table, which a virtual table keeps its data in.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum ShadowTable {
+    Fts5(Fts5Shadow),
+    Rtree(RtreeShadow),
+}
+
+impl ShadowTable {
+    /// Name of the virtual table.
+    pub fn vtab(&self) -> &str {
+        match self {
+            Self::Fts5(shadow) => &shadow.vtab,
+            Self::Rtree(shadow) => &shadow.vtab,
+        }
+    }
+
+    pub fn module(&self) -> &'static str {
+        match self {
+            Self::Fts5(_) => "fts5",
+            Self::Rtree(_) => "rtree",
+        }
+    }
+
+    /// Meaning of the shadow table and its columns.
+    pub fn internal(&self) -> InternalTable {
+        match self {
+            Self::Fts5(shadow) => shadow.table.internal(),
+            Self::Rtree(shadow) => shadow.table.internal(),
+        }
+    }
+}
+
+/// Shadow tables of the virtual tables of the schema with their names.
+pub fn shadow_tables(entries: &[SchemaEntry]) -> Vec<(String, ShadowTable)> {
+    let fts5 = fts5_shadows(entries)
+        .into_iter()
+        .map(|(name, shadow)| (name, ShadowTable::Fts5(shadow)));
+    let rtree = rtree_shadows(entries)
+        .into_iter()
+        .map(|(name, shadow)| (name, ShadowTable::Rtree(shadow)));
+    fts5.chain(rtree).collect()
+}
+
+/// Module of the CREATE VIRTUAL TABLE statement in lowercase and its arguments,
+/// tokens of each of them joined by spaces. None for other statements.
+pub fn virtual_table(sql: &str) -> Option<(String, Vec<String>)> {
+    let tokens = tokenize(sql);
+    let head = ["CREATE", "VIRTUAL", "TABLE"];
+    if tokens.len() < head.len() || !tokens.iter().zip(head).all(|(t, k)| t.is_keyword(k)) {
+        return None;
+    }
+    let using = tokens.iter().position(|t| t.is_keyword("USING"))?;
+    let module = tokens.get(using + 1)?.text().to_ascii_lowercase();
+    let args = match tokens.get(using + 2) {
+        Some(Token::Punct('(')) => top_level_groups(&tokens[using + 3..])
+            .0
+            .iter()
+            .map(|group| group.iter().map(Token::text).collect::<Vec<_>>().join(" "))
+            .filter(|arg| !arg.is_empty())
+            .collect(),
+        _ => vec![],
+    };
+    Some((module, args))
+}
+
+/// Kind of a piece of SQL text, see `highlight`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum SqlSpan {
+    Keyword,
+    Identifier,
+    /// Identifier in quotes or brackets.
+    Quoted,
+    Literal,
+    Number,
+    Comment,
+    Punct,
+    Space,
+}
+
+/// Keywords of the statements, which end up in sqlite_schema.
+const KEYWORDS: &[&str] = &[
+    "ABORT",
+    "ACTION",
+    "AFTER",
+    "ALWAYS",
+    "AS",
+    "ASC",
+    "AUTOINCREMENT",
+    "BEFORE",
+    "BEGIN",
+    "BY",
+    "CASCADE",
+    "CHECK",
+    "COLLATE",
+    "CONFLICT",
+    "CONSTRAINT",
+    "CREATE",
+    "DEFAULT",
+    "DEFERRABLE",
+    "DEFERRED",
+    "DELETE",
+    "DESC",
+    "DISTINCT",
+    "EACH",
+    "END",
+    "FAIL",
+    "FOR",
+    "FOREIGN",
+    "FROM",
+    "GENERATED",
+    "IF",
+    "IGNORE",
+    "IMMEDIATE",
+    "INDEX",
+    "INITIALLY",
+    "INSERT",
+    "INSTEAD",
+    "INTO",
+    "KEY",
+    "NO",
+    "NOT",
+    "NULL",
+    "OF",
+    "ON",
+    "OR",
+    "PRIMARY",
+    "REFERENCES",
+    "REPLACE",
+    "RESTRICT",
+    "ROLLBACK",
+    "ROW",
+    "ROWID",
+    "SELECT",
+    "SET",
+    "STORED",
+    "STRICT",
+    "TABLE",
+    "TEMP",
+    "TEMPORARY",
+    "TRIGGER",
+    "UNIQUE",
+    "UPDATE",
+    "USING",
+    "VALUES",
+    "VIEW",
+    "VIRTUAL",
+    "WHEN",
+    "WHERE",
+    "WITHOUT",
+    "AND",
+    "IS",
+    "IN",
+    "LIKE",
+    "BETWEEN",
+    "CASE",
+    "THEN",
+    "ELSE",
+    "JOIN",
+    "LEFT",
+    "INNER",
+    "GROUP",
+    "ORDER",
+    "LIMIT",
+    "HAVING",
+];
+
+/// Split SQL into spans for highlighting, unlike `tokenize` every character is kept,
+/// so the spans joined give the SQL back.
+pub fn highlight(sql: &str) -> Vec<(SqlSpan, String)> {
+    lex(sql)
+        .into_iter()
+        .map(|l| (l.kind, sql[l.range].to_string()))
+        .collect()
+}
+
+/// Piece of SQL: its kind, where it is in the text and its value, which for quoted
+/// identifiers and literals is the text without quotes and escapes.
+struct Lexeme {
+    kind: SqlSpan,
+    range: Range<usize>,
+    value: String,
+}
+
+/// Split SQL into lexemes, every character belongs to one of them. Both highlighting
+/// and tokens are made of them, so they always agree on where a token ends.
+fn lex(sql: &str) -> Vec<Lexeme> {
+    let mut lexemes = vec![];
+    let mut chars = sql.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let mut value = None;
+        let kind = match c {
+            c if c.is_whitespace() => {
+                while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+                SqlSpan::Space
+            }
+            '-' if matches!(chars.peek(), Some((_, '-'))) => {
+                while chars.next_if(|(_, c)| *c != '\n').is_some() {}
+                SqlSpan::Comment
+            }
+            '/' if matches!(chars.peek(), Some((_, '*'))) => {
+                chars.next();
+                while let Some((_, c)) = chars.next() {
+                    if c == '*' && chars.next_if(|(_, c)| *c == '/').is_some() {
+                        break;
+                    }
+                }
+                SqlSpan::Comment
+            }
+            '"' | '`' | '[' | '\'' => {
+                let close = if c == '[' { ']' } else { c };
+                let mut unquoted = String::new();
+                while let Some((_, c)) = chars.next() {
+                    if c == close {
+                        // Quote is escaped by doubling it, brackets can't be escaped.
+                        if close == ']' || chars.next_if(|(_, c)| *c == close).is_none() {
+                            break;
+                        }
+                    }
+                    unquoted.push(c);
+                }
+                value = Some(unquoted);
+                match c {
+                    '\'' => SqlSpan::Literal,
+                    _ => SqlSpan::Quoted,
+                }
+            }
+            c if c.is_alphanumeric() || c == '_' || c == '$' => {
+                while chars
+                    .next_if(|(_, c)| c.is_alphanumeric() || matches!(c, '_' | '$' | '.'))
+                    .is_some()
+                {}
+                let end = chars.peek().map_or(sql.len(), |(i, _)| *i);
+                let word = &sql[start..end];
+                if c.is_ascii_digit() {
+                    SqlSpan::Number
+                } else if KEYWORDS.iter().any(|k| k.eq_ignore_ascii_case(word)) {
+                    SqlSpan::Keyword
+                } else {
+                    SqlSpan::Identifier
+                }
+            }
+            _ => SqlSpan::Punct,
+        };
+        let end = chars.peek().map_or(sql.len(), |(i, _)| *i);
+        lexemes.push(Lexeme {
+            kind,
+            range: start..end,
+            value: value.unwrap_or_else(|| sql[start..end].to_string()),
+        });
+    }
+    lexemes
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Token {
+    /// Keyword or bare identifier.
+    Word(String),
+    /// Identifier in "double quotes", [brackets] or `backticks`.
+    Quoted(String),
+    /// 'String literal'.
+    Literal(String),
+    Punct(char),
+}
+
+impl Token {
+    /// Text of the token as it would be written in SQL, quotes dropped.
+    pub fn text(&self) -> String {
+        match self {
+            Self::Word(v) | Self::Quoted(v) => v.to_string(),
+            Self::Literal(v) => format!("'{}'", v),
+            Self::Punct(c) => c.to_string(),
+        }
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self, Self::Word(v) if v.eq_ignore_ascii_case(keyword))
+    }
+}
+
+/// Split SQL into tokens, comments and whitespace are skipped.
+pub fn tokenize(sql: &str) -> Vec<Token> {
+    lex(sql)
+        .into_iter()
+        .filter_map(|l| match l.kind {
+            SqlSpan::Space | SqlSpan::Comment => None,
+            SqlSpan::Keyword | SqlSpan::Identifier | SqlSpan::Number => Some(Token::Word(l.value)),
+            SqlSpan::Quoted => Some(Token::Quoted(l.value)),
+            SqlSpan::Literal => Some(Token::Literal(l.value)),
+            SqlSpan::Punct => l.value.chars().next().map(Token::Punct),
+        })
+        .collect()
+}
+
+/// Names of values stored in records of the table or index, created by the statement.
+/// Index records end with the key of the table row, so indexes need the SQL of their table.
+/// Empty if the statement isn't understood, e.g. CREATE TABLE ... AS SELECT.
+pub fn column_names(sql: &str, table_sql: Option<&str>) -> Vec<String> {
+    let tokens = tokenize(sql);
+    let Some(Definition { head, groups, tail }) = definition(&tokens) else {
+        return vec![];
+    };
+
+    if head.iter().any(|t| t.is_keyword("INDEX")) {
+        // Index record holds indexed columns followed by the rowid of the table row,
+        // or by the PRIMARY KEY columns of a WITHOUT ROWID table, which aren't indexed yet.
+        let mut names = groups.iter().map(|g| indexed_column(g)).collect::<Vec<_>>();
+        match table_sql.and_then(without_rowid_key) {
+            Some(key) => {
+                let rest = key
+                    .into_iter()
+                    .filter(|k| !names.iter().any(|n| n.eq_ignore_ascii_case(k)))
+                    .collect::<Vec<_>>();
+                names.extend(rest);
+            }
+            None => names.push("rowid".to_string()),
+        }
+        names
+    } else if head.iter().any(|t| t.is_keyword("TABLE")) {
+        let names = table_columns(&groups);
+        if !is_without_rowid(tail) {
+            return names;
+        }
+        // Records of WITHOUT ROWID tables start with the PRIMARY KEY columns,
+        // the rest follow in the order of definition.
+        let key = primary_key(&groups, &names);
+        let rest = names
+            .into_iter()
+            .filter(|n| !key.iter().any(|k| k.eq_ignore_ascii_case(n)));
+        key.iter().cloned().chain(rest).collect()
+    } else {
+        vec![]
+    }
+}
+
+/// PRIMARY KEY columns of a WITHOUT ROWID table, which lead its records and
+/// form the key of its index b-tree. None for other statements.
+pub fn without_rowid_key(sql: &str) -> Option<Vec<String>> {
+    let tokens = tokenize(sql);
+    let Definition { head, groups, tail } = definition(&tokens)?;
+    if !head.iter().any(|t| t.is_keyword("TABLE")) || !is_without_rowid(tail) {
+        return None;
+    }
+    Some(primary_key(&groups, &table_columns(&groups)))
+}
+
+/// CREATE statement split around its column list.
+struct Definition<'a> {
+    /// Tokens before the column list.
+    head: &'a [Token],
+    /// Comma separated groups of the column list.
+    groups: Vec<&'a [Token]>,
+    /// Table options after the column list.
+    tail: &'a [Token],
+}
+
+/// None for statements without the column list.
+fn definition(tokens: &[Token]) -> Option<Definition<'_>> {
+    let open = tokens.iter().position(|t| *t == Token::Punct('('))?;
+    let head = &tokens[..open];
+    if head
+        .iter()
+        .any(|t| t.is_keyword("VIRTUAL") || t.is_keyword("AS"))
+    {
+        return None;
+    }
+    let (groups, tail) = top_level_groups(&tokens[open + 1..]);
+    Some(Definition { head, groups, tail })
+}
+
+/// Names of the column definitions, table constraints are skipped.
+fn table_columns(groups: &[&[Token]]) -> Vec<String> {
+    groups
+        .iter()
+        .filter(|g| !is_table_constraint(g) && !is_virtual_column(g))
+        .filter_map(|g| g.first())
+        .map(|t| t.text())
+        .collect()
+}
+
+fn is_table_constraint(group: &[Token]) -> bool {
+    let constraints = ["CONSTRAINT", "PRIMARY", "UNIQUE", "CHECK", "FOREIGN"];
+    group
+        .first()
+        .is_some_and(|t| constraints.iter().any(|k| t.is_keyword(k)))
+}
+
+/// Generated column, which isn't STORED: it's computed when read and takes no place
+/// in the record. Expressions in parentheses, e.g. `CAST(x AS TEXT)`, don't count.
+fn is_virtual_column(group: &[Token]) -> bool {
+    let mut depth = 0;
+    let mut generated = false;
+    for (n, token) in group.iter().enumerate() {
+        match token {
+            Token::Punct('(') => depth += 1,
+            Token::Punct(')') => depth -= 1,
+            t if depth == 0 && t.is_keyword("STORED") => return false,
+            t if depth == 0 && t.is_keyword("AS") => {
+                generated |= group.get(n + 1) == Some(&Token::Punct('('));
+            }
+            _ => {}
+        }
+    }
+    generated
+}
+
+/// Table options after the column list, e.g. `WITHOUT ROWID, STRICT`.
+fn is_without_rowid(tail: &[Token]) -> bool {
+    tail.windows(2)
+        .any(|w| w[0].is_keyword("WITHOUT") && w[1].is_keyword("ROWID"))
+}
+
+/// Columns of the PRIMARY KEY, either a column or a table constraint,
+/// named the way the columns are defined.
+fn primary_key(groups: &[&[Token]], columns: &[String]) -> Vec<String> {
+    let is_primary = |w: &[Token]| w[0].is_keyword("PRIMARY") && w[1].is_keyword("KEY");
+    let mut key = vec![];
+    for group in groups {
+        let Some(at) = group.windows(2).position(is_primary) else {
+            continue;
+        };
+        if !is_table_constraint(group) {
+            key.extend(group.first().map(|t| t.text()));
+            continue;
+        }
+        let rest = &group[at + 2..];
+        if let Some(open) = rest.iter().position(|t| *t == Token::Punct('(')) {
+            let (names, _) = top_level_groups(&rest[open + 1..]);
+            key.extend(names.iter().map(|g| indexed_column(g)));
+        }
+    }
+    key.into_iter()
+        .map(|k| {
+            columns
+                .iter()
+                .find(|c| c.eq_ignore_ascii_case(&k))
+                .cloned()
+                .unwrap_or(k)
+        })
+        .collect()
+}
+
+/// Comma separated groups of tokens up to the closing parenthesis, and the
+/// tokens after it.
+fn top_level_groups(tokens: &[Token]) -> (Vec<&[Token]>, &[Token]) {
+    let mut groups = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (n, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Punct('(') => depth += 1,
+            Token::Punct(')') if depth == 0 => {
+                groups.push(&tokens[start..n]);
+                return (groups, &tokens[n + 1..]);
+            }
+            Token::Punct(')') => depth -= 1,
+            Token::Punct(',') if depth == 0 => {
+                groups.push(&tokens[start..n]);
+                start = n + 1;
+            }
+            _ => {}
+        }
+    }
+    groups.push(&tokens[start..]);
+    (groups, &[])
+}
+
+/// Indexed column name or the whole expression, without collation and sort order.
+fn indexed_column(tokens: &[Token]) -> String {
+    let end = tokens
+        .iter()
+        .position(|t| t.is_keyword("COLLATE") || t.is_keyword("ASC") || t.is_keyword("DESC"))
+        .unwrap_or(tokens.len());
+    tokens[..end]
+        .iter()
+        .map(|t| t.text())
+        .collect::<Vec<_>>()
+        .join("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::database;
+    use crate::{BTreeNode, Reader};
+
+    #[test]
+    fn test_column_names() {
+        let sql = r#"CREATE TABLE "my stars"(
+            id INTEGER PRIMARY KEY, -- rowid alias
+            [name] TEXT DEFAULT 'a, b',
+            distance REAL CHECK (distance > 0),
+            UNIQUE (name, distance)
+        )"#;
+        assert_eq!(column_names(sql, None), vec!["id", "name", "distance"]);
+
+        let sql = "CREATE INDEX idx ON stars (name COLLATE NOCASE DESC, lower(name))";
+        assert_eq!(
+            column_names(sql, None),
+            vec!["name", "lower(name)", "rowid"]
+        );
+
+        assert!(column_names("CREATE TABLE t AS SELECT 1 AS (x)", None).is_empty());
+    }
+
+    #[test]
+    fn test_generated_columns() {
+        let sql = "CREATE TABLE t(
+            a INT,
+            b AS (a * 2),
+            c INT GENERATED ALWAYS AS (a + 1) STORED,
+            d TEXT AS (CAST(a AS TEXT)) VIRTUAL,
+            e INT CHECK (CAST(e AS TEXT) <> '')
+        )";
+        assert_eq!(column_names(sql, None), vec!["a", "c", "e"]);
+
+        // Records hold the values of the stored columns only.
+        let bytes = database(
+            "generated",
+            &format!("{}; INSERT INTO t(a, e) VALUES (1, 2);", sql),
+        );
+        let reader = Reader::new(bytes).unwrap();
+        let rows = BTreeNode::new(2, &reader).unwrap().rows(&reader).unwrap();
+        let values = rows[0].values.iter().map(|v| &v.value).collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            vec![&RecordType::One(1), &RecordType::I8(2), &RecordType::I8(2)]
+        );
+    }
+
+    #[test]
+    fn test_without_rowid_columns() {
+        let sql = "CREATE TABLE w(a, b TEXT PRIMARY KEY, c) WITHOUT ROWID";
+        assert_eq!(column_names(sql, None), vec!["b", "a", "c"]);
+        let sql = "CREATE TABLE w(a, b, c, g AS (a + 1), PRIMARY KEY (c, A)) WITHOUT ROWID";
+        assert_eq!(column_names(sql, None), vec!["c", "a", "b"]);
+
+        // Records start with the key columns in the order of the PRIMARY KEY.
+        let bytes = database(
+            "without-rowid",
+            &format!("{}; INSERT INTO w(a, b, c) VALUES (1, 2, 3);", sql),
+        );
+        let reader = Reader::new(bytes).unwrap();
+        let rows = BTreeNode::new(2, &reader).unwrap().rows(&reader).unwrap();
+        let values = rows[0].values.iter().map(|v| &v.value).collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            vec![&RecordType::I8(3), &RecordType::One(1), &RecordType::I8(2)]
+        );
+    }
+
+    #[test]
+    fn test_without_rowid_index_columns() {
+        let table = "CREATE TABLE w(a, b, c, PRIMARY KEY (c, a)) WITHOUT ROWID";
+        let sql = "CREATE INDEX wi ON w(b, A)";
+        assert_eq!(column_names(sql, Some(table)), vec!["b", "A", "c"]);
+        let rowid = "CREATE TABLE w(a, b, c, PRIMARY KEY (c, a))";
+        assert_eq!(column_names(sql, Some(rowid)), vec!["b", "A", "rowid"]);
+
+        // Index records end with the PRIMARY KEY columns, which aren't indexed.
+        let bytes = database(
+            "without-rowid-index",
+            &format!("{}; {}; INSERT INTO w VALUES (1, 2, 3);", table, sql),
+        );
+        let reader = Reader::new(bytes).unwrap();
+        let rows = BTreeNode::new(3, &reader).unwrap().rows(&reader).unwrap();
+        let values = rows[0].values.iter().map(|v| &v.value).collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            vec![&RecordType::I8(2), &RecordType::One(1), &RecordType::I8(3)]
+        );
+        let btrees = reader.get_btrees().unwrap();
+        let index = btrees.iter().find(|b| b.name == "wi").unwrap();
+        assert_eq!(index.columns, vec!["b", "A", "c"]);
+    }
+
+    #[test]
+    fn test_virtual_table() {
+        let sql = "CREATE VIRTUAL TABLE IF NOT EXISTS docs USING FTS5(title, body, detail='none')";
+        let (module, args) = virtual_table(sql).unwrap();
+        assert_eq!(module, "fts5");
+        assert_eq!(args, ["title", "body", "detail = 'none'"]);
+
+        let sql = "CREATE VIRTUAL TABLE box USING rtree(id, minX, maxX, +label)";
+        assert_eq!(
+            virtual_table(sql).unwrap().1,
+            ["id", "minX", "maxX", "+ label"]
+        );
+
+        assert_eq!(virtual_table("CREATE TABLE t(a)"), None);
+    }
+
+    #[test]
+    fn test_without_rowid() {
+        let sql = "CREATE TABLE kv (v BLOB, k TEXT PRIMARY KEY) WITHOUT ROWID";
+        assert_eq!(column_names(sql, None), vec!["k", "v"]);
+        assert_eq!(without_rowid_key(sql), Some(vec!["k".to_string()]));
+
+        let sql =
+            "CREATE TABLE t (a, b, c, CONSTRAINT pk PRIMARY KEY (C, a DESC)) WITHOUT ROWID, STRICT";
+        assert_eq!(column_names(sql, None), vec!["c", "a", "b"]);
+        assert_eq!(without_rowid_key(sql).map(|k| k.len()), Some(2));
+
+        assert_eq!(
+            without_rowid_key("CREATE TABLE t (id INTEGER PRIMARY KEY)"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_highlight() {
+        let sql = "CREATE TABLE \"a \"\"b\"\"\" (x INT DEFAULT 'it''s', y /* c */ REAL) -- end";
+        let spans = highlight(sql);
+        assert_eq!(
+            spans.iter().map(|(_, t)| t.as_str()).collect::<String>(),
+            sql
+        );
+        let kinds = spans
+            .iter()
+            .filter(|(k, _)| *k != SqlSpan::Space)
+            .map(|(k, _)| *k)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                SqlSpan::Keyword,
+                SqlSpan::Keyword,
+                SqlSpan::Quoted,
+                SqlSpan::Punct,
+                SqlSpan::Identifier,
+                SqlSpan::Identifier,
+                SqlSpan::Keyword,
+                SqlSpan::Literal,
+                SqlSpan::Punct,
+                SqlSpan::Identifier,
+                SqlSpan::Comment,
+                SqlSpan::Identifier,
+                SqlSpan::Punct,
+                SqlSpan::Comment,
+            ]
+        );
+        // Tokens are the same spans without whitespace and comments, unquoted.
+        assert_eq!(
+            tokenize(sql),
+            vec![
+                Token::Word("CREATE".to_string()),
+                Token::Word("TABLE".to_string()),
+                Token::Quoted("a \"b\"".to_string()),
+                Token::Punct('('),
+                Token::Word("x".to_string()),
+                Token::Word("INT".to_string()),
+                Token::Word("DEFAULT".to_string()),
+                Token::Literal("it's".to_string()),
+                Token::Punct(','),
+                Token::Word("y".to_string()),
+                Token::Word("REAL".to_string()),
+                Token::Punct(')'),
+            ]
+        );
+    }
+}
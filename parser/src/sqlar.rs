@@ -0,0 +1,122 @@
+//! SQLite Archive is an ordinary database, which `sqlite3 -A` keeps files in,
+//! one row per file of the table:
+//!
+//! ```sql
+//! CREATE TABLE sqlar(name TEXT PRIMARY KEY, mode INT, mtime INT, sz INT, data BLOB)
+//! ```
+//!
+//! Content of a file is compressed with zlib, unless it doesn't get any smaller,
+//! so it's compressed exactly when the stored blob is shorter than the original size.
+
+use crate::inflate::zlib_decompress;
+use crate::{Error, RecordType, RecordValue, Row};
+
+pub const SQLAR_TABLE: &str = "sqlar";
+pub const SQLAR_COLUMNS: [&str; 5] = ["name", "mode", "mtime", "sz", "data"];
+/// Index of the data column, which holds the file content.
+pub const SQLAR_DATA: usize = 4;
+
+/// Row of the sqlar table.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SqlarEntry {
+    pub name: String,
+    /// Type of the file and its permissions, as the Unix st_mode.
+    pub mode: i64,
+    /// Modification time in seconds since the Unix epoch.
+    pub mtime: i64,
+    /// Original size of the file, -1 for symbolic links.
+    pub size: i64,
+    /// Stored content: compressed file, link target, NULL for directories.
+    pub data: Option<Vec<u8>>,
+    /// Page number and page offset of the cell, which holds the row.
+    pub page_num: usize,
+    pub cell_offset: usize,
+}
+
+impl SqlarEntry {
+    /// The table has the name and the columns of an archive.
+    pub fn is_archive(name: &str, columns: &[String]) -> bool {
+        name.eq_ignore_ascii_case(SQLAR_TABLE)
+            && columns.len() == SQLAR_COLUMNS.len()
+            && columns
+                .iter()
+                .zip(SQLAR_COLUMNS)
+                .all(|(column, expected)| column.eq_ignore_ascii_case(expected))
+    }
+
+    /// Entry from the row of the sqlar table, None if the name isn't a text.
+    pub fn new(row: &Row) -> Option<Self> {
+        let int = |n: usize| match row.values.get(n).map(|v| &v.value) {
+            Some(RecordType::I8(v)) => *v as i64,
+            Some(RecordType::I16(v)) => *v as i64,
+            Some(RecordType::I24(v) | RecordType::I32(v)) => *v as i64,
+            Some(RecordType::I48(v) | RecordType::I64(v)) => *v,
+            Some(RecordType::Zero(v) | RecordType::One(v)) => *v as i64,
+            _ => 0,
+        };
+        let name = match row.values.first().map(|v| &v.value) {
+            Some(RecordType::Text(v)) => v.clone().unwrap_or_default(),
+            _ => return None,
+        };
+        let data = match row.values.get(SQLAR_DATA) {
+            Some(RecordValue {
+                value: RecordType::Blob(_) | RecordType::Text(_),
+                bytes,
+            }) => Some(bytes.clone().unwrap_or_default()),
+            _ => None,
+        };
+        Some(Self {
+            name,
+            mode: int(1),
+            mtime: int(2),
+            size: int(3),
+            data,
+            page_num: row.page_num,
+            cell_offset: row.cell_offset,
+        })
+    }
+
+    /// Length of the stored content.
+    pub fn stored_size(&self) -> usize {
+        self.data.as_ref().map_or(0, Vec::len)
+    }
+
+    pub fn is_compressed(&self) -> bool {
+        self.size >= 0 && self.stored_size() != self.size as usize
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self.mode & 0o170000 {
+            0o040000 => "directory",
+            0o120000 => "symbolic link",
+            _ => "file",
+        }
+    }
+
+    /// Type and permissions the way `ls -l` shows them, like drwxr-xr-x.
+    pub fn permissions(&self) -> String {
+        let kind = match self.kind() {
+            "directory" => 'd',
+            "symbolic link" => 'l',
+            _ => '-',
+        };
+        let bits = "rwxrwxrwx"
+            .chars()
+            .enumerate()
+            .map(|(n, c)| match self.mode & (0o400 >> n) {
+                0 => '-',
+                _ => c,
+            });
+        std::iter::once(kind).chain(bits).collect()
+    }
+
+    /// Original content of the file, decompressed if needed.
+    pub fn content(&self) -> Result<Vec<u8>, Error> {
+        let data = self.data.as_deref().unwrap_or_default();
+        match self.is_compressed() {
+            true => zlib_decompress(data, self.size as usize),
+            false => Ok(data.to_vec()),
+        }
+    }
+}
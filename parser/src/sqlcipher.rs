@@ -0,0 +1,309 @@
+/// Decryption of SQLCipher databases, https://www.zetetic.net/sqlcipher/design/
+///
+/// Every page is encrypted on its own with AES-256 in CBC mode. The reserved space
+/// at the end of the page keeps the random IV of the page and the HMAC of the
+/// ciphertext, the IV and the page number, so a page, which was tampered with or
+/// decrypted with a wrong key, is noticed. The first 16 bytes of the file are the
+/// salt of the key instead of the magic string, they aren't encrypted.
+///
+/// The key is derived from the passphrase with PBKDF2, the HMAC key is derived from
+/// the key with 2 more PBKDF2 iterations and the salt XORed with 0x3a. A raw key
+/// given as x'<64 hex digits>' is used as is.
+use std::fmt;
+
+use aes::cipher::{block_padding::NoPadding, BlockDecryptMut, InnerIvInit, KeyInit};
+use aes::Aes256;
+use hmac::digest::{FixedOutput, Update};
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use sha2::Sha512;
+
+use crate::{Error, Storage, SQLITE_MAGIC};
+
+pub const SQLCIPHER_SALT_SIZE: usize = 16;
+pub const SQLCIPHER_IV_SIZE: usize = 16;
+const AES_BLOCK_SIZE: usize = 16;
+const KEY_SIZE: usize = 32;
+/// Iterations of PBKDF2, which derive the HMAC key from the key.
+const HMAC_KDF_ITER: u32 = 2;
+const HMAC_SALT_MASK: u8 = 0x3a;
+
+/// Hash function of the HMAC and the key derivation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum SqlCipherHmac {
+    Sha1,
+    Sha512,
+}
+
+impl SqlCipherHmac {
+    /// Size of the HMAC in the reserved space.
+    pub fn size(&self) -> usize {
+        match self {
+            Self::Sha1 => 20,
+            Self::Sha512 => 64,
+        }
+    }
+}
+
+impl fmt::Display for SqlCipherHmac {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Sha1 => write!(f, "HMAC-SHA1"),
+            Self::Sha512 => write!(f, "HMAC-SHA512"),
+        }
+    }
+}
+
+/// Settings the database was encrypted with, they aren't stored in the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SqlCipherSettings {
+    pub page_size: usize,
+    /// Iterations of PBKDF2, which derive the key from the passphrase.
+    pub kdf_iter: u32,
+    pub hmac: SqlCipherHmac,
+}
+
+impl SqlCipherSettings {
+    /// Defaults of SQLCipher 4.
+    pub const V4: SqlCipherSettings = SqlCipherSettings {
+        page_size: 4096,
+        kdf_iter: 256000,
+        hmac: SqlCipherHmac::Sha512,
+    };
+    /// Defaults of SQLCipher 3.
+    pub const V3: SqlCipherSettings = SqlCipherSettings {
+        page_size: 1024,
+        kdf_iter: 64000,
+        hmac: SqlCipherHmac::Sha1,
+    };
+
+    /// Reserved space of every page: the IV and the HMAC, rounded up to whole AES blocks.
+    pub fn reserve(&self) -> usize {
+        (SQLCIPHER_IV_SIZE + self.hmac.size()).div_ceil(AES_BLOCK_SIZE) * AES_BLOCK_SIZE
+    }
+}
+
+impl fmt::Display for SqlCipherSettings {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "AES-256-CBC, {} with {} PBKDF2 iterations, {}-byte pages",
+            self.hmac, self.kdf_iter, self.page_size
+        )
+    }
+}
+
+/// Keys of the database, derived once for all of its pages.
+pub struct SqlCipher {
+    settings: SqlCipherSettings,
+    aes: Aes256,
+    hmac: HmacKey,
+}
+
+enum HmacKey {
+    Sha1(Hmac<Sha1>),
+    Sha512(Hmac<Sha512>),
+}
+
+impl HmacKey {
+    /// Whether the tag is the HMAC of the parts, compared in constant time.
+    fn verify(&self, parts: &[&[u8]], tag: &[u8]) -> bool {
+        fn verify<M: Mac + Clone>(key: &M, parts: &[&[u8]], tag: &[u8]) -> bool {
+            let mut mac = key.clone();
+            for part in parts {
+                Mac::update(&mut mac, part);
+            }
+            mac.verify_slice(tag).is_ok()
+        }
+        match self {
+            Self::Sha1(key) => verify(key, parts, tag),
+            Self::Sha512(key) => verify(key, parts, tag),
+        }
+    }
+}
+
+impl SqlCipher {
+    /// Derive the keys from the passphrase and the salt, the first 16 bytes of the file.
+    pub fn new(passphrase: &str, salt: &[u8], settings: SqlCipherSettings) -> Result<Self, Error> {
+        let salt = salt
+            .get(..SQLCIPHER_SALT_SIZE)
+            .ok_or_else(|| Error::Malformed("SQLCipher salt is cut.".to_string()))?;
+        let hmac_salt = salt.iter().map(|b| b ^ HMAC_SALT_MASK).collect::<Vec<_>>();
+        let key = derive(passphrase, salt, settings)?;
+        let hmac = match settings.hmac {
+            SqlCipherHmac::Sha1 => HmacKey::Sha1(hmac_key(&key, &hmac_salt)),
+            SqlCipherHmac::Sha512 => HmacKey::Sha512(hmac_key(&key, &hmac_salt)),
+        };
+        Ok(Self {
+            settings,
+            aes: Aes256::new(&key.into()),
+            hmac,
+        })
+    }
+
+    /// Plain content of the page: the first page gets the magic string back in place
+    /// of the salt, the reserved space is kept as it is.
+    pub fn decrypt_page(&self, page_num: usize, page: &[u8]) -> Result<Vec<u8>, Error> {
+        let page_size = self.settings.page_size;
+        if page.len() != page_size {
+            return Err(Error::TruncatedPage {
+                page: page_num,
+                expected: page_size,
+                read: page.len(),
+            });
+        }
+        let start = match page_num {
+            1 => SQLCIPHER_SALT_SIZE,
+            _ => 0,
+        };
+        let end = page_size - self.settings.reserve();
+        let iv = &page[end..end + SQLCIPHER_IV_SIZE];
+        let hmac =
+            &page[end + SQLCIPHER_IV_SIZE..end + SQLCIPHER_IV_SIZE + self.settings.hmac.size()];
+        // HMAC covers the ciphertext with the IV right after it and the page number.
+        let page_no = (page_num as u32).to_le_bytes();
+        let parts: [&[u8]; 2] = [&page[start..end + SQLCIPHER_IV_SIZE], &page_no];
+        if !self.hmac.verify(&parts, hmac) {
+            return Err(Error::Malformed(format!(
+                "HMAC of page {} doesn't match: the passphrase or the SQLCipher settings are wrong, or the page was changed.",
+                page_num
+            )));
+        }
+        let mut iv_block = [0; SQLCIPHER_IV_SIZE];
+        iv_block.copy_from_slice(iv);
+        // Content without the reserved space is whole AES blocks, there is no padding.
+        let mut content = page[start..end].to_vec();
+        cbc::Decryptor::<Aes256>::inner_iv_init(self.aes.clone(), &iv_block.into())
+            .decrypt_padded_mut::<NoPadding>(&mut content)
+            .map_err(|_| {
+                Error::Malformed(format!("Page {} isn't made of whole AES blocks.", page_num))
+            })?;
+        let mut plain = Vec::with_capacity(page_size);
+        if page_num == 1 {
+            plain.extend_from_slice(SQLITE_MAGIC);
+        }
+        plain.extend(content);
+        plain.extend_from_slice(&page[end..]);
+        Ok(plain)
+    }
+}
+
+/// HMAC key, derived from the key with the salt XORed with 0x3a.
+fn hmac_key<M: Mac + KeyInit + Update + FixedOutput + Clone + Sync>(
+    key: &[u8],
+    hmac_salt: &[u8],
+) -> M {
+    let mut hmac_key = [0; KEY_SIZE];
+    pbkdf2::pbkdf2::<M>(key, hmac_salt, HMAC_KDF_ITER, &mut hmac_key)
+        .expect("HMAC takes a key of any size");
+    <M as KeyInit>::new_from_slice(&hmac_key).expect("HMAC takes a key of any size")
+}
+
+/// Key from the passphrase, or the raw key given as x'<64 hex digits>'.
+fn derive(
+    passphrase: &str,
+    salt: &[u8],
+    settings: SqlCipherSettings,
+) -> Result<[u8; KEY_SIZE], Error> {
+    let raw = passphrase
+        .strip_prefix("x'")
+        .and_then(|p| p.strip_suffix('\''));
+    match raw {
+        Some(hex) if hex.len() == 2 * KEY_SIZE => {
+            let mut key = [0; KEY_SIZE];
+            for (i, b) in key.iter_mut().enumerate() {
+                *b = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16)
+                    .map_err(|_| Error::Malformed("Raw SQLCipher key isn't hex.".to_string()))?;
+            }
+            Ok(key)
+        }
+        _ => Ok(match settings.hmac {
+            SqlCipherHmac::Sha1 => pbkdf2::pbkdf2_hmac_array::<Sha1, KEY_SIZE>(
+                passphrase.as_bytes(),
+                salt,
+                settings.kdf_iter,
+            ),
+            SqlCipherHmac::Sha512 => pbkdf2::pbkdf2_hmac_array::<Sha512, KEY_SIZE>(
+                passphrase.as_bytes(),
+                salt,
+                settings.kdf_iter,
+            ),
+        }),
+    }
+}
+
+/// Plain content of the whole SQLCipher database, which parses as any other one.
+pub fn sqlcipher_decrypt(
+    storage: &dyn Storage,
+    passphrase: &str,
+    settings: SqlCipherSettings,
+) -> Result<Vec<u8>, Error> {
+    let size = storage.size();
+    if size == 0 || !size.is_multiple_of(settings.page_size) {
+        return Err(Error::Malformed(format!(
+            "File of {} bytes isn't made of whole {}-byte pages.",
+            size, settings.page_size
+        )));
+    }
+    let mut page = vec![0; settings.page_size];
+    storage.read_at(0, &mut page)?;
+    let cipher = SqlCipher::new(passphrase, &page, settings)?;
+    let mut plain = Vec::with_capacity(size);
+    for n in 0..size / settings.page_size {
+        storage.read_at(n * settings.page_size, &mut page)?;
+        plain.extend(cipher.decrypt_page(n + 1, &page)?);
+    }
+    Ok(plain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::unhex;
+
+    #[test]
+    fn test_decrypt_page() {
+        // Page 2 of 512 bytes, encrypted the SQLCipher 4 way with a zero salt and
+        // the raw key 00 01 .. 1f.
+        let page = unhex(concat!(
+            "91a5840402323b3cc2de5729b8bfee5d0e433411d4a20ecf1d59815f746dbebe666b8fd9a59c9b9dc0bb1ed70d7a9fa8",
+            "37cef2808ad6456dc090ac054eae979a2655af37c03cc60ccedc453f3791f833acbf1c3d05464b650073719a11e297da",
+            "b4541961593a7eac40095410f59199b62129f82d395a2e6d60761402b85b3b752701c4bb1fbd5d3e9f26345f00785e7e",
+            "dd054daf688558e70cefe2ab3a6f0fd903e1f41f3df7cc63cd2b7394dbef4dfc72c6aa0edfc9768a058372b5adb8cecd",
+            "2b880eeeccbfa99c6b5eb863e13858fe49f24d377252a758f239e9fbfb10b26f6df90cf89fbc1c81c2ec7745e30e1eb1",
+            "d1c7578d1443d9ed796457ced3d5cd2d8a4cdc91515ca0baa4424ee4e3ab2d93b1723e43cb86a78a0154f13909e0213d",
+            "e6863078eebe91086e66a84b654e67b1b7cce8742751e8e83faefb02255900f7f4f1aa50d7fcfdcd3a62a9d69250761c",
+            "876c0f8543d12fff4f6747e3ac73b64bab57678c2fdafba2923f70d96d718b4b6a841b800880c17290ac86b56829a4c5",
+            "7b607b358461f57361696dff9a144f427f1e4cd9e3e77226acb1838dd35b0e387cfab1a5401df164e1461e1c5292a406",
+            "6568a305ff6f1b5ae3a81033e46346d764e9c30fa0d3f754c5fd470d89adf66056cd141394aa52b2cb5d1064faaa21a5",
+            "c08d94b07aad8b2910aecbcbc7329dedaf5c92ec1215292a1dd21a17dd4502d1",
+        ));
+        let settings = SqlCipherSettings {
+            page_size: 512,
+            ..SqlCipherSettings::V4
+        };
+        let key = (0..32).map(|i| format!("{:02x}", i)).collect::<String>();
+        let cipher = SqlCipher::new(&format!("x'{}'", key), &[0; 16], settings).unwrap();
+        let plain = cipher.decrypt_page(2, &page).unwrap();
+        assert_eq!(plain.len(), 512);
+        assert!(plain.starts_with(b"SQLCipher page 2\0\0"));
+        assert!(plain[16..512 - 80].iter().all(|b| *b == 0));
+        // Reserved space keeps the IV and the HMAC.
+        assert_eq!(plain[512 - 80..], page[512 - 80..]);
+
+        // HMAC covers the page number and the key.
+        assert!(cipher.decrypt_page(3, &page).is_err());
+        let wrong = SqlCipher::new(
+            "passphrase",
+            &[0; 16],
+            SqlCipherSettings {
+                kdf_iter: 2,
+                ..settings
+            },
+        )
+        .unwrap();
+        assert!(wrong.decrypt_page(2, &page).is_err());
+    }
+}
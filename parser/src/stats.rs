@@ -0,0 +1,63 @@
+//! Space usage of b-tree pages.
+use crate::*;
+
+/// How the bytes of a b-tree page are spent, all sizes are in bytes and add up
+/// to the page size.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PageStats {
+    /// Database header, which takes the first 100 bytes of page 1.
+    pub db_header: usize,
+    pub page_header: usize,
+    pub cell_pointers: usize,
+    /// Cells in the cell content area, including their local payload.
+    pub cells: usize,
+    pub freeblocks: usize,
+    pub fragmented: usize,
+    /// Gap between the cell pointer array and the cell content area.
+    pub unallocated: usize,
+    /// Reserved space at the end of the page, used by extensions.
+    pub reserved: usize,
+}
+
+impl PageStats {
+    pub fn new(page: &Page) -> Self {
+        let page_size = page.db_header.page_size as usize;
+        let db_header = if page.id == 1 { DB_HEADER_SIZE } else { 0 };
+        let freeblocks = page.free_blocks.iter().map(|b| b.size as usize).sum();
+        let fragmented = page.page_header.fragmented_free_bytes as usize;
+        let reserved = page.db_header.reserved_page_space as usize;
+        // Cell content area consists of cells, freeblocks and fragments only.
+        let content_area = page_size
+            .saturating_sub(reserved)
+            .saturating_sub(page.page_header.cell_start_offset as usize);
+        Self {
+            db_header,
+            page_header: page.page_header.size,
+            cell_pointers: page.cell_pointer.array.len() * CELL_PTR_SIZE,
+            cells: content_area.saturating_sub(freeblocks + fragmented),
+            freeblocks,
+            fragmented,
+            unallocated: page.unallocated.len(),
+            reserved,
+        }
+    }
+
+    /// Bytes, which hold the b-tree structure and its content.
+    pub fn used(&self) -> usize {
+        self.db_header + self.page_header + self.cell_pointers + self.cells
+    }
+
+    /// Bytes, which could be reused for new cells.
+    pub fn free(&self) -> usize {
+        self.freeblocks + self.fragmented + self.unallocated
+    }
+
+    /// Share of the usable space of the page in use, from 0 to 1.
+    pub fn fill(&self) -> f64 {
+        match self.used() + self.free() {
+            0 => 0.0,
+            usable => self.used() as f64 / usable as f64,
+        }
+    }
+}
@@ -0,0 +1,160 @@
+//! Backends the database bytes are read from, a page at a time.
+//!
+//! In-memory bytes cover the web UI, which fetches the whole file, while
+//! `SeekStorage` reads only the requested pages of a local file.
+use std::cell::RefCell;
+use std::io::{Read, Seek, SeekFrom};
+
+use crate::Error;
+
+pub trait Storage: std::fmt::Debug {
+    /// Size of the stored database in bytes.
+    fn size(&self) -> usize;
+
+    /// Fill `buf` with the bytes at `offset` and return how many were read,
+    /// it's less than the buffer length only at the end of the storage.
+    fn read_at(&self, offset: usize, buf: &mut [u8]) -> Result<usize, Error>;
+}
+
+impl Storage for &'static [u8] {
+    fn size(&self) -> usize {
+        self.len()
+    }
+
+    fn read_at(&self, offset: usize, buf: &mut [u8]) -> Result<usize, Error> {
+        Ok(copy_at(self, offset, buf))
+    }
+}
+
+impl Storage for Vec<u8> {
+    fn size(&self) -> usize {
+        self.len()
+    }
+
+    fn read_at(&self, offset: usize, buf: &mut [u8]) -> Result<usize, Error> {
+        Ok(copy_at(self, offset, buf))
+    }
+}
+
+/// Any seekable source, like `std::fs::File`. Nothing is read until it's requested.
+#[derive(Debug)]
+pub struct SeekStorage<R> {
+    inner: RefCell<R>,
+    size: usize,
+}
+
+impl<R: Read + Seek> SeekStorage<R> {
+    pub fn new(mut inner: R) -> Result<Self, Error> {
+        let size = inner.seek(SeekFrom::End(0))? as usize;
+        Ok(Self {
+            inner: RefCell::new(inner),
+            size,
+        })
+    }
+}
+
+impl<R: Read + Seek + std::fmt::Debug> Storage for SeekStorage<R> {
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn read_at(&self, offset: usize, buf: &mut [u8]) -> Result<usize, Error> {
+        let mut inner = self.inner.borrow_mut();
+        inner.seek(SeekFrom::Start(offset as u64))?;
+        let mut read = 0;
+        while read < buf.len() {
+            match inner.read(&mut buf[read..])? {
+                0 => break,
+                n => read += n,
+            }
+        }
+        Ok(read)
+    }
+}
+
+fn copy_at(bytes: &[u8], offset: usize, buf: &mut [u8]) -> usize {
+    let start = offset.min(bytes.len());
+    let end = start.saturating_add(buf.len()).min(bytes.len());
+    buf[..end - start].copy_from_slice(&bytes[start..end]);
+    end - start
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::test_util::database;
+    use crate::Reader;
+
+    /// Source, which gives out at most 3 bytes per read, as pipes and sockets might.
+    #[derive(Debug)]
+    struct Trickle(Cursor<Vec<u8>>);
+
+    impl Read for Trickle {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = buf.len().min(3);
+            self.0.read(&mut buf[..n])
+        }
+    }
+
+    impl Seek for Trickle {
+        fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+            self.0.seek(pos)
+        }
+    }
+
+    /// Bytes read at the offset into the buffer of the length, and how many there were.
+    fn read(storage: &dyn Storage, offset: usize, len: usize) -> (usize, Vec<u8>) {
+        let mut buf = vec![0xff; len];
+        let read = storage.read_at(offset, &mut buf).unwrap();
+        (read, buf)
+    }
+
+    #[test]
+    fn test_storage() {
+        let bytes = (0..100).collect::<Vec<u8>>();
+        let storages: [Box<dyn Storage>; 4] = [
+            Box::new(bytes.clone()),
+            Box::new(&*bytes.clone().leak()),
+            Box::new(SeekStorage::new(Cursor::new(bytes.clone())).unwrap()),
+            Box::new(SeekStorage::new(Trickle(Cursor::new(bytes.clone()))).unwrap()),
+        ];
+        for storage in &storages {
+            assert_eq!(storage.size(), 100);
+            assert_eq!(read(&**storage, 10, 5), (5, vec![10, 11, 12, 13, 14]));
+            // Short read at the end, the rest of the buffer is left as it was.
+            assert_eq!(read(&**storage, 98, 4), (2, vec![98, 99, 0xff, 0xff]));
+            assert_eq!(read(&**storage, 0, 0), (0, vec![]));
+            // Offsets at and past the end read nothing.
+            assert_eq!(read(&**storage, 100, 2), (0, vec![0xff, 0xff]));
+            assert_eq!(read(&**storage, 1000, 2), (0, vec![0xff, 0xff]));
+            assert_eq!(read(&**storage, usize::MAX, 2), (0, vec![0xff, 0xff]));
+        }
+    }
+
+    #[test]
+    fn test_seek_storage_parity() {
+        let bytes = database(
+            "storage",
+            "PRAGMA page_size=512;
+            CREATE TABLE t(v);
+            INSERT INTO t VALUES(zeroblob(2000));",
+        );
+        let memory = Reader::new(bytes).unwrap();
+        let seek =
+            Reader::with_storage(SeekStorage::new(Cursor::new(bytes.to_vec())).unwrap()).unwrap();
+        assert_eq!(seek.db_header, memory.db_header);
+        assert_eq!(seek.pages_total(), memory.pages_total());
+        for page_num in 1..=memory.pages_total() {
+            assert_eq!(
+                seek.get_raw_page(page_num).unwrap(),
+                memory.get_raw_page(page_num).unwrap()
+            );
+        }
+        // Page past the end is an error for both.
+        let past = memory.pages_total() + 1;
+        assert!(memory.get_raw_page(past).is_err());
+        assert!(seek.get_raw_page(past).is_err());
+    }
+}
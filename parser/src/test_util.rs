@@ -0,0 +1,102 @@
+//! Helpers and fixtures shared by the unit tests of the modules.
+use std::rc::Rc;
+
+use crate::*;
+
+/// Table leaf cell with the payload of 6 bytes and rowid 7: record header [3, 1, 17],
+/// values 42 and "hi".
+pub const LEAF_CELL: [u8; 8] = [6, 7, 3, 1, 17, 42, b'h', b'i'];
+
+/// Bytes of the hex string, two digits per byte.
+pub fn unhex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+/// Bytes of a new database, which SQLite creates with the script.
+pub fn database(name: &str, script: &str) -> &'static [u8] {
+    reserved_database(name, 0, script)
+}
+
+/// Copy of the database with the bytes written at the file offset.
+pub fn corrupt(bytes: &[u8], offset: usize, patch: &[u8]) -> Reader {
+    let mut bytes = bytes.to_vec();
+    bytes[offset..offset + patch.len()].copy_from_slice(patch);
+    Reader::new(Box::leak(bytes.into_boxed_slice())).unwrap()
+}
+
+/// Bytes of a new database, which SQLite creates with the script, leaving the number
+/// of bytes at the end of every page reserved, as extensions like SQLCipher do.
+pub fn reserved_database(name: &str, reserved: u8, script: &str) -> &'static [u8] {
+    let path = std::env::temp_dir().join(format!(
+        "sqlite-repr-test-{}-{}.db",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    let conn = rusqlite::Connection::open(&path).unwrap();
+    let mut reserved = reserved as std::os::raw::c_int;
+    // Reserved space is only set for the database without pages yet.
+    let code = unsafe {
+        rusqlite::ffi::sqlite3_file_control(
+            conn.handle(),
+            c"main".as_ptr(),
+            rusqlite::ffi::SQLITE_FCNTL_RESERVE_BYTES,
+            &mut reserved as *mut _ as *mut std::os::raw::c_void,
+        )
+    };
+    assert_eq!(code, rusqlite::ffi::SQLITE_OK);
+    conn.execute_batch(script).unwrap();
+    drop(conn);
+    let bytes = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    Box::leak(bytes.into_boxed_slice())
+}
+
+/// Header of a UTF-8 database with the page size and the reserved space at the end
+/// of every page, the rest of it is the defaults of a new database.
+pub fn db_header(page_size: u16, reserved: u8) -> Rc<DBHeader> {
+    Rc::new(DBHeader::new(
+        Rc::new(String::from_utf8_lossy(SQLITE_MAGIC).to_string()),
+        page_size,
+        1,
+        1,
+        reserved,
+        64,
+        32,
+        32,
+        0,
+        0,
+        0,
+        0,
+        0,
+        4,
+        0,
+        0,
+        TextEncoding::UTF8,
+        0,
+        0,
+        0,
+        &[0; 20],
+        0,
+        0,
+    ))
+}
+
+/// Table leaf page 2 of 4096 bytes with the cell content area starting at the offset,
+/// a freeblock of the size there, if any, and `LEAF_CELL` at the end of the page.
+pub fn leaf_page(start: u16, freeblock: u16) -> Page {
+    let mut buf = vec![0; 4096];
+    let first_block = if freeblock > 0 { start } else { 0 };
+    buf[..8].copy_from_slice(&[0x0d, 0, 0, 0, 1, 0, 0, 0]);
+    buf[1..3].copy_from_slice(&first_block.to_be_bytes());
+    buf[5..7].copy_from_slice(&start.to_be_bytes());
+    buf[8..10].copy_from_slice(&4088u16.to_be_bytes());
+    if freeblock > 0 {
+        buf[start as usize + 2..start as usize + 4].copy_from_slice(&freeblock.to_be_bytes());
+    }
+    buf[4088..].copy_from_slice(&LEAF_CELL);
+    Page::try_from((db_header(4096, 0), 2, buf.as_slice())).unwrap()
+}
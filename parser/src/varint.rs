@@ -12,6 +12,7 @@
 /// Implementation in C for reference:
 /// https://github.com/sqlite/sqlite/blob/master/tool/varint.c
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Varint {
     pub value: i64,
     pub bytes: Vec<u8>,
@@ -45,6 +46,33 @@ impl Varint {
         }
         Varint { value, bytes }
     }
+
+    /// Shortest encoding of the value: 7 bits per byte with the high bit set on
+    /// every byte but the last one, values above 56 bits take all 8 bits of the
+    /// ninth byte. Negative values always take 9 bytes.
+    pub fn encode(value: i64) -> Varint {
+        let mut v = value as u64;
+        let bytes = if v & 0xff00_0000_0000_0000 != 0 {
+            let mut bytes = vec![0; 9];
+            bytes[8] = v as u8;
+            v >>= 8;
+            for byte in bytes[..8].iter_mut().rev() {
+                *byte = (v & 0x7f) as u8 | 0x80;
+                v >>= 7;
+            }
+            bytes
+        } else {
+            let mut bytes = vec![(v & 0x7f) as u8];
+            v >>= 7;
+            while v > 0 {
+                bytes.push((v & 0x7f) as u8 | 0x80);
+                v >>= 7;
+            }
+            bytes.reverse();
+            bytes
+        };
+        Varint { value, bytes }
+    }
 }
 
 #[cfg(test)]
@@ -62,4 +90,14 @@ mod tests {
         let res = Varint::new(&[0x88; 10]);
         assert_eq!((res.value, res.bytes), (1161999626690365576, vec![0x88; 9]));
     }
+
+    #[test]
+    fn test_encode_varint() {
+        assert_eq!(Varint::encode(0x443).bytes, vec![0x88, 0x43]);
+        assert_eq!(Varint::encode(1161999626690365576).bytes, vec![0x88; 9]);
+        for value in [0, 127, 128, 0x00ff_ffff_ffff_ffff, i64::MAX, -1, i64::MIN] {
+            assert_eq!(Varint::new(&Varint::encode(value).bytes).value, value);
+        }
+        assert_eq!(Varint::encode(-1).bytes.len(), 9);
+    }
 }
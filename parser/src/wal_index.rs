@@ -0,0 +1,347 @@
+/// The wal-index is the shared-memory file with the "-shm" suffix, which accompanies
+/// the database in WAL mode. It's never needed for recovery and is rebuilt from the
+/// WAL file after a crash, it only lets the connections find the most recent frame
+/// of a page in the WAL without reading the whole WAL.
+///
+/// The file is made of 32 KB blocks. The first one starts with the 136-byte header:
+/// two copies of the index header, which are equal unless a write was interrupted,
+/// and the checkpoint information with the read marks. Each block holds the page
+/// numbers of the consecutive WAL frames and a hash table to find them by page.
+///
+/// Unlike the rest of SQLite files the wal-index is in the native byte order of the
+/// host, which wrote it, the order is recognized by the version number.
+use crate::Error;
+
+pub const WAL_INDEX_VERSION: u32 = 3007000;
+pub const WAL_INDEX_HEADER_SIZE: usize = 136;
+/// Size of a single copy of the index header.
+pub const WAL_INDEX_HDR_SIZE: usize = 48;
+pub const WAL_INDEX_BLOCK_SIZE: usize = 32768;
+/// Frames per block, the first block has fewer of them to make room for the header.
+pub const WAL_INDEX_FRAMES: usize = 4096;
+pub const WAL_INDEX_FIRST_FRAMES: usize = WAL_INDEX_FRAMES - WAL_INDEX_HEADER_SIZE / 4;
+/// Hash table slots per block, twice the number of frames, so it's never full.
+pub const WAL_INDEX_SLOTS: usize = 2 * WAL_INDEX_FRAMES;
+/// Read mark, which isn't taken by any reader.
+pub const READ_MARK_NOT_USED: u32 = 0xffffffff;
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct WalIndexHeader {
+    /// Wal-index format version, 3007000.
+    /// offset: 0, size: 4
+    pub version: u32,
+    /// Counter incremented with every transaction.
+    /// offset: 8, size: 4
+    pub change: u32,
+    /// The header is initialized.
+    /// offset: 12, size: 1
+    pub is_init: bool,
+    /// Checksums of the WAL are computed in big-endian byte order.
+    /// offset: 13, size: 1
+    pub big_end_checksum: bool,
+    /// Database page size, 65536 is stored as 1.
+    /// offset: 14, size: 2
+    pub page_size: u32,
+    /// Index of the last valid frame in the WAL.
+    /// offset: 16, size: 4
+    pub max_frame: u32,
+    /// Size of the database in pages.
+    /// offset: 20, size: 4
+    pub db_size: u32,
+    /// Checksum of the last frame in the WAL.
+    /// offset: 24, size: 8
+    pub frame_checksum: [u32; 2],
+    /// Salt values copied from the WAL header.
+    /// offset: 32, size: 8
+    pub salt: [u32; 2],
+    /// Checksum over the previous fields of the header.
+    /// offset: 40, size: 8
+    pub checksum: [u32; 2],
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct WalCheckpointInfo {
+    /// Number of the WAL frames already copied back into the database.
+    /// offset: 96, size: 4
+    pub backfill: u32,
+    /// Last frame each of the readers is using, the first one always reads the
+    /// database alone.
+    /// offset: 100, size: 20
+    pub read_marks: [u32; 5],
+    /// Bytes the connections lock, their content is never read.
+    /// offset: 120, size: 8
+    pub locks: [u8; 8],
+    /// Number of the frames a checkpoint tried to backfill, possibly not finished.
+    /// offset: 128, size: 4
+    pub backfill_attempted: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct WalIndexBlock {
+    /// Offset of the block within the wal-index file.
+    pub offset: usize,
+    /// Number of the WAL frames before the ones of this block.
+    pub first_frame: u32,
+    /// Database page numbers of the frames, in frame order, up to the last valid frame.
+    pub page_numbers: Vec<u32>,
+    /// Hash table of 1-based indexes into the page numbers, zero slots are empty.
+    pub hash: Vec<u16>,
+}
+
+impl WalIndexBlock {
+    /// Hash table slot, where the search for the page starts.
+    pub fn hash_key(page_num: u32) -> usize {
+        (page_num as usize).wrapping_mul(383) & (WAL_INDEX_SLOTS - 1)
+    }
+
+    /// The latest frame of the block with the page, not beyond the last valid frame.
+    pub fn find_frame(&self, page_num: u32, max_frame: u32) -> Option<u32> {
+        let mut found = None;
+        let mut key = Self::hash_key(page_num);
+        // Table is never full, an empty slot ends the probe.
+        for _ in 0..WAL_INDEX_SLOTS {
+            let index = *self.hash.get(key)? as usize;
+            if index == 0 {
+                break;
+            }
+            let frame = self.first_frame + index as u32;
+            if frame <= max_frame && self.page_numbers.get(index - 1) == Some(&page_num) {
+                found = found.max(Some(frame));
+            }
+            key = (key + 1) & (WAL_INDEX_SLOTS - 1);
+        }
+        found
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct WalIndex {
+    /// Byte order of the host, which wrote the file.
+    pub big_endian: bool,
+    pub header: WalIndexHeader,
+    /// Second copy of the header, written first and read back to detect a torn write.
+    /// offset: 48, size: 48
+    pub header_copy: WalIndexHeader,
+    pub checkpoint: WalCheckpointInfo,
+    pub blocks: Vec<WalIndexBlock>,
+}
+
+impl WalIndex {
+    /// Both copies of the header are the same, so the header is safe to use.
+    pub fn is_consistent(&self) -> bool {
+        self.header == self.header_copy
+    }
+
+    /// The latest WAL frame with the content of the page, which readers see instead
+    /// of the page in the database file.
+    pub fn frame_for_page(&self, page_num: u32) -> Option<u32> {
+        let max_frame = self.header.max_frame;
+        self.blocks
+            .iter()
+            .rev()
+            .find_map(|block| block.find_frame(page_num, max_frame))
+    }
+
+    /// Readers, which are using a snapshot of the WAL, with the last frame they see.
+    pub fn readers(&self) -> impl Iterator<Item = (usize, u32)> + '_ {
+        self.checkpoint
+            .read_marks
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|&(_, mark)| mark != READ_MARK_NOT_USED)
+    }
+}
+
+/// Integers of the wal-index are in the byte order of the host.
+struct NativeBytes<'a> {
+    buf: &'a [u8],
+    big_endian: bool,
+}
+
+impl NativeBytes<'_> {
+    fn u16(&self, offset: usize) -> Result<u16, Error> {
+        let bytes = self.buf[offset..offset + 2].try_into()?;
+        Ok(match self.big_endian {
+            true => u16::from_be_bytes(bytes),
+            false => u16::from_le_bytes(bytes),
+        })
+    }
+
+    fn u32(&self, offset: usize) -> Result<u32, Error> {
+        let bytes = self.buf[offset..offset + 4].try_into()?;
+        Ok(match self.big_endian {
+            true => u32::from_be_bytes(bytes),
+            false => u32::from_le_bytes(bytes),
+        })
+    }
+
+    fn header(&self, offset: usize) -> Result<WalIndexHeader, Error> {
+        let page_size = match self.u16(offset + 14)? {
+            1 => 65536,
+            size => size as u32,
+        };
+        Ok(WalIndexHeader {
+            version: self.u32(offset)?,
+            change: self.u32(offset + 8)?,
+            is_init: self.buf[offset + 12] != 0,
+            big_end_checksum: self.buf[offset + 13] != 0,
+            page_size,
+            max_frame: self.u32(offset + 16)?,
+            db_size: self.u32(offset + 20)?,
+            frame_checksum: [self.u32(offset + 24)?, self.u32(offset + 28)?],
+            salt: [self.u32(offset + 32)?, self.u32(offset + 36)?],
+            checksum: [self.u32(offset + 40)?, self.u32(offset + 44)?],
+        })
+    }
+
+    fn checkpoint(&self, offset: usize) -> Result<WalCheckpointInfo, Error> {
+        let mut read_marks = [0; 5];
+        for (i, mark) in read_marks.iter_mut().enumerate() {
+            *mark = self.u32(offset + 4 + i * 4)?;
+        }
+        Ok(WalCheckpointInfo {
+            backfill: self.u32(offset)?,
+            read_marks,
+            locks: self.buf[offset + 24..offset + 32].try_into()?,
+            backfill_attempted: self.u32(offset + 32)?,
+        })
+    }
+
+    /// Block of the frames, which are valid, its page numbers of the first block
+    /// follow the header, the hash table is at the same place in every block.
+    fn block(&self, n: usize, max_frame: u32) -> Result<WalIndexBlock, Error> {
+        let offset = n * WAL_INDEX_BLOCK_SIZE;
+        let (start, first_frame, frames) = match n {
+            0 => (WAL_INDEX_HEADER_SIZE, 0, WAL_INDEX_FIRST_FRAMES),
+            _ => (
+                offset,
+                (WAL_INDEX_FIRST_FRAMES + (n - 1) * WAL_INDEX_FRAMES) as u32,
+                WAL_INDEX_FRAMES,
+            ),
+        };
+        let valid = (max_frame.saturating_sub(first_frame) as usize).min(frames);
+        let page_numbers = (0..valid)
+            .map(|i| self.u32(start + i * 4))
+            .collect::<Result<_, _>>()?;
+        let hash_start = offset + WAL_INDEX_FRAMES * 4;
+        let hash = (0..WAL_INDEX_SLOTS)
+            .map(|i| self.u16(hash_start + i * 2))
+            .collect::<Result<_, _>>()?;
+        Ok(WalIndexBlock {
+            offset,
+            first_frame,
+            page_numbers,
+            hash,
+        })
+    }
+}
+
+impl TryFrom<&[u8]> for WalIndex {
+    type Error = Error;
+
+    fn try_from(buf: &[u8]) -> Result<Self, Self::Error> {
+        if buf.len() < WAL_INDEX_HEADER_SIZE {
+            return Err(Error::Malformed(format!(
+                "Incomplete wal-index header, expected {} bytes, got: {}",
+                WAL_INDEX_HEADER_SIZE,
+                buf.len()
+            )));
+        }
+        let version = buf[0..4].try_into()?;
+        let big_endian = match (u32::from_le_bytes(version), u32::from_be_bytes(version)) {
+            (WAL_INDEX_VERSION, _) => false,
+            (_, WAL_INDEX_VERSION) => true,
+            _ => {
+                return Err(Error::Malformed(
+                    "Not a wal-index, version doesn't match.".into(),
+                ))
+            }
+        };
+        let bytes = NativeBytes { buf, big_endian };
+        let header = bytes.header(0)?;
+        let header_copy = bytes.header(WAL_INDEX_HDR_SIZE)?;
+        let checkpoint = bytes.checkpoint(2 * WAL_INDEX_HDR_SIZE)?;
+
+        // Blocks beyond the last valid frame are left from the WAL before the reset.
+        let mut blocks = vec![];
+        for n in 0..buf.len() / WAL_INDEX_BLOCK_SIZE {
+            let block = bytes.block(n, header.max_frame)?;
+            if block.first_frame >= header.max_frame {
+                break;
+            }
+            blocks.push(block);
+        }
+        Ok(Self {
+            big_endian,
+            header,
+            header_copy,
+            checkpoint,
+            blocks,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Wal-index of a little-endian host with the frames of the pages, in order.
+    fn wal_index(pages: &[u32]) -> Vec<u8> {
+        let mut buf = vec![0; WAL_INDEX_BLOCK_SIZE];
+        for copy in [0, WAL_INDEX_HDR_SIZE] {
+            buf[copy..copy + 4].copy_from_slice(&WAL_INDEX_VERSION.to_le_bytes());
+            buf[copy + 12] = 1;
+            buf[copy + 14..copy + 16].copy_from_slice(&4096_u16.to_le_bytes());
+            buf[copy + 16..copy + 20].copy_from_slice(&(pages.len() as u32).to_le_bytes());
+        }
+        // Read mark 1 is at the last frame, the rest are free.
+        for i in 1..5 {
+            let mark = if i == 1 {
+                pages.len() as u32
+            } else {
+                READ_MARK_NOT_USED
+            };
+            let offset = 2 * WAL_INDEX_HDR_SIZE + 4 + i * 4;
+            buf[offset..offset + 4].copy_from_slice(&mark.to_le_bytes());
+        }
+        for (i, page) in pages.iter().enumerate() {
+            let offset = WAL_INDEX_HEADER_SIZE + i * 4;
+            buf[offset..offset + 4].copy_from_slice(&page.to_le_bytes());
+            let mut key = WalIndexBlock::hash_key(*page);
+            let slot = |key: usize| WAL_INDEX_FRAMES * 4 + key * 2;
+            while buf[slot(key)..slot(key) + 2] != [0, 0] {
+                key = (key + 1) & (WAL_INDEX_SLOTS - 1);
+            }
+            buf[slot(key)..slot(key) + 2].copy_from_slice(&(i as u16 + 1).to_le_bytes());
+        }
+        buf
+    }
+
+    #[test]
+    fn test_parse_wal_index() {
+        let buf = wal_index(&[2, 5, 2]);
+        let index = WalIndex::try_from(buf.as_slice()).unwrap();
+        assert!(!index.big_endian);
+        assert!(index.is_consistent());
+        assert_eq!((index.header.page_size, index.header.max_frame), (4096, 3));
+        assert_eq!(index.blocks[0].page_numbers, vec![2, 5, 2]);
+        assert_eq!(index.readers().collect::<Vec<_>>(), vec![(0, 0), (1, 3)]);
+    }
+
+    #[test]
+    fn test_frame_for_page() {
+        let buf = wal_index(&[2, 5, 2]);
+        let mut index = WalIndex::try_from(buf.as_slice()).unwrap();
+        assert_eq!(index.frame_for_page(2), Some(3));
+        assert_eq!(index.frame_for_page(5), Some(2));
+        assert_eq!(index.frame_for_page(7), None);
+        // Frames beyond the last valid one are ignored.
+        index.header.max_frame = 2;
+        assert_eq!(index.frame_for_page(2), Some(1));
+    }
+}
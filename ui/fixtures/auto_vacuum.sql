@@ -0,0 +1,9 @@
+PRAGMA page_size=512;
+PRAGMA auto_vacuum=FULL;
+CREATE TABLE comets(name TEXT, period REAL);
+INSERT INTO comets VALUES('Halley', 75.3), ('Encke', 3.3), ('Hale-Bopp', 2533.0);
+CREATE TABLE notes(body TEXT);
+INSERT INTO notes WITH RECURSIVE n(v) AS (SELECT 1 UNION ALL SELECT v + 1 FROM n WHERE v < 30) SELECT 'Note ' || v || ' ' || printf('%.*c', v * 5, '.') FROM n;
+INSERT INTO notes VALUES(printf('%.*c', 1200, 'x'));
+CREATE INDEX idx_comets_name ON comets(name);
+DELETE FROM notes WHERE rowid % 3 = 0;
@@ -0,0 +1,8 @@
+PRAGMA page_size=65536;
+CREATE TABLE big_page(int);
+INSERT INTO big_page VALUES(1), (2), (3), (4);
+-- Root page without cells: the cell content area starts at 65536, which is stored as 0.
+CREATE TABLE empty(int);
+-- Payload larger than a 64 KiB page spills onto overflow pages.
+CREATE TABLE big_blob(data);
+INSERT INTO big_blob VALUES(zeroblob(100000));
@@ -0,0 +1,11 @@
+PRAGMA page_size=512;
+CREATE TABLE asteroids(name TEXT, diameter REAL);
+CREATE INDEX idx_asteroids_name ON asteroids(name);
+INSERT INTO asteroids WITH RECURSIVE n(v) AS (SELECT 1 UNION ALL SELECT v + 1 FROM n WHERE v < 120) SELECT 'Asteroid ' || v || substr('-----', 1, v % 5), v * 1.5 FROM n;
+-- snapshot 1
+-- every fourth row in the middle of the table, so neither b-tree shrinks
+DELETE FROM asteroids WHERE rowid % 4 = 2 AND rowid BETWEEN 20 AND 100;
+-- snapshot 2
+-- smaller index entries are carved out of bigger freeblocks, leaving fragments
+INSERT INTO asteroids WITH RECURSIVE n(v) AS (SELECT 1 UNION ALL SELECT v + 1 FROM n WHERE v < 10) SELECT 'Minor ' || v, v FROM n;
+-- snapshot 3
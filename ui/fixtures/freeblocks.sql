@@ -0,0 +1,8 @@
+PRAGMA page_size=1024;
+CREATE TABLE comets(name TEXT, period REAL);
+INSERT INTO comets VALUES('Halley', 75.3), ('Encke', 3.3), ('Hale-Bopp', 2533.0), ('Swift-Tuttle', 133.3), ('Tempel-Tuttle', 32.9), ('Churyumov-Gerasimenko', 6.4), ('Shoemaker-Levy 9', 0.0), ('Hyakutake', 70000.0), ('Wild 2', 6.4), ('Borrelly', 6.8);
+-- snapshot 1
+DELETE FROM comets WHERE rowid IN (2, 5, 6, 9);
+-- snapshot 2
+INSERT INTO comets VALUES('Biela', 6.6), ('Tuttle', 13.6);
+-- snapshot 3
@@ -0,0 +1,7 @@
+PRAGMA page_size=1024;
+CREATE TABLE mixed_overflow(text, blob);
+CREATE TABLE blob_overflow(blob);
+INSERT INTO blob_overflow VALUES(readfile('dev/overflow.txt'));
+INSERT INTO mixed_overflow SELECT CAST(blob as TEXT), blob FROM blob_overflow;
+DELETE FROM mixed_overflow;
+DROP TABLE blob_overflow;
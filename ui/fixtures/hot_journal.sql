@@ -0,0 +1,11 @@
+PRAGMA page_size=1024;
+CREATE TABLE planets(name TEXT, moons INT);
+INSERT INTO planets VALUES('Mercury', 0), ('Venus', 0), ('Earth', 1), ('Mars', 2);
+CREATE TABLE notes(body TEXT);
+INSERT INTO notes WITH RECURSIVE n(v) AS (SELECT 0 UNION ALL SELECT v + 1 FROM n WHERE v < 39) SELECT replace(printf('%.8c', '*'), '*', 'note ' || v || ' ') FROM n;
+-- tiny page cache spills modified pages into the database before COMMIT
+PRAGMA cache_size=1;
+BEGIN;
+UPDATE planets SET moons = moons + 100;
+UPDATE notes SET body = upper(body);
+-- database and journal are copied before COMMIT
@@ -0,0 +1,5 @@
+PRAGMA page_size=512;
+CREATE TABLE observations(star TEXT, night INT, magnitude REAL);
+INSERT INTO observations WITH RECURSIVE n(v) AS (SELECT 1 UNION ALL SELECT v + 1 FROM n WHERE v < 150) SELECT 'Star ' || (v % 30) || ' ' || printf('%.*c', iif(v % 17 = 0, 150, 70), '*'), v, (v % 13) * 0.5 FROM n;
+CREATE INDEX idx_observations_star_night ON observations(star, night DESC);
+CREATE INDEX idx_observations_magnitude ON observations(magnitude DESC);
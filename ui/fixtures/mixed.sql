@@ -0,0 +1,8 @@
+PRAGMA page_size=1024;
+CREATE TABLE blob_overflow(blob);
+INSERT INTO blob_overflow VALUES(readfile('dev/overflow.txt'));
+CREATE TABLE macro_story(line);
+INSERT INTO macro_story SELECT value FROM json_each(readlines('dev/lines.txt'));
+INSERT INTO macro_story SELECT CAST(blob as TEXT) FROM blob_overflow;
+CREATE INDEX idx_macro_story_line ON macro_story(line);
+DROP TABLE blob_overflow;
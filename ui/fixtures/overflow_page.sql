@@ -0,0 +1,6 @@
+PRAGMA page_size=1024;
+CREATE TABLE mixed_overflow(text, longint, int, blob);
+CREATE TABLE blob_overflow(blob);
+INSERT INTO blob_overflow VALUES(readfile('dev/overflow.txt'));
+INSERT INTO mixed_overflow SELECT CAST(blob as TEXT), 234234235, 0, blob FROM blob_overflow;
+INSERT INTO mixed_overflow SELECT CAST(blob as TEXT), 94542343, 1, blob FROM blob_overflow;
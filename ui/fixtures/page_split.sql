@@ -0,0 +1,11 @@
+PRAGMA page_size=512;
+CREATE TABLE planets(name TEXT, radius INT);
+-- snapshot 1
+INSERT INTO planets VALUES('Mercury', 2440), ('Venus', 6052), ('Earth', 6371);
+-- snapshot 2
+INSERT INTO planets WITH RECURSIVE n(v) AS (SELECT 1 UNION ALL SELECT v + 1 FROM n WHERE v < 10) SELECT 'Moon of Saturn ' || v, v * 10 FROM n;
+-- snapshot 3
+INSERT INTO planets WITH RECURSIVE n(v) AS (SELECT 1 UNION ALL SELECT v + 1 FROM n WHERE v < 10) SELECT 'Moon of Jupiter ' || v, v * 20 FROM n;
+-- snapshot 4
+INSERT INTO planets WITH RECURSIVE n(v) AS (SELECT 1 UNION ALL SELECT v + 1 FROM n WHERE v < 20) SELECT 'Moon of Uranus ' || v, v * 30 FROM n;
+-- snapshot 5
@@ -0,0 +1,2 @@
+CREATE TABLE simple(int);
+INSERT INTO simple VALUES(1), (2), (3), (4);
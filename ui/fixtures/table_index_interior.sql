@@ -0,0 +1,4 @@
+PRAGMA page_size=512;
+CREATE TABLE macro_story(line);
+INSERT INTO macro_story SELECT value FROM json_each(readlines('dev/lines.txt'));
+CREATE INDEX idx_macro_story_line ON macro_story(line);
@@ -0,0 +1,6 @@
+CREATE TABLE stars(id INTEGER PRIMARY KEY, name TEXT, distance REAL, brightness REAL);
+INSERT INTO stars VALUES(100, 'Sirius', 8.6, -1.46), (200, 'Altair', 16.7, 0.77), (300, 'Vega', 25, 0.03), (400, 'Polaris', 323, 2.02);
+CREATE INDEX idx_stars_name ON stars (name);
+CREATE TABLE spaceships(launched, name, operator);
+INSERT INTO spaceships VALUES(1977, 'Voyager 1', 'NASA'), (1984, 'Space Shuttle Discovery', 'NASA'), (2020, 'SpaceX Crew Dragon', 'SpaceX');
+CREATE INDEX idx_spaceships_name ON spaceships(name);
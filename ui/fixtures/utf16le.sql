@@ -0,0 +1,5 @@
+PRAGMA page_size=512;
+PRAGMA encoding='UTF-16le';
+CREATE TABLE greetings(lang TEXT, text TEXT);
+INSERT INTO greetings VALUES('English', 'Hello'), ('Русский', 'Привет'), ('日本語', 'こんにちは'), ('Emoji', '👋🌍');
+INSERT INTO greetings VALUES('Long', 'x' || replace(hex(zeroblob(249)), '00', 'ü'));
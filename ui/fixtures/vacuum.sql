@@ -0,0 +1,13 @@
+PRAGMA page_size=512;
+CREATE TABLE observations(star TEXT, note TEXT);
+INSERT INTO observations WITH RECURSIVE n(v) AS (SELECT 1 UNION ALL SELECT v + 1 FROM n WHERE v < 40) SELECT 'Star ' || v, printf('%.*c', 40 + v * 3, '.') FROM n;
+CREATE TABLE stars(name TEXT, magnitude REAL);
+CREATE INDEX idx_stars_name ON stars(name);
+INSERT INTO stars WITH RECURSIVE n(v) AS (SELECT 1 UNION ALL SELECT v + 1 FROM n WHERE v < 150) SELECT 'Star ' || v, v * 0.1 FROM n;
+-- the first table goes to the freelist as a whole, most of the stars leave freeblocks behind
+DROP TABLE observations;
+DELETE FROM stars WHERE rowid % 3 != 0;
+-- snapshot 1
+-- the database is copied into a new file table by table and the file is cut to its size
+VACUUM;
+-- snapshot 2
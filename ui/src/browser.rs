@@ -0,0 +1,317 @@
+//! Browser integration helpers.
+
+use js_sys::{Array, Function, Promise, Reflect, Uint8Array};
+use parser::StdError;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    Blob, BlobPropertyBag, Element, HtmlAnchorElement, HtmlScriptElement, Request, Response, Url,
+};
+
+/// sql.js, SQLite compiled to WebAssembly, is loaded on the first use of the playground.
+const SQL_JS_ROOT: &str = "https://cdnjs.cloudflare.com/ajax/libs/sql.js/1.10.3/";
+
+/// Fetch the whole resource at `url` as raw bytes.
+pub async fn fetch_bytes(url: &str) -> Result<Vec<u8>, StdError> {
+    let window = web_sys::window().ok_or("Fetch requires a browser window.")?;
+    let response: Response = JsFuture::from(window.fetch_with_str(url))
+        .await
+        .map_err(js_error)?
+        .dyn_into()
+        .map_err(js_error)?;
+    if !response.ok() {
+        return Err(format!("Failed to fetch {}: HTTP {}", url, response.status()).into());
+    }
+    let buffer = JsFuture::from(response.array_buffer().map_err(js_error)?)
+        .await
+        .map_err(js_error)?;
+    Ok(Uint8Array::new(&buffer).to_vec())
+}
+
+/// Bytes asked for at once from the servers of the remote databases.
+const FETCH_CHUNK: usize = 1 << 20;
+
+/// Fetch the resource at `url` by Range requests of a chunk each, reporting the
+/// bytes received so far and the total size, once it's known.
+/// Servers without range support answer the first request with the whole resource.
+pub async fn fetch_chunked(
+    url: &str,
+    mut progress: impl FnMut(usize, Option<usize>),
+) -> Result<Vec<u8>, StdError> {
+    let mut bytes = vec![];
+    loop {
+        let chunk = fetch_range(url, bytes.len(), FETCH_CHUNK).await?;
+        bytes.extend_from_slice(&chunk.bytes);
+        let total = match chunk.partial {
+            true => chunk.total,
+            false => Some(bytes.len()),
+        };
+        progress(bytes.len(), total);
+        let done = !chunk.partial
+            || chunk.bytes.len() < FETCH_CHUNK
+            || total.is_some_and(|total| bytes.len() >= total);
+        if done {
+            return Ok(bytes);
+        }
+    }
+}
+
+/// Response to a Range request.
+pub struct Chunk {
+    pub bytes: Vec<u8>,
+    /// Server has sent only the asked range, not the whole resource.
+    pub partial: bool,
+    /// Size of the resource from the Content-Range, cross-origin servers might not expose it.
+    pub total: Option<usize>,
+}
+
+/// Fetch `len` bytes of the resource at `url` starting at `start`.
+pub async fn fetch_range(url: &str, start: usize, len: usize) -> Result<Chunk, StdError> {
+    let window = web_sys::window().ok_or("Fetch requires a browser window.")?;
+    let request = Request::new_with_str(url).map_err(js_error)?;
+    let range = format!("bytes={}-{}", start, start + len - 1);
+    request.headers().set("Range", &range).map_err(js_error)?;
+    let response: Response = JsFuture::from(window.fetch_with_request(&request))
+        .await
+        .map_err(js_error)?
+        .dyn_into()
+        .map_err(js_error)?;
+    // Resource of a multiple of the chunk in size has ended with the previous chunk.
+    if response.status() == 416 && start > 0 {
+        return Ok(Chunk {
+            bytes: vec![],
+            partial: true,
+            total: Some(start),
+        });
+    }
+    if !response.ok() {
+        return Err(format!("Failed to fetch {}: HTTP {}", url, response.status()).into());
+    }
+    let total = response
+        .headers()
+        .get("Content-Range")
+        .ok()
+        .flatten()
+        .and_then(|r| r.rsplit('/').next().and_then(|total| total.parse().ok()));
+    let buffer = JsFuture::from(response.array_buffer().map_err(js_error)?)
+        .await
+        .map_err(js_error)?;
+    Ok(Chunk {
+        bytes: Uint8Array::new(&buffer).to_vec(),
+        partial: response.status() == 206,
+        total,
+    })
+}
+
+pub fn js_error(value: JsValue) -> StdError {
+    format!("{:?}", value).into()
+}
+
+/// Give control back to the browser, so it can render what is ready so far.
+pub async fn yield_now() {
+    let promise = Promise::new(&mut |resolve, _| {
+        if let Some(window) = web_sys::window() {
+            let _ = window.set_timeout_with_callback(&resolve);
+        }
+    });
+    let _ = JsFuture::from(promise).await;
+}
+
+/// Wait for the milliseconds, the browser goes on meanwhile.
+pub async fn sleep(ms: i32) {
+    let promise = Promise::new(&mut |resolve, _| {
+        if let Some(window) = web_sys::window() {
+            let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(&resolve, ms);
+        }
+    });
+    let _ = JsFuture::from(promise).await;
+}
+
+/// Replace the current URL without reloading the page or adding a history entry.
+/// Relative paths resolve against the document base.
+pub fn replace_url(path: &str) {
+    if let Some(history) = web_sys::window().and_then(|w| w.history().ok()) {
+        let _ = history.replace_state_with_url(&JsValue::NULL, "", Some(path));
+    }
+}
+
+/// Absolute URL of the path, resolved against the document base as `replace_url` does.
+pub fn absolute_url(path: &str) -> Result<String, StdError> {
+    let document = web_sys::window()
+        .and_then(|w| w.document())
+        .ok_or("Links require a browser document.")?;
+    let base = document
+        .base_uri()
+        .map_err(js_error)?
+        .ok_or("Document has no base URL.")?;
+    Ok(Url::new_with_base(path, &base).map_err(js_error)?.href())
+}
+
+/// Save bytes as a file through the browser download.
+pub fn download(name: &str, bytes: &[u8]) -> Result<(), StdError> {
+    let document = web_sys::window()
+        .and_then(|w| w.document())
+        .ok_or("Download requires a browser document.")?;
+    let options = BlobPropertyBag::new();
+    options.set_type("application/octet-stream");
+    let parts = Array::of1(&Uint8Array::from(bytes));
+    let blob = Blob::new_with_u8_array_sequence_and_options(&parts, &options).map_err(js_error)?;
+    let url = Url::create_object_url_with_blob(&blob).map_err(js_error)?;
+    let link: HtmlAnchorElement = document
+        .create_element("a")
+        .map_err(js_error)?
+        .dyn_into()
+        .map_err(|_| "Failed to create a download link.")?;
+    link.set_href(&url);
+    link.set_download(name);
+    link.click();
+    Url::revoke_object_url(&url).map_err(js_error)
+}
+
+/// Put text into the clipboard.
+pub async fn copy_to_clipboard(text: &str) -> Result<(), StdError> {
+    let window = web_sys::window().ok_or("Clipboard requires a browser window.")?;
+    // Clipboard bindings are unstable in web-sys, call the JS API directly.
+    let clipboard = Reflect::get(&window.navigator(), &"clipboard".into()).map_err(js_error)?;
+    let write_text: Function = Reflect::get(&clipboard, &"writeText".into())
+        .map_err(js_error)?
+        .dyn_into()
+        .map_err(js_error)?;
+    let promise: Promise = write_text
+        .call1(&clipboard, &text.into())
+        .map_err(js_error)?
+        .dyn_into()
+        .map_err(js_error)?;
+    JsFuture::from(promise).await.map_err(js_error)?;
+    Ok(())
+}
+
+/// Scroll element with the given id into view, if it's rendered.
+pub fn scroll_into_view(id: &str) {
+    if let Some(element) = element_by_id(id) {
+        element.scroll_into_view();
+    }
+}
+
+/// Values of `data-offset` attribute of the first and the last elements visible
+/// inside of the scrollable container, probing its left edge from top to bottom.
+pub fn visible_offsets(container_id: &str) -> Option<(usize, usize)> {
+    let document = web_sys::window()?.document()?;
+    let rect = element_by_id(container_id)?.get_bounding_client_rect();
+    let x = (rect.left() + 24.0) as f32;
+    let offset_at = |y: f64| -> Option<usize> {
+        document
+            .element_from_point(x, y as f32)?
+            .closest("[data-offset]")
+            .ok()??
+            .get_attribute("data-offset")?
+            .parse()
+            .ok()
+    };
+
+    let step = 16.0;
+    let probes = ((rect.height() / step) as usize).max(1);
+    let top = (0..probes).find_map(|n| offset_at(rect.top() + n as f64 * step))?;
+    let bottom = (0..probes).find_map(|n| offset_at(rect.bottom() - 1.0 - n as f64 * step))?;
+    Some((top, bottom))
+}
+
+/// Read value from the browser local storage.
+pub fn storage_get(key: &str) -> Option<String> {
+    web_sys::window()?
+        .local_storage()
+        .ok()??
+        .get_item(key)
+        .ok()?
+}
+
+/// Write value into the browser local storage, silently ignored if it's not available.
+pub fn storage_set(key: &str, value: &str) {
+    if let Some(Ok(Some(storage))) = web_sys::window().map(|w| w.local_storage()) {
+        let _ = storage.set_item(key, value);
+    }
+}
+
+/// Run SQL script against a new in-memory database with sql.js and export the file.
+pub async fn build_database(script: &str) -> Result<Vec<u8>, StdError> {
+    let window = web_sys::window().ok_or("SQL playground requires a browser window.")?;
+    if Reflect::get(&window, &"initSqlJs".into())
+        .map_err(js_error)?
+        .is_undefined()
+    {
+        load_script(&format!("{}sql-wasm.js", SQL_JS_ROOT)).await?;
+    }
+    let init: Function = Reflect::get(&window, &"initSqlJs".into())
+        .map_err(js_error)?
+        .dyn_into()
+        .map_err(js_error)?;
+    // WASM binary of sql.js is fetched from the same place as the script.
+    let config = js_sys::Object::new();
+    let locate_file = Function::new_with_args("file", &format!("return '{}' + file", SQL_JS_ROOT));
+    Reflect::set(&config, &"locateFile".into(), &locate_file).map_err(js_error)?;
+    let promise: Promise = init
+        .call1(&JsValue::NULL, &config)
+        .map_err(js_error)?
+        .dyn_into()
+        .map_err(js_error)?;
+    let sql = JsFuture::from(promise).await.map_err(js_error)?;
+
+    let database: Function = Reflect::get(&sql, &"Database".into())
+        .map_err(js_error)?
+        .dyn_into()
+        .map_err(js_error)?;
+    let db = Reflect::construct(&database, &Array::new()).map_err(js_error)?;
+    let call = |method: &str, args: &Array| -> Result<JsValue, StdError> {
+        let f: Function = Reflect::get(&db, &method.into())
+            .map_err(js_error)?
+            .dyn_into()
+            .map_err(js_error)?;
+        Reflect::apply(&f, &db, args).map_err(sql_error)
+    };
+    let bytes = call("exec", &Array::of1(&script.into()))
+        .and_then(|_| call("export", &Array::new()))
+        .map(|bytes| Uint8Array::new(&bytes).to_vec());
+    call("close", &Array::new())?;
+    bytes
+}
+
+/// Add a script element to the document and wait until it's loaded.
+async fn load_script(src: &str) -> Result<(), StdError> {
+    let document = web_sys::window()
+        .and_then(|w| w.document())
+        .ok_or("Loading a script requires a browser document.")?;
+    let script: HtmlScriptElement = document
+        .create_element("script")
+        .map_err(js_error)?
+        .dyn_into()
+        .map_err(|_| "Failed to create a script element.")?;
+    script.set_src(src);
+    let loaded = Promise::new(&mut |resolve, reject| {
+        script.set_onload(Some(&resolve));
+        script.set_onerror(Some(&reject));
+    });
+    document
+        .body()
+        .ok_or("Document has no body.")?
+        .append_child(&script)
+        .map_err(js_error)?;
+    JsFuture::from(loaded)
+        .await
+        .map_err(|_| format!("Failed to load {}", src))?;
+    Ok(())
+}
+
+/// Message of the error thrown by sql.js, which is what SQLite reported.
+fn sql_error(value: JsValue) -> StdError {
+    match Reflect::get(&value, &"message".into())
+        .ok()
+        .and_then(|m| m.as_string())
+    {
+        Some(message) => message.into(),
+        None => js_error(value),
+    }
+}
+
+fn element_by_id(id: &str) -> Option<Element> {
+    web_sys::window()?.document()?.get_element_by_id(id)
+}
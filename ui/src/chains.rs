@@ -0,0 +1,251 @@
+//! Pages linked into chains: the overflow chain of a cell with the record put back
+//! together from it, and the freelist with its trunk and leaf pages.
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+use dioxus_free_icons::icons::bs_icons::BsArrowRight;
+use dioxus_free_icons::Icon;
+use parser::PayloadChunk;
+
+use crate::index::{jump_to_field, jump_to_value, update_selected_page};
+use crate::state::AppState;
+use crate::Value;
+
+/// Cell of the b-tree page, which payload spilled onto the selected overflow page,
+/// opens the cell at the value the page continues.
+pub fn OverflowOwnerLink() -> Element {
+    let viewer = use_context::<AppState>().viewer;
+    let selected_page = use_context::<AppState>().selected_page;
+    let owner = selected_page().overflow_owner()?;
+    let page = viewer.read().get_page(owner.page_num as u32);
+    let cell = page
+        .field_at(owner.cell_offset)
+        .map_or(format!("cell {}", owner.cell + 1), |(np, _)| {
+            page.parts()[np].label()
+        });
+    let value = match owner.column {
+        Some(n) => match page.columns().get(n) {
+            Some(column) => format!("column '{column}'"),
+            None => format!("value {}", n + 1),
+        },
+        None => "record header".to_string(),
+    };
+    rsx! {
+        div {
+            class: "pt-2 text-xs",
+            "Owned by "
+            span {
+                class: "cursor-pointer font-medium hover:bg-secondary",
+                title: "Open the cell",
+                onclick: move |_| match owner.column {
+                    Some(n) => jump_to_value(owner.page_num, owner.cell_offset, n),
+                    None => jump_to_field(owner.page_num, owner.cell_offset),
+                },
+                "Page {owner.page_num}, {cell}, {value}"
+            }
+        }
+    }
+}
+
+/// Overflow chain the selected overflow page belongs to, from the owner cell to the
+/// end of the chain, every node opens its page.
+pub fn OverflowChainMap() -> Element {
+    let viewer = use_context::<AppState>().viewer;
+    let selected_page = use_context::<AppState>().selected_page;
+    let chain = viewer.read().overflow_chain(&*selected_page())?;
+    let current = selected_page().id();
+    let owner = chain.owner;
+    rsx! {
+        div {
+            class: "flex flex-wrap items-center gap-1 pt-2 text-xs",
+            button {
+                class: "btn btn-xs btn-ghost",
+                title: "Open the cell",
+                onclick: move |_| jump_to_field(owner.page_num, owner.cell_offset),
+                "Cell {owner.cell + 1} of page {owner.page_num}"
+            }
+            for page_num in chain.pages {
+                Icon { width: 12, height: 12, icon: BsArrowRight }
+                button {
+                    class: "btn btn-xs btn-ghost font-medium",
+                    class: if page_num == current {"btn-active"},
+                    onclick: move |_| {
+                        let page = viewer.read().get_page(page_num as u32);
+                        update_selected_page(page);
+                    },
+                    "{page_num}"
+                }
+            }
+            Icon { width: 12, height: 12, icon: BsArrowRight }
+            if chain.end == 0 {
+                span {
+                    class: "px-2",
+                    title: "End of the chain",
+                    "0"
+                }
+            } else {
+                span {
+                    class: "badge badge-xs badge-warning",
+                    title: "Chain breaks: the page can't be read or is already in the chain",
+                    "{chain.end}"
+                }
+            }
+        }
+    }
+}
+
+/// Freelist the selected page is on, trunk pages in the chain order from the database
+/// header, each with its leaf pages below, every node opens its page.
+pub fn FreelistMap() -> Element {
+    let viewer = use_context::<AppState>().viewer;
+    let selected_page = use_context::<AppState>().selected_page;
+    let current = selected_page().id();
+    let freelist = viewer.read().freelist.clone();
+    if !freelist.contains(current) {
+        return rsx! {};
+    }
+    let open_page = move |page_num: usize| {
+        let page = viewer.read().get_page(page_num as u32);
+        update_selected_page(page);
+    };
+    rsx! {
+        div {
+            class: "flex flex-wrap items-start gap-1 pt-2 text-xs",
+            button {
+                class: "btn btn-xs btn-ghost",
+                title: "Open the first freelist trunk page field of the database header",
+                onclick: move |_| jump_to_field(1, 32),
+                "Header"
+            }
+            for trunk in freelist.trunks {
+                div {
+                    class: "pt-1",
+                    Icon { width: 12, height: 12, icon: BsArrowRight }
+                }
+                div {
+                    class: "flex flex-col items-start",
+                    button {
+                        class: "btn btn-xs btn-ghost font-medium",
+                        class: if trunk.page_num == current {"btn-active"},
+                        title: "Trunk page",
+                        onclick: move |_| open_page(trunk.page_num),
+                        "⩩ {trunk.page_num}"
+                    }
+                    div {
+                        class: "flex flex-wrap max-w-48 max-h-32 overflow-y-auto",
+                        for leaf in trunk.leaves {
+                            button {
+                                class: "btn btn-xs btn-ghost",
+                                class: if leaf == current {"btn-active"},
+                                title: "Leaf page",
+                                onclick: move |_| open_page(leaf),
+                                "● {leaf}"
+                            }
+                        }
+                    }
+                }
+            }
+            div {
+                class: "pt-1",
+                Icon { width: 12, height: 12, icon: BsArrowRight }
+            }
+            if freelist.end == 0 {
+                span {
+                    class: "px-2 pt-0.5",
+                    title: "End of the trunk chain",
+                    "0"
+                }
+            } else {
+                span {
+                    class: "badge badge-xs badge-warning mt-1",
+                    title: "Chain breaks: the trunk page can't be read or is already in the chain",
+                    "{freelist.end}"
+                }
+            }
+        }
+    }
+}
+
+/// Whole record of the cell, which spilled onto overflow pages, with the pages
+/// every value is read from.
+#[component]
+pub fn ReassembledPayload(page_num: usize, cell_offset: usize) -> Element {
+    let viewer = use_context::<AppState>().viewer;
+    let selected_page = use_context::<AppState>().selected_page;
+    let columns = selected_page().columns().to_vec();
+    let payload = match viewer.read().reassemble(page_num, cell_offset) {
+        Ok(payload) => payload,
+        Err(e) => {
+            return rsx! {
+                div {
+                    class: "pt-6 text-xs text-orange-700",
+                    "Failed to reassemble the payload: {e}"
+                }
+            }
+        }
+    };
+    let size = payload.chunks.iter().map(|c| c.size).sum::<usize>();
+    let pages = payload.chunks.len();
+    let values = payload
+        .values
+        .into_iter()
+        .enumerate()
+        .map(|(n, v)| {
+            let name = columns
+                .get(n)
+                .cloned()
+                .unwrap_or_else(|| format!("#{}", n + 1));
+            (name, Value::Record(v.value).to_string(), v.chunks)
+        })
+        .collect::<Vec<_>>();
+    rsx! {
+        div {
+            class: "pt-6 text-xs",
+            div {
+                class: "font-medium",
+                "Reassembled payload: {size} byte(s) from {pages} page(s)"
+            }
+            table {
+                class: "table table-xs",
+                thead {
+                    tr {
+                        th { "Column" }
+                        th { "Value" }
+                        th { "Pages" }
+                    }
+                }
+                tbody {
+                    tr {
+                        td { "Record header" }
+                        td { "{values.len()} serial type(s)" }
+                        td { ChunkLinks { chunks: payload.header } }
+                    }
+                    for (name, value, chunks) in values {
+                        tr {
+                            td { "{name}" }
+                            td {
+                                class: "max-w-48 truncate",
+                                "{value}"
+                            }
+                            td { ChunkLinks { chunks } }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Pieces of the payload, each one opens its place on the page.
+#[component]
+pub fn ChunkLinks(chunks: Vec<PayloadChunk>) -> Element {
+    rsx! {
+        for chunk in chunks {
+            button {
+                class: "btn btn-ghost btn-xs font-normal",
+                onclick: move |_| jump_to_field(chunk.page_num, chunk.offset),
+                "Page {chunk.page_num} @{chunk.offset}, {chunk.size} B"
+            }
+        }
+    }
+}
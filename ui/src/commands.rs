@@ -0,0 +1,110 @@
+//! Command palette: jump to a page, a file offset or a b-tree by typing it.
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+use parser::StdError;
+
+use crate::index::{jump_to_field, update_selected_page};
+use crate::state::{AppState, Command};
+
+/// Jump to a page, a file offset or a b-tree by typing it, opened with Ctrl+K.
+pub fn CommandPalette() -> Element {
+    let viewer = use_context::<AppState>().viewer;
+    let mut commands = use_context::<AppState>().commands;
+    let mut input = use_signal(String::new);
+    let mut failure = use_signal(|| None::<String>);
+    // Tree names, which complete the typed one.
+    let typed = input();
+    let prefix = match Command::parse(&typed) {
+        Ok(Command::Tree(name)) => Some(name.to_lowercase()),
+        _ if typed.trim_end().to_lowercase().ends_with("tree") => Some(String::new()),
+        _ => None,
+    };
+    let trees = viewer
+        .read()
+        .btrees
+        .iter()
+        .map(|btree| btree.name.clone())
+        .filter(|name| {
+            prefix
+                .as_ref()
+                .is_some_and(|p| name.to_lowercase().starts_with(p))
+        })
+        .collect::<Vec<_>>();
+    rsx! {
+        div {
+            class: "fixed inset-0 z-50 flex justify-center items-start pt-24 bg-black/30",
+            onclick: move |_| *commands.write() = false,
+            div {
+                class: "w-[32rem] p-2 rounded-box shadow bg-base-100 text-sm",
+                onclick: move |e| e.stop_propagation(),
+                input {
+                    class: "input input-bordered input-sm w-full",
+                    placeholder: "go to page 37, go to offset 0x3A00, open tree idx_stars_name",
+                    value: "{input}",
+                    onmounted: move |e| async move {
+                        let _ = e.data().set_focus(true).await;
+                    },
+                    oninput: move |e| {
+                        input.set(e.value());
+                        failure.set(None);
+                    },
+                    onkeydown: move |e| {
+                        e.stop_propagation();
+                        match e.key() {
+                            Key::Escape => *commands.write() = false,
+                            Key::Enter => match run_command(&input()) {
+                                Ok(()) => *commands.write() = false,
+                                Err(err) => failure.set(Some(err.to_string())),
+                            },
+                            _ => (),
+                        }
+                    },
+                }
+                if let Some(err) = failure() {
+                    div {
+                        class: "px-2 pt-2 text-xs text-orange-700",
+                        "{err}"
+                    }
+                }
+                for name in trees {
+                    div {
+                        class: "px-2 py-1 text-xs cursor-pointer hover:bg-secondary",
+                        onclick: move |_| {
+                            if run_command(&format!("tree {}", name)).is_ok() {
+                                *commands.write() = false;
+                            }
+                        },
+                        "open tree {name}"
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn run_command(input: &str) -> Result<(), StdError> {
+    let viewer = use_context::<AppState>().viewer;
+    let page_count = viewer.read().pages.len();
+    match Command::parse(input)? {
+        Command::Page(n) => match viewer.read().pages.get(&n).cloned() {
+            Some(page) => update_selected_page(page),
+            None => return Err(format!("There are {} pages, no Page {}", page_count, n).into()),
+        },
+        Command::Offset(offset) => {
+            let found = viewer.read().page_for_offset(offset);
+            match found {
+                Some((page_num, page_offset)) => jump_to_field(page_num, page_offset),
+                None => return Err(format!("Offset {} is past the end of the file", offset).into()),
+            }
+        }
+        Command::Tree(name) => {
+            let root = viewer.read().tree_by_name(&name).map(|b| b.root.page_num);
+            match root {
+                Some(root) => update_selected_page(viewer.read().get_page(root as u32)),
+                None => return Err(format!("There is no table or index {}", name).into()),
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,117 @@
+//! Compared database next to the current one: the summary of the changed pages, fields
+//! removed from the page and the same page of the compared database.
+#![allow(non_snake_case)]
+
+use std::rc::Rc;
+
+use dioxus::prelude::*;
+
+use crate::diff::Change;
+use crate::index::FormattedValue;
+use crate::state::AppState;
+
+/// Number of pages changed against the compared database.
+pub fn DiffSummary() -> Element {
+    let viewer = use_context::<AppState>().viewer;
+    let compare = use_context::<AppState>().compare;
+    let compare = compare.read();
+    let compare = compare.as_ref()?;
+    let changed = compare.changed_pages(&viewer.read());
+    let count = |change: Change| changed.values().filter(|&&c| c == change).count();
+    rsx! {
+        div {
+            class: "px-2 tracking-tighter",
+            "vs {compare.name}: {count(Change::Modified)} changed, {count(Change::Added)} added, {count(Change::Removed)} removed pages"
+        }
+    }
+}
+
+/// Fields of the compared page, which aren't on the current page anymore.
+#[component]
+pub fn RemovedFields(page_num: usize) -> Element {
+    let viewer = use_context::<AppState>().viewer;
+    let theme = use_context::<AppState>().theme;
+    let compare = use_context::<AppState>().compare;
+    let addressing = use_context::<AppState>().addressing;
+    let language = use_context::<AppState>().language;
+    let removed = match compare.read().as_ref() {
+        Some(compare) => compare.removed_fields(&viewer.read(), page_num),
+        None => vec![],
+    };
+    let page = viewer.read().get_page(page_num as u32);
+    rsx! {
+        for field in removed {
+            div {
+                div {
+                    class: "mb-0 mt-1 pr-2 leading-tight tracking-tighter font-medium",
+                    "{addressing().offset(page.as_ref(), field.offset)}",
+                }
+                div {
+                    class: "p-1 outline outline-1 outline-secondary border-t-4 {theme().surface()}",
+                    class: "{Change::Removed.style()}",
+                    title: "Removed: {language().tr(field.desc)}",
+                    FormattedValue {field: Rc::new(field.clone()), trimmed: true}
+                }
+            }
+        }
+    }
+}
+
+/// The same page of the compared database with changes marked.
+#[component]
+pub fn ComparePane(page_num: usize) -> Element {
+    let viewer = use_context::<AppState>().viewer;
+    let compare = use_context::<AppState>().compare;
+    let palette = use_context::<AppState>().palette;
+    let theme = use_context::<AppState>().theme;
+    let addressing = use_context::<AppState>().addressing;
+    let compare = compare.read();
+    let compare = compare.as_ref()?;
+    let Some(page) = compare.base.pages.get(&page_num).cloned() else {
+        return rsx! {
+            div {
+                class: "p-4 text-sm font-medium tracking-tighter",
+                "{compare.name}: there is no Page {page_num}"
+            }
+        };
+    };
+    let fields = page
+        .parts()
+        .iter()
+        .flat_map(|part| {
+            part.fields()
+                .iter()
+                .map(move |field| (palette().color(part.role()), field.clone()))
+        })
+        .map(|(color, field)| {
+            let change = compare.base_field_change(&viewer.read(), page.as_ref(), &field);
+            (color, field, change.map_or("", |c| c.style()))
+        })
+        .collect::<Vec<_>>();
+    rsx! {
+        div {
+            class: "p-4 text-xs",
+            div {
+                class: "text-sm font-medium tracking-tighter",
+                "{compare.name}: Page {page_num}"
+            }
+            div {
+                class: "flex flex-wrap content-start",
+                for (color, field, change) in fields {
+                    div {
+                        div {
+                            class: "mb-0 mt-1 pr-2 leading-tight tracking-tighter font-medium text-{color}-{theme().part_shade()}",
+                            "{addressing().offset(page.as_ref(), field.offset)}",
+                        }
+                        div {
+                            class: "p-1 outline outline-1 outline-secondary border-t-4 border-{color}-{theme().part_shade()} {theme().surface()}",
+                            class: "{field.style.class(theme())}",
+                            class: "{change}",
+                            FormattedValue {field: field.clone(), trimmed: true}
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,108 @@
+//! Recognize common content kept in blobs by its leading bytes, so the Description
+//! panel can preview it: images as thumbnails, text as is, zlib streams by what they
+//! decompress to.
+
+use parser::inflate::zlib_decompress;
+
+/// Characters of text shown in a preview.
+pub const TEXT_PREVIEW: usize = 4000;
+/// Zlib streams, which decompress to more bytes, aren't previewed.
+pub const ZLIB_PREVIEW_LIMIT: usize = 1 << 20;
+
+const PNG_MAGIC: &[u8] = b"\x89PNG\r\n\x1a\n";
+const JPEG_MAGIC: &[u8] = b"\xff\xd8\xff";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlobContent {
+    Png,
+    Jpeg,
+    /// UTF-8 without control characters but whitespace.
+    Text,
+    /// Zlib stream with the data it decompresses to.
+    Zlib(Vec<u8>),
+}
+
+impl BlobContent {
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(PNG_MAGIC) {
+            return Some(Self::Png);
+        }
+        if bytes.starts_with(JPEG_MAGIC) {
+            return Some(Self::Jpeg);
+        }
+        if is_zlib(bytes) {
+            if let Ok(data) = zlib_decompress(bytes, ZLIB_PREVIEW_LIMIT) {
+                return Some(Self::Zlib(data));
+            }
+        }
+        text(bytes).map(|_| Self::Text)
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Self::Png => "PNG image".to_string(),
+            Self::Jpeg => "JPEG image".to_string(),
+            Self::Text => "UTF-8 text".to_string(),
+            Self::Zlib(data) => match Self::detect(data) {
+                Some(inner) => format!("zlib stream of {} bytes of {}", data.len(), inner.label()),
+                None => format!("zlib stream of {} bytes", data.len()),
+            },
+        }
+    }
+
+    /// Media type of the images, which the browser shows.
+    pub fn mime(&self) -> Option<&'static str> {
+        match self {
+            Self::Png => Some("image/png"),
+            Self::Jpeg => Some("image/jpeg"),
+            _ => None,
+        }
+    }
+}
+
+/// Two header bytes of a zlib stream of DEFLATE data: compression method 8 and
+/// the check bits, which make them a multiple of 31.
+fn is_zlib(bytes: &[u8]) -> bool {
+    match bytes {
+        [cmf, flg, ..] => cmf & 0x0f == 8 && (u16::from(*cmf) << 8 | u16::from(*flg)) % 31 == 0,
+        _ => false,
+    }
+}
+
+/// Bytes as text, if they're readable.
+pub fn text(bytes: &[u8]) -> Option<&str> {
+    let text = std::str::from_utf8(bytes).ok()?;
+    let readable = !text.is_empty()
+        && text
+            .chars()
+            .all(|c| !c.is_control() || matches!(c, '\n' | '\r' | '\t'));
+    readable.then_some(text)
+}
+
+/// Leading characters of the text for a preview.
+pub fn text_preview(text: &str) -> String {
+    text.chars().take(TEXT_PREVIEW).collect()
+}
+
+/// URL, which embeds the bytes, to show them in an `img` without a request.
+pub fn data_url(mime: &str, bytes: &[u8]) -> String {
+    format!("data:{};base64,{}", mime, base64(bytes))
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0_u32, |n, (i, b)| n | u32::from(*b) << (16 - 8 * i));
+        for i in 0..4 {
+            match i <= chunk.len() {
+                true => out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char),
+                false => out.push('='),
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,51 @@
+//! Corrupt page UI representation and description
+use std::rc::Rc;
+
+use parser::Error;
+
+use crate::{Field, FieldKind, Part, Role, Style, Value};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorruptPart {
+    error: Error,
+    fields: Vec<Rc<Field>>,
+}
+
+impl CorruptPart {
+    pub fn new(bytes: &[u8], error: &Error) -> Self {
+        let fields = vec![Rc::new(Field::new(
+            FieldKind::Corrupt,
+            "Raw content of the page, as much of it as the file has. It's expected to be a part of some structure, but fails to parse as one.",
+            0,
+            bytes.len(),
+            Value::Unallocated(bytes.into()),
+            Style::Plain,
+        ))];
+        Self {
+            error: error.clone(),
+            fields,
+        }
+    }
+}
+
+impl Part for CorruptPart {
+    fn label(&self) -> String {
+        format!("Corrupt: {}", self.error)
+    }
+
+    fn desc(&self) -> &'static str {
+        match self.error {
+            Error::TruncatedPage { .. } => "The file ends before this page does, the database was cut short: copied or downloaded partially, or the write was interrupted. Only the bytes, which the file has, are shown. SQLite would report SQLITE_CORRUPT when reading it, the rest of the file can still be explored.",
+            Error::OutOfBounds { .. } => "The page is referenced, but its number is beyond the end of the database: either the pointer to it is broken or the file is shorter than the database header says. There are no bytes to show for it.",
+            _ => "The page is referenced, but its content doesn't make sense: pointers lead outside of the page, values are out of range or the file ends too early. SQLite would report SQLITE_CORRUPT when reading it, the rest of the file can still be explored.",
+        }
+    }
+
+    fn role(&self) -> Role {
+        Role::Unused
+    }
+
+    fn fields(&self) -> &[Rc<Field>] {
+        self.fields.as_slice()
+    }
+}
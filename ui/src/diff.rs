@@ -0,0 +1,233 @@
+//! Compare two databases, e.g. before and after an INSERT, page by page and field by field.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::rc::Rc;
+
+use crate::viewer::Viewer;
+use crate::{Field, PageView};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Change {
+    Added,
+    Removed,
+    Modified,
+}
+
+impl Change {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Added => "added",
+            Self::Removed => "removed",
+            Self::Modified => "changed",
+        }
+    }
+
+    /// Custom class of the field, see input.css.
+    pub fn style(&self) -> &'static str {
+        match self {
+            Self::Added => "diff-added",
+            Self::Removed => "diff-removed",
+            Self::Modified => "diff-modified",
+        }
+    }
+}
+
+/// What VACUUM did to the base database to turn it into the current one, see `Compare::vacuumed`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Vacuumed {
+    /// Freelist pages of the base, which aren't free anymore: either reused, or cut off
+    /// together with the end of the file, if the current database is shorter.
+    pub reclaimed: Vec<usize>,
+    /// Pages of the base, besides the freelist ones, with different bytes now or past
+    /// the end of the current file, as their content is copied to other pages.
+    pub rewritten: Vec<usize>,
+    /// B-trees, which start at another page now: name, base and current root page.
+    pub moved: Vec<(String, usize, usize)>,
+}
+
+/// Changes of the fields of one page, by the field offset and size.
+type FieldChanges = HashMap<(usize, usize), Change>;
+
+/// Changed fields of the same page in the current and the base database.
+#[derive(Debug)]
+struct PageFields {
+    current: FieldChanges,
+    base: FieldChanges,
+}
+
+/// Database the current one is compared with.
+#[derive(Clone, Debug)]
+pub struct Compare {
+    /// What the base database is, shown to the user.
+    pub name: String,
+    pub base: Viewer,
+    /// Field changes of the current and the base page, by the hash of the current
+    /// database and the page number, see `Compare::page_fields`. Shared by the clones.
+    #[allow(clippy::type_complexity)]
+    fields: Rc<RefCell<HashMap<(u64, usize), Rc<PageFields>>>>,
+}
+
+impl Compare {
+    pub fn new(name: String, base: Viewer) -> Self {
+        Self {
+            name,
+            base,
+            fields: Rc::default(),
+        }
+    }
+
+    /// Changes of the pages, which differ between the base and the current database.
+    pub fn changed_pages(&self, current: &Viewer) -> BTreeMap<usize, Change> {
+        let mut pages = self.base.pages.keys().collect::<Vec<_>>();
+        pages.extend(current.pages.keys());
+        pages.sort();
+        pages.dedup();
+        pages
+            .into_iter()
+            .filter_map(|&n| Some((n, self.page_change(current, n)?)))
+            .collect()
+    }
+
+    /// Freelist pages taken back and pages rewritten on the way from the base to the
+    /// current database, which is meant to be the base after VACUUM.
+    pub fn vacuumed(&self, current: &Viewer) -> Vacuumed {
+        let freelist = &self.base.freelist;
+        let mut reclaimed = freelist
+            .trunks
+            .iter()
+            .map(|t| t.page_num)
+            .chain(freelist.leaves())
+            .filter(|&n| !current.freelist.contains(n))
+            .collect::<Vec<_>>();
+        reclaimed.sort();
+        let rewritten = self
+            .changed_pages(current)
+            .into_iter()
+            .filter(|&(n, change)| change != Change::Added && !freelist.contains(n))
+            .map(|(n, _)| n)
+            .collect();
+        let moved = self
+            .base
+            .btrees
+            .iter()
+            .filter_map(|base| {
+                let tree = current.btrees.iter().find(|t| t.name == base.name)?;
+                let (from, to) = (base.root.page_num, tree.root.page_num);
+                (from != to).then(|| (base.name.clone(), from, to))
+            })
+            .collect();
+        Vacuumed {
+            reclaimed,
+            rewritten,
+            moved,
+        }
+    }
+
+    /// Change of the whole page, None if its bytes are the same.
+    pub fn page_change(&self, current: &Viewer, page_num: usize) -> Option<Change> {
+        match (self.base.pages.get(&page_num), current.pages.get(&page_num)) {
+            (None, Some(_)) => Some(Change::Added),
+            (Some(_), None) => Some(Change::Removed),
+            (Some(base), Some(page)) => {
+                let same = self.base.page_bytes(base.as_ref()) == current.page_bytes(page.as_ref());
+                (!same).then_some(Change::Modified)
+            }
+            (None, None) => None,
+        }
+    }
+
+    /// Change of the field of the current page compared to the base.
+    pub fn field_change(
+        &self,
+        current: &Viewer,
+        page: &dyn PageView,
+        field: &Field,
+    ) -> Option<Change> {
+        let fields = self.page_fields(current, page.id());
+        fields.current.get(&(field.offset, field.size)).copied()
+    }
+
+    /// Change of the field of the base page compared to the current one: fields,
+    /// which don't exist anymore, are removed.
+    pub fn base_field_change(
+        &self,
+        current: &Viewer,
+        page: &dyn PageView,
+        field: &Field,
+    ) -> Option<Change> {
+        let fields = self.page_fields(current, page.id());
+        fields.base.get(&(field.offset, field.size)).copied()
+    }
+
+    /// Fields of the base page without a counterpart at the same offset in the current page.
+    pub fn removed_fields(&self, current: &Viewer, page_num: usize) -> Vec<Field> {
+        let Some(base) = self.base.pages.get(&page_num) else {
+            return vec![];
+        };
+        base.parts()
+            .iter()
+            .flat_map(|part| part.fields().iter())
+            .filter(|f| self.base_field_change(current, base.as_ref(), f) == Some(Change::Removed))
+            .map(|f| f.as_ref().clone())
+            .collect()
+    }
+
+    /// Changed fields of the current and the base page, compared once for all the
+    /// fields of the page rather than on every render of one of them.
+    fn page_fields(&self, current: &Viewer, page_num: usize) -> Rc<PageFields> {
+        let key = (current.hash, page_num);
+        if let Some(fields) = self.fields.borrow().get(&key) {
+            return fields.clone();
+        }
+        let base = Self::compare_fields(current, &self.base, page_num)
+            .into_iter()
+            .map(|(field, change)| match change {
+                Change::Added => (field, Change::Removed),
+                change => (field, change),
+            })
+            .collect();
+        let fields = Rc::new(PageFields {
+            current: Self::compare_fields(&self.base, current, page_num),
+            base,
+        });
+        // Pages of a remote database are fetched as they're opened, so the bytes
+        // compared might not be there yet.
+        if current.remote.is_none() && self.base.remote.is_none() {
+            self.fields.borrow_mut().insert(key, fields.clone());
+        }
+        fields
+    }
+
+    /// Field of the page in `to` is added, if `from` has no field of the same size
+    /// at the same offset, and modified, if the bytes under it differ.
+    fn compare_fields(from: &Viewer, to: &Viewer, page_num: usize) -> FieldChanges {
+        // Journal pseudo page isn't a part of the database file.
+        let Some(page) = to.pages.get(&page_num).filter(|_| page_num != 0) else {
+            return FieldChanges::new();
+        };
+        let fields = |page: &Rc<dyn PageView>| {
+            page.parts()
+                .iter()
+                .flat_map(|part| part.fields().iter())
+                .map(|f| (f.offset, f.size))
+                .collect::<Vec<_>>()
+        };
+        let from_page = from.pages.get(&page_num);
+        let counterparts = from_page.map(fields).unwrap_or_default();
+        let counterparts = counterparts.into_iter().collect::<HashSet<_>>();
+        let from_bytes = from_page.map_or(&[][..], |p| from.page_bytes(p.as_ref()));
+        let to_bytes = to.page_bytes(page.as_ref());
+        fields(page)
+            .into_iter()
+            .filter_map(|(offset, size)| {
+                if !counterparts.contains(&(offset, size)) {
+                    return Some(((offset, size), Change::Added));
+                }
+                let range = offset..offset + size;
+                let same = from_bytes.get(range.clone()) == to_bytes.get(range);
+                (!same).then_some(((offset, size), Change::Modified))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,361 @@
+//! Selected page as bytes: the hex/ASCII dump synchronized with the field grid and
+//! the raw byte grid, which doesn't rely on the page being parsed.
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+use parser::Interpretation;
+
+use crate::state::{AppState, HexSettings, HEX_GROUP_SIZES, HEX_ROW_SIZES};
+
+/// Bytes rendered at once, the window follows the selected field.
+const DUMP_WINDOW_SIZE: usize = 1024;
+
+/// Classic hex/ASCII dump of the selected page. Every byte is mapped onto the field
+/// covering it, so hovering a byte selects the field and the selected field is
+/// highlighted in the dump.
+pub fn HexDump() -> Element {
+    let selected_page = use_context::<AppState>().selected_page;
+    let selected_field = use_context::<AppState>().selected_field;
+    let viewer = use_context::<AppState>().viewer;
+    let mut hex_settings = use_context::<AppState>().hex_settings;
+    let addressing = use_context::<AppState>().addressing;
+    // Window moved manually: first row and the offset of the field selected at the time.
+    let mut moved = use_signal(|| None::<(usize, usize)>);
+
+    let HexSettings { row_size, group } = hex_settings();
+    let window_rows = DUMP_WINDOW_SIZE / row_size;
+    let page = selected_page();
+    let parts = page.parts();
+    let bytes = viewer.read().page_bytes(page.as_ref());
+    let field = selected_field();
+    let total_rows = bytes.len().div_ceil(row_size);
+    let field_row = field.offset / row_size;
+    let first_row = match moved() {
+        Some((offset, field_offset))
+            if field_offset == field.offset
+                || (offset..offset + DUMP_WINDOW_SIZE).contains(&field.offset) =>
+        {
+            offset / row_size
+        }
+        _ => field_row.saturating_sub(window_rows / 4),
+    }
+    .min(total_rows.saturating_sub(window_rows));
+    let last_row = (first_row + window_rows).min(total_rows);
+
+    // Map every byte of the window onto the part and field covering it.
+    let start = first_row * row_size;
+    let end = (last_row * row_size).min(bytes.len());
+    let mut owners = vec![None; end - start];
+    for (np, part) in parts.iter().enumerate() {
+        for (nf, f) in part.fields().iter().enumerate() {
+            for offset in f.offset.max(start)..(f.offset + f.size).min(end) {
+                owners[offset - start] = Some((np, nf));
+            }
+        }
+    }
+    let selected = field.offset..field.offset + field.size;
+    let field_offset = field.offset;
+    let mut update = move |settings: HexSettings| {
+        settings.save();
+        *hex_settings.write() = settings;
+    };
+
+    rsx! {
+        div {
+            class: "p-4 text-xs font-mono",
+            div {
+                class: "flex items-center font-sans",
+                div {
+                    class: "text-sm font-medium tracking-tighter",
+                    "Hex dump: bytes {start}..{end} of {bytes.len()}"
+                }
+                div { class: "flex-grow" }
+                div {
+                    class: "join pr-2",
+                    for size in HEX_ROW_SIZES {
+                        button {
+                            class: "join-item btn btn-xs btn-ghost",
+                            class: if size == row_size {"btn-active"},
+                            title: "Bytes per row",
+                            onclick: move |_| update(HexSettings { row_size: size, group }),
+                            "{size}"
+                        }
+                    }
+                }
+                div {
+                    class: "join pr-2",
+                    for size in HEX_GROUP_SIZES {
+                        button {
+                            class: "join-item btn btn-xs btn-ghost",
+                            class: if size == group {"btn-active"},
+                            title: "Bytes per group",
+                            onclick: move |_| update(HexSettings { row_size, group: size }),
+                            "×{size}"
+                        }
+                    }
+                }
+                button {
+                    class: "btn btn-xs btn-ghost",
+                    disabled: first_row == 0,
+                    onclick: move |_| {
+                        *moved.write() = Some((start.saturating_sub(DUMP_WINDOW_SIZE), field_offset));
+                    },
+                    "Earlier"
+                }
+                button {
+                    class: "btn btn-xs btn-ghost",
+                    disabled: last_row >= total_rows,
+                    onclick: move |_| {
+                        *moved.write() = Some((end, field_offset));
+                    },
+                    "Later"
+                }
+            }
+            for row in first_row..last_row {
+                div {
+                    class: "flex space-x-3 leading-tight",
+                    div {
+                        class: "w-14 text-right text-cyan-950",
+                        "{addressing().offset(page.as_ref(), row * row_size)}"
+                    }
+                    div {
+                        class: "flex",
+                        for offset in row * row_size..((row + 1) * row_size).min(end) {
+                            DumpByte {
+                                text: format!("{:02x}", bytes[offset]),
+                                owner: owners[offset - start],
+                                selected: selected.contains(&offset),
+                                gap: (offset + 1) % group == 0,
+                            }
+                        }
+                    }
+                    div {
+                        class: "flex",
+                        for offset in row * row_size..((row + 1) * row_size).min(end) {
+                            DumpByte {
+                                text: printable(bytes[offset]).to_string(),
+                                owner: owners[offset - start],
+                                selected: selected.contains(&offset),
+                                gap: false,
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Bytes rendered at once in the raw mode.
+const RAW_WINDOW_SIZE: usize = 4096;
+
+/// Selected page as a plain grid of bytes under a ruler of the column offsets.
+/// Nothing of the parsed page is used but its place in the file, so the bytes
+/// are there to look at even if the page isn't understood. Bytes selected by a
+/// click and a shift-click are read as any structure of the format.
+pub fn RawPage() -> Element {
+    let selected_page = use_context::<AppState>().selected_page;
+    let viewer = use_context::<AppState>().viewer;
+    let hex_settings = use_context::<AppState>().hex_settings;
+    let addressing = use_context::<AppState>().addressing;
+    // Start of the window and the page it was moved on.
+    let mut moved = use_signal(|| None::<(usize, usize)>);
+    // Page and the first and the last selected offsets, in the order they were clicked.
+    let mut range = use_signal(|| None::<(usize, usize, usize)>);
+    let mut how = use_signal(|| Interpretation::Varint);
+
+    let HexSettings { row_size, group } = hex_settings();
+    let page = selected_page();
+    let bytes = viewer.read().page_bytes(page.as_ref());
+    let start = match moved() {
+        Some((page_num, offset)) if page_num == page.id() && offset < bytes.len() => offset,
+        _ => 0,
+    };
+    let end = (start + RAW_WINDOW_SIZE).min(bytes.len());
+    let page_num = page.id();
+    let rows = (start..end).step_by(row_size).collect::<Vec<_>>();
+    let selected = match range() {
+        Some((p, from, to)) if p == page_num && from.max(to) < bytes.len() => {
+            Some(from.min(to)..from.max(to) + 1)
+        }
+        _ => None,
+    };
+    let result = selected
+        .clone()
+        .map(|s| viewer.read().interpret(&bytes[s], how()));
+
+    rsx! {
+        div {
+            class: "p-4 text-xs font-mono",
+            div {
+                class: "flex items-center font-sans pb-1",
+                div {
+                    class: "text-sm font-medium tracking-tighter",
+                    "Raw bytes {start}..{end} of {bytes.len()}"
+                }
+                div { class: "flex-grow" }
+                button {
+                    class: "btn btn-xs btn-ghost",
+                    disabled: start == 0,
+                    onclick: move |_| {
+                        *moved.write() = Some((page_num, start.saturating_sub(RAW_WINDOW_SIZE)));
+                    },
+                    "Earlier"
+                }
+                button {
+                    class: "btn btn-xs btn-ghost",
+                    disabled: end >= bytes.len(),
+                    onclick: move |_| {
+                        *moved.write() = Some((page_num, end));
+                    },
+                    "Later"
+                }
+            }
+            if bytes.is_empty() {
+                div {
+                    class: "font-sans italic text-slate-500",
+                    "Bytes of the page aren't loaded yet."
+                }
+            }
+            div {
+                class: "font-sans pb-2",
+                match &selected {
+                    Some(s) => rsx! {
+                        div {
+                            class: "flex flex-wrap items-center gap-1",
+                            span {
+                                class: "pr-1 tracking-tighter",
+                                "Bytes {s.start}..{s.end} as"
+                            }
+                            for option in Interpretation::ALL {
+                                button {
+                                    class: "btn btn-xs btn-ghost",
+                                    class: if how() == option {"btn-active"},
+                                    onclick: move |_| how.set(option),
+                                    "{option}"
+                                }
+                            }
+                        }
+                        match result {
+                            Some(Ok(lines)) => rsx! {
+                                div {
+                                    class: "alert bg-secondary mt-2 text-xs flex flex-col items-start gap-1",
+                                    for line in lines {
+                                        div { "{line}" }
+                                    }
+                                }
+                            },
+                            Some(Err(err)) => rsx! {
+                                div {
+                                    class: "pt-2 text-orange-700",
+                                    "{err}"
+                                }
+                            },
+                            None => rsx! {},
+                        }
+                    },
+                    None => rsx! {
+                        div {
+                            class: "italic text-slate-500",
+                            "Click a byte and shift-click another one to read the bytes between as a varint, a u32, a record header or a cell."
+                        }
+                    },
+                }
+            }
+            div {
+                class: "flex space-x-3 leading-tight border-b border-slate-500 text-cyan-950",
+                div { class: "w-14" }
+                div {
+                    class: "flex",
+                    for column in 0..row_size {
+                        span {
+                            class: "px-px",
+                            class: if (column + 1) % group == 0 {"mr-1"},
+                            "{column:02x}"
+                        }
+                    }
+                }
+            }
+            for row in rows {
+                div {
+                    class: "flex space-x-3 leading-tight",
+                    div {
+                        class: "w-14 text-right text-cyan-950",
+                        "{addressing().offset(page.as_ref(), row)}"
+                    }
+                    div {
+                        class: "flex",
+                        for offset in row..(row + row_size).min(end) {
+                            span {
+                                class: "px-px cursor-pointer hover:bg-secondary",
+                                class: if selected.as_ref().is_some_and(|s| s.contains(&offset)) {"locked"},
+                                class: if (offset + 1) % group == 0 {"mr-1"},
+                                title: "{offset}",
+                                onclick: move |e| {
+                                    let from = match range() {
+                                        Some((p, from, _)) if p == page_num && e.modifiers().shift() => from,
+                                        _ => offset,
+                                    };
+                                    range.set(Some((page_num, from, offset)));
+                                },
+                                "{bytes[offset]:02x}"
+                            }
+                        }
+                    }
+                    div {
+                        for offset in row..(row + row_size).min(end) {
+                            "{printable(bytes[offset])}"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Single byte of the hex dump, synchronized with the field it belongs to.
+#[component]
+fn DumpByte(text: String, owner: Option<(usize, usize)>, selected: bool, gap: bool) -> Element {
+    let selected_page = use_context::<AppState>().selected_page;
+    let mut selected_field = use_context::<AppState>().selected_field;
+    let mut selected_part = use_context::<AppState>().selected_part;
+    let mut locked = use_context::<AppState>().locked_field;
+    rsx! {
+        span {
+            class: "px-px hover:bg-secondary",
+            class: if selected {"locked"},
+            class: if owner.is_none() {"text-slate-400"},
+            class: if gap {"mr-1"},
+            onmouseover: move |_| {
+                if locked().is_some() {return}
+                let Some((np, nf)) = owner else {return};
+
+                let part = selected_page().parts()[np].clone();
+                *selected_field.write() = part.fields()[nf].clone();
+                *selected_part.write() = part;
+            },
+            onclick: move |_| {
+                let Some((np, nf)) = owner else {return};
+                if locked() == Some((np, nf)) {
+                    *locked.write() = None;
+                    return;
+                }
+
+                let part = selected_page().parts()[np].clone();
+                *locked.write() = Some((np, nf));
+                *selected_field.write() = part.fields()[nf].clone();
+                *selected_part.write() = part;
+            },
+            "{text}"
+        }
+    }
+}
+
+/// ASCII representation of a byte, dot for non-printable ones.
+fn printable(byte: u8) -> char {
+    if byte.is_ascii_graphic() || byte == b' ' {
+        byte as char
+    } else {
+        '.'
+    }
+}
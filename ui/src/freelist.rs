@@ -1,6 +1,6 @@
 use std::rc::Rc;
 
-use crate::{Field, Part, Value};
+use crate::{Field, FieldKind, Part, Role, Style, Value};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct NextPagePart {
@@ -10,11 +10,12 @@ pub struct NextPagePart {
 impl NextPagePart {
     pub fn new(next_page: u32) -> Self {
         let fields = vec![Rc::new(Field::new(
+            FieldKind::FreelistNextTrunk,
             "Value is the next freelist trunk page in a linked list.",
             0,
             4,
             Value::PageNumber(next_page),
-            "",
+            Style::Plain,
         ))];
         Self { fields }
     }
@@ -29,8 +30,8 @@ impl Part for NextPagePart {
         "Freelist trunk pages form a linked list. The first four bytes of each freelist trunk page are a big-endian integer which is the page number of the next page in the chain, or zero for the final page in the chain."
     }
 
-    fn color(&self) -> String {
-        "green".to_string()
+    fn role(&self) -> Role {
+        Role::Pointers
     }
 
     fn fields(&self) -> &[Rc<Field>] {
@@ -46,11 +47,12 @@ pub struct LeafPageAmountPart {
 impl LeafPageAmountPart {
     pub fn new(leaf_page_amount: u32) -> Self {
         let fields = vec![Rc::new(Field::new(
+            FieldKind::FreelistLeafCount,
             "",
             4,
             4,
             Value::U32(leaf_page_amount),
-            "",
+            Style::Plain,
         ))];
         Self { fields }
     }
@@ -65,8 +67,8 @@ impl Part for LeafPageAmountPart {
         "Amount of freelist leaf pages to follow found on this trunk page."
     }
 
-    fn color(&self) -> String {
-        "orange".to_string()
+    fn role(&self) -> Role {
+        Role::Header
     }
 
     fn fields(&self) -> &[Rc<Field>] {
@@ -86,11 +88,12 @@ impl LeafPageNumbersPart {
 
         for pn in page_numbers {
             fields.push(Rc::new(Field::new(
+                FieldKind::FreelistLeafPage,
                 "Freelist leaf page number.",
                 offset,
                 4,
                 Value::PageNumber(*pn),
-                "",
+                Style::Plain,
             )));
             offset += 4;
         }
@@ -107,8 +110,8 @@ impl Part for LeafPageNumbersPart {
         "A freelist trunk page consists of an array of 4-byte big-endian integers. The size of the array is as many integers as will fit in the usable space of a page. Call the second integer on a freelist trunk page L. If L >= 0 then integers with array indexes between 2 and L+1 inclusive contain page numbers for freelist leaf pages."
     }
 
-    fn color(&self) -> String {
-        "green".to_string()
+    fn role(&self) -> Role {
+        Role::Pointers
     }
 
     fn fields(&self) -> &[Rc<Field>] {
@@ -124,11 +127,12 @@ pub struct UnallocatedPart {
 impl UnallocatedPart {
     pub fn new(unallocated: &[u8], offset: usize) -> Self {
         let fields = vec![Rc::new(Field::new(
+            FieldKind::Unallocated,
             "",
             offset,
             unallocated.len(),
             Value::Unallocated(unallocated.into()),
-            "",
+            Style::Plain,
         ))];
         Self { fields }
     }
@@ -143,8 +147,8 @@ impl Part for UnallocatedPart {
         "The whole area of the freelist leaf is not allocated."
     }
 
-    fn color(&self) -> String {
-        "orange".to_string()
+    fn role(&self) -> Role {
+        Role::Unused
     }
 
     fn fields(&self) -> &[Rc<Field>] {
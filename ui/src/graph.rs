@@ -0,0 +1,210 @@
+//! B-tree drawn as a graph of its pages, which can be zoomed, panned and clicked.
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+
+use crate::index::update_selected_page;
+use crate::state::AppState;
+use crate::BTreeNodeView;
+
+/// Distance between the levels of the b-tree graph.
+const GRAPH_LEVEL: f64 = 70.0;
+/// Height of a page on the b-tree graph, the width depends on the number of cells.
+const GRAPH_NODE: f64 = 24.0;
+/// Side of an overflow page on the b-tree graph.
+const GRAPH_OVERFLOW: f64 = 10.0;
+const GRAPH_GAP: f64 = 8.0;
+
+/// Page placed on the b-tree graph, coordinates are of its top left corner.
+#[derive(Debug, Clone, PartialEq)]
+struct GraphNode {
+    page_num: usize,
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    label: String,
+    title: String,
+}
+
+/// Edge of the b-tree graph, either to a child page or to an overflow page.
+#[derive(Debug, Clone, PartialEq)]
+struct GraphEdge {
+    from: (f64, f64),
+    to: (f64, f64),
+    overflow: bool,
+}
+
+/// Lay the tree out: leaves go left to right, a parent is centered over its
+/// children and overflow pages are chained under their page.
+/// Returns the center of the node, nodes and edges are collected on the way.
+fn layout_node(
+    node: &BTreeNodeView,
+    depth: usize,
+    next_x: &mut f64,
+    nodes: &mut Vec<GraphNode>,
+    edges: &mut Vec<GraphEdge>,
+) -> f64 {
+    let width = GRAPH_NODE + (node.cells as f64).sqrt() * 6.0;
+    let chain = node.overflow.len() as f64 * (GRAPH_OVERFLOW + 4.0);
+    let y = depth as f64 * GRAPH_LEVEL;
+    let mut centers = vec![];
+    let center = if node.children.is_empty() {
+        let slot = width.max(chain);
+        let center = *next_x + slot / 2.0;
+        *next_x += slot + GRAPH_GAP;
+        center
+    } else {
+        centers = node
+            .children
+            .iter()
+            .map(|child| layout_node(child, depth + 1, next_x, nodes, edges))
+            .collect::<Vec<_>>();
+        (centers[0] + centers[centers.len() - 1]) / 2.0
+    };
+    for c in centers {
+        edges.push(GraphEdge {
+            from: (center, y + GRAPH_NODE),
+            to: (c, y + GRAPH_LEVEL),
+            overflow: false,
+        });
+    }
+
+    nodes.push(GraphNode {
+        page_num: node.page_num,
+        x: center - width / 2.0,
+        y,
+        width,
+        height: GRAPH_NODE,
+        label: node.page_num.to_string(),
+        title: format!("Page {}, {} cells", node.page_num, node.cells),
+    });
+    let mut from = (center, y + GRAPH_NODE);
+    let mut x = center - chain / 2.0;
+    for &page_num in &node.overflow {
+        let to = (x + GRAPH_OVERFLOW / 2.0, y + GRAPH_NODE + 8.0);
+        edges.push(GraphEdge {
+            from,
+            to,
+            overflow: true,
+        });
+        nodes.push(GraphNode {
+            page_num,
+            x,
+            y: to.1,
+            width: GRAPH_OVERFLOW,
+            height: GRAPH_OVERFLOW,
+            label: String::new(),
+            title: format!("Overflow page {}", page_num),
+        });
+        from = (to.0 + GRAPH_OVERFLOW / 2.0, to.1 + GRAPH_OVERFLOW / 2.0);
+        x += GRAPH_OVERFLOW + 4.0;
+    }
+    center
+}
+
+/// B-tree drawn as a graph: pages are sized by the number of cells, dashed
+/// edges lead to overflow pages. Wheel zooms, dragging pans, click opens a page.
+#[component]
+pub fn BTreeGraph(root: BTreeNodeView) -> Element {
+    let viewer = use_context::<AppState>().viewer;
+    let selected_page = use_context::<AppState>().selected_page;
+    let mut zoom = use_signal(|| 1.0f64);
+    let mut pan = use_signal(|| (0.0f64, 0.0f64));
+    let mut drag = use_signal(|| None::<(f64, f64)>);
+
+    let (mut nodes, mut edges) = (vec![], vec![]);
+    let mut width = 0.0;
+    layout_node(&root, 0, &mut width, &mut nodes, &mut edges);
+    let height = nodes.iter().map(|n| n.y + n.height).fold(0.0, f64::max);
+    let view_box = format!("-10 -10 {} {}", width + 20.0, height + 20.0);
+    let transform = format!("translate({} {}) scale({})", pan().0, pan().1, zoom());
+    let current = selected_page().id();
+    rsx! {
+        div {
+            class: "flex items-center space-x-1 pb-1",
+            button {
+                class: "btn btn-xs btn-ghost",
+                onclick: move |_| zoom.set((zoom() * 1.25).min(10.0)),
+                "+"
+            }
+            button {
+                class: "btn btn-xs btn-ghost",
+                onclick: move |_| zoom.set((zoom() / 1.25).max(0.1)),
+                "-"
+            }
+            button {
+                class: "btn btn-xs btn-ghost",
+                onclick: move |_| {
+                    zoom.set(1.0);
+                    pan.set((0.0, 0.0));
+                },
+                "Reset"
+            }
+        }
+        svg {
+            class: "w-full h-96 border border-slate-800 cursor-grab bg-base-100",
+            "viewBox": "{view_box}",
+            onwheel: move |e| {
+                let factor = match e.delta().strip_units().y < 0.0 {
+                    true => 1.1,
+                    false => 1.0 / 1.1,
+                };
+                zoom.set((zoom() * factor).clamp(0.1, 10.0));
+            },
+            onmousedown: move |e| {
+                let point = e.client_coordinates();
+                drag.set(Some((point.x, point.y)));
+            },
+            onmousemove: move |e| {
+                let Some((x, y)) = drag() else { return };
+                let point = e.client_coordinates();
+                let (px, py) = pan();
+                pan.set((px + point.x - x, py + point.y - y));
+                drag.set(Some((point.x, point.y)));
+            },
+            onmouseup: move |_| drag.set(None),
+            onmouseleave: move |_| drag.set(None),
+            g {
+                transform: "{transform}",
+                for edge in edges {
+                    line {
+                        "x1": "{edge.from.0}",
+                        "y1": "{edge.from.1}",
+                        "x2": "{edge.to.0}",
+                        "y2": "{edge.to.1}",
+                        stroke: "#64748b",
+                        "stroke-dasharray": if edge.overflow {"2 2"},
+                    }
+                }
+                for node in nodes {
+                    g {
+                        class: "cursor-pointer",
+                        onclick: {
+                            let page = viewer.read().get_page(node.page_num as u32);
+                            move |_| update_selected_page(page.clone())
+                        },
+                        title { "{node.title}" }
+                        rect {
+                            x: "{node.x}",
+                            y: "{node.y}",
+                            width: "{node.width}",
+                            height: "{node.height}",
+                            rx: "3",
+                            fill: if node.page_num == current {"#1e293b"} else {"#cbd5e1"},
+                            stroke: "#1e293b",
+                        }
+                        text {
+                            x: "{node.x + node.width / 2.0}",
+                            y: "{node.y + node.height / 2.0 + 4.0}",
+                            "text-anchor": "middle",
+                            "font-size": "10",
+                            fill: if node.page_num == current {"#f1f5f9"} else {"#1e293b"},
+                            "{node.label}"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
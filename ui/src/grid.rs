@@ -0,0 +1,220 @@
+//! Field grid of the selected page, rendered in a window of fields around its visible part,
+//! with the offset ruler above it and the mini-map of the whole page next to it.
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+
+use crate::browser::{scroll_into_view, visible_offsets, yield_now};
+use crate::index::FieldElement;
+use crate::state::AppState;
+use crate::{PageView, Role};
+
+/// Id of the scrollable container with the field grid.
+pub const VISUAL_SCROLL_ID: &str = "visual-scroll";
+
+/// Fields of the page mounted in the grid at once. Pages with thousands of record
+/// fields get only a window of them around the visible part, which moves along
+/// as the grid is scrolled or a field is jumped to.
+const FIELD_WINDOW: usize = 600;
+
+/// Position of the field in the grid, counting fields of all the parts.
+fn field_index(page: &dyn PageView, np: usize, nf: usize) -> usize {
+    page.parts()[..np]
+        .iter()
+        .map(|p| p.fields().len())
+        .sum::<usize>()
+        + nf
+}
+
+/// Move the window of the grid, so the field is mounted.
+pub fn reveal_field(np: usize, nf: usize) {
+    let selected_page = use_context::<AppState>().selected_page;
+    let mut grid_window = use_context::<AppState>().grid_window;
+    let index = field_index(selected_page().as_ref(), np, nf);
+    let start = grid_window();
+    if !(start..start + FIELD_WINDOW).contains(&index) {
+        *grid_window.write() = index.saturating_sub(FIELD_WINDOW / 4);
+    }
+}
+
+/// Scroll the grid to the field, once it's mounted.
+pub fn scroll_to_field(np: usize, nf: usize) {
+    reveal_field(np, nf);
+    spawn(async move {
+        yield_now().await;
+        scroll_into_view(&format!("field-{np}-{nf}"));
+    });
+}
+
+/// Move the window of the grid before its edge is scrolled into view, so the
+/// visible fields end up in its first third.
+pub fn follow_viewport() {
+    let selected_page = use_context::<AppState>().selected_page;
+    let viewport = use_context::<AppState>().viewport;
+    let mut grid_window = use_context::<AppState>().grid_window;
+    let Some((top, bottom)) = viewport() else {
+        return;
+    };
+    let page = selected_page();
+    let total = page.parts().iter().map(|p| p.fields().len()).sum::<usize>();
+    let index_at = |offset| {
+        page.field_at(offset)
+            .map(|(np, nf)| field_index(page.as_ref(), np, nf))
+    };
+    let (Some(first), Some(last)) = (index_at(top), index_at(bottom)) else {
+        return;
+    };
+    let start = grid_window();
+    let end = start + FIELD_WINDOW;
+    let margin = FIELD_WINDOW / 8;
+    if (end < total && last + margin >= end) || (start > 0 && first < start + margin) {
+        *grid_window.write() = first.saturating_sub(FIELD_WINDOW / 3);
+    }
+}
+
+/// Fields of the window with notes of how many are left out above and below it.
+pub fn FieldGrid() -> Element {
+    let selected_page = use_context::<AppState>().selected_page;
+    let grid_window = use_context::<AppState>().grid_window;
+    let page = selected_page();
+    let parts = page.parts();
+    let total = parts.iter().map(|p| p.fields().len()).sum::<usize>();
+    let start = grid_window().min(total.saturating_sub(FIELD_WINDOW));
+    let end = (start + FIELD_WINDOW).min(total);
+    let fields = parts
+        .iter()
+        .enumerate()
+        .flat_map(|(p, part)| (0..part.fields().len()).map(move |f| (p, f)))
+        .skip(start)
+        .take(FIELD_WINDOW)
+        .collect::<Vec<_>>();
+    rsx! {
+        if start > 0 {
+            div {
+                class: "w-full pb-2 italic text-slate-500",
+                "{start} fields above, scroll up to show them"
+            }
+        }
+        for (p, f) in fields {
+            FieldElement {key: "{p}-{f}", nf: f, np: p}
+        }
+        if end < total {
+            div {
+                class: "w-full pt-2 italic text-slate-500",
+                "{total - end} fields below, scroll down to show them"
+            }
+        }
+    }
+}
+
+/// Number of ticks on the offset ruler.
+const RULER_TICKS: usize = 8;
+
+/// Row of evenly spaced offsets across the page, the selected field is marked
+/// at its place. Clicking a tick scrolls the grid to the field at this offset.
+pub fn OffsetRuler() -> Element {
+    let selected_page = use_context::<AppState>().selected_page;
+    let selected_field = use_context::<AppState>().selected_field;
+    let addressing = use_context::<AppState>().addressing;
+    let page = selected_page();
+    let field = selected_field();
+    let size = page.size().max(1);
+    let step = size.div_ceil(RULER_TICKS).max(1);
+    let ticks = (0..size)
+        .step_by(step)
+        .map(|offset| {
+            (
+                addressing().offset(page.as_ref(), offset),
+                page.field_at(offset),
+            )
+        })
+        .collect::<Vec<_>>();
+    let left = field.offset * 100 / size;
+    let width = (field.size.max(1) * 100 / size).max(1);
+    let end = addressing().offset(page.as_ref(), size);
+    rsx! {
+        div {
+            class: "relative mx-4 mt-2 border-b border-slate-500 text-xs tracking-tighter text-cyan-950",
+            div {
+                class: "flex",
+                for (offset, owner) in ticks {
+                    div {
+                        class: "flex-1 pl-1 border-l border-slate-500 cursor-pointer hover:bg-secondary",
+                        onclick: move |_| {
+                            if let Some((np, nf)) = owner {
+                                scroll_to_field(np, nf);
+                            }
+                        },
+                        "{offset}"
+                    }
+                }
+                div {
+                    class: "pr-1 border-r border-slate-500",
+                    "{end}"
+                }
+            }
+            div {
+                class: "absolute bottom-0 h-1 bg-slate-800",
+                style: "left: {left}%; width: {width}%",
+                title: "Selected field",
+            }
+        }
+    }
+}
+
+/// Number of slices the page is split into on the mini-map.
+const MINIMAP_SLICES: usize = 100;
+
+/// Thin strip of the whole page next to the field grid: parts are colored,
+/// the visible part of the grid and the selected field are marked.
+/// Clicking a slice scrolls the grid to the field at this place of the page.
+pub fn MiniMap() -> Element {
+    let selected_page = use_context::<AppState>().selected_page;
+    let selected_field = use_context::<AppState>().selected_field;
+    let palette = use_context::<AppState>().palette;
+    let mut viewport = use_context::<AppState>().viewport;
+
+    // Grid is rendered anew for every page, so is its visible part.
+    use_effect(move || {
+        let _ = selected_page.read();
+        *viewport.write() = visible_offsets(VISUAL_SCROLL_ID);
+    });
+
+    let page = selected_page();
+    let field = selected_field();
+    let selected = field.offset..field.offset + field.size.max(1);
+    let visible = viewport().map(|(top, bottom)| top..bottom + 1);
+    let slice_size = page.size().div_ceil(MINIMAP_SLICES).max(1);
+    let slices = (0..page.size())
+        .step_by(slice_size)
+        .map(|start| {
+            let end = (start + slice_size).min(page.size());
+            let owner = page.field_at(start);
+            let role = owner.map_or(Role::Unused, |(np, _)| page.parts()[np].role());
+            let color = palette().color(role);
+            let is_selected = selected.start < end && start < selected.end;
+            let is_visible = visible
+                .as_ref()
+                .is_some_and(|v| v.start < end && start < v.end);
+            (start, owner, color, is_selected, is_visible)
+        })
+        .collect::<Vec<_>>();
+    rsx! {
+        div {
+            class: "sticky top-0 self-start flex flex-col w-3 h-[calc(100vh-48px)] py-4",
+            for (start, owner, color, is_selected, is_visible) in slices {
+                div {
+                    class: "w-full flex-grow cursor-pointer",
+                    class: if is_selected {"bg-slate-800".to_string()} else {format!("bg-{color}-600")},
+                    class: if !is_visible {"opacity-40"},
+                    title: "{start}",
+                    onclick: move |_| {
+                        if let Some((np, nf)) = owner {
+                            scroll_to_field(np, nf);
+                        }
+                    },
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,119 @@
+//! Translations of the static descriptions of pages, parts and fields.
+//!
+//! Descriptions are written in English next to the parsing code and the English text
+//! is the key to look up a translation with, the same way gettext does it. Adding
+//! a translation doesn't touch the parts: put the pair into the catalog of the
+//! language, anything missing in the catalog is shown in English.
+use crate::browser::{storage_get, storage_set};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Language {
+    English,
+    Russian,
+}
+
+impl Language {
+    const STORAGE_KEY: &'static str = "language";
+    pub const ALL: [Language; 2] = [Language::English, Language::Russian];
+
+    /// Name of the language in the language itself.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Russian => "Русский",
+        }
+    }
+
+    /// Description in the language, the English one if there is no translation.
+    pub fn tr(&self, text: &'static str) -> &'static str {
+        let catalog = match self {
+            Language::English => return text,
+            Language::Russian => RUSSIAN,
+        };
+        catalog
+            .iter()
+            .find(|(english, _)| *english == text)
+            .map_or(text, |(_, translated)| translated)
+    }
+
+    /// Restore the language saved by its name, English if there is nothing valid.
+    pub fn load() -> Self {
+        let saved = storage_get(Self::STORAGE_KEY);
+        Self::ALL
+            .into_iter()
+            .find(|l| saved.as_deref() == Some(l.name()))
+            .unwrap_or(Language::English)
+    }
+
+    pub fn save(&self) {
+        storage_set(Self::STORAGE_KEY, self.name());
+    }
+}
+
+/// English descriptions and their Russian translations.
+static RUSSIAN: &[(&str, &str)] = &[
+    (
+        "The first 100 bytes of the database file comprise the database file header. All multibyte fields in the database file header are stored with the most significant byte first (big-endian).",
+        "Первые 100 байт файла базы данных составляют её заголовок. Все многобайтовые поля заголовка хранятся, начиная со старшего байта (big-endian).",
+    ),
+    (
+        "Magic header string, which corresponds to the UTF-8 string: 'SQLite format 3\\000. Every valid SQLite database file begins with these 16 bytes (in hex): 53 51 4c 69 74 65 20 66 6f 72 6d 61 74 20 33 00.",
+        "Магическая строка заголовка, соответствующая строке UTF-8 'SQLite format 3\\000'. Каждый корректный файл базы данных SQLite начинается с этих 16 байт (в hex): 53 51 4c 69 74 65 20 66 6f 72 6d 61 74 20 33 00.",
+    ),
+    (
+        "Page size of the database, interpreted as a big-endian integer and must be a power of two between 512 and 32786, inclusive. Starting from version 3.7.1 page size of 65536 bytes is supported, but since it won't fit in a two-byte integer, big-endian magic number 1 is used to represent it: 0x00 0x01.",
+        "Размер страницы базы данных, целое число big-endian, степень двойки от 512 до 32768 включительно. Начиная с версии 3.7.1 поддерживается размер страницы 65536 байт, но так как он не помещается в двухбайтовое целое, для него используется магическое число 1: 0x00 0x01.",
+    ),
+    (
+        "File format write version, 1 for legacy, 2 for WAL. Intended to allow for enhancements of the file format in future versions of SQLite. If read version is 1 or 2, but the write version is greater than 2, then the database file must be treated as read-only. If read version is greater than 2, then database cannot be read or written.",
+        "Версия формата файла для записи: 1 для классического журнала, 2 для WAL. Оставляет возможность расширять формат файла в будущих версиях SQLite. Если версия для чтения равна 1 или 2, а версия для записи больше 2, файл базы данных доступен только для чтения. Если версия для чтения больше 2, базу данных нельзя ни читать, ни изменять.",
+    ),
+    (
+        "File format read version, 1 for legacy, 2 for WAL. Intended to allow for enhancements of the file format in future versions of SQLite. If read version is 1 or 2, but the write version is greater than 2, then the database file must be treated as read-only. If read version is greater than 2, then database cannot be read or written.",
+        "Версия формата файла для чтения: 1 для классического журнала, 2 для WAL. Оставляет возможность расширять формат файла в будущих версиях SQLite. Если версия для чтения равна 1 или 2, а версия для записи больше 2, файл базы данных доступен только для чтения. Если версия для чтения больше 2, базу данных нельзя ни читать, ни изменять.",
+    ),
+    (
+        "Number of bytes to define unused (reserved) space at the end of each page, usually 0. These bytes are used by extensions, for example, by the SQLite Encryption Extension to store a nonce and/or cryptographic checksum associated with each page. The 'usable size' of a database page is: Page size - Reserved space. It could be an odd number, but it's not allowed to be less than 480, which means that in this case reserved space size won't exceed 32.",
+        "Число байт неиспользуемого (зарезервированного) места в конце каждой страницы, обычно 0. Эти байты нужны расширениям, например, SQLite Encryption Extension хранит в них nonce и/или криптографическую контрольную сумму страницы. Полезный размер страницы равен размеру страницы минус зарезервированное место. Он может быть нечётным, но не может быть меньше 480.",
+    ),
+    (
+        "Maximum embedded payload fraction, must be 64. Intended to be tunable parameters that could be used to modify the storage format of the b-tree algorithm. However, that functionality is not supported and there are no current plans to add support in the future, thus these bytes are fixed at the specified values.",
+        "Максимальная доля встроенных данных, должна быть равна 64. Задумывалась как настраиваемый параметр формата хранения b-деревьев, но эта возможность не поддерживается и не планируется, поэтому значение фиксировано.",
+    ),
+    (
+        "Minimum embedded payload fraction, must be 32. Intended to be tunable parameters that could be used to modify the storage format of the b-tree algorithm. However, that functionality is not supported and there are no current plans to add support in the future, thus these bytes are fixed at the specified values.",
+        "Минимальная доля встроенных данных, должна быть равна 32. Задумывалась как настраиваемый параметр формата хранения b-деревьев, но эта возможность не поддерживается и не планируется, поэтому значение фиксировано.",
+    ),
+    (
+        "Leaf payload fraction, must be 32. Intended to be tunable parameters that could be used to modify the storage format of the b-tree algorithm. However, that functionality is not supported and there are no current plans to add support in the future, thus these bytes are fixed at the specified values.",
+        "Доля данных листовой страницы, должна быть равна 32. Задумывалась как настраиваемый параметр формата хранения b-деревьев, но эта возможность не поддерживается и не планируется, поэтому значение фиксировано.",
+    ),
+    (
+        "Page number of the first freelist trunk page. Unused pages in the database file are stored on a freelist or zero if the freelist is empty.",
+        "Номер первой магистральной страницы списка свободных страниц. Неиспользуемые страницы файла хранятся в этом списке, ноль, если он пуст.",
+    ),
+    (
+        "Total number of freelist pages.",
+        "Общее число страниц в списке свободных страниц.",
+    ),
+    (
+        "Suggested default page cache size. This value is the suggestion only and SQLite is under no obligation to honor it. Suggested cache size can be set using the default_cache_size pragma.",
+        "Рекомендуемый размер кэша страниц. Это только рекомендация, SQLite не обязана ей следовать. Его можно задать с помощью pragma default_cache_size.",
+    ),
+    (
+        "The database text encoding. A value of 1 means UTF-8, 2: UTF-16le, 3: UTF-16be. No other values are allowed.",
+        "Кодировка текста базы данных: 1 означает UTF-8, 2: UTF-16le, 3: UTF-16be. Другие значения недопустимы.",
+    ),
+    (
+        "The 'user version' as read and set by the user_version pragma. The user version is not used by SQLite.",
+        "Пользовательская версия, которую читает и задаёт pragma user_version. Сама SQLite её не использует.",
+    ),
+    (
+        "Reserved for future expansion, must be set to zero.",
+        "Зарезервировано для будущих расширений, должно быть заполнено нулями.",
+    ),
+    (
+        "The version-valid-for number is the value of the change counter when the version number was stored, indicates which transaction the version number is valid for.",
+        "Значение счётчика изменений на момент записи номера версии, показывает, для какой транзакции номер версии действителен.",
+    ),
+];
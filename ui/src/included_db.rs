@@ -1,7 +1,15 @@
 /// Preloaded examples of databases to start UI with somethinh
-
+///
+/// Only the Simple example is compiled into the binary, the rest are served
+/// as static assets and fetched on demand to keep the WASM bundle small.
+use std::cell::RefCell;
+use std::collections::BTreeMap;
 use std::include_bytes;
 
+use parser::StdError;
+
+use crate::browser::fetch_bytes;
+
 pub const SIMPLE_DB: &str = "Simple";
 pub const BIG_PAGE_DB: &str = "Max page size";
 pub const TABLE_INDEX_LEAF_DB: &str = "Leaf nodes";
@@ -9,99 +17,240 @@ pub const OVERFLOW_PAGE_DB: &str = "Overflow pages";
 pub const FREELIST_PAGE_DB: &str = "Freelist pages";
 pub const MIXED_PAGE_DB: &str = "All types";
 pub const TABLE_INDEX_INTERIOR_DB: &str = "Interior nodes";
+pub const HOT_JOURNAL_DB: &str = "Hot journal";
+pub const FREEBLOCKS_DB: &str = "Freeblocks";
+pub const PAGE_SPLIT_DB: &str = "Page split";
+pub const DELETED_ROWS_DB: &str = "Deleted rows";
+pub const AUTO_VACUUM_DB: &str = "Auto-vacuum";
+pub const VACUUM_DB: &str = "VACUUM";
+pub const INDEX_LEVELS_DB: &str = "Multi-level index";
+pub const UTF16LE_DB: &str = "UTF-16 LE";
+pub const UTF16BE_DB: &str = "UTF-16 BE";
+/// Database built in the browser from the SQL typed into the playground.
+pub const USER_DB: &str = "Custom SQL";
+/// Database from the disk, served by `sqlite-repr serve` next to the UI.
+pub const LOCAL_DB: &str = "Local file";
+/// Database fetched by the URL given in the Remote tab.
+pub const REMOTE_DB: &str = "Remote URL";
+/// Paths the local server provides the database and its rollback journal at.
+pub const LOCAL_DB_PATH: &str = "local.db";
+pub const LOCAL_JOURNAL_PATH: &str = "local.db-journal";
+pub const LOCAL_SUPER_JOURNAL_PATH: &str = "local.db-superjournal";
+
+/// Where the bytes of an included database live.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DBSource {
+    /// Compiled into the binary.
+    Inline(&'static [u8]),
+    /// Static asset path, relative to the served root.
+    Asset(&'static str),
+}
 
+thread_local! {
+    static LOADED: RefCell<BTreeMap<&'static str, &'static [u8]>> = const { RefCell::new(BTreeMap::new()) };
+}
+
+impl DBSource {
+    /// Get database bytes, fetching the asset on the first request.
+    pub async fn load(&self) -> Result<&'static [u8], StdError> {
+        match *self {
+            Self::Inline(bytes) => Ok(bytes),
+            Self::Asset(path) => {
+                if let Some(bytes) = LOADED.with(|l| l.borrow().get(path).copied()) {
+                    return Ok(bytes);
+                }
+                // Fetched once per asset and kept for the lifetime of the app,
+                // the same way include_bytes! data would be.
+                let bytes: &'static [u8] = Box::leak(fetch_bytes(path).await?.into_boxed_slice());
+                LOADED.with(|l| l.borrow_mut().insert(path, bytes));
+                Ok(bytes)
+            }
+        }
+    }
+}
+
+/// Included database source, SQL script it was created with and, for examples
+/// captured at several stages, ordered snapshots with their labels. The first
+/// snapshot is the database source itself.
+///
+/// Databases are generated from the scripts in `ui/fixtures` with `cargo xtask fixtures`,
+/// the inline Simple one is also kept in the repository for the UI to build without it.
 #[allow(clippy::type_complexity)]
-pub static INCLUDED_DB: &[(&str, (&[u8], &[&str]))] = &[
+pub static INCLUDED_DB: &[(&str, (DBSource, &str, &[(&str, DBSource)]))] = &[
     (
         SIMPLE_DB,
         (
-            include_bytes!("../included/simple"),
-            &[
-                "CREATE TABLE simple(int)",
-                "INSERT INTO simple VALUES(1), (2), (3), (4)",
-            ],
+            DBSource::Inline(include_bytes!("../included/simple")),
+            include_str!("../fixtures/simple.sql"),
+            &[],
         ),
     ), 
     (
         BIG_PAGE_DB, 
         (
-            include_bytes!("../included/big_page"),
-            &[
-                "PRAGMA page_size=65536",
-                "CREATE TABLE big_page(int)",
-                "INSERT INTO simple VALUES(1), (2), (3), (4)",
-            ],
+            DBSource::Asset("./included/big_page"),
+            include_str!("../fixtures/big_page.sql"),
+            &[],
         ),
     ),
     (
        TABLE_INDEX_LEAF_DB,
        (
-            include_bytes!("../included/table_index_leaf"),
-            &[
-                "CREATE TABLE stars(id INTEGER PRIMARY KEY, name TEXT, distance REAL, brightness REAL)",
-                "INSERT INTO stars VALUES(100, 'Sirius', 8.6, -1.46), ... ",
-                "CREATE INDEX idx_stars_name ON stars (name)",
-                "CREATE TABLE spaceships(launched, name, operator)",
-                "INSERT INTO spaceships VALUES(1977, 'Voyager 1', 'NASA'), ... ",
-                "CREATE INDEX idx_spaceships_name ON spaceships(name)",
-            ],
+            DBSource::Asset("./included/table_index_leaf"),
+            include_str!("../fixtures/table_index_leaf.sql"),
+            &[],
         ),
     ),
     (
        OVERFLOW_PAGE_DB,
        (
-            include_bytes!("../included/overflow_page"),
+            DBSource::Asset("./included/overflow_page"),
+            include_str!("../fixtures/overflow_page.sql"),
+            &[],
+        ),
+    ),
+    (
+        TABLE_INDEX_INTERIOR_DB,
+        (
+            DBSource::Asset("./included/table_index_interior"),
+            include_str!("../fixtures/table_index_interior.sql"),
+            &[],
+        ),
+    ),
+    (
+       FREELIST_PAGE_DB,
+       (
+            DBSource::Asset("./included/freelist_page"),
+            include_str!("../fixtures/freelist_page.sql"),
+            &[],
+        ),
+    ),
+    (
+       MIXED_PAGE_DB,
+       (
+            DBSource::Asset("./included/mixed"),
+            include_str!("../fixtures/mixed.sql"),
+            &[],
+        ),
+    ),
+    (
+       HOT_JOURNAL_DB,
+       (
+            DBSource::Asset("./included/hot_journal"),
+            include_str!("../fixtures/hot_journal.sql"),
+            &[],
+        ),
+    ),
+    (
+       FREEBLOCKS_DB,
+       (
+            DBSource::Asset("./included/freeblocks-1"),
+            include_str!("../fixtures/freeblocks.sql"),
             &[
-                "PRAGMA page_size=1024",
-                "CREATE TABLE mixed_overflow(text, longint, int, blob)",
-                "CREATE TABLE blob_overflow(blob)",
-                "INSERT INTO blob_overflow VALUES(fileio_read('dev/overflow.txt'))",
-                "INSERT INTO mixed_overflow SELECT CAST(blob as TEXT), 234234235, 0, blob FROM blob_overflow",
-                "INSERT INTO mixed_overflow SELECT CAST(blob as TEXT), 94542343, 1, blob FROM blob_overflow",
+                ("After inserts", DBSource::Asset("./included/freeblocks-1")),
+                ("After deletes", DBSource::Asset("./included/freeblocks-2")),
+                ("After re-insert", DBSource::Asset("./included/freeblocks-3")),
             ],
         ),
     ),
     (
-        TABLE_INDEX_INTERIOR_DB,
+        DELETED_ROWS_DB,
         (
-            include_bytes!("../included/table_index_interior"),
+            DBSource::Asset("./included/deleted_rows-1"),
+            include_str!("../fixtures/deleted_rows.sql"),
             &[
-                "PRAGMA page_size=512",
-                "CREATE TABLE macro_story(line)",
-                "INSERT INTO macro_story SELECT VALUE FROM fileio_scan('dev/lines.txt')",
-                "CREATE INDEX idx_macro_story_line ON macro_story(line)",
+                ("After inserts", DBSource::Asset("./included/deleted_rows-1")),
+                ("After deletes, no VACUUM", DBSource::Asset("./included/deleted_rows-2")),
+                ("After re-insert", DBSource::Asset("./included/deleted_rows-3")),
             ],
         ),
     ),
     (
-       FREELIST_PAGE_DB,
-       (
-            include_bytes!("../included/freelist_page"),
+        AUTO_VACUUM_DB,
+        (
+            DBSource::Asset("./included/auto_vacuum"),
+            include_str!("../fixtures/auto_vacuum.sql"),
+            &[],
+        ),
+    ),
+    (
+        VACUUM_DB,
+        (
+            DBSource::Asset("./included/vacuum-1"),
+            include_str!("../fixtures/vacuum.sql"),
             &[
-                "PRAGMA page_size=1024",
-                "CREATE TABLE mixed_overflow(text, blob)",
-                "CREATE TABLE blob_overflow(blob)",
-                "INSERT INTO blob_overflow VALUES(fileio_read('dev/overflow.txt'))",
-                "INSERT INTO mixed_overflow SELECT CAST(blob as TEXT), blob FROM blob_overflow",
-                "DELETE FROM mixed_overflow",
-                "DROP TABLE blob_overflow",
+                ("Fragmented, before VACUUM", DBSource::Asset("./included/vacuum-1")),
+                ("After VACUUM", DBSource::Asset("./included/vacuum-2")),
             ],
         ),
     ),
     (
-       MIXED_PAGE_DB,
+        INDEX_LEVELS_DB,
+        (
+            DBSource::Asset("./included/index_levels"),
+            include_str!("../fixtures/index_levels.sql"),
+            &[],
+        ),
+    ),
+    (
+       PAGE_SPLIT_DB,
        (
-            include_bytes!("../included/mixed"),
+            DBSource::Asset("./included/page_split-1"),
+            include_str!("../fixtures/page_split.sql"),
             &[
-                "PRAGMA page_size=1024",
-                "CREATE TABLE blob_overflow(blob)",
-                "INSERT INTO blob_overflow VALUES(fileio_read('dev/overflow.txt'))",
-                "CREATE TABLE macro_story(line)",
-                "INSERT INTO macro_story SELECT VALUE FROM fileio_scan('dev/lines.txt')",
-                "INSERT INTO macro_story SELECT CAST(blob as TEXT) FROM blob_overflow",
-                "CREATE INDEX idx_macro_story_line ON macro_story(line)",
-                "DROP TABLE blob_overflow",
+                ("Empty table", DBSource::Asset("./included/page_split-1")),
+                ("3 rows", DBSource::Asset("./included/page_split-2")),
+                ("13 rows, the leaf is almost full", DBSource::Asset("./included/page_split-3")),
+                ("23 rows, the root is split", DBSource::Asset("./included/page_split-4")),
+                ("43 rows, one more leaf", DBSource::Asset("./included/page_split-5")),
             ],
         ),
     ),
+    (
+        UTF16LE_DB,
+        (
+            DBSource::Asset("./included/utf16le"),
+            include_str!("../fixtures/utf16le.sql"),
+            &[],
+        ),
+    ),
+    (
+        UTF16BE_DB,
+        (
+            DBSource::Asset("./included/utf16be"),
+            include_str!("../fixtures/utf16be.sql"),
+            &[],
+        ),
+    ),
 ];
+
+/// Statements of the script one per line, the comments are kept as they are, as
+/// they mark the snapshots.
+pub fn statements(script: &str) -> Vec<String> {
+    let mut statements = vec![];
+    let mut statement = String::new();
+    for line in script.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        if statement.is_empty() && line.starts_with("--") {
+            statements.push(line.to_string());
+            continue;
+        }
+        if !statement.is_empty() {
+            statement.push(' ');
+        }
+        statement.push_str(line);
+        if let Some(complete) = statement.strip_suffix(';') {
+            statements.push(complete.to_string());
+            statement.clear();
+        }
+    }
+    if !statement.is_empty() {
+        statements.push(statement);
+    }
+    statements
+}
+
+/// Rollback journals, which belong to the included databases.
+pub static INCLUDED_JOURNAL: &[(&str, DBSource)] = &[(
+    HOT_JOURNAL_DB,
+    DBSource::Asset("./included/hot_journal-journal"),
+)];
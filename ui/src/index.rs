@@ -1,22 +1,85 @@
 //! Main UI page.
 #![allow(non_snake_case)]
 
+use std::collections::HashSet;
 use std::rc::Rc;
 
 use dioxus::prelude::*;
 use dioxus_free_icons::icons::bs_icons::{
-    BsArrowBarLeft, BsArrowBarRight, BsArrowReturnRight, BsArrowRight,
+    BsArrowBarLeft, BsArrowBarRight, BsArrowReturnRight, BsArrowRight, BsBookmark, BsBookmarkFill,
+    BsChevronLeft, BsChevronRight, BsClipboard, BsDownload, BsFiletypeJson, BsGear, BsImage,
+    BsMoon, BsSun, BsX,
 };
 use dioxus_free_icons::Icon;
+use dioxus_logger::tracing::error;
+use parser::{
+    highlight, internal_table, jsonb_to_json, sqlcipher_decrypt, Cipher, PageHash, RecordType, Row,
+    SqlCipherSettings, SqlSpan, SqlarEntry, StdError, TextEncoding, DB_HEADER_SIZE,
+};
+
+use crate::browser::{
+    absolute_url, build_database, copy_to_clipboard, download, fetch_bytes, fetch_chunked,
+    fetch_range, replace_url, visible_offsets, yield_now, Chunk,
+};
+use crate::chains::{FreelistMap, OverflowChainMap, OverflowOwnerLink, ReassembledPayload};
+use crate::commands::CommandPalette;
+use crate::compare::{ComparePane, DiffSummary, RemovedFields};
+use crate::diff::Compare;
+use crate::dump::{HexDump, RawPage};
+use crate::graph::BTreeGraph;
+use crate::grid::{
+    follow_viewport, reveal_field, scroll_to_field, FieldGrid, MiniMap, OffsetRuler,
+    VISUAL_SCROLL_ID,
+};
+use crate::i18n::Language;
+use crate::included_db::{
+    statements, LOCAL_DB, LOCAL_DB_PATH, LOCAL_JOURNAL_PATH, LOCAL_SUPER_JOURNAL_PATH, REMOTE_DB,
+    USER_DB,
+};
+use crate::insertion::InsertionSandbox;
+use crate::lookup::RowidLookup;
+use crate::preview::BlobPreview;
+use crate::remote::{PageSource, WHOLE_FILE_LIMIT};
+use crate::sqlar::SqlarListing;
+use crate::state::{
+    Addressing, AppState, Bookmark, DeepLink, Format, HexStyle, Palette, Search, SearchMode, Theme,
+    ValueLimit, HEX_VALUE_GROUPS, MAX_SEARCH_HITS, VALUE_LIMITS,
+};
+use crate::svg::page_svg;
+use crate::tools::{ToolsTab, VarintPlayground};
+use crate::usage::{PageUsage, StorageTab};
+use crate::vacuum::VacuumWalkthrough;
+use crate::viewer::{Viewer, ViewerLoader};
+use crate::{
+    BTreeNodeView, BTreeView, Field, PageFilter, PageSort, PageSummary, PageView, Role, Value,
+};
 
-use crate::state::{AppState, Format};
-use crate::viewer::Viewer;
-use crate::{BTreeNodeView, Field, PageView, Value};
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LeftTab {
+    Pages,
+    Tree,
+    Table,
+    Search,
+    Tools,
+    Storage,
+    Schema,
+    Sql,
+    Remote,
+    Bookmarks,
+}
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum NavMove {
     Left,
     Right,
+    /// First field of the previous part.
+    Up,
+    /// First field of the next part.
+    Down,
+    /// First field of the page.
+    Home,
+    /// Last field of the page.
+    End,
 }
 
 fn move_to(direction: NavMove, nf: usize, np: usize) {
@@ -52,6 +115,10 @@ fn move_to(direction: NavMove, nf: usize, np: usize) {
                 (nf, np)
             }
         }
+        NavMove::Up => (0, np.saturating_sub(1)),
+        NavMove::Down => (0, (np + 1).min(parts.len() - 1)),
+        NavMove::Home => (0, 0),
+        NavMove::End => (parts[parts.len() - 1].fields().len() - 1, parts.len() - 1),
     };
 
     let part = &parts[next_np];
@@ -60,45 +127,392 @@ fn move_to(direction: NavMove, nf: usize, np: usize) {
     *locked_field.write() = Some((next_np, next_nf));
     *selected_field.write() = field.clone();
     *selected_part.write() = part.clone();
+    // Neighbour fields are mostly in view already, jumps over parts might be not.
+    if matches!(direction, NavMove::Left | NavMove::Right) {
+        reveal_field(next_np, next_nf);
+    } else {
+        scroll_to_field(next_np, next_nf);
+    }
+}
+
+/// Open the previous or the next loaded page.
+fn step_page(forward: bool) {
+    let viewer = use_context::<AppState>().viewer;
+    let selected_page = use_context::<AppState>().selected_page;
+    let id = selected_page().id();
+    let next = {
+        let viewer = viewer.read();
+        let next = match forward {
+            true => viewer.pages.range(id + 1..).next(),
+            false => viewer.pages.range(..id).next_back(),
+        };
+        next.map(|(_, page)| page.clone())
+    };
+    if let Some(page) = next {
+        update_selected_page(page);
+    }
 }
 
 fn try_jump(nf: usize, np: usize) {
     let viewer = use_context::<AppState>().viewer;
     let selected_page = use_context::<AppState>().selected_page;
     let page = &selected_page();
-    let field = &page.parts()[np].fields()[nf];
+    let parts = page.parts();
+    let field = &parts[np].fields()[nf];
     if let Ok(n) = field.try_page_number() {
         update_selected_page(viewer.read().get_page(n));
     }
 }
 
-fn update_selected_page(page: Rc<dyn PageView>) {
+/// Open the page and lock the field, which covers the page offset.
+pub fn jump_to_field(page_num: usize, offset: usize) {
+    let viewer = use_context::<AppState>().viewer;
+    let mut selected_part = use_context::<AppState>().selected_part;
+    let mut selected_field = use_context::<AppState>().selected_field;
+    let mut locked_field = use_context::<AppState>().locked_field;
+
+    let page = viewer.read().get_page(page_num as u32);
+    update_selected_page(page.clone());
+    if let Some((np, nf)) = page.field_at(offset) {
+        let part = viewer.read().get_part(&page, np);
+        let field = viewer.read().get_field(&part, nf);
+        *selected_part.write() = part;
+        *selected_field.write() = field;
+        *locked_field.write() = Some((np, nf));
+        reveal_field(np, nf);
+    }
+}
+
+/// Open the page and lock the nth record value of the cell, which starts at the page offset.
+/// Values spilled onto overflow pages aren't on the page, the cell itself is locked instead.
+pub fn jump_to_value(page_num: usize, cell_offset: usize, n: usize) {
+    let viewer = use_context::<AppState>().viewer;
+    let mut selected_part = use_context::<AppState>().selected_part;
+    let mut selected_field = use_context::<AppState>().selected_field;
+    let mut locked_field = use_context::<AppState>().locked_field;
+
+    let page = viewer.read().get_page(page_num as u32);
+    update_selected_page(page.clone());
+    if let Some((np, nf)) = page.field_at(cell_offset) {
+        let part = viewer.read().get_part(&page, np);
+        let nf = part
+            .fields()
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| matches!(f.value, Value::Record(_)))
+            .nth(n)
+            .map_or(nf, |(nf, _)| nf);
+        let field = viewer.read().get_field(&part, nf);
+        *selected_part.write() = part;
+        *selected_field.write() = field;
+        *locked_field.write() = Some((np, nf));
+        reveal_field(np, nf);
+    }
+}
+
+/// Switch field format, keeping the locked field or the top of the grid in view,
+/// as fields change their width and the grid reflows.
+fn set_format(format: Format) {
+    let mut formatting = use_context::<AppState>().format;
+    let locked_field = use_context::<AppState>().locked_field;
+    let viewport = use_context::<AppState>().viewport;
+    let selected_page = use_context::<AppState>().selected_page;
+    if formatting() == format {
+        return;
+    }
+
+    let anchor = locked_field()
+        .or_else(|| viewport().and_then(|(start, _)| selected_page().field_at(start)));
+    *formatting.write() = format;
+    if let Some((np, nf)) = anchor {
+        scroll_to_field(np, nf);
+    }
+}
+
+/// Open the page, the page left is remembered in the history.
+pub fn update_selected_page(page: Rc<dyn PageView>) {
+    let selected_page = use_context::<AppState>().selected_page;
+    let mut history = use_context::<AppState>().history;
+    let left = selected_page().id();
+    if left != page.id() {
+        history.write().visit(left);
+    }
+    show_page(page);
+}
+
+/// Go back or forward in the history of the visited pages.
+fn step_history(forward: bool) {
+    let viewer = use_context::<AppState>().viewer;
+    let selected_page = use_context::<AppState>().selected_page;
+    let mut history = use_context::<AppState>().history;
+    let current = selected_page().id();
+    let next = match forward {
+        true => history.write().forward(current),
+        false => history.write().back(current),
+    };
+    let page = match next {
+        // Journal is a pseudo page with id 0.
+        Some(0) => viewer.read().journal.clone(),
+        Some(n) => Some(viewer.read().get_page(n as u32)),
+        None => None,
+    };
+    if let Some(page) = page {
+        show_page(page);
+    }
+}
+
+fn show_page(page: Rc<dyn PageView>) {
     let viewer = use_context::<AppState>().viewer;
     let mut selected_page = use_context::<AppState>().selected_page;
     let mut selected_part = use_context::<AppState>().selected_part;
     let mut selected_field = use_context::<AppState>().selected_field;
     let mut locked_field = use_context::<AppState>().locked_field;
+    let mut grid_window = use_context::<AppState>().grid_window;
+    viewer.read().touch(&page);
+    let id = page.id();
     let part = viewer.read().get_part(&page, 0);
     let field = viewer.read().get_field(&part, 0);
     *selected_page.write() = page;
     *selected_part.write() = part;
     *selected_field.write() = field;
     *locked_field.write() = None;
+    *grid_window.write() = 0;
+    // Pages of a remote database are fetched the first time they're opened.
+    if viewer.read().is_pending(id) {
+        spawn(fetch_remote_page(id));
+    }
+}
+
+/// Fetch the page of the remote database and show it in place of the placeholder,
+/// if it's still the selected one.
+async fn fetch_remote_page(page_num: usize) {
+    let mut viewer = use_context::<AppState>().viewer;
+    let selected_page = use_context::<AppState>().selected_page;
+    let mut loading = use_context::<AppState>().loading;
+    let Some(source) = viewer.read().remote.clone() else {
+        return;
+    };
+    *loading.write() = true;
+    let fetched = source.fetch(page_num).await;
+    *loading.write() = false;
+    if let Err(err) = fetched {
+        error!("Failed to fetch page {}: {}", page_num, err);
+        return;
+    }
+    let page = viewer.write().load_remote_page(page_num);
+    if selected_page().id() == page_num {
+        show_page(page);
+    }
+}
+
+async fn load_included(app_state: AppState, name: String) -> Result<(), StdError> {
+    // Included databases other than Simple are fetched on demand.
+    let bytes = Viewer::included_bytes(name.as_str()).await?;
+    let journal = Viewer::included_journal_bytes(name.as_str()).await?;
+    load_bytes(app_state, name, bytes, journal).await
+}
+
+/// Load the database served from the disk by `sqlite-repr serve`, fails when
+/// the UI is served some other way.
+async fn load_local(app_state: AppState) -> Result<(), StdError> {
+    let bytes: &'static [u8] = Box::leak(fetch_bytes(LOCAL_DB_PATH).await?.into_boxed_slice());
+    // Journal exists only while the database is mid-transaction.
+    let journal = match fetch_bytes(LOCAL_JOURNAL_PATH).await {
+        Ok(journal) => Some(&*Box::leak(journal.into_boxed_slice())),
+        Err(_) => None,
+    };
+    let mut local_db = app_state.local_db;
+    *local_db.write() = true;
+    let mut viewer = app_state.viewer;
+    load_bytes(app_state, LOCAL_DB.to_string(), bytes, journal).await?;
+    // Super-journal exists only for a multi-database transaction.
+    if journal.is_some() {
+        if let Ok(super_journal) = fetch_bytes(LOCAL_SUPER_JOURNAL_PATH).await {
+            viewer.write().attach_super_journal(&super_journal)?;
+        }
+    }
+    Ok(())
+}
+
+/// Build the database from the SQL script in the browser.
+async fn load_user(app_state: AppState, script: String) -> Result<(), StdError> {
+    let bytes: &'static [u8] = Box::leak(build_database(&script).await?.into_boxed_slice());
+    let mut user_db = app_state.user_db;
+    *user_db.write() = Some(script);
+    load_bytes(app_state, USER_DB.to_string(), bytes, None).await
+}
+
+/// Fetch the database by the URL in chunks, showing how much is received so far.
+/// It's parsed once it's complete, since the pages are indexed all at once.
+/// Databases too large for that are explored page by page, see `PageSource`.
+async fn load_remote(app_state: AppState, url: String) -> Result<(), StdError> {
+    let mut remote_db = app_state.remote_db;
+    // The header tells the size of the file and whether the server takes Range requests.
+    let bytes = match fetch_range(&url, 0, DB_HEADER_SIZE).await? {
+        Chunk {
+            bytes,
+            partial: true,
+            total: Some(total),
+        } if total > WHOLE_FILE_LIMIT => {
+            let source = PageSource::new(url.clone(), total, &bytes)?;
+            source.fetch(1).await?;
+            let viewer = Viewer::from_remote(source)?;
+            *remote_db.write() = Some(url);
+            app_state.set_viewer(REMOTE_DB.to_string(), viewer);
+            return Ok(());
+        }
+        // Server without range support has sent the whole file.
+        Chunk {
+            bytes,
+            partial: false,
+            ..
+        } => bytes,
+        _ => {
+            let mut fetched = app_state.fetched;
+            let bytes = fetch_chunked(&url, |received, total| {
+                *fetched.write() = Some((received, total));
+            })
+            .await;
+            *fetched.write() = None;
+            bytes?
+        }
+    };
+    let bytes: &'static [u8] = Box::leak(bytes.into_boxed_slice());
+    *remote_db.write() = Some(url);
+    load_bytes(app_state, REMOTE_DB.to_string(), bytes, None).await
+}
+
+/// Load database progressively: render the first page as soon as it's ready
+/// and keep adding b-trees while giving the browser a chance to render.
+async fn load_bytes(
+    app_state: AppState,
+    name: String,
+    bytes: &'static [u8],
+    journal: Option<&'static [u8]>,
+) -> Result<(), StdError> {
+    if let Some(cached) = Viewer::from_cache(bytes) {
+        app_state.set_viewer(name, cached);
+        return Ok(());
+    }
+    if let Some(encrypted) = Viewer::encrypted(bytes) {
+        app_state.set_viewer(name, encrypted);
+        return Ok(());
+    }
+    let (mut new_viewer, mut loader) = ViewerLoader::start(bytes)?;
+    if let Some(journal) = journal {
+        new_viewer.attach_journal(journal)?;
+    }
+    app_state.set_viewer(name, new_viewer);
+
+    let mut viewer = app_state.viewer;
+    while !loader.is_done() {
+        yield_now().await;
+        loader.load_next(&mut viewer.write())?;
+    }
+    viewer.read().store_in_cache();
+    Ok(())
+}
+
+/// Decrypt the current SQLCipher database and show its plain content instead, the
+/// settings are kept to explain the reserved space of its pages.
+async fn load_sqlcipher(
+    app_state: AppState,
+    passphrase: String,
+    settings: SqlCipherSettings,
+) -> Result<(), StdError> {
+    let encrypted = app_state.viewer.read().bytes;
+    let bytes = sqlcipher_decrypt(&encrypted, &passphrase, settings)?;
+    let bytes: &'static [u8] = Box::leak(bytes.into_boxed_slice());
+    load_bytes(app_state.clone(), (app_state.current_db)(), bytes, None).await?;
+    let mut viewer = app_state.viewer;
+    viewer.write().cipher = Some(settings);
+    viewer.read().store_in_cache();
+    Ok(())
+}
+
+/// Switch current multi-snapshot example to another snapshot.
+pub async fn load_snapshot(app_state: AppState, snapshot: usize) -> Result<(), StdError> {
+    let name = (app_state.current_db)();
+    let Some((_, source)) = Viewer::included_snapshots(name.as_str()).get(snapshot) else {
+        return Err(format!("Snapshot {} is not included.", snapshot).into());
+    };
+    let viewer = Viewer::new_from_bytes(source.load().await?)?;
+    app_state.set_snapshot(snapshot, viewer);
+    Ok(())
 }
 
 #[component]
 pub fn Home(route: Vec<String>) -> Element {
-    let locked_field = use_context::<AppState>().locked_field;
+    let app_state = use_context::<AppState>();
+    let locked_field = app_state.locked_field;
+    let formatting = app_state.format;
+    let mut raw_mode = app_state.raw_mode;
+    let theme = app_state.theme;
+    let mut loading = app_state.loading;
+    let mut commands = app_state.commands;
+    let settings = app_state.settings;
+
+    // Restore the selection from the link once, the URL follows the selection afterwards.
+    // Without a link, the database from the disk is shown, if the local server provides one.
+    use_hook(|| {
+        let app_state = app_state.clone();
+        *loading.write() = true;
+        spawn(async move {
+            match DeepLink::from_route(&route) {
+                Some(link) => match load_included(app_state.clone(), link.db.clone()).await {
+                    Ok(()) => app_state.restore(&link),
+                    Err(err) => error!("Failed to load {}: {}", link.db, err),
+                },
+                None => {
+                    let _ = load_local(app_state).await;
+                }
+            }
+            *loading.write() = false;
+        });
+    });
+    use_effect(move || replace_url(&app_state.deep_link().to_path()));
+
     rsx! {
         div {
-            class: "focus:outline-none",
+            class: "focus:outline-none bg-base-100 text-base-content",
+            "data-theme": "{theme().daisy_theme()}",
             // Allows to have a focus on div, which is necessary to catch keyboard events.
             tabindex: 0,
             onkeydown: move |e| {
+                if e.modifiers().ctrl() && e.key() == Key::Character("k".to_string()) {
+                    // Browsers focus their own search bar on Ctrl+K otherwise.
+                    if let Some(event) = e.data().downcast::<web_sys::KeyboardEvent>() {
+                        event.prevent_default();
+                    }
+                    *commands.write() = true;
+                    return;
+                }
+                if e.modifiers().alt() {
+                    match e.key() {
+                        Key::ArrowLeft => step_history(false),
+                        Key::ArrowRight => step_history(true),
+                        _ => ()
+                    }
+                    return;
+                }
+                match e.key() {
+                    Key::Character(c) if c == "1" => set_format(Format::Hybrid),
+                    Key::Character(c) if c == "2" => set_format(Format::Hex),
+                    Key::Character(c) if c == "3" => set_format(Format::Text),
+                    Key::Character(c) if c == "f" => set_format(formatting().next()),
+                    Key::Character(c) if c == "r" => *raw_mode.write() = !raw_mode(),
+                    Key::PageUp => step_page(false),
+                    Key::PageDown => step_page(true),
+                    Key::Home => move_to(NavMove::Home, 0, 0),
+                    Key::End => move_to(NavMove::End, 0, 0),
+                    _ => ()
+                }
                 if let Some((np, nf)) = locked_field() {
                     match e.key() {
                         Key::ArrowLeft => move_to(NavMove::Left, nf, np),
                         Key::ArrowRight => move_to(NavMove::Right, nf, np),
+                        Key::ArrowUp => move_to(NavMove::Up, nf, np),
+                        Key::ArrowDown => move_to(NavMove::Down, nf, np),
                         Key::Enter => try_jump(nf, np),
                         _ => ()
                     }
@@ -106,16 +520,31 @@ pub fn Home(route: Vec<String>) -> Element {
             },
             Header { }
             Body { }
+            if commands() {
+                CommandPalette { }
+            }
+            if settings() {
+                SettingsDrawer { }
+            }
         }
     }
 }
 
 pub fn Header() -> Element {
-    let mut current_db = use_context::<AppState>().current_db;
-    let mut viewer = use_context::<AppState>().viewer;
+    let app_state = use_context::<AppState>();
+    let current_db = app_state.current_db;
+    let mut theme = app_state.theme;
+    let mut language = app_state.language;
+    let mut settings = app_state.settings;
+    let viewer = app_state.viewer;
+    let local_db = app_state.local_db;
+    let user_db = app_state.user_db;
+    let remote_db = app_state.remote_db;
+    let fetched = app_state.fetched;
+    let mut loading = app_state.loading;
     rsx! {
         div {
-            class: "h-12 flex items-center bg-slate-200",
+            class: "h-12 flex items-center {theme().surface()}",
             div {
                 class: "pl-4",
                 a {
@@ -136,14 +565,43 @@ pub fn Header() -> Element {
                 ExampleDetails { }
                 select {
                     class: "join-item select select-secondary select-bordered font-bold tracking-tighter focus:outline-none",
+                    disabled: loading(),
                     oninput: move |e| {
-                        *current_db.write() = e.value().to_string();
-                        // preloaded databases shouldn't fail
-                        let new_viewer = Viewer::new_from_included(e.value().as_str()).expect("Viewer failed");
-                        let first_page = new_viewer.get_page(1);
-                        update_selected_page(first_page);
-                        *viewer.write() = new_viewer;
+                        let name = e.value().to_string();
+                        let app_state = app_state.clone();
+                        *loading.write() = true;
+                        spawn(async move {
+                            let loaded = match name.as_str() {
+                                LOCAL_DB => load_local(app_state).await,
+                                USER_DB => load_user(app_state, user_db().unwrap_or_default()).await,
+                                REMOTE_DB => load_remote(app_state, remote_db().unwrap_or_default()).await,
+                                _ => load_included(app_state, name.clone()).await,
+                            };
+                            if let Err(err) = loaded {
+                                error!("Failed to load {}: {}", name, err);
+                            }
+                            *loading.write() = false;
+                        });
                     },
+                    if local_db() {
+                        option {
+                            selected: if current_db() == LOCAL_DB {"true"},
+                            "{LOCAL_DB}",
+                        }
+                    }
+                    if user_db().is_some() {
+                        option {
+                            selected: if current_db() == USER_DB {"true"},
+                            "{USER_DB}",
+                        }
+                    }
+                    if let Some(url) = remote_db() {
+                        option {
+                            title: "{url}",
+                            selected: if current_db() == REMOTE_DB {"true"},
+                            "{REMOTE_DB}",
+                        }
+                    }
                     for name in viewer.read().included_dbnames() {
                         option {
                             selected: if *name == current_db() {"true"},
@@ -152,6 +610,46 @@ pub fn Header() -> Element {
                     }
                 }
             }
+            div {
+                class: "badge badge-outline ml-2",
+                class: if viewer.read().db_header.text_encoding != TextEncoding::UTF8 {"badge-secondary"},
+                title: "Text encoding of the database, all text values are stored in it",
+                "{viewer.read().db_header.text_encoding}"
+            }
+            SnapshotStepper { }
+            CompareSelect { }
+            button {
+                class: "btn btn-sm btn-ghost ml-2",
+                title: "Download parsed structure of the database as JSON",
+                disabled: loading(),
+                onclick: move |_| {
+                    let name = format!("{}.json", DeepLink::slug(&current_db()));
+                    let exported = viewer
+                        .read()
+                        .to_json()
+                        .and_then(|json| download(&name, json.as_bytes()));
+                    if let Err(err) = exported {
+                        error!("Failed to export JSON: {}", err);
+                    }
+                },
+                Icon {
+                    width: 15,
+                    height: 15,
+                    icon: BsFiletypeJson,
+                }
+            }
+            div {
+                class: "min-w-8 pl-2 flex items-center text-xs",
+                if loading() {
+                    span { class: "loading loading-spinner loading-sm" }
+                }
+                if let Some((received, total)) = fetched() {
+                    span {
+                        class: "pl-1 whitespace-nowrap",
+                        {fetched_text(received, total)}
+                    }
+                }
+            }
             div { class: "flex-grow" }
             div {
                 class: "flex text-sm items-center tracking-lighter font-thin",
@@ -165,6 +663,43 @@ pub fn Header() -> Element {
                     }
                 }
             }
+            select {
+                class: "select select-xs select-bordered ml-2",
+                title: "Language of the descriptions",
+                onchange: move |e| {
+                    let name = e.value();
+                    if let Some(l) = Language::ALL.into_iter().find(|l| l.name() == name) {
+                        l.save();
+                        *language.write() = l;
+                    }
+                },
+                for l in Language::ALL {
+                    option {
+                        selected: language() == l,
+                        "{l.name()}"
+                    }
+                }
+            }
+            button {
+                class: "btn btn-sm btn-ghost ml-2",
+                class: if settings() {"btn-active"},
+                title: "Settings of how the values are shown",
+                onclick: move |_| settings.set(!settings()),
+                Icon { icon: BsGear }
+            }
+            button {
+                class: "btn btn-sm btn-ghost ml-2",
+                title: "Switch between the light and the dark theme",
+                onclick: move |_| {
+                    let toggled = theme().toggled();
+                    toggled.save();
+                    *theme.write() = toggled;
+                },
+                match theme() {
+                    Theme::Light => rsx! { Icon { icon: BsMoon } },
+                    Theme::Dark => rsx! { Icon { icon: BsSun } },
+                }
+            }
             div {
                 class: "tooltip tooltip-left pl-2 pr-4",
                 "data-tip": "Like the project? Give us a star ☆",
@@ -180,14 +715,257 @@ pub fn Header() -> Element {
     }
 }
 
+/// Side panel with the settings of how the values are written.
+pub fn SettingsDrawer() -> Element {
+    let mut settings = use_context::<AppState>().settings;
+    let mut hex_style = use_context::<AppState>().hex_style;
+    let style = hex_style();
+    let mut update = move |style: HexStyle| {
+        style.save();
+        *hex_style.write() = style;
+    };
+    rsx! {
+        div {
+            class: "fixed top-12 right-0 z-20 w-72 h-[calc(100vh-48px)] p-4 shadow-xl bg-base-100 text-xs",
+            div {
+                class: "flex items-center pb-2",
+                div {
+                    class: "text-sm font-medium tracking-tighter flex-grow",
+                    "Settings"
+                }
+                button {
+                    class: "btn btn-xs btn-ghost",
+                    onclick: move |_| settings.set(false),
+                    Icon { width: 12, height: 12, icon: BsX }
+                }
+            }
+            div {
+                class: "font-medium pt-2",
+                "Hex of the values"
+            }
+            div {
+                class: "flex items-center pt-2",
+                div { class: "w-24", "Bytes per group" }
+                div {
+                    class: "join",
+                    for group in HEX_VALUE_GROUPS {
+                        button {
+                            class: "join-item btn btn-xs btn-ghost",
+                            class: if style.group == group {"btn-active"},
+                            onclick: move |_| update(HexStyle { group, ..style }),
+                            "{group}"
+                        }
+                    }
+                }
+            }
+            div {
+                class: "flex items-center pt-2",
+                div { class: "w-24", "Digits" }
+                div {
+                    class: "join",
+                    button {
+                        class: "join-item btn btn-xs btn-ghost",
+                        class: if style.uppercase {"btn-active"},
+                        onclick: move |_| update(HexStyle { uppercase: true, ..style }),
+                        "AB"
+                    }
+                    button {
+                        class: "join-item btn btn-xs btn-ghost",
+                        class: if !style.uppercase {"btn-active"},
+                        onclick: move |_| update(HexStyle { uppercase: false, ..style }),
+                        "ab"
+                    }
+                }
+            }
+            label {
+                class: "flex items-center pt-2 cursor-pointer",
+                input {
+                    class: "checkbox checkbox-xs mr-2",
+                    r#type: "checkbox",
+                    checked: style.prefix,
+                    onchange: move |_| update(HexStyle { prefix: !style.prefix, ..style }),
+                }
+                "Prefix every group with 0x"
+            }
+            label {
+                class: "flex items-center pt-2 cursor-pointer",
+                input {
+                    class: "checkbox checkbox-xs mr-2",
+                    r#type: "checkbox",
+                    checked: style.big_endian,
+                    onchange: move |_| update(HexStyle { big_endian: !style.big_endian, ..style }),
+                }
+                "Show values of 2 to 8 bytes read as big-endian integers"
+            }
+            div {
+                class: "pt-4 font-mono",
+                "{style.hex(&[0x53, 0x51, 0x4c, 0x69, 0x74, 0x65, 0x20, 0x33])}"
+            }
+        }
+    }
+}
+
+/// Load the database to compare the current one with, `choice` is either
+/// "db:<included name>", "snapshot:<index>" or empty to stop comparing.
+pub async fn load_compare(app_state: AppState, choice: String) -> Result<(), StdError> {
+    let mut compare = app_state.compare;
+    let (name, bytes) = if let Some(name) = choice.strip_prefix("db:") {
+        (name.to_string(), Viewer::included_bytes(name).await?)
+    } else if let Some(n) = choice.strip_prefix("snapshot:") {
+        let snapshots = Viewer::included_snapshots((app_state.current_db)().as_str());
+        let Some((label, source)) = snapshots.get(n.parse::<usize>()?) else {
+            return Err(format!("Snapshot {} is not included.", n).into());
+        };
+        (compare_snapshot_name(label), source.load().await?)
+    } else {
+        *compare.write() = None;
+        return Ok(());
+    };
+    let base = Viewer::new_from_bytes(bytes)?;
+    *compare.write() = Some(Compare::new(name, base));
+    Ok(())
+}
+
+pub fn compare_snapshot_name(label: &str) -> String {
+    format!("Snapshot: {label}")
+}
+
+/// Pick a database to compare the current one with.
+pub fn CompareSelect() -> Element {
+    let app_state = use_context::<AppState>();
+    let current_db = app_state.current_db;
+    let viewer = app_state.viewer;
+    let compare = app_state.compare;
+    let mut loading = app_state.loading;
+    let compared = compare.read().as_ref().map(|c| c.name.clone());
+    let snapshots = Viewer::included_snapshots(current_db().as_str());
+    rsx! {
+        select {
+            class: "select select-secondary select-bordered select-sm ml-2 font-bold tracking-tighter focus:outline-none",
+            disabled: loading(),
+            oninput: move |e| {
+                let choice = e.value().to_string();
+                let app_state = app_state.clone();
+                *loading.write() = true;
+                spawn(async move {
+                    if let Err(err) = load_compare(app_state, choice.clone()).await {
+                        error!("Failed to compare with {}: {}", choice, err);
+                    }
+                    *loading.write() = false;
+                });
+            },
+            option {
+                value: "",
+                selected: compared.is_none(),
+                "Compare with…"
+            }
+            for (n, (label, _)) in snapshots.iter().enumerate() {
+                option {
+                    value: "snapshot:{n}",
+                    selected: compared == Some(compare_snapshot_name(label)),
+                    "{compare_snapshot_name(label)}"
+                }
+            }
+            for name in viewer.read().included_dbnames() {
+                option {
+                    value: "db:{name}",
+                    selected: compared.as_deref() == Some(name.as_str()),
+                    "{name}"
+                }
+            }
+        }
+    }
+}
+
+/// Step through snapshots of the example captured at several stages.
+pub fn SnapshotStepper() -> Element {
+    let app_state = use_context::<AppState>();
+    let current_db = app_state.current_db;
+    let snapshot = app_state.snapshot;
+    let mut loading = app_state.loading;
+    let snapshots = Viewer::included_snapshots(current_db().as_str());
+    if snapshots.is_empty() {
+        return rsx! {};
+    }
+
+    let current = snapshot();
+    let (label, _) = snapshots[current];
+    let step = move |to: usize| {
+        let app_state = app_state.clone();
+        *loading.write() = true;
+        spawn(async move {
+            if let Err(err) = load_snapshot(app_state, to).await {
+                error!("Failed to load snapshot {}: {}", to, err);
+            }
+            *loading.write() = false;
+        });
+    };
+    rsx! {
+        div {
+            class: "join pl-2",
+            button {
+                class: "join-item btn btn-sm bg-secondary border-secondary",
+                disabled: loading() || current == 0,
+                onclick: {
+                    let mut step = step.clone();
+                    move |_| step(current - 1)
+                },
+                Icon {
+                    icon: BsArrowBarLeft,
+                }
+            }
+            div {
+                class: "join-item btn btn-sm bg-secondary border-secondary no-animation tracking-tighter font-bold",
+                input {
+                    r#type: "range",
+                    class: "range range-xs w-24",
+                    min: 0,
+                    max: "{snapshots.len() - 1}",
+                    value: "{current}",
+                    disabled: loading(),
+                    onchange: {
+                        let mut step = step.clone();
+                        move |e: FormEvent| {
+                            if let Ok(to) = e.value().parse::<usize>() {
+                                if to != current {
+                                    step(to);
+                                }
+                            }
+                        }
+                    },
+                }
+                "{current + 1}/{snapshots.len()}: {label}"
+            }
+            button {
+                class: "join-item btn btn-sm bg-secondary border-secondary",
+                disabled: loading() || current + 1 == snapshots.len(),
+                onclick: {
+                    let mut step = step.clone();
+                    move |_| step(current + 1)
+                },
+                Icon {
+                    icon: BsArrowBarRight,
+                }
+            }
+        }
+    }
+}
+
 pub fn ExampleDetails() -> Element {
     let current_db = use_context::<AppState>().current_db;
     let viewer = use_context::<AppState>().viewer;
+    let user_db = use_context::<AppState>().user_db;
     let rviewer = viewer.read();
-    let details = rviewer.included_db.get(current_db().as_str());
-    match details {
+    let script = match current_db().as_str() {
+        USER_DB => user_db(),
+        name => rviewer
+            .included_db
+            .get(name)
+            .map(|(_, script, _)| script.to_string()),
+    };
+    match script {
         None => rsx! { div { } },
-        Some((_, desc)) => {
+        Some(script) => {
             rsx! {
                 div {
                     class: "dropdown dropdown-hover",
@@ -199,7 +977,7 @@ pub fn ExampleDetails() -> Element {
                     }
                     ul {
                         class: "text-xs dropdown-content z-[1] menu bg-secondary shadow w-max tracking-tighter",
-                        for line in desc {
+                        for line in statements(&script) {
                             li {
                                 a {
                                     "{line}"
@@ -231,9 +1009,19 @@ pub fn Body() -> Element {
 }
 
 pub fn RightSide() -> Element {
+    let mut viewport = use_context::<AppState>().viewport;
     rsx! {
         div {
             class: "h-[calc(100vh-48px)] overflow-y-auto",
+            id: VISUAL_SCROLL_ID,
+            onscroll: move |_| {
+                *viewport.write() = visible_offsets(VISUAL_SCROLL_ID);
+                follow_viewport();
+            },
+            EncryptedBanner { }
+            UncleanBanner { }
+            VacuumWalkthrough { }
+            Breadcrumb { }
             div {
                 Description { }
             }
@@ -244,69 +1032,482 @@ pub fn RightSide() -> Element {
     }
 }
 
-pub fn LeftSide() -> Element {
-    let mut list = use_signal(|| true);
+/// Back and forward buttons with the path from the b-tree root to the selected page.
+pub fn Breadcrumb() -> Element {
+    let viewer = use_context::<AppState>().viewer;
+    let selected_page = use_context::<AppState>().selected_page;
+    let history = use_context::<AppState>().history;
+    let page_num = selected_page().id();
+    let (name, path) = match viewer.read().btree_path(page_num) {
+        Some((btree, path)) => (btree.name.clone(), path),
+        None => (String::new(), vec![page_num]),
+    };
+    let pointers = viewer
+        .read()
+        .pointers
+        .get(&page_num)
+        .cloned()
+        .unwrap_or_default();
     rsx! {
         div {
-            class: "p-4 h-[calc(100vh-48px)] overflow-y-auto w-full text-sm font-medium",
-            div {
-                class: "flex w-full opacity-90",
+            class: "flex items-center bg-secondary tracking-tighter",
+            button {
+                class: "btn btn-xs btn-ghost focus:outline-none",
+                title: "Back, press Alt+Left",
+                disabled: history.read().back.is_empty(),
+                onclick: move |_| step_history(false),
+                Icon {
+                    icon: BsChevronLeft,
+                }
+            }
+            button {
+                class: "btn btn-xs btn-ghost focus:outline-none",
+                title: "Forward, press Alt+Right",
+                disabled: history.read().forward.is_empty(),
+                onclick: move |_| step_history(true),
+                Icon {
+                    icon: BsChevronRight,
+                }
+            }
+            if !name.is_empty() {
                 div {
-                    class: "border border-slate-800 hover:bg-slate-800 hover:text-slate-330",
-                    class: if list() {"bg-slate-800 text-slate-330"},
+                    class: "px-2 font-bold",
+                    "{name}"
+                }
+            }
+            for (i, n) in path.into_iter().enumerate() {
+                if i > 0 {
+                    div { "›" }
+                }
+                button {
+                    class: "btn btn-xs btn-ghost font-bold",
+                    class: if n == page_num {"btn-active"},
                     onclick: move |_| {
-                        list.set(true);
+                        if n != page_num {
+                            update_selected_page(viewer.read().get_page(n as u32));
+                        }
                     },
-                    div {
-                        class: "px-2 py-1",
-                        "Page View"
-                    }
+                    "{n}"
                 }
+            }
+            div { class: "flex-grow" }
+            if !pointers.is_empty() {
                 div {
-                    class: "border border-slate-800 hover:bg-slate-800 hover:text-slate-330",
-                    class: if !list() {"bg-slate-800 text-slate-330"},
-                    onclick: move |_| {
-                        list.set(false);
-                    },
-                    div {
-                        class: "px-2 py-1",
-                        "Tree View"
-                    }
+                    class: "px-2",
+                    "Pointed to from"
                 }
-                div { class: "flex-grow border-b border-b-slate-800" }
             }
-            div {
-                if list() {PageListTab { }} else {PageTreeTab { }}
+            for (from, offset) in pointers {
+                button {
+                    class: "btn btn-xs btn-ghost font-bold",
+                    title: "Field at offset {offset} of page {from}",
+                    onclick: move |_| jump_to_field(from, offset),
+                    "{from}:{offset}"
+                }
             }
         }
     }
 }
 
-pub fn PageListTab() -> Element {
-    let viewer = use_context::<AppState>().viewer;
-    let pages = viewer.read().pages.clone();
-    let selected_page = use_context::<AppState>().selected_page;
+/// Explain why the pages of an encrypted database are shown only as raw bytes and
+/// decrypt the SQLCipher one with the passphrase.
+pub fn EncryptedBanner() -> Element {
+    let app_state = use_context::<AppState>();
+    let viewer = app_state.viewer;
+    let mut passphrase = use_signal(String::new);
+    let mut legacy = use_signal(|| false);
+    let mut loading = use_signal(|| false);
+    let mut failure = use_signal(|| None::<String>);
+    let encryption = viewer.read().encryption.clone()?;
+    let header = match encryption.cipher {
+        Cipher::See => "Bytes 16..24 of the header are left plain by SEE, that's where the page size is read from, the rest of the file is ciphertext.",
+        Cipher::Unknown => "The header is encrypted too: SQLCipher keeps the salt of the key in the first 16 bytes instead of the magic string. The page size is a guess from the defaults, 4096 bytes since SQLCipher 4, 1024 before.",
+    };
+    let page_size = encryption.page_size;
     rsx! {
         div {
-            class: "rounded-box p-4 min-w-fit max-w-fit",
+            role: "alert",
+            class: "alert alert-warning rounded-none text-xs items-start",
             div {
-                for (n, page) in pages.into_iter().enumerate() {
-                    div {
-                        class: "flex",
-                        div { class: "flex-grow" }
-                        div {
-                            class: "leading-tight tracking-tighter font-medium text-cyan-950 text-xs border-r-4 border-cyan-950 pr-1",
-                            "{&page.size() * n}", // page offset
-                        }
-                        button {
-                            class: "w-40 h-fit text-left btn-ghost btn-sm btn-block font-medium tracking-tighter truncate",
-                            class: if selected_page.read().id() == page.id() {"btn-active"},
-                            onclick: move |_| {
-                                update_selected_page(page.clone());
-                            },
-                            "Page {n+1}",
-                            br {}
-                            "{&page.label()}",
+                div {
+                    class: "font-bold pb-1",
+                    "The database is likely encrypted, its pages are shown as raw bytes"
+                }
+                div { "{encryption}" }
+                div { "{header}" }
+                match encryption.cipher {
+                    Cipher::See => rsx! {
+                        div { "Every page is encrypted on its own, so the file keeps the page layout, but nothing inside a page can be parsed without the key. To explore the content, decrypt a copy with the key first and open the copy." }
+                    },
+                    Cipher::Unknown => rsx! {
+                        div { "Every page is encrypted on its own, so the file keeps the page layout, but nothing inside a page can be parsed without the key. SQLCipher databases are decrypted right here with the passphrase, or with the raw key as x'<64 hex digits>', the settings aren't stored in the file. Deriving the key takes a moment." }
+                        form {
+                            class: "pt-2 flex items-center space-x-2",
+                            prevent_default: "onsubmit",
+                            onsubmit: move |_| {
+                                let app_state = app_state.clone();
+                                let preset = match legacy() {
+                                    true => SqlCipherSettings::V3,
+                                    false => SqlCipherSettings::V4,
+                                };
+                                let settings = SqlCipherSettings { page_size, ..preset };
+                                *loading.write() = true;
+                                failure.set(None);
+                                spawn(async move {
+                                    // Let the browser show the progress before the key derivation.
+                                    yield_now().await;
+                                    if let Err(err) = load_sqlcipher(app_state, passphrase(), settings).await {
+                                        failure.set(Some(err.to_string()));
+                                    }
+                                    *loading.write() = false;
+                                });
+                            },
+                            input {
+                                class: "input input-bordered input-xs w-full font-mono",
+                                r#type: "password",
+                                placeholder: "Passphrase",
+                                value: "{passphrase}",
+                                oninput: move |e| passphrase.set(e.value()),
+                                onkeydown: move |e| e.stop_propagation(),
+                            }
+                            select {
+                                class: "select select-bordered select-xs",
+                                onchange: move |e| legacy.set(e.value() == "v3"),
+                                option { value: "v4", selected: !legacy(), "SQLCipher 4" }
+                                option { value: "v3", selected: legacy(), "SQLCipher 3" }
+                            }
+                            button {
+                                class: "btn btn-xs btn-secondary",
+                                r#type: "submit",
+                                disabled: loading() || passphrase().is_empty(),
+                                if loading() { "Decrypting…" } else { "Decrypt" }
+                            }
+                        }
+                        if let Some(err) = failure() {
+                            div {
+                                class: "pt-2 text-orange-700",
+                                "{err}"
+                            }
+                        }
+                    },
+                }
+            }
+        }
+    }
+}
+
+/// Warn that the database might be mid-transaction.
+pub fn UncleanBanner() -> Element {
+    let viewer = use_context::<AppState>().viewer;
+    let warnings = viewer.read().warnings();
+    if warnings.is_empty() {
+        return rsx! {};
+    }
+    rsx! {
+        div {
+            role: "alert",
+            class: "alert alert-warning rounded-none text-xs items-start",
+            div {
+                div {
+                    class: "font-bold pb-1",
+                    "The database file may not reflect the logical database content"
+                }
+                for warning in warnings {
+                    div { "{warning}" }
+                }
+            }
+        }
+    }
+}
+
+pub fn LeftSide() -> Element {
+    let mut tab = use_signal(|| LeftTab::Pages);
+    let tabs = [
+        (LeftTab::Pages, "Page View"),
+        (LeftTab::Tree, "Tree View"),
+        (LeftTab::Table, "Table View"),
+        (LeftTab::Search, "Search"),
+        (LeftTab::Tools, "Tools"),
+        (LeftTab::Storage, "Storage"),
+        (LeftTab::Schema, "Schema"),
+        (LeftTab::Sql, "SQL"),
+        (LeftTab::Remote, "URL"),
+        (LeftTab::Bookmarks, "Bookmarks"),
+    ];
+    rsx! {
+        div {
+            class: "p-4 h-[calc(100vh-48px)] overflow-y-auto w-full text-sm font-medium",
+            div {
+                class: "flex w-full opacity-90",
+                for (t, name) in tabs {
+                    div {
+                        class: "border border-slate-800 hover:bg-slate-800 hover:text-slate-330",
+                        class: if tab() == t {"bg-slate-800 text-slate-330"},
+                        onclick: move |_| {
+                            tab.set(t);
+                        },
+                        div {
+                            class: "px-2 py-1",
+                            "{name}"
+                        }
+                    }
+                }
+                div { class: "flex-grow border-b border-b-slate-800" }
+            }
+            div {
+                match tab() {
+                    LeftTab::Pages => rsx! { PageListTab { } },
+                    LeftTab::Tree => rsx! { PageTreeTab { } },
+                    LeftTab::Table => rsx! { TableViewTab { } },
+                    LeftTab::Search => rsx! { SearchTab { } },
+                    LeftTab::Tools => rsx! { ToolsTab { } },
+                    LeftTab::Storage => rsx! { StorageTab { } },
+                    LeftTab::Schema => rsx! { SchemaTab { } },
+                    LeftTab::Sql => rsx! { SqlPlaygroundTab { } },
+                    LeftTab::Remote => rsx! { RemoteTab { } },
+                    LeftTab::Bookmarks => rsx! { BookmarksTab { } },
+                }
+            }
+        }
+    }
+}
+
+pub fn PageListTab() -> Element {
+    let viewer = use_context::<AppState>().viewer;
+    // Pages of any of the chosen kinds are shown, all of them if none is chosen.
+    let mut filters = use_signal(Vec::<PageFilter>::new);
+    let mut grouped = use_signal(|| false);
+    let pages = viewer
+        .read()
+        .pages
+        .values()
+        .filter(|page| {
+            let filters = filters.read();
+            filters.is_empty() || filters.iter().any(|&f| page.matches(f))
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    let journal = viewer.read().journal.clone();
+    let selected_page = use_context::<AppState>().selected_page;
+    let selected_field = use_context::<AppState>().selected_field;
+    let target = selected_field().try_page_number().ok().map(|n| n as usize);
+    let key_search = use_context::<AppState>().key_search;
+    let visited = key_search
+        .read()
+        .as_ref()
+        .map(|k| k.path.iter().map(|s| s.page_num).collect::<Vec<_>>())
+        .unwrap_or_default();
+    let changed = use_context::<AppState>()
+        .compare
+        .read()
+        .as_ref()
+        .map(|c| c.changed_pages(&viewer.read()))
+        .unwrap_or_default();
+    let mut sort = use_signal(|| PageSort::Number);
+    // Pages of a remote database are summarized as they're shown, they are loaded one by one.
+    let mut pages = pages
+        .into_iter()
+        .map(|page| {
+            let summary = viewer
+                .read()
+                .summaries
+                .get(&page.id())
+                .cloned()
+                .unwrap_or_else(|| page.summary());
+            (page, summary)
+        })
+        .collect::<Vec<_>>();
+    pages.sort_by(|(a, a_summary), (b, b_summary)| {
+        PageSummary::cmp_by(sort(), (a.id(), a_summary), (b.id(), b_summary))
+    });
+    // Hashes of the raw page bytes are computed only when asked for.
+    let mut hash = use_signal(|| None::<PageHash>);
+    let pages = pages
+        .into_iter()
+        .map(|(page, summary)| {
+            let digest = hash().and_then(|h| {
+                let bytes = viewer.read().page_bytes(page.as_ref());
+                (!bytes.is_empty()).then(|| h.hex(bytes))
+            });
+            (page, summary, digest)
+        })
+        .collect::<Vec<_>>();
+    // Pages of every b-tree go in a group of their own, the rest of the pages go last.
+    let groups = match grouped() {
+        false => vec![(String::new(), pages)],
+        true => {
+            let mut groups = vec![];
+            let mut rest = pages;
+            for tree in viewer.read().btrees.iter() {
+                let tree_pages = tree.root.all_pages().into_iter().collect::<HashSet<_>>();
+                let (owned, other): (Vec<_>, Vec<_>) = rest
+                    .into_iter()
+                    .partition(|(page, _, _)| tree_pages.contains(&page.id()));
+                rest = other;
+                if !owned.is_empty() {
+                    groups.push((tree.name.clone(), owned));
+                }
+            }
+            if !rest.is_empty() {
+                groups.push(("Other pages".to_string(), rest));
+            }
+            groups
+        }
+    };
+    let empty = groups.iter().all(|(_, pages)| pages.is_empty());
+    rsx! {
+        div {
+            class: "rounded-box p-4 min-w-fit max-w-fit",
+            div {
+                class: "flex justify-end items-center pb-2 text-xs",
+                span { class: "pr-1", "Hash" }
+                for option in [None, Some(PageHash::Crc32), Some(PageHash::Sha256)] {
+                    button {
+                        class: "btn btn-xs btn-ghost",
+                        class: if hash() == option {"btn-active"},
+                        onclick: move |_| hash.set(option),
+                        {option.map_or("Off".to_string(), |h| h.to_string())}
+                    }
+                }
+            }
+            div {
+                class: "flex justify-end items-center pb-2 text-xs",
+                span { class: "pr-1", "Sort" }
+                for option in PageSort::ALL {
+                    button {
+                        class: "btn btn-xs btn-ghost",
+                        class: if sort() == option {"btn-active"},
+                        onclick: move |_| sort.set(option),
+                        "{option}"
+                    }
+                }
+            }
+            div {
+                class: "flex flex-wrap justify-end gap-1 pb-2 w-56 ml-auto",
+                for filter in PageFilter::ALL {
+                    button {
+                        class: "badge badge-sm cursor-pointer",
+                        class: if filters.read().contains(&filter) {"badge-secondary"} else {"badge-outline"},
+                        onclick: move |_| {
+                            let mut filters = filters.write();
+                            match filters.iter().position(|&f| f == filter) {
+                                Some(n) => {
+                                    filters.remove(n);
+                                }
+                                None => filters.push(filter),
+                            }
+                        },
+                        "{filter}"
+                    }
+                }
+                button {
+                    class: "btn btn-xs btn-ghost",
+                    class: if grouped() {"btn-active"},
+                    onclick: move |_| grouped.toggle(),
+                    "Group by b-tree"
+                }
+            }
+            if empty {
+                div {
+                    class: "text-xs text-right",
+                    "No pages of the chosen kinds."
+                }
+            }
+            div {
+                for (name, pages) in groups.into_iter() {
+                    if !name.is_empty() {
+                        div {
+                            class: "pt-2 pb-1 w-56 ml-auto text-xs font-bold text-right truncate",
+                            title: "{name}",
+                            "{name}"
+                        }
+                    }
+                    for (page, summary, digest) in pages.into_iter() {
+                        div {
+                            class: "flex",
+                            div { class: "flex-grow" }
+                            div {
+                                class: "leading-tight tracking-tighter font-medium text-cyan-950 text-xs border-r-4 border-cyan-950 pr-1",
+                                "{page.base_offset()}", // page offset
+                            }
+                            button {
+                                class: "w-40 h-fit text-left btn-ghost btn-sm btn-block font-medium tracking-tighter truncate",
+                                class: if selected_page.read().id() == page.id() {"btn-active"},
+                                class: if target == Some(page.id()) {"ring-1 ring-orange-700"},
+                                onclick: move |_| {
+                                    update_selected_page(page.clone());
+                                },
+                                "Page {page.id()}",
+                                if page.unreferenced() {
+                                    span {
+                                        class: "badge badge-xs badge-warning ml-1",
+                                        "unreferenced"
+                                    }
+                                }
+                                if let Some(change) = changed.get(&page.id()) {
+                                    span {
+                                        class: "badge badge-xs ml-1",
+                                        class: "{change.style()}",
+                                        "{change.label()}"
+                                    }
+                                }
+                                if visited.contains(&page.id()) {
+                                    span {
+                                        class: "badge badge-xs badge-secondary ml-1",
+                                        "lookup"
+                                    }
+                                }
+                                if summary.fill.is_some() {
+                                    span {
+                                        class: "badge badge-xs badge-outline ml-1",
+                                        title: "Cells on the page",
+                                        "{summary.cells}"
+                                    }
+                                }
+                                if let Some(fill) = summary.fill.filter(|_| sort() == PageSort::Fill) {
+                                    span {
+                                        class: "badge badge-xs badge-outline ml-1",
+                                        title: "Space in use",
+                                        {format!("{:.0}%", fill * 100.0)}
+                                    }
+                                }
+                                if summary.overflow {
+                                    span {
+                                        class: "badge badge-xs badge-outline ml-1",
+                                        title: "Some of the cells spilled onto overflow pages",
+                                        "ᨒ"
+                                    }
+                                }
+                                br {}
+                                "{&page.label()}",
+                                if let Some(digest) = digest {
+                                    span {
+                                        class: "block font-mono font-normal text-xs truncate",
+                                        title: "{digest}",
+                                        "{digest}"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                if let Some(journal) = journal {
+                    div {
+                        class: "flex pt-2",
+                        div { class: "flex-grow" }
+                        div {
+                            class: "leading-tight tracking-tighter font-medium text-cyan-950 text-xs border-r-4 border-cyan-950 pr-1",
+                            "-journal",
+                        }
+                        button {
+                            class: "w-40 h-fit text-left btn-ghost btn-sm btn-block font-medium tracking-tighter truncate",
+                            class: if selected_page.read().id() == journal.id() {"btn-active"},
+                            onclick: move |_| {
+                                update_selected_page(journal.clone());
+                            },
+                            "Journal file",
+                            br {}
+                            "{&journal.label()}",
                         }
                     }
                 }
@@ -317,38 +1518,138 @@ pub fn PageListTab() -> Element {
 
 pub fn PageTreeTab() -> Element {
     let viewer = use_context::<AppState>().viewer;
-    let btrees = &viewer.read().btrees;
+    let mut graph = use_signal(|| false);
+    let mut query = use_signal(String::new);
+    // Root pages of the expanded trees, the first tree is expanded from the start.
+    let mut expanded = use_signal(|| {
+        viewer
+            .read()
+            .btrees
+            .first()
+            .map(|tree| tree.root.page_num)
+            .into_iter()
+            .collect::<HashSet<_>>()
+    });
+    let btrees = viewer
+        .read()
+        .btrees
+        .iter()
+        .filter(|tree| tree.matches(&query()))
+        .cloned()
+        .collect::<Vec<_>>();
+    let roots = btrees.iter().map(|t| t.root.page_num).collect::<Vec<_>>();
     rsx! {
         div {
             class: "rounded-box min-w-48 max-w-96",
+            div {
+                class: "flex items-center gap-1 pt-2",
+                input {
+                    class: "input input-bordered input-xs flex-grow min-w-0",
+                    placeholder: "Name or page number",
+                    value: "{query}",
+                    oninput: move |e| query.set(e.value()),
+                    onkeydown: move |e| e.stop_propagation(),
+                }
+                button {
+                    class: "btn btn-xs btn-ghost",
+                    title: "Expand all the trees shown",
+                    onclick: move |_| expanded.write().extend(roots.iter().copied()),
+                    "Expand"
+                }
+                button {
+                    class: "btn btn-xs btn-ghost",
+                    title: "Collapse all the trees",
+                    onclick: move |_| expanded.write().clear(),
+                    "Collapse"
+                }
+            }
+            div {
+                class: "flex justify-end pt-2",
+                for (is_graph, name) in [(false, "Nodes"), (true, "Graph")] {
+                    button {
+                        class: "btn btn-xs btn-ghost",
+                        class: if graph() == is_graph {"btn-active"},
+                        onclick: move |_| graph.set(is_graph),
+                        "{name}"
+                    }
+                }
+            }
+            if btrees.is_empty() {
+                div {
+                    class: "pt-2 text-xs",
+                    "No tree matches."
+                }
+            }
             div {
                 class: "join join-vertical w-full",
-                for (n, tree) in btrees.iter().enumerate() {
+                for (tree, nested) in BTreeView::nest(btrees) {
                     div {
                         class: "collapse collapse-arrow join-item border-b border-b-slate-800",
+                        class: if nested {"ml-4 w-auto border-l-2 border-l-slate-500"},
                         input {
-                            r#type: "radio",
-                            name: "my-accordion-1",
-                            "checked": if n == 0 {"true"},
+                            r#type: "checkbox",
+                            checked: expanded.read().contains(&tree.root.page_num),
+                            onchange: {
+                                let root = tree.root.page_num;
+                                move |_| {
+                                    let mut expanded = expanded.write();
+                                    if !expanded.remove(&root) {
+                                        expanded.insert(root);
+                                    }
+                                }
+                            },
                         }
                         div {
                             class: "collapse-title text-sm capitalize font-medium truncate",
                             div {
                                 class: "truncate pb-2",
                                 "{tree.name}"
+                                if let Some(internal) = internal_table(&tree.name) {
+                                    span {
+                                        class: "badge badge-xs ml-1 normal-case",
+                                        title: "{internal.desc}",
+                                        "internal"
+                                    }
+                                }
+                                if let Some(shadow) = &tree.shadow {
+                                    span {
+                                        class: "badge badge-xs ml-1 normal-case",
+                                        title: "{shadow.internal().desc}",
+                                        "{shadow.module()} {shadow.vtab()}"
+                                    }
+                                }
                             }
                             div {
                                 class: "text-xs font-normal truncate",
                                 "{tree.ttype} Type Btree"
                             }
+                            if nested {
+                                div {
+                                    class: "text-xs font-normal normal-case truncate",
+                                    title: "The index is on this table, see tbl_name of its schema entry",
+                                    "↳ on {tree.tbl_name}"
+                                }
+                            }
+                            if tree.key_columns.is_some() {
+                                div {
+                                    class: "text-xs font-normal truncate",
+                                    title: "WITHOUT ROWID table is stored in an index b-tree ordered by its PRIMARY KEY.",
+                                    "Without Rowid, Index Btree"
+                                }
+                            }
                             div {
                                 class: "text-xs font-normal truncate",
                                 "Root Page {tree.root.page_num}"
                             }
+                            TreeStats { root: tree.root.clone() }
                         }
                         div {
                             class: "collapse-content text-xs overflow-x-auto overflow-y-hidden",
-                            NodeElement { node: tree.root.clone(), root: true }
+                            if graph() {
+                                BTreeGraph { root: tree.root.clone() }
+                            } else {
+                                NodeElement { node: tree.root.clone(), root: true }
+                            }
                         }
                     }
                 }
@@ -357,9 +1658,537 @@ pub fn PageTreeTab() -> Element {
     }
 }
 
+/// Shape of the b-tree in the header of its tree view entry.
+#[component]
+pub fn TreeStats(root: BTreeNodeView) -> Element {
+    let stats = root.stats();
+    let per_page = format!("{:.1}", stats.cells_per_page());
+    rsx! {
+        div {
+            class: "flex flex-wrap gap-1 pt-1 normal-case",
+            span {
+                class: "badge badge-xs badge-outline",
+                title: "Levels of pages from the root down to the leaves",
+                "depth {stats.depth}"
+            }
+            span {
+                class: "badge badge-xs badge-outline",
+                title: "B-tree pages, overflow pages aren't counted",
+                "{stats.pages} pages"
+            }
+            span {
+                class: "badge badge-xs badge-outline",
+                title: "Cells on all the pages",
+                "{stats.cells} cells"
+            }
+            span {
+                class: "badge badge-xs badge-outline",
+                title: "Cells per page on average",
+                "{per_page} / page"
+            }
+            if stats.overflow_pages > 0 {
+                span {
+                    class: "badge badge-xs badge-outline",
+                    title: "Pages the payload of the cells spilled onto",
+                    "{stats.overflow_pages} overflow"
+                }
+            }
+        }
+    }
+}
+
+pub fn TableViewTab() -> Element {
+    let viewer = use_context::<AppState>().viewer;
+    let tables = viewer
+        .read()
+        .btrees
+        .iter()
+        .filter(|t| t.ttype == "table")
+        .cloned()
+        .collect::<Vec<_>>();
+    let mut selected = use_signal(|| 0);
+    // Rows are read from the raw bytes again only once the database or the table
+    // changes, not on every render or change of the viewer.
+    let shown = use_memo(move || {
+        let viewer = viewer.read();
+        let tables = viewer
+            .btrees
+            .iter()
+            .filter(|t| t.ttype == "table")
+            .collect::<Vec<_>>();
+        let root = tables.get(selected()).or(tables.first()).map(|t| t.root.page_num);
+        (viewer.hash, root)
+    });
+    let rows = use_memo(move || shown().1.map(|root| viewer.peek().table_rows(root)));
+    let table = tables.get(selected()).or(tables.first()).cloned()?;
+    let rows = rows()?;
+    let gaps = rows.as_ref().ok().and_then(|rows| rowid_summary(rows));
+    let freeblocks = match &rows {
+        Ok(rows) => {
+            let mut pages = rows.iter().map(|r| r.page_num).collect::<Vec<_>>();
+            pages.dedup();
+            viewer.read().freeblocks(&pages)
+        }
+        Err(_) => vec![],
+    };
+    rsx! {
+        div {
+            class: "pt-4",
+            select {
+                class: "select select-bordered select-sm w-full max-w-xs",
+                onchange: move |e| {
+                    if let Ok(n) = e.value().parse::<usize>() {
+                        selected.set(n);
+                    }
+                },
+                for (n, t) in tables.iter().enumerate() {
+                    option {
+                        value: "{n}",
+                        selected: n == selected(),
+                        "{t.name}"
+                    }
+                }
+            }
+            // WITHOUT ROWID tables are stored in an index b-tree, which isn't searched by rowid.
+            if table.key_columns.is_none() {
+                RowidLookup { root: table.root.page_num }
+            }
+            if SqlarEntry::is_archive(&table.name, &table.columns) {
+                SqlarListing { root: table.root.page_num }
+            }
+            if let Some(gaps) = gaps {
+                div {
+                    class: "pt-4 text-xs",
+                    title: "Rows deleted after the last one can't be noticed this way.",
+                    "{gaps}"
+                }
+            }
+            if !freeblocks.is_empty() {
+                div {
+                    class: "pt-2 text-xs flex flex-wrap items-center gap-1",
+                    "Deleted content might remain in freeblocks on",
+                    for (page_num, offset) in freeblocks {
+                        button {
+                            class: "btn btn-ghost btn-xs",
+                            onclick: move |_| jump_to_field(page_num, offset),
+                            "Page {page_num}"
+                        }
+                    }
+                }
+            }
+            match rows {
+                Err(e) => rsx! {
+                    div {
+                        class: "pt-4 text-xs text-orange-700",
+                        "Failed to read rows: {e}"
+                    }
+                },
+                Ok(rows) => rsx! {
+                    div {
+                        class: "pt-4 overflow-x-auto",
+                        table {
+                            class: "table table-xs table-pin-rows",
+                            thead {
+                                tr {
+                                    if table.key_columns.is_none() {
+                                        th { "rowid" }
+                                    }
+                                    for (n, column) in table.columns.iter().enumerate() {
+                                        th {
+                                            title: if table.key_columns.is_some_and(|key| n < key) {"PRIMARY KEY"},
+                                            "{column}"
+                                        }
+                                    }
+                                }
+                            }
+                            tbody {
+                                for row in rows.into_iter() {
+                                    tr {
+                                        class: "hover",
+                                        if table.key_columns.is_none() {
+                                            th {
+                                                class: "cursor-pointer",
+                                                onclick: move |_| jump_to_field(row.page_num, row.cell_offset),
+                                                if let Some(rowid) = row.rowid {"{rowid}"}
+                                            }
+                                        }
+                                        for (n, value) in row.values.into_iter().map(|v| Value::Record(v).to_string()).enumerate() {
+                                            td {
+                                                class: "cursor-pointer max-w-48 truncate",
+                                                onclick: move |_| jump_to_value(row.page_num, row.cell_offset, n),
+                                                "{value}"
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                },
+            }
+        }
+    }
+}
+
+/// Look for hex byte sequences or text in the whole database file.
+pub fn SearchTab() -> Element {
+    let app_state = use_context::<AppState>();
+    let viewer = app_state.viewer;
+    let mut search = app_state.search;
+    let mut query = use_signal(String::new);
+    let mut mode = use_signal(|| SearchMode::Text);
+    let mut error = use_signal(|| None::<String>);
+    let page_size = viewer.read().db_header.page_size as usize;
+
+    let text_encoding = viewer.read().db_header.text_encoding;
+    let mut run = move || match Search::parse(&query(), mode(), text_encoding) {
+        Ok(needle) => {
+            *search.write() = Search::run(viewer.read().bytes, needle);
+            error.set(None);
+        }
+        Err(err) => error.set(Some(err.to_string())),
+    };
+    let hits = search.read().hits.clone();
+    let truncated = search.read().truncated;
+    rsx! {
+        div {
+            class: "pt-4 max-w-96",
+            div {
+                class: "join w-full",
+                input {
+                    class: "join-item input input-bordered input-sm w-full",
+                    placeholder: if mode() == SearchMode::Hex {"53 51 4c 69 74 65"} else {"SQLite"},
+                    value: "{query}",
+                    oninput: move |e| query.set(e.value()),
+                    onkeydown: move |e| {
+                        // Typed characters aren't shortcuts of the page.
+                        e.stop_propagation();
+                        if e.key() == Key::Enter {
+                            run();
+                        }
+                    },
+                }
+                for (m, name) in [(SearchMode::Text, "Text"), (SearchMode::Hex, "Hex")] {
+                    button {
+                        class: "join-item btn btn-sm",
+                        class: if mode() == m {"btn-active"},
+                        onclick: move |_| mode.set(m),
+                        "{name}"
+                    }
+                }
+            }
+            if let Some(err) = error() {
+                div {
+                    class: "pt-2 text-xs text-orange-700",
+                    "Invalid query: {err}"
+                }
+            } else if !search.read().needle.is_empty() {
+                div {
+                    class: "pt-2 text-xs",
+                    if truncated {
+                        "First {MAX_SEARCH_HITS} matches"
+                    } else {
+                        "{hits.len()} matches"
+                    }
+                }
+            }
+            div {
+                class: "pt-2",
+                for hit in hits {
+                    button {
+                        class: "btn-ghost btn-xs btn-block text-left font-normal",
+                        onclick: move |_| jump_to_field(hit / page_size + 1, hit % page_size),
+                        "Page {hit / page_size + 1}, offset {hit % page_size}"
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Entries of sqlite_schema with highlighted SQL and links to their root pages.
+pub fn SchemaTab() -> Element {
+    let viewer = use_context::<AppState>().viewer;
+    let entries = match viewer.read().schema_entries() {
+        Ok(entries) => entries,
+        Err(e) => {
+            return rsx! {
+                div {
+                    class: "pt-4 text-xs text-orange-700",
+                    "Failed to read sqlite_schema: {e}"
+                }
+            }
+        }
+    };
+    rsx! {
+        div {
+            class: "pt-4 max-w-96 text-xs",
+            for entry in entries {
+                div {
+                    class: "pb-3 mb-3 border-b border-b-slate-800",
+                    div {
+                        class: "flex items-center space-x-2",
+                        span { class: "badge badge-xs", "{entry.etype}" }
+                        span { class: "font-bold truncate", "{entry.name}" }
+                        if entry.tbl_name != entry.name {
+                            span { class: "truncate", "on {entry.tbl_name}" }
+                        }
+                        div { class: "flex-grow" }
+                        if let Some(root) = entry.rootpage {
+                            button {
+                                class: "btn btn-xs btn-ghost",
+                                title: "Open the root page",
+                                onclick: move |_| {
+                                    update_selected_page(viewer.read().get_page(root as u32));
+                                },
+                                "Page {root}"
+                            }
+                        }
+                    }
+                    if let Some(sql) = &entry.sql {
+                        SqlText { sql: sql.clone() }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Bookmarked fields of the current database with their notes.
+pub fn BookmarksTab() -> Element {
+    let current_db = use_context::<AppState>().current_db;
+    let mut bookmarks = use_context::<AppState>().bookmarks;
+    let list = bookmarks.read().of_db(&current_db());
+    rsx! {
+        div {
+            class: "pt-4 max-w-96 text-xs",
+            if list.is_empty() {
+                div {
+                    "Bookmark the selected field from its description below the page to find it here."
+                }
+            }
+            for bookmark in list {
+                div {
+                    class: "pb-2 mb-2 border-b border-b-slate-800",
+                    div {
+                        class: "flex items-center",
+                        button {
+                            class: "btn btn-xs btn-ghost font-bold",
+                            onclick: move |_| jump_to_field(bookmark.page, bookmark.offset),
+                            "Page {bookmark.page} @{bookmark.offset}"
+                        }
+                        div { class: "flex-grow" }
+                        button {
+                            class: "btn btn-xs btn-ghost",
+                            title: "Remove the bookmark",
+                            onclick: {
+                                let bookmark = bookmark.clone();
+                                move |_| bookmarks.write().remove(&bookmark.db, bookmark.page, bookmark.offset)
+                            },
+                            "Remove"
+                        }
+                    }
+                    input {
+                        class: "input input-bordered input-xs w-full",
+                        placeholder: "Note",
+                        value: "{bookmark.note}",
+                        oninput: {
+                            let bookmark = bookmark.clone();
+                            move |e: FormEvent| {
+                                bookmarks.write().set(Bookmark {
+                                    note: e.value(),
+                                    ..bookmark.clone()
+                                })
+                            }
+                        },
+                        onkeydown: move |e| e.stop_propagation(),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Build a database in the browser from the typed SQL and show its layout.
+pub fn SqlPlaygroundTab() -> Element {
+    let app_state = use_context::<AppState>();
+    let mut loading = app_state.loading;
+    let user_db = app_state.user_db;
+    let mut script = use_signal(|| user_db().unwrap_or_else(|| SQL_PLAYGROUND_SCRIPT.to_string()));
+    let mut failure = use_signal(|| None::<String>);
+    rsx! {
+        div {
+            class: "pt-4 max-w-96 text-xs",
+            div {
+                class: "font-medium",
+                "SQL playground"
+            }
+            div {
+                class: "pt-1",
+                "Statements run against a new in-memory database with SQLite compiled to WebAssembly, the resulting file is shown as any other database. PRAGMA page_size and encoding take effect only before the first table is created."
+            }
+            textarea {
+                class: "textarea textarea-bordered textarea-xs w-full h-64 mt-2 font-mono",
+                spellcheck: "false",
+                value: "{script}",
+                oninput: move |e| script.set(e.value()),
+                onkeydown: move |e| e.stop_propagation(),
+            }
+            div {
+                class: "pt-2 flex items-center space-x-2",
+                button {
+                    class: "btn btn-xs btn-secondary",
+                    disabled: loading(),
+                    onclick: move |_| {
+                        let app_state = app_state.clone();
+                        *loading.write() = true;
+                        failure.set(None);
+                        spawn(async move {
+                            if let Err(err) = load_user(app_state, script()).await {
+                                failure.set(Some(err.to_string()));
+                            }
+                            *loading.write() = false;
+                        });
+                    },
+                    "Build database"
+                }
+            }
+            if let Some(err) = failure() {
+                div {
+                    class: "pt-2 text-orange-700",
+                    "{err}"
+                }
+            }
+        }
+    }
+}
+
+/// Open a database by its HTTP(S) URL, fetched in chunks with Range requests.
+pub fn RemoteTab() -> Element {
+    let app_state = use_context::<AppState>();
+    let mut loading = app_state.loading;
+    let remote_db = app_state.remote_db;
+    let mut url = use_signal(|| remote_db().unwrap_or_default());
+    let mut failure = use_signal(|| None::<String>);
+    rsx! {
+        div {
+            class: "pt-4 max-w-96 text-xs",
+            div {
+                class: "font-medium",
+                "Remote database"
+            }
+            div {
+                class: "pt-1",
+                "The file is fetched by 1 MB Range requests and shown once it's complete. Files over 64 MB are explored page by page instead: only the opened pages are fetched, one request each, and b-trees aren't traversed. The server has to allow cross-origin requests and expose Content-Range for the size to be known."
+            }
+            form {
+                class: "pt-2 flex items-center space-x-2",
+                prevent_default: "onsubmit",
+                onsubmit: move |_| {
+                    let app_state = app_state.clone();
+                    *loading.write() = true;
+                    failure.set(None);
+                    spawn(async move {
+                        if let Err(err) = load_remote(app_state, url().trim().to_string()).await {
+                            failure.set(Some(err.to_string()));
+                        }
+                        *loading.write() = false;
+                    });
+                },
+                input {
+                    class: "input input-bordered input-xs w-full font-mono",
+                    r#type: "url",
+                    placeholder: "https://example.com/database.sqlite",
+                    value: "{url}",
+                    oninput: move |e| url.set(e.value()),
+                    onkeydown: move |e| e.stop_propagation(),
+                }
+                button {
+                    class: "btn btn-xs btn-secondary",
+                    r#type: "submit",
+                    disabled: loading() || url().trim().is_empty(),
+                    "Open"
+                }
+            }
+            if let Some(err) = failure() {
+                div {
+                    class: "pt-2 text-orange-700",
+                    "{err}"
+                }
+            }
+        }
+    }
+}
+
+/// Received and total size of the fetched database, in KB.
+fn fetched_text(received: usize, total: Option<usize>) -> String {
+    match total {
+        Some(total) => format!("{} / {} KB", received >> 10, total >> 10),
+        None => format!("{} KB", received >> 10),
+    }
+}
+
+/// Starting point of the SQL playground.
+const SQL_PLAYGROUND_SCRIPT: &str = "PRAGMA page_size=1024;
+CREATE TABLE planets(name TEXT, moons INT);
+INSERT INTO planets VALUES('Mercury', 0), ('Venus', 0), ('Earth', 1), ('Mars', 2);
+CREATE INDEX idx_planets_name ON planets(name);
+";
+
+/// SQL statement with keywords, identifiers and literals highlighted.
+#[component]
+pub fn SqlText(sql: String) -> Element {
+    let spans = highlight(&sql)
+        .into_iter()
+        .map(|(kind, text)| {
+            let class = match kind {
+                SqlSpan::Keyword => "font-bold text-blue-800",
+                SqlSpan::Quoted | SqlSpan::Identifier => "",
+                SqlSpan::Literal => "text-green-800",
+                SqlSpan::Number => "text-orange-800",
+                SqlSpan::Comment => "italic text-slate-500",
+                SqlSpan::Punct | SqlSpan::Space => "",
+            };
+            (class, text)
+        })
+        .collect::<Vec<_>>();
+    rsx! {
+        pre {
+            class: "pt-1 whitespace-pre-wrap break-words font-mono",
+            for (class, text) in spans {
+                span { class: "{class}", "{text}" }
+            }
+        }
+    }
+}
+
+/// Describe continuity of rowids, e.g. "Rowids 1–100 present except 17, 42".
+fn rowid_summary(rows: &[Row]) -> Option<String> {
+    const MAX_GAPS: usize = 20;
+    let first = rows.iter().find_map(|r| r.rowid)?;
+    let last = rows.iter().rev().find_map(|r| r.rowid)?;
+    let gaps = Row::rowid_gaps(rows);
+    if gaps.is_empty() {
+        return Some(format!("Rowids {first}–{last} all present"));
+    }
+    let mut missing = gaps
+        .iter()
+        .take(MAX_GAPS)
+        .map(|&(start, end)| match start == end {
+            true => format!("{start}"),
+            false => format!("{start}–{end}"),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    if gaps.len() > MAX_GAPS {
+        missing.push_str(&format!(" and {} more gaps", gaps.len() - MAX_GAPS));
+    }
+    Some(format!("Rowids {first}–{last} present except {missing}"))
+}
+
 #[component]
 pub fn NodeElement(node: BTreeNodeView, root: bool) -> Element {
-    let children_interior = node.children.iter().any(|c| c.children.is_empty() == false);
+    let children_interior = node.children.iter().any(|c| !c.children.is_empty());
     let node_type = if node.children.is_empty() {
         "Leaf".to_string()
     } else {
@@ -368,6 +2197,13 @@ pub fn NodeElement(node: BTreeNodeView, root: bool) -> Element {
 
     let viewer = use_context::<AppState>().viewer;
     let selected_page = use_context::<AppState>().selected_page;
+    let selected_field = use_context::<AppState>().selected_field;
+    let target = selected_field().try_page_number().ok().map(|n| n as usize);
+    let key_search = use_context::<AppState>().key_search;
+    let visited = key_search
+        .read()
+        .as_ref()
+        .is_some_and(|k| k.path.iter().any(|s| s.page_num == node.page_num));
     rsx! {
         div {
             class: "w-full",
@@ -377,11 +2213,11 @@ pub fn NodeElement(node: BTreeNodeView, root: bool) -> Element {
                     div {
                         class: "flex items-center space-x-1 btn-ghost btn-xs btn-block",
                         class: if selected_page.read().id() == node.page_num {"btn-active"},
+                        class: if target == Some(node.page_num) {"ring-1 ring-orange-700"},
                         onclick: {
-
-                            let pages = viewer.read().pages.to_vec();
+                            let page = viewer.read().get_page(node.page_num as u32);
                             move |_| {
-                                update_selected_page(pages[node.page_num - 1].clone());
+                                update_selected_page(page.clone());
                             }
                         },
                         Icon {
@@ -396,16 +2232,22 @@ pub fn NodeElement(node: BTreeNodeView, root: bool) -> Element {
                         div {
                             if root {"Root {node_type}"} else {"{node_type}"}
                         }
+                        if visited {
+                            span {
+                                class: "badge badge-xs badge-secondary",
+                                "lookup"
+                            }
+                        }
                     }
                     for page_num in node.overflow {
                         div {
                             class: "flex pl-3 items-center space-x-1 btn-ghost btn-xs btn-block",
                             class: if selected_page.read().id() == page_num {"btn-active"},
+                            class: if target == Some(page_num) {"ring-1 ring-orange-700"},
                             onclick: {
-
-                                let pages = viewer.read().pages.to_vec();
+                                let page = viewer.read().get_page(page_num as u32);
                                 move |_| {
-                                    update_selected_page(pages[page_num - 1].clone());
+                                    update_selected_page(page.clone());
                                 }
                             },
                             Icon {
@@ -441,15 +2283,71 @@ pub fn Description() -> Element {
     let selected_page = use_context::<AppState>().selected_page;
     let selected_part = use_context::<AppState>().selected_part;
     let selected_field = use_context::<AppState>().selected_field;
-    let part_desc = selected_part().desc();
+    let addressing = use_context::<AppState>().addressing;
+    let current_db = use_context::<AppState>().current_db;
+    let mut bookmarks = use_context::<AppState>().bookmarks;
+    let language = use_context::<AppState>().language;
+    let hex_style = use_context::<AppState>().hex_style;
+    let part_desc = language().tr(selected_part().desc());
     let part_label = selected_part().label();
     let field = selected_field();
+    let (page_num, offset) = (selected_page().id(), field.offset);
+    let bookmarked = bookmarks
+        .read()
+        .get(&current_db(), page_num, offset)
+        .is_some();
+    let blob = match &field.value {
+        Value::Record(record) => match &record.value {
+            RecordType::Blob(Some(bytes)) => Some(bytes.clone()),
+            _ => None,
+        },
+        _ => None,
+    };
+    // Blob, which looks like JSONB, can be shown as the JSON it holds.
+    let mut show_json = use_signal(|| false);
+    let json = blob
+        .as_deref()
+        .and_then(|bytes| jsonb_to_json(bytes, true).ok());
     rsx! {
         div {
             class: "p-4 h-80 w-full overflow-auto",
             div {
-                "{selected_page().desc()}"
+                class: "flex items-start",
+                div {
+                    class: "flex-grow",
+                    "{language().tr(selected_page().desc())}"
+                }
+                // Journal pseudo page isn't a part of the database file.
+                if page_num != 0 {
+                    button {
+                        class: "btn btn-xs btn-ghost",
+                        title: if bookmarked {"Remove the bookmark"} else {"Bookmark the field"},
+                        onclick: move |_| {
+                            let db = current_db();
+                            match bookmarked {
+                                true => bookmarks.write().remove(&db, page_num, offset),
+                                false => bookmarks.write().set(Bookmark {
+                                    db,
+                                    page: page_num,
+                                    offset,
+                                    note: String::new(),
+                                }),
+                            }
+                        },
+                        if bookmarked {
+                            Icon { width: 15, height: 15, icon: BsBookmarkFill }
+                        } else {
+                            Icon { width: 15, height: 15, icon: BsBookmark }
+                        }
+                    }
+                }
+                ExportBar {}
             }
+            PageUsage { page_num: selected_page().id() }
+            InsertionSandbox { page_num: selected_page().id() }
+            OverflowOwnerLink {}
+            OverflowChainMap {}
+            FreelistMap {}
             FieldNavigation { title: part_label }
             div {
                 class: "text-xs",
@@ -459,7 +2357,7 @@ pub fn Description() -> Element {
                 class: "flex pt-6 text-xs space-x-6",
                 div {
                     class: "w-2/3",
-                    "{field.desc}"
+                    "{language().tr(field.desc)}"
                 }
                 div {
                     class: "w-1/3",
@@ -468,10 +2366,13 @@ pub fn Description() -> Element {
                         tbody {
                             tr {
                                 td {
-                                    "Offset"
+                                    match addressing() {
+                                        Addressing::Page => "Offset",
+                                        Addressing::File => "File offset",
+                                    }
                                 }
                                 td {
-                                    "{field.offset} byte(s)"
+                                    "{addressing().offset(selected_page().as_ref(), field.offset)} byte(s)"
                                 }
                             }
                             tr {
@@ -482,14 +2383,36 @@ pub fn Description() -> Element {
                                     "{field.size} byte(s)"
                                 }
                             }
+                            if let Some(column) = &field.column {
+                                tr {
+                                    td {
+                                        "Column"
+                                    }
+                                    td {
+                                        "{column}"
+                                    }
+                                }
+                            }
                             tr {
                                 td {
                                     "Value"
                                 }
                                 td {
                                     div {
-                                        class: "truncate",
-                                        "{field.value}"
+                                        class: "flex items-center",
+                                        div {
+                                            class: "truncate flex-grow",
+                                            {field.custom_render().unwrap_or(field.value.to_string())}
+                                        }
+                                        if json.is_some() {
+                                            button {
+                                                class: "btn btn-xs btn-ghost",
+                                                class: if show_json() {"btn-active"},
+                                                title: "The blob is JSONB, show it decoded as JSON",
+                                                onclick: move |_| show_json.set(!show_json()),
+                                                "JSON"
+                                            }
+                                        }
                                     }
                                 }
                             }
@@ -500,7 +2423,7 @@ pub fn Description() -> Element {
                                 td {
                                     div {
                                         class: "truncate",
-                                        "{field.to_hex()}"
+                                        "{field.to_hex(&hex_style())}"
                                     }
                                 }
                             }
@@ -508,6 +2431,176 @@ pub fn Description() -> Element {
                     }
                 }
             }
+            if let Some(json) = json.filter(|_| show_json()) {
+                pre {
+                    class: "mt-4 p-2 max-h-64 overflow-auto whitespace-pre-wrap bg-base-200 text-xs",
+                    "{json}"
+                }
+            }
+            if let Some(bytes) = blob {
+                BlobPreview { bytes }
+            }
+            FieldNotes { field: field.clone() }
+            if let Value::Varint(varint) = &field.value {
+                VarintPlayground { value: varint.value }
+            }
+            if let Some(cell_offset) = selected_part().overflow_cell() {
+                ReassembledPayload { page_num: selected_page().id(), cell_offset }
+            }
+        }
+    }
+}
+
+/// Save the selected page or field as raw bytes, or copy the field hex.
+pub fn ExportBar() -> Element {
+    let app_state = use_context::<AppState>();
+    let page = (app_state.selected_page)();
+    let field = (app_state.selected_field)();
+    let prefix = match page.id() {
+        0 => format!("{}-journal", DeepLink::slug(&(app_state.current_db)())),
+        id => format!("{}-page-{}", DeepLink::slug(&(app_state.current_db)()), id),
+    };
+    let page_name = format!("{}.bin", prefix);
+    let svg_name = format!("{}.svg", prefix);
+    let field_name = format!("{}-offset-{}.bin", prefix, field.offset);
+    let (page_bytes, field_bytes) = {
+        let viewer = app_state.viewer.read();
+        (
+            viewer.page_bytes(page.as_ref()),
+            viewer.field_bytes(page.as_ref(), &field),
+        )
+    };
+    let palette = app_state.palette;
+    rsx! {
+        div {
+            class: "flex flex-none space-x-1 pl-4",
+            button {
+                class: "btn btn-xs btn-ghost",
+                title: "Download the fields of the page as a picture, {svg_name}",
+                onclick: {
+                    let page = page.clone();
+                    move |_| {
+                        let svg = page_svg(page.as_ref(), palette());
+                        if let Err(err) = download(&svg_name, svg.as_bytes()) {
+                            error!("Failed to export SVG: {}", err);
+                        }
+                    }
+                },
+                Icon {
+                    width: 12,
+                    height: 12,
+                    icon: BsImage,
+                }
+                "SVG"
+            }
+            button {
+                class: "btn btn-xs btn-ghost",
+                title: "Download the whole page as {page_name}",
+                onclick: move |_| {
+                    if let Err(err) = download(&page_name, page_bytes) {
+                        error!("Failed to export page: {}", err);
+                    }
+                },
+                Icon {
+                    width: 12,
+                    height: 12,
+                    icon: BsDownload,
+                }
+                "Page"
+            }
+            button {
+                class: "btn btn-xs btn-ghost",
+                title: "Download bytes of the field as {field_name}",
+                onclick: move |_| {
+                    if let Err(err) = download(&field_name, field_bytes) {
+                        error!("Failed to export field: {}", err);
+                    }
+                },
+                Icon {
+                    width: 12,
+                    height: 12,
+                    icon: BsDownload,
+                }
+                "Field"
+            }
+            button {
+                class: "btn btn-xs btn-ghost",
+                title: "Copy bytes of the field as a hex string",
+                onclick: move |_| {
+                    spawn(async move {
+                        if let Err(err) = copy_to_clipboard(&hex::encode(field_bytes)).await {
+                            error!("Failed to copy field: {}", err);
+                        }
+                    });
+                },
+                Icon {
+                    width: 12,
+                    height: 12,
+                    icon: BsClipboard,
+                }
+                "Hex"
+            }
+        }
+    }
+}
+
+/// Mismatches of the selected field with the file and derived explanations of its value
+/// with links to related fields.
+#[component]
+pub fn FieldNotes(field: Rc<Field>) -> Element {
+    let viewer = use_context::<AppState>().viewer;
+    let selected_page = use_context::<AppState>().selected_page;
+    let selected_part = use_context::<AppState>().selected_part;
+    let notes = field.notes(&viewer.read().db_header);
+    let diagnostics = viewer
+        .read()
+        .diagnostics(selected_page().id(), &field)
+        .to_vec();
+    let fts5 = viewer.read().fts5_notes(selected_part().as_ref(), &field);
+    let cipher = viewer.read().cipher_notes(&field);
+    rsx! {
+        for diagnostic in diagnostics {
+            div {
+                class: "alert alert-warning mt-4 text-xs",
+                "{diagnostic}"
+            }
+        }
+        for note in notes {
+            div {
+                class: "alert bg-secondary mt-4 text-xs flex",
+                div {
+                    class: "flex-grow",
+                    "{note.text}"
+                }
+                if let Some((page_num, offset)) = note.related {
+                    button {
+                        class: "btn btn-xs btn-ghost tracking-tighter font-bold",
+                        onclick: move |_| jump_to_field(page_num, offset),
+                        "{note.related_label}",
+                        Icon {
+                            width: 15,
+                            height: 15,
+                            icon: BsArrowRight,
+                        }
+                    }
+                }
+            }
+        }
+        if !fts5.is_empty() {
+            div {
+                class: "alert bg-secondary mt-4 text-xs flex flex-col items-start gap-1",
+                for line in fts5 {
+                    div { "{line}" }
+                }
+            }
+        }
+        if !cipher.is_empty() {
+            div {
+                class: "alert bg-secondary mt-4 text-xs flex flex-col items-start gap-1",
+                for line in cipher {
+                    div { "{line}" }
+                }
+            }
         }
     }
 }
@@ -562,42 +2655,341 @@ pub fn FieldNavigation(title: String) -> Element {
 pub fn Visual() -> Element {
     let selected_page = use_context::<AppState>().selected_page;
     let page = selected_page();
-    let parts = page.parts();
-    let mut formatting = use_context::<AppState>().format;
+    let formatting = use_context::<AppState>().format;
+    let mut hex_dump = use_context::<AppState>().hex_dump;
+    let mut raw_mode = use_context::<AppState>().raw_mode;
+    let mut value_limit = use_context::<AppState>().value_limit;
+    let viewer = use_context::<AppState>().viewer;
+    let has_journal = viewer.read().journal_page(page.id()).is_some();
+    let compare = use_context::<AppState>().compare;
+    let mut diff_split = use_context::<AppState>().diff_split;
+    let split = diff_split() && compare.read().is_some();
+    let mut pinned_page = use_context::<AppState>().pinned_page;
+    let mut pinned_field = use_context::<AppState>().pinned_field;
+    let pinned = pinned_page().is_some_and(|p| p.id() != page.id());
+    let side_pane = has_journal || hex_dump() || split || pinned;
+    // Freelist page might be shown as its stale b-tree content instead.
+    let primary = viewer.read().get_page(page.id() as u32);
+    let alternate = primary.alternates().first().cloned();
+    let stale = primary.alternates().iter().any(|a| Rc::ptr_eq(a, &page));
+    let mut legend = use_signal(|| false);
+    let mut addressing = use_context::<AppState>().addressing;
     rsx! {
         div {
             class: "flex items-center bg-secondary",
-            div { class: "flex-grow" }
             div {
                 class: "btn btn-xs btn-ghost tracking-tighter font-bold",
-                class: if formatting() == Format::Hybrid {"btn-active"},
+                class: if hex_dump() {"btn-active"},
                 onclick: move |_| {
-                    *formatting.write() = Format::Hybrid
+                    *hex_dump.write() = !hex_dump()
                 },
-                "Hybrid",
+                "Dump",
             }
             div {
                 class: "btn btn-xs btn-ghost tracking-tighter font-bold",
-                class: if formatting() == Format::Hex {"btn-active"},
+                class: if raw_mode() {"btn-active"},
+                title: "Show the page as plain bytes, whatever it's parsed as, press R",
                 onclick: move |_| {
-                    *formatting.write() = Format::Hex
+                    *raw_mode.write() = !raw_mode()
                 },
-                "Hex",
+                "Raw",
             }
             div {
                 class: "btn btn-xs btn-ghost tracking-tighter font-bold",
-                class: if formatting() == Format::Text {"btn-active"},
+                class: if addressing() == Addressing::File {"btn-active"},
+                title: "Count offsets from the start of the database file instead of the page",
                 onclick: move |_| {
-                    *formatting.write() = Format::Text
+                    *addressing.write() = addressing().toggled()
+                },
+                "File offsets",
+            }
+            div {
+                class: "btn btn-xs btn-ghost tracking-tighter font-bold",
+                class: if pinned_page().is_some_and(|p| p.id() == page.id()) {"btn-active"},
+                title: "Keep this page open next to the others",
+                onclick: {
+                    let page = page.clone();
+                    move |_| {
+                        let unpin = pinned_page().is_some_and(|p| p.id() == page.id());
+                        *pinned_page.write() = (!unpin).then(|| page.clone());
+                        *pinned_field.write() = None;
+                    }
                 },
+                "Pin",
+            }
+            if compare.read().is_some() {
+                div {
+                    class: "btn btn-xs btn-ghost tracking-tighter font-bold",
+                    class: if diff_split() {"btn-active"},
+                    title: "Show the compared page side by side",
+                    onclick: move |_| {
+                        *diff_split.write() = !diff_split()
+                    },
+                    "Split",
+                }
+                DiffSummary { }
+            }
+            if let Some(alternate) = alternate {
+                div {
+                    class: "btn btn-xs btn-ghost tracking-tighter font-bold",
+                    class: if stale {"btn-active"},
+                    title: "Show the b-tree content left on the page before it was freed",
+                    onclick: move |_| {
+                        match stale {
+                            true => update_selected_page(primary.clone()),
+                            false => update_selected_page(alternate.clone()),
+                        }
+                    },
+                    "Ghost",
+                }
+            }
+            div { class: "flex-grow" }
+            div {
+                class: "btn btn-xs btn-ghost tracking-tighter font-bold",
+                class: if legend() {"btn-active"},
+                title: "What the colors of the parts mean",
+                onclick: move |_| legend.set(!legend()),
+                "Legend",
+            }
+            select {
+                class: "select select-xs select-ghost tracking-tighter font-bold",
+                title: "Bytes of long values shown before the rest is cut",
+                onchange: move |e| {
+                    if let Ok(limit) = e.value().parse() {
+                        let limit = ValueLimit(limit);
+                        limit.save();
+                        *value_limit.write() = limit;
+                    }
+                },
+                for limit in VALUE_LIMITS {
+                    option {
+                        value: "{limit}",
+                        selected: value_limit().0 == limit,
+                        "Cut at {limit} B"
+                    }
+                }
+            }
+            div {
+                class: "btn btn-xs btn-ghost tracking-tighter font-bold",
+                class: if formatting() == Format::Hybrid {"btn-active"},
+                title: "Hybrid format, press 1",
+                onclick: move |_| set_format(Format::Hybrid),
+                "Hybrid",
+            }
+            div {
+                class: "btn btn-xs btn-ghost tracking-tighter font-bold",
+                class: if formatting() == Format::Hex {"btn-active"},
+                title: "Hex format, press 2",
+                onclick: move |_| set_format(Format::Hex),
+                "Hex",
+            }
+            div {
+                class: "btn btn-xs btn-ghost tracking-tighter font-bold",
+                class: if formatting() == Format::Text {"btn-active"},
+                title: "Text format, press 3",
+                onclick: move |_| set_format(Format::Text),
                 "Text",
             }
         }
+        if legend() {
+            Legend { }
+        }
+        if !raw_mode() {
+            OffsetRuler { }
+        }
+        div {
+            class: "flex",
+            if raw_mode() {
+                div {
+                    class: "flex-grow",
+                    class: if side_pane {"w-1/2"},
+                    RawPage { }
+                }
+            } else {
+                div {
+                    class: "flex flex-wrap flex-grow content-start p-4 text-xs",
+                    class: if side_pane {"w-1/2"},
+                    FieldGrid { }
+                    if compare.read().is_some() && !split {
+                        RemovedFields { page_num: page.id() }
+                    }
+                }
+                MiniMap { }
+            }
+            if side_pane {
+                div {
+                    class: "w-1/2 border-l-4 border-secondary",
+                    if hex_dump() {
+                        HexDump { }
+                    }
+                    if has_journal {
+                        JournalPane { page_num: page.id() }
+                    }
+                    if split {
+                        ComparePane { page_num: page.id() }
+                    }
+                    if pinned {
+                        PinnedPane { }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Colors of the part roles in the active palette, which can be switched here.
+pub fn Legend() -> Element {
+    let mut palette = use_context::<AppState>().palette;
+    rsx! {
+        div {
+            class: "flex flex-wrap items-center px-4 pt-2 space-x-4 text-xs tracking-tighter",
+            for role in Role::ALL {
+                div {
+                    class: "flex items-center space-x-1",
+                    title: "{role.desc()}",
+                    div {
+                        class: "w-3 h-3 bg-{palette().color(role)}-600",
+                    }
+                    div { "{role.label()}" }
+                }
+            }
+            div { class: "flex-grow" }
+            select {
+                class: "select select-xs select-bordered",
+                onchange: move |e| {
+                    let name = e.value();
+                    if let Some(p) = Palette::ALL.into_iter().find(|p| p.name() == name) {
+                        p.save();
+                        *palette.write() = p;
+                    }
+                },
+                for p in Palette::ALL {
+                    option {
+                        selected: palette() == p,
+                        "{p.name()}"
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Pinned page next to the selected one. Its fields are selected independently,
+/// the pointers to the selected page are marked and open the pointed page on click.
+pub fn PinnedPane() -> Element {
+    let selected_page = use_context::<AppState>().selected_page;
+    let palette = use_context::<AppState>().palette;
+    let theme = use_context::<AppState>().theme;
+    let addressing = use_context::<AppState>().addressing;
+    let viewer = use_context::<AppState>().viewer;
+    let language = use_context::<AppState>().language;
+    let mut pinned_page = use_context::<AppState>().pinned_page;
+    let mut pinned_field = use_context::<AppState>().pinned_field;
+    let page = pinned_page()?;
+    let current = selected_page().id();
+    let parts = page.parts();
+    let selected = pinned_field().and_then(|(np, nf)| {
+        let part = parts.get(np)?;
+        Some((part.label(), part.fields().get(nf)?.clone()))
+    });
+    rsx! {
+        div {
+            class: "p-4 text-xs",
+            div {
+                class: "flex items-center text-sm font-medium tracking-tighter",
+                div {
+                    class: "flex-grow",
+                    "Pinned: Page {page.id()}, {page.label()}"
+                }
+                button {
+                    class: "btn btn-xs btn-ghost",
+                    title: "Open the pinned page in the main pane",
+                    onclick: {
+                        let page = page.clone();
+                        move |_| update_selected_page(page.clone())
+                    },
+                    "Open",
+                }
+                button {
+                    class: "btn btn-xs btn-ghost",
+                    onclick: move |_| {
+                        *pinned_page.write() = None;
+                        *pinned_field.write() = None;
+                    },
+                    "Unpin",
+                }
+            }
+            if let Some((label, field)) = selected {
+                div {
+                    class: "pt-1",
+                    span { class: "font-medium", "{label}: " }
+                    "{language().tr(field.desc)}"
+                }
+            }
+            div {
+                class: "flex flex-wrap content-start",
+                for (np, part) in page.parts().iter().enumerate() {
+                    for (nf, field) in part.fields().iter().enumerate() {
+                        div {
+                            div {
+                                class: "mb-0 mt-1 pr-2 leading-tight tracking-tighter font-medium text-{palette().color(part.role())}-{theme().part_shade()}",
+                                "{addressing().offset(page.as_ref(), field.offset)}",
+                            }
+                            div {
+                                class: "p-1 outline outline-1 outline-secondary hover:bg-secondary cursor-pointer border-t-4 border-{palette().color(part.role())}-{theme().part_shade()} {theme().surface()}",
+                                class: "{field.style.class(theme())}",
+                                class: if pinned_field() == Some((np, nf)) {"locked"},
+                                class: if field.try_page_number().is_ok_and(|n| n as usize == current) {"paired"},
+                                onclick: {
+                                    let field = field.clone();
+                                    move |_| {
+                                        *pinned_field.write() = Some((np, nf));
+                                        if let Ok(n) = field.try_page_number() {
+                                            update_selected_page(viewer.read().get_page(n));
+                                        }
+                                    }
+                                },
+                                FormattedValue {field: field.clone(), trimmed: true}
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Original content of the page, as it's kept in the rollback journal.
+#[component]
+pub fn JournalPane(page_num: usize) -> Element {
+    let viewer = use_context::<AppState>().viewer;
+    let palette = use_context::<AppState>().palette;
+    let theme = use_context::<AppState>().theme;
+    let addressing = use_context::<AppState>().addressing;
+    let page = viewer.read().journal_page(page_num)?;
+    rsx! {
         div {
-            class: "flex flex-wrap p-4 text-xs",
-            for (p, part) in parts.iter().enumerate() {
-                for (f, _) in part.fields().iter().enumerate() {
-                    FieldElement {nf: f, np: p}
+            class: "p-4 text-xs",
+            div {
+                class: "text-sm font-medium tracking-tighter",
+                "Rollback journal: original content of Page {page_num}"
+            }
+            div {
+                class: "flex flex-wrap content-start",
+                for part in page.parts().iter() {
+                    for field in part.fields().iter() {
+                        div {
+                            div {
+                                class: "mb-0 mt-1 pr-2 leading-tight tracking-tighter font-medium text-{palette().color(part.role())}-{theme().part_shade()}",
+                                "{addressing().offset(page.as_ref(), field.offset)}",
+                            }
+                            div {
+                                class: "p-1 outline outline-1 outline-secondary border-t-4 border-{palette().color(part.role())}-{theme().part_shade()} {theme().surface()}",
+                                class: "{field.style.class(theme())}",
+                                FormattedValue {field: field.clone(), trimmed: true}
+                            }
+                        }
+                    }
                 }
             }
         }
@@ -612,18 +3004,77 @@ pub fn FieldElement(nf: usize, np: usize) -> Element {
     let mut trimmed = use_signal(|| true);
     let mut locked = use_context::<AppState>().locked_field;
 
-    let part = &selected_page().parts()[np].clone();
+    let search = use_context::<AppState>().search;
+    let viewer = use_context::<AppState>().viewer;
+    let compare = use_context::<AppState>().compare;
+
+    let page = selected_page();
+    let part = &page.parts()[np].clone();
     let field = &part.fields()[nf];
+    // Journal pseudo page isn't a part of the database file.
+    let found = page.id() != 0
+        && search
+            .read()
+            .covers(page.base_offset() + field.offset, field.size);
+    let change = compare
+        .read()
+        .as_ref()
+        .and_then(|c| c.field_change(&viewer.read(), page.as_ref(), field));
+    let change_style = change.map_or("", |c| c.style());
+    let paired = is_paired(&page, np, field);
+    let diagnostics = viewer.read().diagnostics(page.id(), field).join(" ");
+    let color = use_context::<AppState>().palette.read().color(part.role());
+    let theme = use_context::<AppState>().theme;
+    let addressing = use_context::<AppState>().addressing;
+    let current_db = use_context::<AppState>().current_db;
+    let value_limit = use_context::<AppState>().value_limit;
+    // Unallocated space is expanded by a click on the field itself.
+    let long = field.is_long(value_limit().0) && !matches!(field.value, Value::Unallocated(_));
+    let mut menu = use_signal(|| false);
+    // Texts to copy are only made for the open menu, the hex of large fields is long.
+    let copies = match menu() {
+        true => vec![
+            ("Copy value", field.value.to_string()),
+            (
+                "Copy hex",
+                hex::encode(viewer.read().field_bytes(page.as_ref(), field)),
+            ),
+            (
+                "Copy absolute offset",
+                (page.base_offset() + field.offset).to_string(),
+            ),
+        ],
+        false => vec![],
+    };
+    let link = DeepLink {
+        db: current_db(),
+        page: page.id(),
+        field: Some((np, nf)),
+    };
     rsx! {
         div {
+            id: "field-{np}-{nf}",
+            class: "relative",
+            "data-offset": "{field.offset}",
             div {
-                class: "mb-0 mt-1 pr-2 leading-tight tracking-tighter font-medium text-{part.color()}-800",
-                "{field.offset}",
+                class: "mb-0 mt-1 pr-2 leading-tight tracking-tighter font-medium text-{color}-{theme().part_shade()}",
+                "{addressing().offset(page.as_ref(), field.offset)}",
+                if !diagnostics.is_empty() {
+                    span {
+                        class: "pl-1 text-warning",
+                        title: "{diagnostics}",
+                        "⚠"
+                    }
+                }
             }
             div {
-                class: "p-1 outline outline-1 outline-secondary hover:bg-secondary border-t-4 border-{part.color()}-800 bg-slate-200",
-                class: "{field.style}",
+                class: "p-1 outline outline-1 outline-secondary hover:bg-secondary border-t-4 border-{color}-{theme().part_shade()} {theme().surface()}",
+                class: "{field.style.class(theme())}",
                 class: if locked() == Some((np, nf)) {"locked"},
+                class: if found {"found"},
+                class: if paired {"paired"},
+                class: "{change_style}",
+                prevent_default: "oncontextmenu",
                 onmouseover: {
                     let part = part.clone();
                     let field = field.clone();
@@ -658,26 +3109,113 @@ pub fn FieldElement(nf: usize, np: usize) -> Element {
                         }
                     }
                 },
+                oncontextmenu: move |_| {
+                    menu.set(true);
+                },
                 FormattedValue {field: field.clone(), trimmed: trimmed()}
+                if long {
+                    div {
+                        class: "pt-1 text-right text-slate-500 hover:underline cursor-pointer",
+                        onclick: move |e| {
+                            e.stop_propagation();
+                            *trimmed.write() = !trimmed();
+                        },
+                        if trimmed() { "Expand {field.size} bytes" } else { "Collapse" }
+                    }
+                }
+            }
+            if menu() {
+                ul {
+                    class: "menu menu-xs absolute z-10 left-0 top-full w-44 p-1 shadow rounded-box bg-base-100 text-xs",
+                    onmouseleave: move |_| menu.set(false),
+                    for (label, text) in copies {
+                        li {
+                            a {
+                                onclick: move |_| {
+                                    menu.set(false);
+                                    copy_text(text.clone());
+                                },
+                                "{label}"
+                            }
+                        }
+                    }
+                    li {
+                        a {
+                            onclick: move |_| {
+                                menu.set(false);
+                                match absolute_url(&link.to_path()) {
+                                    Ok(url) => copy_text(url),
+                                    Err(err) => error!("Failed to copy the link: {}", err),
+                                }
+                            },
+                            "Copy deep link"
+                        }
+                    }
+                }
             }
         }
     }
 }
 
+/// Put the text into the clipboard, failures are only logged.
+fn copy_text(text: String) {
+    spawn(async move {
+        if let Err(err) = copy_to_clipboard(&text).await {
+            error!("Failed to copy: {}", err);
+        }
+    });
+}
+
+/// Whether the field is a part of the cell the selected cell pointer points to,
+/// or the cell pointer to the selected cell.
+fn is_paired(page: &Rc<dyn PageView>, np: usize, field: &Field) -> bool {
+    let selected_field = use_context::<AppState>().selected_field;
+    let selected_part = use_context::<AppState>().selected_part;
+    if let Value::CellStartOffset(ptr) = selected_field().value {
+        return page.field_at(ptr as usize).is_some_and(|(p, _)| p == np);
+    }
+    match field.value {
+        Value::CellStartOffset(ptr) => selected_part()
+            .fields()
+            .first()
+            .is_some_and(|f| f.offset == ptr as usize),
+        _ => false,
+    }
+}
+
+/// Bytes of the value read as a big-endian integer.
+#[component]
+fn BigEndianHint(n: u64) -> Element {
+    rsx! {
+        div {
+            class: "text-slate-500",
+            title: "Bytes read as a big-endian unsigned integer, the byte order of the file format",
+            "BE {n}"
+        }
+    }
+}
+
 #[component]
 pub fn FormattedValue(field: Rc<Field>, trimmed: bool) -> Element {
     let formatting = use_context::<AppState>().format;
-    let limit: usize = 10;
+    let ValueLimit(limit) = use_context::<AppState>().value_limit.read().to_owned();
+    let style = use_context::<AppState>().hex_style.read().to_owned();
     let hex = if trimmed {
-        field.trim_hex(limit)
+        field.trim_hex(limit, &style)
     } else {
-        field.to_hex()
+        field.to_hex(&style)
     };
+    let big_endian = field.big_endian().filter(|_| style.big_endian);
     let text = if trimmed {
         field.trim_str(limit)
     } else {
         field.value.to_string()
     };
+    let text = field.custom_render().unwrap_or(text);
+    let text = match &field.column {
+        Some(column) => format!("{column}: {text}"),
+        None => text,
+    };
     match formatting() {
         Format::Hybrid => {
             rsx! {
@@ -689,6 +3227,9 @@ pub fn FormattedValue(field: Rc<Field>, trimmed: bool) -> Element {
                     div {
                         "{hex}"
                     }
+                    if let Some(n) = big_endian {
+                        BigEndianHint { n }
+                    }
                 }
             }
         }
@@ -697,6 +3238,9 @@ pub fn FormattedValue(field: Rc<Field>, trimmed: bool) -> Element {
                 div {
                     "{hex}"
                 }
+                if let Some(n) = big_endian {
+                    BigEndianHint { n }
+                }
             }
         }
         Format::Text => {
@@ -0,0 +1,108 @@
+//! Sandbox of what inserting one more cell would do to the selected b-tree page.
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+use parser::Placement;
+
+use crate::state::AppState;
+use crate::Role;
+
+/// What inserting one more cell would do to the b-tree page, for a payload size to try.
+#[component]
+pub fn InsertionSandbox(page_num: usize) -> Element {
+    let viewer = use_context::<AppState>().viewer;
+    let palette = use_context::<AppState>().palette;
+    let mut open = use_signal(|| false);
+    let mut payload = use_signal(|| "100".to_string());
+    let size = payload().trim().parse::<u64>().ok();
+    let insertion = viewer
+        .read()
+        .simulate_insertion(page_num, size.unwrap_or_default())?;
+    let page_size = viewer.read().db_header.page_size as usize;
+    let cell_size = insertion.cell_size;
+    let (offset, outcome) = match insertion.placement {
+        Placement::Unallocated { offset } => (
+            Some(offset),
+            format!("Fits into the unallocated space, the cell goes at {offset}, right before the cell content area."),
+        ),
+        Placement::Freeblock { offset, block_offset, left } => (
+            Some(offset),
+            match left {
+                0 => format!("Takes the whole freeblock at {block_offset}, the cell goes at {offset}."),
+                1..=3 => format!("Takes the freeblock at {block_offset}, the cell goes at {offset}, {left} byte(s) left of it become fragmented."),
+                _ => format!("Cut from the end of the freeblock at {block_offset}, the cell goes at {offset}, {left} byte(s) stay a freeblock."),
+            },
+        ),
+        Placement::Defragment { offset } => (
+            Some(offset),
+            format!("Free bytes are enough, but scattered: the page is defragmented first, then the cell goes at {offset}."),
+        ),
+        Placement::Split => (
+            None,
+            format!(
+                "Doesn't fit: {} free byte(s) are less than the cell and its pointer, the page is split, its cells are balanced with the sibling pages.",
+                insertion.free
+            ),
+        ),
+    };
+    let spill = insertion
+        .split
+        .as_ref()
+        .filter(|s| s.overflow > 0)
+        .map(|s| {
+            format!(
+                "Payload spills: {} byte(s) stay on the page, {} go onto {} overflow page(s).",
+                s.local, s.overflow, s.overflow_pages
+            )
+        });
+    let left = offset.map(|o| format!("{:.2}%", o as f64 * 100.0 / page_size as f64));
+    let width = format!(
+        "{:.2}%",
+        (cell_size as f64 * 100.0 / page_size as f64).max(0.5)
+    );
+    rsx! {
+        div {
+            class: "pt-2 text-xs",
+            button {
+                class: "btn btn-xs btn-ghost",
+                onclick: move |_| open.toggle(),
+                if open() {"Hide insertion sandbox"} else {"What if a cell is inserted?"}
+            }
+            if open() {
+                div {
+                    class: "flex items-center gap-2 pt-1",
+                    "Payload of"
+                    input {
+                        class: "input input-bordered input-xs w-24",
+                        r#type: "number",
+                        min: "0",
+                        value: "{payload}",
+                        oninput: move |e| payload.set(e.value()),
+                        onkeydown: move |e| e.stop_propagation(),
+                    }
+                    "bytes makes a cell of {cell_size} byte(s), the page has {insertion.free} free"
+                }
+                if size.is_none() {
+                    div {
+                        class: "pt-1 text-orange-700",
+                        "Payload size is a number of bytes."
+                    }
+                }
+                if let Some(left) = left {
+                    div {
+                        class: "relative w-full h-3 mt-1 bg-slate-300",
+                        title: "Page, the new cell is marked where it would go",
+                        div {
+                            class: "absolute h-full bg-{palette().color(Role::Content)}-700",
+                            style: "left: {left}; width: {width}",
+                        }
+                    }
+                }
+                div { class: "pt-1", "{outcome}" }
+                if let Some(spill) = spill {
+                    div { class: "pt-1", "{spill}" }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,232 @@
+//! Rollback journal UI representation and description
+use std::rc::Rc;
+
+use parser::*;
+
+use crate::{Field, FieldKind, Part, Role, Style, Value};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JournalHeaderPart {
+    id: usize,
+    fields: Vec<Rc<Field>>,
+}
+
+impl JournalHeaderPart {
+    pub fn new(segment: &JournalSegment, id: usize) -> Self {
+        let offset = segment.offset;
+        let header = &segment.header;
+        let fields = vec![
+            Rc::new(Field::new(
+                FieldKind::JournalMagic,
+                "Header string: 0xd9, 0xd5, 0x05, 0xf9, 0x20, 0xa1, 0x63, 0xd7. It has no significance except that it's an easily recognizable pattern to identify the beginning of a journal header.",
+                offset,
+                8,
+                Value::Array(Box::new(header.magic)),
+                Style::Plain,
+            )),
+            Rc::new(Field::new(
+                FieldKind::JournalPageCount,
+                "The 'Page Count': the number of pages in the next segment of the journal, or -1 to mean all content to the end of the file. A journal is only valid for playback if the page count was synced to disk before the database file was modified.",
+                offset + 8,
+                4,
+                Value::U32(header.page_count),
+                Style::Plain,
+            )),
+            Rc::new(Field::new(
+                FieldKind::JournalNonce,
+                "A random nonce for the checksum. Every page record checksum starts from this value, so stale records left over from previous transactions are recognized as invalid.",
+                offset + 12,
+                4,
+                Value::U32(header.nonce),
+                Style::Plain,
+            )),
+            Rc::new(Field::new(
+                FieldKind::JournalInitialSize,
+                "Initial size of the database in pages. On rollback the database file is truncated back to this size.",
+                offset + 16,
+                4,
+                Value::U32(header.initial_db_size),
+                Style::Plain,
+            )),
+            Rc::new(Field::new(
+                FieldKind::JournalSectorSize,
+                "Size of a disk sector assumed by the process that wrote this journal. The journal header is padded with zeros out to the next sector boundary and every following segment starts on a sector boundary.",
+                offset + 20,
+                4,
+                Value::U32(header.sector_size),
+                Style::Plain,
+            )),
+            Rc::new(Field::new(
+                FieldKind::JournalPageSize,
+                "Size of pages in this journal, which is the same as the page size of the database.",
+                offset + 24,
+                4,
+                Value::U32(header.page_size),
+                Style::Plain,
+            )),
+        ];
+        Self { id, fields }
+    }
+}
+
+impl Part for JournalHeaderPart {
+    fn label(&self) -> String {
+        format!("Journal Header {}", self.id)
+    }
+
+    fn desc(&self) -> &'static str {
+        "A rollback journal begins with a header that is padded out to the size of one disk sector. The same header appears again at the start of every following journal segment. All integers are big-endian."
+    }
+
+    fn role(&self) -> Role {
+        Role::Header
+    }
+
+    fn fields(&self) -> &[Rc<Field>] {
+        self.fields.as_slice()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JournalRecordPart {
+    id: usize,
+    fields: Vec<Rc<Field>>,
+}
+
+impl JournalRecordPart {
+    pub fn new(record: &JournalRecord, id: usize) -> Self {
+        let offset = record.offset;
+        let checksum_desc = if record.valid {
+            "Checksum of the page record: the segment nonce plus every 200th byte of the page data, starting from the byte at offset page size - 200 and going towards the beginning of the page. It matches, so this record would be played back on rollback."
+        } else {
+            "Checksum of the page record: the segment nonce plus every 200th byte of the page data, starting from the byte at offset page size - 200 and going towards the beginning of the page. It doesn't match, which means the record is incomplete or stale and rollback stops here."
+        };
+        let fields = vec![
+            Rc::new(Field::new(
+                FieldKind::JournalPageNumber,
+                "The page number in the database file, which content is stored in this record. Click to open the current version of the page.",
+                offset,
+                4,
+                Value::PageNumber(record.page_num),
+                Style::Plain,
+            )),
+            Rc::new(Field::new(
+                FieldKind::JournalPageContent,
+                "Original content of the page prior to the start of the transaction.",
+                offset + 4,
+                record.data.len(),
+                Value::Unallocated(record.data.as_slice().into()),
+                Style::Plain,
+            )),
+            Rc::new(Field::new(
+                FieldKind::JournalChecksum,
+                checksum_desc,
+                offset + 4 + record.data.len(),
+                4,
+                Value::U32(record.checksum),
+                Style::Plain,
+            )),
+        ];
+        Self { id, fields }
+    }
+}
+
+impl Part for JournalRecordPart {
+    fn label(&self) -> String {
+        format!("Page Record {}", self.id)
+    }
+
+    fn desc(&self) -> &'static str {
+        "Each page record stores the original content of one database page, so the page can be restored if the transaction rolls back. Only the first change of a page within a transaction makes it into the journal."
+    }
+
+    fn role(&self) -> Role {
+        if self.id.is_multiple_of(2) {
+            Role::Content
+        } else {
+            Role::ContentAlt
+        }
+    }
+
+    fn fields(&self) -> &[Rc<Field>] {
+        self.fields.as_slice()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SuperJournalPointerPart {
+    fields: Vec<Rc<Field>>,
+}
+
+impl SuperJournalPointerPart {
+    pub fn new(pointer: &SuperJournalPointer) -> Self {
+        let offset = pointer.offset;
+        let name_len = pointer.length as usize;
+        let checksum_desc = if pointer.valid {
+            "Checksum of the name: the sum of its bytes. It matches, so the super-journal decides whether this journal is played back: if the super-journal is gone, the multi-database transaction has committed and the journal is stale."
+        } else {
+            "Checksum of the name: the sum of its bytes. It doesn't match, so the pointer is ignored and the journal is treated as the one of a single database transaction."
+        };
+        let fields = vec![
+            Rc::new(Field::new(
+                FieldKind::SuperJournalPageNumber,
+                "Number of the lock-byte page, which never has a page record, so a pointer is told apart from a page record by it.",
+                offset,
+                4,
+                Value::PageNumber(pointer.page_num),
+                Style::Plain,
+            )),
+            Rc::new(Field::new(
+                FieldKind::SuperJournalName,
+                "Name of the super-journal file, which lists the journals of all the databases changed by the transaction.",
+                offset + 4,
+                name_len,
+                Value::Text(Rc::new(pointer.name.clone())),
+                Style::Plain,
+            )),
+            Rc::new(Field::new(
+                FieldKind::SuperJournalLength,
+                "Length of the super-journal name in bytes.",
+                offset + 4 + name_len,
+                4,
+                Value::U32(pointer.length),
+                Style::Plain,
+            )),
+            Rc::new(Field::new(
+                FieldKind::SuperJournalChecksum,
+                checksum_desc,
+                offset + 8 + name_len,
+                4,
+                Value::U32(pointer.checksum),
+                Style::Plain,
+            )),
+            Rc::new(Field::new(
+                FieldKind::JournalMagic,
+                "Header string: 0xd9, 0xd5, 0x05, 0xf9, 0x20, 0xa1, 0x63, 0xd7, the same as the one of the journal header. It ends the file, so the pointer is found by reading the journal backwards.",
+                offset + 12 + name_len,
+                8,
+                Value::Array(Box::new(pointer.magic)),
+                Style::Plain,
+            )),
+        ];
+        Self { fields }
+    }
+}
+
+impl Part for SuperJournalPointerPart {
+    fn label(&self) -> String {
+        "Super-journal Pointer".to_string()
+    }
+
+    fn desc(&self) -> &'static str {
+        "A transaction, which changes several attached databases at once, is made atomic with a super-journal. Each database gets its own journal, which ends with a pointer to the super-journal, and the super-journal lists all of these journals. Deleting the super-journal is the moment the whole transaction commits."
+    }
+
+    fn role(&self) -> Role {
+        Role::Header
+    }
+
+    fn fields(&self) -> &[Rc<Field>] {
+        self.fields.as_slice()
+    }
+}
@@ -1,49 +1,319 @@
 //! UI related traits, data transformations and descriptons to simplify
 //! rendering of parsed structures.
 
+pub mod browser;
+pub mod chains;
+pub mod commands;
+pub mod compare;
+pub mod content;
+pub mod corrupt;
+pub mod diff;
+pub mod dump;
 pub mod freelist;
+pub mod graph;
+pub mod grid;
 pub mod header;
+pub mod i18n;
 pub mod included_db;
+pub mod insertion;
 pub mod index;
+pub mod journal;
+pub mod lookup;
+pub mod lock_byte;
 pub mod overflow_pages;
+pub mod preview;
 pub mod pages;
+pub mod ptrmap;
+pub mod remote;
+pub mod renderers;
+pub mod reserved;
+pub mod rtree;
+pub mod sqlar;
 pub mod state;
+pub mod svg;
+pub mod tools;
+pub mod unknown;
+pub mod unreferenced;
+pub mod usage;
+pub mod vacuum;
 pub mod viewer;
 
 use core::fmt;
+use std::cell::RefCell;
 use std::rc::Rc;
 
 use parser::*;
 
+use crate::state::{HexStyle, Theme};
+
+/// Parts of the page in the order of their offsets.
+pub type Parts = Rc<[Rc<dyn Part>]>;
+
 pub trait PageView: std::fmt::Debug {
     fn id(&self) -> usize;
     fn size(&self) -> usize;
     fn label(&self) -> String;
     fn desc(&self) -> &'static str;
-    fn parts(&self) -> &[Rc<dyn Part>];
+    fn parts(&self) -> Parts;
+
+    /// Drop the built parts, they are built again once asked for.
+    fn release_parts(&self) {}
+
+    /// Page isn't reachable from any b-tree or the freelist.
+    fn unreferenced(&self) -> bool {
+        false
+    }
+
+    /// Other interpretations of the same bytes, e.g. stale b-tree content of a freelist page.
+    fn alternates(&self) -> &[Rc<dyn PageView>] {
+        &[]
+    }
+
+    /// Names of record values of the b-tree the page belongs to.
+    fn columns(&self) -> &[String] {
+        &[]
+    }
+
+    /// B-tree cell, which payload spilled onto the overflow page.
+    fn overflow_owner(&self) -> Option<OverflowOwner> {
+        None
+    }
+
+    /// Whether the page is of the kind the page list is filtered by.
+    fn matches(&self, _filter: PageFilter) -> bool {
+        false
+    }
+
+    /// What the page list shows about the page, see `Viewer::summaries`.
+    fn summary(&self) -> PageSummary {
+        PageSummary::new(self.label())
+    }
+
+    /// Offset of the page in the database file. Journal pseudo page starts at zero,
+    /// as it's a file of its own.
+    fn base_offset(&self) -> usize {
+        self.id().saturating_sub(1) * self.size()
+    }
+
+    /// Bytes of the page in the database file, shorter if the file is truncated.
+    fn slice<'a>(&self, file: &'a [u8]) -> &'a [u8] {
+        let start = self.base_offset();
+        let end = (start + self.size()).min(file.len());
+        file.get(start..end).unwrap_or_default()
+    }
+
+    /// Part and field indexes of the field, which covers page offset.
+    fn field_at(&self, offset: usize) -> Option<(usize, usize)> {
+        self.parts().iter().enumerate().find_map(|(np, part)| {
+            part.fields()
+                .iter()
+                .position(|f| offset >= f.offset && offset < f.offset + f.size.max(1))
+                .map(|nf| (np, nf))
+        })
+    }
+}
+
+/// Kind of pages the page list can be narrowed down to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PageFilter {
+    TableLeaves,
+    IndexPages,
+    Overflow,
+    Freelist,
+    /// B-tree pages with cells, which payload spilled onto overflow pages.
+    WithOverflow,
+    Corrupt,
+}
+
+impl PageFilter {
+    pub const ALL: [PageFilter; 6] = [
+        PageFilter::TableLeaves,
+        PageFilter::IndexPages,
+        PageFilter::Overflow,
+        PageFilter::Freelist,
+        PageFilter::WithOverflow,
+        PageFilter::Corrupt,
+    ];
+}
+
+impl fmt::Display for PageFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let label = match self {
+            PageFilter::TableLeaves => "Table leaves",
+            PageFilter::IndexPages => "Index pages",
+            PageFilter::Overflow => "Overflow",
+            PageFilter::Freelist => "Freelist",
+            PageFilter::WithOverflow => "With overflow",
+            PageFilter::Corrupt => "Corrupt",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// Order of the pages in the page list.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PageSort {
+    Number,
+    Type,
+    Fill,
+    Cells,
+}
+
+impl PageSort {
+    pub const ALL: [PageSort; 4] = [
+        PageSort::Number,
+        PageSort::Type,
+        PageSort::Fill,
+        PageSort::Cells,
+    ];
+}
+
+impl fmt::Display for PageSort {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let label = match self {
+            PageSort::Number => "Number",
+            PageSort::Type => "Type",
+            PageSort::Fill => "Fill",
+            PageSort::Cells => "Cells",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// Page at a glance, cheap to sort the page list by.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PageSummary {
+    pub label: String,
+    /// Number of cells, 0 for pages other than b-tree ones.
+    pub cells: usize,
+    /// Share of the usable space in use, from 0 to 1, None for pages other than b-tree ones.
+    pub fill: Option<f64>,
+    /// Payload of some of the cells spilled onto overflow pages.
+    pub overflow: bool,
+}
+
+impl PageSummary {
+    /// Summary of a page other than a b-tree one.
+    pub fn new(label: String) -> Self {
+        Self {
+            label,
+            cells: 0,
+            fill: None,
+            overflow: false,
+        }
+    }
+
+    /// Order of the summaries, pages with the same key go by their numbers.
+    pub fn cmp_by(
+        sort: PageSort,
+        (a_num, a): (usize, &Self),
+        (b_num, b): (usize, &Self),
+    ) -> std::cmp::Ordering {
+        let by_key = match sort {
+            PageSort::Number => std::cmp::Ordering::Equal,
+            PageSort::Type => a.label.cmp(&b.label),
+            // Pages without fill go last.
+            PageSort::Fill => match (a.fill, b.fill) {
+                (Some(a), Some(b)) => a.total_cmp(&b),
+                (a, b) => b.is_some().cmp(&a.is_some()),
+            },
+            PageSort::Cells => a.cells.cmp(&b.cells),
+        };
+        by_key.then(a_num.cmp(&b_num))
+    }
 }
 
 pub trait Part: std::fmt::Debug {
     fn label(&self) -> String;
     fn desc(&self) -> &'static str;
     fn fields(&self) -> &[Rc<Field>];
-    fn color(&self) -> String;
+    fn role(&self) -> Role;
+
+    /// Page offset of the cell, if its payload spilled onto overflow pages.
+    fn overflow_cell(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// What a part is for, the active palette decides on its color.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Role {
+    Header,
+    Pointers,
+    Content,
+    /// Content next to another content, so the two can be told apart.
+    ContentAlt,
+    Unused,
 }
 
+impl Role {
+    pub const ALL: [Role; 5] = [
+        Role::Header,
+        Role::Pointers,
+        Role::Content,
+        Role::ContentAlt,
+        Role::Unused,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Role::Header => "Headers",
+            Role::Pointers => "Pointers",
+            Role::Content => "Content",
+            Role::ContentAlt => "Adjacent content",
+            Role::Unused => "Unused",
+        }
+    }
+
+    pub fn desc(&self) -> &'static str {
+        match self {
+            Role::Header => "Headers of the database, pages and journal, which describe what follows them.",
+            Role::Pointers => "Cell pointers and page numbers, which lead to other places of the page or the file.",
+            Role::Content => "Cells, page records and overflow payload.",
+            Role::ContentAlt => "Every other cell or page record, to see where the previous one ends.",
+            Role::Unused => "Unallocated space, freeblocks and bytes, which aren't in use or can't be interpreted.",
+        }
+    }
+}
+
+/// Parsed page, its UI parts are built on the first access, so the pages,
+/// which are never opened, stay cheap on big databases. Only the pages shown
+/// last keep them, see `Viewer::touch`.
 #[derive(Debug, Clone)]
 pub struct PageElement {
     pub id: usize,
     pub page: Rc<PageLayout>,
     pub size: usize,
-    parts: Vec<Rc<dyn Part>>,
+    pub unreferenced: bool,
+    /// Content the page had before it was freed, parsed as it was.
+    pub stale: bool,
+    /// Name and column names of the b-tree the page belongs to.
+    pub table: String,
+    pub columns: Rc<Vec<String>>,
+    /// Number of PRIMARY KEY columns, if the b-tree holds a WITHOUT ROWID table.
+    pub key_columns: Option<usize>,
+    /// Shadow table of a virtual table, which the b-tree holds.
+    pub shadow: Option<ShadowTable>,
+    pub alternates: Vec<Rc<dyn PageView>>,
+    /// Built on demand, see `Viewer::touch`.
+    parts: RefCell<Option<Parts>>,
 }
 
 pub struct PageElementBuilder {
     pub id: usize,
     pub page: PageLayout,
     pub size: usize,
-    #[allow(dead_code)]
-    parts: Option<Vec<Rc<dyn Part>>>,
+    pub unreferenced: bool,
+    /// Content the page had before it was freed, parsed as it was.
+    pub stale: bool,
+    /// Name and column names of the b-tree the page belongs to.
+    pub table: String,
+    pub columns: Rc<Vec<String>>,
+    /// Number of PRIMARY KEY columns, if the b-tree holds a WITHOUT ROWID table.
+    pub key_columns: Option<usize>,
+    /// Shadow table of a virtual table, which the b-tree holds.
+    pub shadow: Option<ShadowTable>,
+    pub alternates: Vec<Rc<dyn PageView>>,
 }
 
 #[derive(Debug, Clone)]
@@ -52,22 +322,98 @@ pub enum PageLayout {
     Overflow(OverflowPage),
     TrunkFreelist(TrunkFreelistPage),
     LeafFreelist(LeafFreelistPage),
+    Journal(Journal),
+    LockByte(LockBytePage),
+    Ptrmap(PtrmapPage),
+    /// Raw content of a page, which isn't referenced and can't be parsed as a b-tree page.
+    Unreferenced(Vec<u8>),
+    /// Raw content of a page in use, which structure isn't interpreted, with the reason why.
+    Unknown(Vec<u8>, &'static str),
+    /// Raw content of a page, which failed to parse, with the error.
+    Corrupt(Vec<u8>, Error),
+    /// Raw content of a page of the encrypted database.
+    Encrypted(Vec<u8>),
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Default)]
 pub struct BTreeNodeView {
     pub page_num: usize,
+    /// Number of cells on the page.
+    pub cells: usize,
     pub children: Vec<BTreeNodeView>,
     pub overflow: Vec<usize>,
 }
 
-impl Default for BTreeNodeView {
-    fn default() -> Self {
-        Self {
-            page_num: 0,
-            children: vec![],
-            overflow: vec![],
+/// Shape of a b-tree, see `BTreeNodeView::stats`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct BTreeStats {
+    /// Levels of pages, a tree of the root page alone has the depth of 1.
+    pub depth: usize,
+    pub pages: usize,
+    pub cells: usize,
+    pub overflow_pages: usize,
+}
+
+impl BTreeStats {
+    pub fn cells_per_page(&self) -> f64 {
+        self.cells as f64 / self.pages.max(1) as f64
+    }
+}
+
+impl BTreeNodeView {
+    /// Depth, pages, cells and overflow pages of this node and the nodes under it.
+    pub fn stats(&self) -> BTreeStats {
+        self.children.iter().map(|c| c.stats()).fold(
+            BTreeStats {
+                depth: 1,
+                pages: 1,
+                cells: self.cells,
+                overflow_pages: self.overflow.len(),
+            },
+            |acc, child| BTreeStats {
+                depth: acc.depth.max(child.depth + 1),
+                pages: acc.pages + child.pages,
+                cells: acc.cells + child.cells,
+                overflow_pages: acc.overflow_pages + child.overflow_pages,
+            },
+        )
+    }
+
+    /// Number of overflow pages of this node and the nodes under it.
+    pub fn overflow_pages(&self) -> usize {
+        self.overflow.len()
+            + self
+                .children
+                .iter()
+                .map(|c| c.overflow_pages())
+                .sum::<usize>()
+    }
+
+    /// Page numbers of the b-tree pages under this node, overflow pages aren't included.
+    pub fn pages(&self) -> Vec<usize> {
+        let mut pages = vec![self.page_num];
+        pages.extend(self.children.iter().flat_map(|c| c.pages()));
+        pages
+    }
+
+    /// Page numbers of the b-tree pages under this node with their overflow pages.
+    pub fn all_pages(&self) -> Vec<usize> {
+        let mut pages = vec![self.page_num];
+        pages.extend(&self.overflow);
+        pages.extend(self.children.iter().flat_map(|c| c.all_pages()));
+        pages
+    }
+
+    /// Page numbers from this node down to the page, overflow page is the last one.
+    pub fn path_to(&self, page_num: usize) -> Option<Vec<usize>> {
+        if self.page_num == page_num {
+            return Some(vec![page_num]);
         }
+        let rest = match self.overflow.contains(&page_num) {
+            true => Some(vec![page_num]),
+            false => self.children.iter().find_map(|c| c.path_to(page_num)),
+        }?;
+        Some([vec![self.page_num], rest].concat())
     }
 }
 
@@ -75,7 +421,81 @@ impl Default for BTreeNodeView {
 pub struct BTreeView {
     pub ttype: String,
     pub name: String,
+    /// Table the b-tree belongs to, see `BTreeView::nest`.
+    pub tbl_name: String,
     pub root: BTreeNodeView,
+    pub columns: Rc<Vec<String>>,
+    /// Number of PRIMARY KEY columns of a WITHOUT ROWID table.
+    pub key_columns: Option<usize>,
+    /// Shadow table of a virtual table, like the full-text index of FTS5.
+    pub shadow: Option<ShadowTable>,
+}
+
+impl BTreeView {
+    pub fn is_index(&self) -> bool {
+        self.ttype == "index"
+    }
+
+    /// Trees in the order they're listed: every table is followed by its indexes,
+    /// marked true, the indexes of the tables, which aren't in the list, go last.
+    pub fn nest(trees: Vec<BTreeView>) -> Vec<(BTreeView, bool)> {
+        let (indexes, tables): (Vec<_>, Vec<_>) = trees.into_iter().partition(|t| t.is_index());
+        let mut indexes = indexes.into_iter().map(Some).collect::<Vec<_>>();
+        let mut nested = vec![];
+        for table in tables {
+            let name = table.name.clone();
+            nested.push((table, false));
+            for index in indexes.iter_mut() {
+                if index
+                    .as_ref()
+                    .is_some_and(|i| i.tbl_name.eq_ignore_ascii_case(&name))
+                {
+                    nested.extend(index.take().map(|i| (i, true)));
+                }
+            }
+        }
+        nested.extend(indexes.into_iter().flatten().map(|i| (i, false)));
+        nested
+    }
+
+    /// Whether the name contains the query, ignoring case, or the query is the number
+    /// of one of its pages, overflow pages included.
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.trim();
+        if let Ok(page_num) = query.parse::<usize>() {
+            return self.root.all_pages().contains(&page_num);
+        }
+        self.name.to_lowercase().contains(&query.to_lowercase())
+    }
+}
+
+/// Freelist as it's linked: trunk pages in the chain order with their leaf pages.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FreelistView {
+    pub trunks: Vec<FreelistTrunkView>,
+    /// Page the trunk chain breaks at, either an unreadable trunk or one already in the chain,
+    /// 0 if the chain ends as it should.
+    pub end: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FreelistTrunkView {
+    pub page_num: usize,
+    pub leaves: Vec<usize>,
+}
+
+impl FreelistView {
+    /// Page numbers of the leaf pages of all the trunks.
+    pub fn leaves(&self) -> Vec<usize> {
+        self.trunks.iter().flat_map(|t| t.leaves.clone()).collect()
+    }
+
+    /// Whether the page is one of the trunk or leaf pages.
+    pub fn contains(&self, page_num: usize) -> bool {
+        self.trunks
+            .iter()
+            .any(|t| t.page_num == page_num || t.leaves.contains(&page_num))
+    }
 }
 
 impl PageElementBuilder {
@@ -84,22 +504,88 @@ impl PageElementBuilder {
             id: page_num,
             page,
             size,
-            parts: None,
+            unreferenced: false,
+            stale: false,
+            table: String::new(),
+            columns: Rc::new(vec![]),
+            key_columns: None,
+            shadow: None,
+            alternates: vec![],
         }
     }
 
+    /// Name record values after columns of the b-tree.
+    pub fn columns(mut self, table: &str, columns: Rc<Vec<String>>) -> Self {
+        self.table = table.to_string();
+        self.columns = columns;
+        self
+    }
+
+    /// Tell the key columns from the data ones in records of a WITHOUT ROWID table.
+    pub fn key_columns(mut self, key_columns: Option<usize>) -> Self {
+        self.key_columns = key_columns;
+        self
+    }
+
+    /// Describe records of a shadow table, which is recognized by the schema,
+    /// not by its name alone.
+    pub fn shadow(mut self, shadow: Option<ShadowTable>) -> Self {
+        self.shadow = shadow;
+        self
+    }
+
+    /// Mark page as not reachable from any b-tree or the freelist.
+    pub fn unreferenced(mut self) -> Self {
+        self.unreferenced = true;
+        self
+    }
+
+    /// Mark page as the content left from before the page was freed.
+    pub fn stale(mut self) -> Self {
+        self.stale = true;
+        self
+    }
+
+    /// Add another interpretation of the same page bytes.
+    pub fn alternate(mut self, page: Rc<dyn PageView>) -> Self {
+        self.alternates.push(page);
+        self
+    }
+
     pub fn build(self) -> PageElement {
-        let parts = match &self.page {
-            PageLayout::Btree(page) => self.build_btree_parts(page),
-            PageLayout::Overflow(page) => self.build_overflow_parts(page),
-            PageLayout::TrunkFreelist(page) => self.build_trunk_freelist_parts(page),
-            PageLayout::LeafFreelist(page) => self.build_leaf_freelist_parts(page),
-        };
         PageElement {
             id: self.id,
             page: Rc::new(self.page),
             size: self.size,
-            parts,
+            unreferenced: self.unreferenced,
+            stale: self.stale,
+            table: self.table,
+            columns: self.columns,
+            key_columns: self.key_columns,
+            shadow: self.shadow,
+            alternates: self.alternates,
+            parts: RefCell::new(None),
+        }
+    }
+}
+
+impl PageElement {
+    fn build_parts(&self) -> Vec<Rc<dyn Part>> {
+        match &*self.page {
+            PageLayout::Btree(page) => self.build_btree_parts(page),
+            PageLayout::Overflow(page) => self.build_overflow_parts(page),
+            PageLayout::TrunkFreelist(page) => self.build_trunk_freelist_parts(page),
+            PageLayout::LeafFreelist(page) => self.build_leaf_freelist_parts(page),
+            PageLayout::Journal(journal) => self.build_journal_parts(journal),
+            PageLayout::LockByte(page) => self.build_lock_byte_parts(page),
+            PageLayout::Ptrmap(page) => self.build_ptrmap_parts(page),
+            PageLayout::Unreferenced(bytes) => self.build_unreferenced_parts(bytes),
+            PageLayout::Unknown(bytes, reason) => self.build_unknown_parts(bytes, reason),
+            PageLayout::Corrupt(bytes, error) => self.build_corrupt_parts(bytes, error),
+            PageLayout::Encrypted(bytes) => self.build_unknown_parts(
+                bytes,
+                "Ciphertext of the page, it's indistinguishable from random bytes without the key.",
+            ),
         }
     }
 
@@ -118,31 +604,71 @@ impl PageElementBuilder {
         cells.reverse();
         let mut offsets = page.cell_pointer.array.clone();
         offsets.reverse();
-        let mut cell_parts: Vec<Rc<dyn Part>> = vec![];
+        let mut content_parts: Vec<(usize, Rc<dyn Part>)> = vec![];
+        let internal = self.shadow.as_ref().map(ShadowTable::internal);
         for (n, cell) in cells.iter().enumerate() {
             let offset = offsets[n] as usize;
-            cell_parts.push(Rc::new(CellPart::new(cell, offset, n + 1)))
+            let mut part = CellPart::new(
+                cell,
+                offset,
+                n + 1,
+                (&self.table, &self.columns, self.key_columns, internal),
+            );
+            // Nodes of an R*Tree are described by their own parts, next to their cells.
+            let node = match &self.shadow {
+                Some(ShadowTable::Rtree(rtree)) if rtree.table == RtreeTable::Node => {
+                    rtree::node_part(&mut part, rtree, n + 1)
+                }
+                _ => None,
+            };
+            content_parts.push((offset, Rc::new(part)));
+            if let Some(node) = node {
+                content_parts.push((node.offset(), Rc::new(node)));
+            }
+        }
+
+        // Freeblocks are scattered in between cells of the content area.
+        for (n, block) in page.free_blocks.iter().enumerate() {
+            content_parts.push((block.offset, Rc::new(FreeBlockPart::new(block, n + 1))))
+        }
+        for (n, fragment) in page.fragments.iter().enumerate() {
+            content_parts.push((fragment.offset, Rc::new(FragmentPart::new(fragment, n + 1))))
         }
-        parts.extend(cell_parts);
+        content_parts.sort_by_key(|(offset, _)| *offset);
+        parts.extend(content_parts.into_iter().map(|(_, part)| part));
 
         // Consider for database header to go first.
         if self.id == 1 {
             parts.insert(0, Rc::new(DBHeaderPart::new(&page.db_header)))
         };
+        self.push_reserved(&mut parts, &page.reserved);
         parts
     }
 
+    /// Reserved space goes last on pages of all kinds, if the database has any.
+    fn push_reserved(&self, parts: &mut Vec<Rc<dyn Part>>, reserved: &[u8]) {
+        if !reserved.is_empty() {
+            parts.push(Rc::new(reserved::ReservedSpacePart::new(
+                reserved, self.size,
+            )));
+        }
+    }
+
     fn build_overflow_parts(&self, page: &OverflowPage) -> Vec<Rc<dyn Part>> {
         use overflow_pages::*;
 
-        let mut parts: Vec<Rc<dyn Part>> = vec![
-            Rc::new(NextPagePart::new(page.next_page)),
-            Rc::new(DataPart::new(&page.data)),
-        ];
+        let mut parts: Vec<Rc<dyn Part>> = vec![Rc::new(NextPagePart::new(page.next_page))];
+        let mut offset = 4;
+        if let Some(header) = &page.header {
+            parts.push(Rc::new(HeaderPart::new(header)));
+            offset += header.len();
+        }
+        parts.push(Rc::new(DataPart::new(&page.data, offset)));
 
         if let Some(unallocated) = &page.unallocated {
             parts.push(Rc::new(UnallocatedOverflowPart::new(unallocated, page)));
         }
+        self.push_reserved(&mut parts, &page.reserved);
         parts
     }
 
@@ -162,13 +688,77 @@ impl PageElementBuilder {
             let offset = (page.leaf_page_amount * 4 + 8) as usize;
             parts.push(Rc::new(UnallocatedPart::new(unallocated, offset)));
         }
+        self.push_reserved(&mut parts, &page.reserved);
         parts
     }
 
     fn build_leaf_freelist_parts(&self, page: &LeafFreelistPage) -> Vec<Rc<dyn Part>> {
         use freelist::*;
 
-        vec![Rc::new(UnallocatedPart::new(&page.unallocated, 0))]
+        let mut parts: Vec<Rc<dyn Part>> =
+            vec![Rc::new(UnallocatedPart::new(&page.unallocated, 0))];
+        self.push_reserved(&mut parts, &page.reserved);
+        parts
+    }
+
+    fn build_lock_byte_parts(&self, page: &LockBytePage) -> Vec<Rc<dyn Part>> {
+        use lock_byte::*;
+
+        vec![Rc::new(LockBytePart::new(&page.unallocated))]
+    }
+
+    fn build_ptrmap_parts(&self, page: &PtrmapPage) -> Vec<Rc<dyn Part>> {
+        use ptrmap::*;
+
+        let mut parts: Vec<Rc<dyn Part>> = vec![];
+        for (n, entry) in page.entries.iter().enumerate() {
+            parts.push(Rc::new(PtrmapEntryPart::new(entry, n + 1)));
+        }
+        if !page.unallocated.is_empty() {
+            let offset = page.entries.len() * PTRMAP_ENTRY_SIZE;
+            parts.push(Rc::new(UnallocatedPtrmapPart::new(
+                &page.unallocated,
+                offset,
+            )));
+        }
+        self.push_reserved(&mut parts, &page.reserved);
+        parts
+    }
+
+    fn build_unreferenced_parts(&self, bytes: &[u8]) -> Vec<Rc<dyn Part>> {
+        use unreferenced::*;
+
+        vec![Rc::new(UnreferencedPart::new(bytes))]
+    }
+
+    fn build_unknown_parts(&self, bytes: &[u8], reason: &'static str) -> Vec<Rc<dyn Part>> {
+        use unknown::*;
+
+        vec![Rc::new(UnknownStructurePart::new(bytes, 0, reason))]
+    }
+
+    fn build_corrupt_parts(&self, bytes: &[u8], error: &Error) -> Vec<Rc<dyn Part>> {
+        use corrupt::*;
+
+        vec![Rc::new(CorruptPart::new(bytes, error))]
+    }
+
+    fn build_journal_parts(&self, journal: &Journal) -> Vec<Rc<dyn Part>> {
+        use journal::*;
+
+        let mut parts: Vec<Rc<dyn Part>> = vec![];
+        let mut record_id = 0;
+        for (n, segment) in journal.segments.iter().enumerate() {
+            parts.push(Rc::new(JournalHeaderPart::new(segment, n + 1)));
+            for record in &segment.records {
+                record_id += 1;
+                parts.push(Rc::new(JournalRecordPart::new(record, record_id)));
+            }
+        }
+        if let Some(pointer) = &journal.super_journal {
+            parts.push(Rc::new(SuperJournalPointerPart::new(pointer)));
+        }
+        parts
     }
 }
 
@@ -195,11 +785,22 @@ impl PageView for PageElement {
             PageLayout::Overflow(_) => "ᨒ  Overflow".to_string(),
             PageLayout::TrunkFreelist(_) => "⩩ Trunk Freelist".to_string(),
             PageLayout::LeafFreelist(_) => "● Leaf Freelist".to_string(),
+            PageLayout::Journal(_) => "⟲ Rollback Journal".to_string(),
+            PageLayout::LockByte(_) => "⊘ Lock-byte".to_string(),
+            PageLayout::Ptrmap(_) => "⇱ Pointer Map".to_string(),
+            PageLayout::Unreferenced(_) => "◌ Unreferenced".to_string(),
+            PageLayout::Unknown(..) => "? Unrecognized".to_string(),
+            PageLayout::Corrupt(_, Error::TruncatedPage { .. }) => "✂ Truncated".to_string(),
+            PageLayout::Corrupt(_, Error::OutOfBounds { .. }) => "✗ Missing".to_string(),
+            PageLayout::Corrupt(..) => "✗ Corrupt".to_string(),
+            PageLayout::Encrypted(_) => "⚿ Encrypted".to_string(),
         }
     }
 
     fn desc(&self) -> &'static str {
         match &*self.page {
+            PageLayout::Btree(_) if self.stale => "This page is on the freelist, but it still holds the b-tree content it had before it was freed: SQLite doesn't clear freed pages unless secure_delete is on, so deleted rows stay readable until the page is reused. That's how the bytes read as a b-tree page, the page isn't a part of any b-tree anymore.",
+            PageLayout::Btree(_) if self.unreferenced => "This page parses as a b-tree page, but neither a b-tree nor the freelist references it. It might belong to a dropped table or index, which pages weren't reused yet, or the database wasn't closed cleanly. SQLite itself never reads it until the page is allocated again.",
             PageLayout::Btree(_) => {
                 if self.id == 1 {
                     "The 100-byte database file header is found only on Page 1, meaning that root page has 100 fewer bytes of storage space available. It's always a table b-tree page: interior or leaf. Page 1 is the root page of a table b-tree, that holds a special table named 'sqlite_schema'. This b-tree is known as the 'schema table' since it stores the complete database schema."
@@ -210,55 +811,279 @@ impl PageView for PageElement {
             PageLayout::Overflow(_) => "When the size of payload for a cell exceeds a certain threshold, then only the first few bytes of the payload are stored on the b-tree page and the balance is stored in a linked list of content overflow pages.",
             PageLayout::TrunkFreelist(_) => "A database file might contain one or more pages that are not in active use. Unused pages can come about, for example, when information is deleted from the database. Unused pages are stored on the freelist and are reused when additional pages are required. The freelist is organized as a linked list of freelist trunk pages with each trunk page containing page numbers for zero or more freelist leaf pages. The database header also stores the page number of the first freelist trunk page and the number of freelist pages.",
             PageLayout::LeafFreelist(_) => "Freelist leaf pages contain no information. SQLite avoids reading or writing freelist leaf pages in order to reduce disk I/O.",
+            PageLayout::Journal(_) => "The rollback journal is a separate '-journal' file, which holds the original content of every page changed by the current transaction, so the database can be restored if the transaction rolls back. If it exists without a lock on the database, it's a 'hot journal': the database file may be half-written and the journal must be played back before reading it.",
+            PageLayout::LockByte(_) => "The lock-byte page is the single page of the database file that contains the bytes at offsets between 1073741824 and 1073742335, inclusive. A database file that is less than or equal to 1073741824 bytes in size contains no lock-byte page. A database file larger than 1073741824 contains exactly one lock-byte page. It is set aside for use by the operating-system specific VFS implementation in implementing the database file locking primitives. SQLite does not use the lock-byte page.",
+            PageLayout::Ptrmap(_) => "Pointer map pages exist only in auto-vacuum and incremental-vacuum databases, which have the largest root page set in the database header. The first one is page 2, it's followed by as many pages as it has 5-byte entries for, then comes the next pointer map page. Entries keep the type and the parent of every page, so vacuum can move pages around and update the references to them without scanning the whole file.",
+            PageLayout::Unreferenced(_) => "This page is within the database file, but neither a b-tree nor the freelist references it and it doesn't parse as a b-tree page. Normally every page is in use or on the freelist, so an unreferenced page is a sign of an interrupted write, a corrupted freelist or a file edited outside of SQLite.",
+            PageLayout::Unknown(..) => "This page is in use, but the structure it holds isn't interpreted here yet. Its raw content is shown instead, so the rest of the file stays explorable.",
+            PageLayout::Corrupt(_, Error::TruncatedPage { .. }) => "This page is referenced, but the file ends before it does: the file is truncated. Whatever bytes of it the file has are shown, so the rest of the file stays explorable.",
+            PageLayout::Corrupt(_, Error::OutOfBounds { .. }) => "This page is referenced, but it's beyond the end of the database file, there is nothing to read. The reference to it is broken or the file is truncated.",
+            PageLayout::Corrupt(..) => "This page is referenced, but fails to parse: the file is corrupt or truncated. Its raw content is shown instead, so the rest of the file stays explorable.",
+            PageLayout::Encrypted(_) => "This page belongs to an encrypted database. The cipher encrypts every page on its own, often with a nonce and a message authentication code in the reserved space at the end of the page, so the pages keep their size and place in the file, but their content can't be read without the key.",
+        }
+    }
+
+    fn parts(&self) -> Parts {
+        self.parts
+            .borrow_mut()
+            .get_or_insert_with(|| self.build_parts().into())
+            .clone()
+    }
+
+    fn release_parts(&self) {
+        self.parts.borrow_mut().take();
+    }
+
+    fn unreferenced(&self) -> bool {
+        self.unreferenced
+    }
+
+    fn alternates(&self) -> &[Rc<dyn PageView>] {
+        &self.alternates
+    }
+
+    fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    fn overflow_owner(&self) -> Option<OverflowOwner> {
+        match &*self.page {
+            PageLayout::Overflow(page) => page.owner,
+            _ => None,
+        }
+    }
+
+    fn summary(&self) -> PageSummary {
+        let PageLayout::Btree(page) = &*self.page else {
+            return PageSummary::new(self.label());
+        };
+        PageSummary {
+            label: self.label(),
+            cells: page.cells.len(),
+            fill: Some(PageStats::new(page).fill()),
+            overflow: page.cells.iter().any(|c| c.overflow().is_some()),
+        }
+    }
+
+    fn matches(&self, filter: PageFilter) -> bool {
+        match (&*self.page, filter) {
+            (PageLayout::Btree(page), PageFilter::TableLeaves) => {
+                page.page_header.page_type == PageHeaderType::LeafTable
+            }
+            (PageLayout::Btree(page), PageFilter::IndexPages) => matches!(
+                page.page_header.page_type,
+                PageHeaderType::LeafIndex | PageHeaderType::InteriorIndex
+            ),
+            (PageLayout::Btree(page), PageFilter::WithOverflow) => {
+                page.cells.iter().any(|c| c.overflow().is_some())
+            }
+            (PageLayout::Overflow(_), PageFilter::Overflow) => true,
+            (PageLayout::TrunkFreelist(_) | PageLayout::LeafFreelist(_), PageFilter::Freelist) => {
+                true
+            }
+            (PageLayout::Corrupt(..), PageFilter::Corrupt) => true,
+            _ => false,
         }
     }
+}
+
+/// Background of a field, resolved to the classes of the active theme at render time.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Style {
+    Plain,
+    /// Headers of cells and freeblocks.
+    CellHeader,
+    RecordHeader,
+    RecordValue,
+    /// Record value without any bytes, like NULL or 0 and 1 integers.
+    EmptyRecordValue,
+    /// Payload and record header continued on overflow pages.
+    Overflow,
+    /// Overflow payload chunk without bytes of the value.
+    EmptyOverflow,
+}
 
-    fn parts(&self) -> &[Rc<dyn Part>] {
-        self.parts.as_slice()
+impl Style {
+    pub fn class(&self, theme: Theme) -> &'static str {
+        match (theme, self) {
+            (_, Style::Plain) => "",
+            (Theme::Light, Style::CellHeader) => "bg-slate-300",
+            (Theme::Light, Style::RecordHeader) => "bg-slate-330",
+            (Theme::Light, Style::RecordValue) => "bg-slate-360",
+            (Theme::Light, Style::EmptyRecordValue) => "pattern-vertical-lines pattern-white pattern-bg-slate-200 pattern-size-1 pattern-opacity-60 bg-slate-360",
+            (Theme::Light, Style::Overflow) => "bg-slate-390",
+            (Theme::Light, Style::EmptyOverflow) => "pattern-vertical-lines pattern-white pattern-bg-slate-200 pattern-size-1 pattern-opacity-60 bg-slate-390",
+            (Theme::Dark, Style::CellHeader) => "bg-slate-500",
+            (Theme::Dark, Style::RecordHeader) => "bg-slate-600",
+            (Theme::Dark, Style::RecordValue) => "bg-slate-700",
+            (Theme::Dark, Style::EmptyRecordValue) => "pattern-vertical-lines pattern-white pattern-bg-slate-800 pattern-size-1 pattern-opacity-60 bg-slate-700",
+            (Theme::Dark, Style::Overflow) => "bg-slate-700",
+            (Theme::Dark, Style::EmptyOverflow) => "pattern-vertical-lines pattern-white pattern-bg-slate-800 pattern-size-1 pattern-opacity-60 bg-slate-700",
+        }
     }
 }
 
+/// Stable identity of a field, which doesn't change with its description, to refer
+/// to fields of the same meaning across pages and databases.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FieldKind {
+    // Database header.
+    DbHeaderString,
+    DbPageSize,
+    DbWriteVersion,
+    DbReadVersion,
+    DbReservedSpace,
+    DbMaxPayloadFraction,
+    DbMinPayloadFraction,
+    DbLeafPayloadFraction,
+    DbChangeCounter,
+    DbSizeInPages,
+    DbFirstFreelistTrunk,
+    DbFreelistPages,
+    DbSchemaCookie,
+    DbSchemaFormat,
+    DbDefaultCacheSize,
+    DbLargestRootPage,
+    DbTextEncoding,
+    DbUserVersion,
+    DbIncrementalVacuum,
+    DbApplicationId,
+    DbReservedExpansion,
+    DbVersionValidFor,
+    DbSqliteVersion,
+    // B-tree page header.
+    PageType,
+    PageFirstFreeblock,
+    PageCellCount,
+    PageCellContentStart,
+    PageFragmentedBytes,
+    PageRightmostPointer,
+    // B-tree page content.
+    CellPointer,
+    FreeblockNext,
+    FreeblockSize,
+    FreeblockContent,
+    Fragment,
+    CellPayloadSize,
+    CellRowid,
+    CellLeftChild,
+    CellOverflowPage,
+    RecordHeaderSize,
+    RecordSerialType,
+    RecordValue,
+    // Overflow pages.
+    OverflowNextPage,
+    OverflowRecordHeader,
+    OverflowPayload,
+    // Freelist pages.
+    FreelistNextTrunk,
+    FreelistLeafCount,
+    FreelistLeafPage,
+    // Pointer map pages.
+    PtrmapType,
+    PtrmapParent,
+    // Rollback journal.
+    JournalMagic,
+    JournalPageCount,
+    JournalNonce,
+    JournalInitialSize,
+    JournalSectorSize,
+    JournalPageSize,
+    JournalPageNumber,
+    JournalPageContent,
+    JournalChecksum,
+    SuperJournalPageNumber,
+    SuperJournalName,
+    SuperJournalLength,
+    SuperJournalChecksum,
+    RtreeDepth,
+    RtreeCellCount,
+    RtreeCellId,
+    RtreeCoordinate,
+    RtreeUnused,
+    /// Space at the end of a page, which isn't used yet.
+    Unallocated,
+    /// Reserved space of a page, used by extensions.
+    Reserved,
+    LockByte,
+    // Raw content of the pages, which can't be interpreted.
+    Unreferenced,
+    Unknown,
+    Corrupt,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Field {
+    pub kind: FieldKind,
     pub desc: &'static str,
     pub offset: usize,
     pub size: usize,
     pub value: Value,
-    pub style: &'static str,
+    pub style: Style,
+    /// Name of the table or index column for record values.
+    pub column: Option<String>,
+    /// Name of the table or index the record value belongs to.
+    pub table: Option<String>,
 }
 
 impl Field {
-    pub fn to_hex(&self) -> String {
-        match &self.value {
-            Value::U8(v) => Self::pretty_hex(&v.to_be_bytes()),
-            Value::U16(v) => Self::pretty_hex(&v.to_be_bytes()),
-            Value::U32(v) => Self::pretty_hex(&v.to_be_bytes()),
-            Value::Text(v) => Self::pretty_hex(v.as_bytes()),
-            Value::Bool(v) => Self::pretty_hex(&v.to_be_bytes()),
+    /// Bytes under the field, given the bytes of its page.
+    pub fn slice<'a>(&self, page: &'a [u8]) -> &'a [u8] {
+        let start = self.offset.min(page.len());
+        let end = (self.offset + self.size).min(page.len());
+        &page[start..end]
+    }
+
+    /// Bytes of the value as they're stored, None for the record values, which
+    /// take no bytes of their own.
+    fn value_bytes(&self) -> Option<Vec<u8>> {
+        let bytes = match &self.value {
+            Value::U8(v) => v.to_be_bytes().to_vec(),
+            Value::U16(v) => v.to_be_bytes().to_vec(),
+            Value::U32(v) => v.to_be_bytes().to_vec(),
+            Value::Text(v) => v.as_bytes().to_vec(),
+            Value::Bool(v) => v.to_be_bytes().to_vec(),
             Value::PageSize(v) => match v {
-                65536 => Self::pretty_hex(&1_u16.to_be_bytes()),
-                _ => Self::pretty_hex(&(*v as u16).to_be_bytes()),
+                65536 => 1_u16.to_be_bytes().to_vec(),
+                _ => (*v as u16).to_be_bytes().to_vec(),
             },
-            Value::Array(v) => Self::pretty_hex(v),
-            Value::Encoding(v) => Self::pretty_hex(&v.to_be_bytes()),
-            Value::Version(v) => Self::pretty_hex(&v.to_be_bytes()),
-            Value::PageType(v) => Self::pretty_hex(&v.to_be_bytes()),
+            Value::Array(v) => v.to_vec(),
+            Value::Encoding(v) => v.to_be_bytes().to_vec(),
+            Value::Version(v) => v.to_be_bytes().to_vec(),
+            Value::PageType(v) => v.to_be_bytes().to_vec(),
+            Value::PtrmapType(v) => vec![*v as u8],
             Value::CellStartOffset(v) => match v {
-                65536 => Self::pretty_hex(&0_u16.to_be_bytes()),
-                _ => Self::pretty_hex(&(*v as u16).to_be_bytes()),
+                65536 => 0_u16.to_be_bytes().to_vec(),
+                _ => (*v as u16).to_be_bytes().to_vec(),
             },
-            Value::Unallocated(v) => Self::pretty_hex(v),
-            Value::Varint(v) => Self::pretty_hex(&v.bytes),
-            Value::PageNumber(v) => Self::pretty_hex(&v.to_be_bytes()),
+            Value::Unallocated(v) => v.to_vec(),
+            Value::Varint(v) => v.bytes.clone(),
+            Value::PageNumber(v) => v.to_be_bytes().to_vec(),
+            Value::I64(v) => v.to_be_bytes().to_vec(),
+            Value::Coord(v) => v.to_be_bytes().to_vec(),
             Value::Record(record) => match record.value {
                 RecordType::Null
                 | RecordType::Zero(_)
                 | RecordType::One(_)
                 | RecordType::Blob(None)
-                | RecordType::Text(None) => "─".to_string(),
-                _ => Self::pretty_hex(record.bytes.as_ref().map_or(&[], |b| b)),
+                | RecordType::Text(None) => return None,
+                _ => record.bytes.clone().unwrap_or_default(),
             },
-        }
+        };
+        Some(bytes)
+    }
+
+    pub fn to_hex(&self, style: &HexStyle) -> String {
+        self.value_bytes()
+            .map_or_else(|| "─".to_string(), |bytes| style.hex(&bytes))
+    }
+
+    /// Bytes of the value read as a big-endian unsigned integer, if there are 2 to 8 of them.
+    pub fn big_endian(&self) -> Option<u64> {
+        let bytes = self.value_bytes()?;
+        (2..=8)
+            .contains(&bytes.len())
+            .then(|| bytes.iter().fold(0, |n, b| n << 8 | u64::from(*b)))
     }
 
     pub fn try_page_number(&self) -> Result<u32, StdError> {
@@ -268,39 +1093,61 @@ impl Field {
         }
     }
 
-    pub fn trim_hex(&self, limit: usize) -> String {
+    /// Bytes of the values, which are cut to the limit: unallocated space, texts and
+    /// record texts and blobs. None for the values of a fixed small size.
+    fn long_bytes(&self) -> Option<&[u8]> {
         match &self.value {
-            Value::Unallocated(v) => {
-                if limit.min(v.len()) == limit {
-                    format!("{} ...", Self::pretty_hex(&v[..limit]))
-                } else {
-                    self.to_hex()
-                }
-            }
-            _ => self.to_hex(),
+            Value::Unallocated(v) => Some(v),
+            Value::Text(v) => Some(v.as_bytes()),
+            Value::Record(record) => match record.value {
+                RecordType::Blob(Some(_)) | RecordType::Text(Some(_)) => record.bytes.as_deref(),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Whether the value is longer than the limit and is shown cut.
+    pub fn is_long(&self, limit: usize) -> bool {
+        self.long_bytes().is_some_and(|b| b.len() > limit)
+    }
+
+    pub fn trim_hex(&self, limit: usize, style: &HexStyle) -> String {
+        match self.long_bytes() {
+            Some(v) if v.len() > limit => format!("{} ...", style.hex(&v[..limit])),
+            _ => self.to_hex(style),
         }
     }
 
     pub fn trim_str(&self, limit: usize) -> String {
+        if !self.is_long(limit) {
+            return match &self.value {
+                Value::Unallocated(v) => format!("{:?}", v),
+                v => format!("{v}"),
+            };
+        }
         match &self.value {
-            Value::Unallocated(v) => {
-                if limit.min(v.len()) == limit {
-                    format!("{:?} ...", &v[..limit])
-                } else {
-                    format!("{:?}", v)
-                }
-            }
+            Value::Unallocated(v) => format!("{:?} ...", &v[..limit]),
+            Value::Text(v) => format!("{:?} ...", cut_text(v, limit)),
+            Value::Record(record) => match &record.value {
+                RecordType::Text(Some(v)) => format!("{} ...", cut_text(v, limit)),
+                RecordType::Blob(Some(v)) => format!("Blob {:?} ...", &v[..limit]),
+                _ => format!("{}", self.value),
+            },
             v => format!("{v}"),
         }
     }
+}
 
-    fn pretty_hex(bytes: &[u8]) -> String {
-        bytes
-            .iter()
-            .map(|b| format!("{:02X}", b))
-            .collect::<Vec<String>>()
-            .join(" ")
-    }
+/// Leading characters of the text, which take up to `limit` bytes in UTF-8.
+fn cut_text(text: &str, limit: usize) -> &str {
+    let end = text
+        .char_indices()
+        .map(|(i, c)| i + c.len_utf8())
+        .take_while(|end| *end <= limit)
+        .last()
+        .unwrap_or(0);
+    &text[..end]
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -315,11 +1162,14 @@ pub enum Value {
     Encoding(TextEncoding),
     Version(u32),
     PageType(PageHeaderType),
+    PtrmapType(PtrmapType),
     CellStartOffset(u32),
     Unallocated(Box<[u8]>),
     Varint(Varint),
     Record(RecordValue),
     PageNumber(u32),
+    I64(i64),
+    Coord(RtreeCoord),
 }
 
 impl fmt::Display for Value {
@@ -347,10 +1197,13 @@ impl fmt::Display for Value {
                 write!(f, "{x}.{y}.{z}")
             }
             Self::PageType(v) => write!(f, "{v}"),
+            Self::PtrmapType(v) => write!(f, "{v}"),
             Self::CellStartOffset(v) => write!(f, "{v}"),
             Self::Unallocated(v) => write!(f, "{:?}", *v),
             Self::Varint(v) => write!(f, "{}", v.value),
             Self::PageNumber(v) => write!(f, "{v}"),
+            Self::I64(v) => write!(f, "{v}"),
+            Self::Coord(v) => write!(f, "{v}"),
             Value::Record(record) => match &record.value {
                 RecordType::Null => write!(f, "Null"),
                 RecordType::Zero(v) | RecordType::One(v) => write!(f, "Integer {v}"),
@@ -369,20 +1222,121 @@ impl fmt::Display for Value {
     }
 }
 
+/// Derived explanation of a field value, which depends on the rest of the database.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldNote {
+    pub text: String,
+    /// Page number and page offset of the related field.
+    pub related: Option<(usize, usize)>,
+    pub related_label: &'static str,
+}
+
 impl Field {
+    /// Annotations for values with special encodings, like 65536 page size.
+    pub fn notes(&self, db_header: &DBHeader) -> Vec<FieldNote> {
+        let page_size = db_header.page_size;
+        let reserved = db_header.reserved_page_space as u64;
+        match self.value {
+            Value::PageSize(v) => {
+                let mut notes = vec![];
+                if v == 65536 {
+                    notes.push(FieldNote {
+                        text: "Stored as 0x00 0x01: 65536 doesn't fit into a 2-byte integer, so the magic value 1 is used instead. The same limit applies to the start of the cell content area on every b-tree page of this database, where 65536 is stored as 0.".to_string(),
+                        related: Some((1, DB_HEADER_SIZE + 5)),
+                        related_label: "Cell content area start",
+                    });
+                }
+                let u = v - reserved;
+                notes.push(FieldNote {
+                    text: format!(
+                        "Usable size U = page size - reserved space = {} - {} = {}. Payload kept on a table leaf page X = U - 35 = {}, on index pages X = ((U - 12) * 64 / 255) - 23 = {}, before spilling at least M = ((U - 12) * 32 / 255) - 23 = {} bytes stay on the b-tree page.",
+                        v,
+                        reserved,
+                        u,
+                        u - 35,
+                        ((u - 12) * 64 / 255) - 23,
+                        ((u - 12) * 32 / 255) - 23,
+                    ),
+                    related: None,
+                    related_label: "",
+                });
+                notes
+            }
+            Value::CellStartOffset(v) if page_size == 65536 => {
+                let text = if v == 65536 {
+                    "Stored as 0x00 0x00: a zero value is interpreted as 65536, which is only possible on a 65536-byte page without cells and reserved space.".to_string()
+                } else {
+                    format!(
+                        "Content starts at {}, the usable size of the page is {}. If this page had no cells the offset would be 65536, which doesn't fit into a 2-byte integer and is stored as 0.",
+                        v,
+                        page_size - reserved
+                    )
+                };
+                vec![FieldNote {
+                    text,
+                    related: Some((1, 16)),
+                    related_label: "Page size",
+                }]
+            }
+            Value::Record(RecordValue {
+                value: RecordType::Text(Some(ref text)),
+                ..
+            }) => {
+                let encoding = &db_header.text_encoding;
+                let bytes = encoding.encode(text).len();
+                let chars = text.chars().count();
+                vec![FieldNote {
+                    text: format!(
+                        "Text length is {} byte(s), that's what serial type {} = {} * 2 + 13 encodes, and {} character(s) in {}. Bytes and characters only match for ASCII text in UTF-8: other characters take several bytes, in UTF-16 every character takes at least 2.",
+                        bytes,
+                        bytes * 2 + 13,
+                        bytes,
+                        chars,
+                        encoding,
+                    ),
+                    related: None,
+                    related_label: "",
+                }]
+            }
+            _ => vec![],
+        }
+    }
+
     pub fn new(
+        kind: FieldKind,
         desc: &'static str,
         offset: usize,
         size: usize,
         value: Value,
-        style: &'static str,
+        style: Style,
     ) -> Self {
         Self {
+            kind,
             desc,
             offset,
             size,
             value,
             style,
+            column: None,
+            table: None,
+        }
+    }
+
+    pub fn with_column(mut self, table: &str, column: Option<String>) -> Self {
+        if column.is_some() {
+            self.table = Some(table.to_string());
+        }
+        self.column = column;
+        self
+    }
+
+    /// Value rendered by one of the registered custom renderers, if any.
+    pub fn custom_render(&self) -> Option<String> {
+        match (&self.table, &self.column, &self.value) {
+            (Some(table), Some(column), Value::Record(record)) => {
+                renderers::render(table, column, record.bytes.as_deref().unwrap_or_default())
+            }
+            _ => None,
         }
     }
 }
@@ -0,0 +1,41 @@
+//! Lock-byte page UI representation and description
+use std::rc::Rc;
+
+use crate::{Field, FieldKind, Part, Role, Style, Value};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LockBytePart {
+    fields: Vec<Rc<Field>>,
+}
+
+impl LockBytePart {
+    pub fn new(unallocated: &[u8]) -> Self {
+        let fields = vec![Rc::new(Field::new(
+            FieldKind::LockByte,
+            "Bytes of the lock-byte page are never read or written by SQLite core. Operating-system specific VFS implementations acquire locks on the byte ranges of this page to implement database file locking, file content is irrelevant.",
+            0,
+            unallocated.len(),
+            Value::Unallocated(unallocated.into()),
+            Style::Plain,
+        ))];
+        Self { fields }
+    }
+}
+
+impl Part for LockBytePart {
+    fn label(&self) -> String {
+        "Lock bytes".to_string()
+    }
+
+    fn desc(&self) -> &'static str {
+        "The lock-byte page contains the bytes at file offsets between 1073741824 and 1073742335, inclusive. It's set aside for the file locking primitives and is never used to store b-tree, freelist or overflow content."
+    }
+
+    fn role(&self) -> Role {
+        Role::Unused
+    }
+
+    fn fields(&self) -> &[Rc<Field>] {
+        self.fields.as_slice()
+    }
+}
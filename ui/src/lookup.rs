@@ -0,0 +1,254 @@
+//! Replay of a rowid lookup: every page visited on the way to the leaf and every key
+//! compared there, step by step.
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+use dioxus_free_icons::icons::bs_icons::{BsChevronLeft, BsChevronRight};
+use dioxus_free_icons::Icon;
+use parser::{KeySearch, SearchStep, StdError};
+
+use crate::browser::sleep;
+use crate::index::{jump_to_field, update_selected_page};
+use crate::state::AppState;
+
+/// Look up a rowid the way SQLite does and show the pages visited on the way to the leaf.
+#[component]
+pub fn RowidLookup(root: usize) -> Element {
+    let viewer = use_context::<AppState>().viewer;
+    let mut key_search = use_context::<AppState>().key_search;
+    let mut query = use_signal(String::new);
+    let mut error = use_signal(|| None::<String>);
+    // Moment of the lookup replay, see `lookup_frames`.
+    let mut frame = use_signal(|| 0);
+    let mut playing = use_signal(|| false);
+
+    let mut run = move || {
+        let result = query()
+            .trim()
+            .parse::<i64>()
+            .map_err(StdError::from)
+            .and_then(|rowid| Ok(viewer.read().search_rowid(root, rowid)?));
+        match result {
+            Ok(found) => {
+                if let Some(&SearchStep {
+                    page_num,
+                    cell_offset: Some(offset),
+                    ..
+                }) = found.path.last()
+                {
+                    jump_to_field(page_num, offset);
+                }
+                // Replay starts over, the found cell is shown already.
+                frame.set(lookup_frames(&found).len().saturating_sub(1));
+                playing.set(false);
+                *key_search.write() = Some(found);
+                error.set(None);
+            }
+            Err(err) => {
+                *key_search.write() = None;
+                error.set(Some(err.to_string()));
+            }
+        }
+    };
+    // Lookup might belong to another table.
+    let found = key_search().filter(|k| k.path.first().is_some_and(|s| s.page_num == root));
+    let frames = found.as_ref().map(lookup_frames).unwrap_or_default();
+    let current = frames.get(frame()).cloned();
+    rsx! {
+        div {
+            class: "pt-4 join",
+            input {
+                class: "join-item input input-bordered input-sm w-40",
+                placeholder: "rowid",
+                value: "{query}",
+                oninput: move |e| query.set(e.value()),
+                onkeydown: move |e| {
+                    // Typed characters aren't shortcuts of the page.
+                    e.stop_propagation();
+                    if e.key() == Key::Enter {
+                        run();
+                    }
+                },
+            }
+            button {
+                class: "join-item btn btn-sm",
+                onclick: move |_| run(),
+                "Find"
+            }
+        }
+        if let Some(err) = error() {
+            div {
+                class: "pt-2 text-xs text-orange-700",
+                "Invalid rowid: {err}"
+            }
+        }
+        if let Some(found) = found {
+            div {
+                class: "pt-2 text-xs",
+                for (n, step) in found.path.iter().cloned().enumerate() {
+                    button {
+                        class: "btn-ghost btn-xs btn-block text-left font-normal",
+                        class: if current.as_ref().is_some_and(|c| c.page_num == step.page_num) {"btn-active"},
+                        onclick: move |_| match step.cell_offset {
+                            Some(offset) => jump_to_field(step.page_num, offset),
+                            None => update_selected_page(viewer.read().get_page(step.page_num as u32)),
+                        },
+                        "{n + 1}. Page {step.page_num}",
+                        {
+                            match (step.cell_offset, n + 1 == found.path.len()) {
+                                (Some(offset), false) => format!(" → cell at {offset}, left child"),
+                                (None, false) => " → right-most pointer".to_string(),
+                                (Some(offset), true) => format!(" → rowid {} in the cell at {offset}", found.rowid),
+                                (None, true) => format!(" → rowid {} isn't in the table", found.rowid),
+                            }
+                        }
+                    }
+                }
+            }
+            div {
+                class: "pt-2 flex items-center gap-1 text-xs",
+                button {
+                    class: "btn btn-xs btn-ghost",
+                    disabled: frame() == 0,
+                    onclick: {
+                        let frames = frames.clone();
+                        move |_| {
+                            playing.set(false);
+                            frame -= 1;
+                            if let Some(current) = frames.get(frame()) {
+                                show_lookup_frame(current);
+                            }
+                        }
+                    },
+                    Icon { width: 12, height: 12, icon: BsChevronLeft }
+                }
+                span { "Step {frame() + 1} of {frames.len()}" }
+                button {
+                    class: "btn btn-xs btn-ghost",
+                    disabled: frame() + 1 >= frames.len(),
+                    onclick: {
+                        let frames = frames.clone();
+                        move |_| {
+                            playing.set(false);
+                            frame += 1;
+                            if let Some(current) = frames.get(frame()) {
+                                show_lookup_frame(current);
+                            }
+                        }
+                    },
+                    Icon { width: 12, height: 12, icon: BsChevronRight }
+                }
+                button {
+                    class: "btn btn-xs btn-ghost",
+                    onclick: {
+                        let frames = frames.clone();
+                        move |_| {
+                            if playing() {
+                                playing.set(false);
+                                return;
+                            }
+                            playing.set(true);
+                            let frames = frames.clone();
+                            spawn(async move {
+                                if frame() + 1 >= frames.len() {
+                                    frame.set(0);
+                                    if let Some(first) = frames.first() {
+                                        show_lookup_frame(first);
+                                    }
+                                }
+                                while frame() + 1 < frames.len() {
+                                    sleep(LOOKUP_STEP_MS).await;
+                                    if !playing() {
+                                        return;
+                                    }
+                                    frame += 1;
+                                    if let Some(current) = frames.get(frame()) {
+                                show_lookup_frame(current);
+                            }
+                                }
+                                playing.set(false);
+                            });
+                        }
+                    },
+                    if playing() {"Stop"} else {"Play"}
+                }
+            }
+            if let Some(current) = &current {
+                div {
+                    class: "alert bg-secondary text-xs",
+                    "{current.text}"
+                }
+            }
+        }
+    }
+}
+
+/// Time every step of the rowid lookup replay is shown for.
+const LOOKUP_STEP_MS: i32 = 1200;
+
+/// Moment of the rowid lookup replay: the page, the cell to point at and what happens.
+#[derive(Clone, Debug, PartialEq)]
+struct LookupFrame {
+    page_num: usize,
+    cell_offset: Option<usize>,
+    text: String,
+}
+
+/// Every key comparison of the lookup and every decision it makes, in order.
+fn lookup_frames(found: &KeySearch) -> Vec<LookupFrame> {
+    let mut frames = vec![];
+    for (n, step) in found.path.iter().enumerate() {
+        let page_num = step.page_num;
+        for &(offset, key) in &step.compared {
+            let sign = match key.cmp(&found.rowid) {
+                std::cmp::Ordering::Less => "<",
+                std::cmp::Ordering::Equal => "=",
+                std::cmp::Ordering::Greater => ">",
+            };
+            frames.push(LookupFrame {
+                page_num,
+                cell_offset: Some(offset),
+                text: format!(
+                    "Page {page_num}: key of the cell at {offset} is {key} {sign} {}",
+                    found.rowid
+                ),
+            });
+        }
+        let next = found.path.get(n + 1).map(|s| s.page_num);
+        let text = match (step.cell_offset, next) {
+            (Some(offset), Some(next)) => format!(
+                "Page {page_num}: the cell at {offset} has the first key not less than {}, follow its left child to page {next}",
+                found.rowid
+            ),
+            (None, Some(next)) => format!(
+                "Page {page_num}: every key is less than {}, follow the right-most pointer to page {next}",
+                found.rowid
+            ),
+            (Some(offset), None) => format!(
+                "Page {page_num}: rowid {} is in the cell at {offset}",
+                found.rowid
+            ),
+            (None, None) => format!(
+                "Page {page_num}: rowid {} isn't in the table",
+                found.rowid
+            ),
+        };
+        frames.push(LookupFrame {
+            page_num,
+            cell_offset: step.cell_offset,
+            text,
+        });
+    }
+    frames
+}
+
+/// Open the page of the lookup replay moment and lock the cell it points at.
+fn show_lookup_frame(frame: &LookupFrame) {
+    match frame.cell_offset {
+        Some(offset) => jump_to_field(frame.page_num, offset),
+        None => {
+            let viewer = use_context::<AppState>().viewer;
+            update_selected_page(viewer.read().get_page(frame.page_num as u32));
+        }
+    }
+}
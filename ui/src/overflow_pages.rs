@@ -2,7 +2,7 @@ use std::rc::Rc;
 
 use parser::*;
 
-use crate::{Field, Part, Value};
+use crate::{Field, FieldKind, Part, Role, Style, Value};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct NextPagePart {
@@ -12,11 +12,12 @@ pub struct NextPagePart {
 impl NextPagePart {
     pub fn new(next_page: u32) -> Self {
         let fields = vec![Rc::new(Field::new(
+            FieldKind::OverflowNextPage,
             "Value is the next overflow page in a linked list.",
             0,
             4,
             Value::PageNumber(next_page),
-            "",
+            Style::Plain,
         ))];
         Self { fields }
     }
@@ -31,8 +32,45 @@ impl Part for NextPagePart {
         "Overflow pages form a linked list. The first four bytes of each overflow page are a big-endian integer which is the page number of the next page in the chain, or zero for the final page in the chain."
     }
 
-    fn color(&self) -> String {
-        "green".to_string()
+    fn role(&self) -> Role {
+        Role::Pointers
+    }
+
+    fn fields(&self) -> &[Rc<Field>] {
+        self.fields.as_slice()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeaderPart {
+    fields: Vec<Rc<Field>>,
+}
+
+impl HeaderPart {
+    pub fn new(header: &[u8]) -> Self {
+        let fields = vec![Rc::new(Field::new(
+            FieldKind::OverflowRecordHeader,
+            "Rest of the record header: serial types of the columns, which didn't fit onto the b-tree page.",
+            4,
+            header.len(),
+            Value::Array(header.into()),
+            Style::RecordHeader,
+        ))];
+        Self { fields }
+    }
+}
+
+impl Part for HeaderPart {
+    fn label(&self) -> String {
+        "Record header overflow".to_string()
+    }
+
+    fn desc(&self) -> &'static str {
+        "Record header usually fits onto the b-tree page, but a record with many columns might have a header larger than the minimal amount of payload kept on the b-tree page. Then the header continues on the overflow page and all the values follow it here."
+    }
+
+    fn role(&self) -> Role {
+        Role::Header
     }
 
     fn fields(&self) -> &[Rc<Field>] {
@@ -46,17 +84,17 @@ pub struct DataPart {
 }
 
 impl DataPart {
-    pub fn new(overflow: &[OverflowData]) -> Self {
-        let mut offset = 4;
+    pub fn new(overflow: &[OverflowData], mut offset: usize) -> Self {
         let mut fields = vec![];
 
         for data in overflow {
             let style = if data.bytes.is_empty() {
-                "pattern-vertical-lines pattern-white pattern-bg-slate-200 pattern-size-1 pattern-opacity-60 bg-slate-390"
+                Style::EmptyOverflow
             } else {
-                "bg-slate-390"
+                Style::Overflow
             };
             fields.push(Rc::new(Field::new(
+                FieldKind::OverflowPayload,
                 "Cell's payload spilled over.",
                 offset,
                 data.bytes.len(),
@@ -78,8 +116,8 @@ impl Part for DataPart {
         "The amount of payload that spills onto overflow pages also depends on the page type. The overflow thresholds are designed to give a minimum fanout of 4 for index b-trees and to make sure enough of the payload is on the b-tree page that the record header can usually be accessed without consulting an overflow page."
     }
 
-    fn color(&self) -> String {
-        "orange".to_string()
+    fn role(&self) -> Role {
+        Role::Content
     }
 
     fn fields(&self) -> &[Rc<Field>] {
@@ -94,14 +132,15 @@ pub struct UnallocatedOverflowPart {
 
 impl UnallocatedOverflowPart {
     pub fn new(unallocated: &[u8], page: &OverflowPage) -> Self {
-        let mut offset = 4;
+        let mut offset = 4 + page.header.as_ref().map_or(0, |h| h.len());
         page.data.iter().for_each(|d| offset += d.bytes.len());
         let fields = vec![Rc::new(Field::new(
+            FieldKind::Unallocated,
             "",
             offset,
             unallocated.len(),
             Value::Unallocated(unallocated.into()),
-            "",
+            Style::Plain,
         ))];
         Self { fields }
     }
@@ -116,8 +155,8 @@ impl Part for UnallocatedOverflowPart {
         "The area in between the last cell payload and end of the overflow page."
     }
 
-    fn color(&self) -> String {
-        "green".to_string()
+    fn role(&self) -> Role {
+        Role::Unused
     }
 
     fn fields(&self) -> &[Rc<Field>] {
@@ -2,7 +2,7 @@ use std::rc::Rc;
 
 use parser::*;
 
-use crate::{Field, Part, Value};
+use crate::{Field, FieldKind, Part, Role, Style, Value};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct PageHeaderPart {
@@ -14,13 +14,15 @@ impl PageHeaderPart {
         let offset = if page.id == 1 { DB_HEADER_SIZE } else { 0 };
         let mut fields = vec![
             Rc::new(Field::new(
+                FieldKind::PageType,
                 "B-tree page type. 2 (0x02) means the page is an interior index b-tree page, 5 (0x05): interior table b-tree page, 10 (0x0a): leaf index b-tree page, 13 (0x0d): leaf table b-tree page. Any other value for the b-tree page type is an error.",
                 offset,
                 1,
                 Value::PageType(page.page_header.page_type),
-                ""
+                Style::Plain
             )),
             Rc::new(Field::new(
+                FieldKind::PageFirstFreeblock,
                 "Start of the first freeblock on the page or zero if there are no freeblocks. A freeblock is a structure used to identify unallocated space within a b-tree page. Freeblocks are organized as a chain. The first 2 bytes of a freeblock are a big-endian integer which is the offset in the b-tree page of the next freeblock in the chain, or zero if the freeblock is the last on the chain. The third and fourth bytes of each freeblock form a big-endian integer which is the size of the freeblock in bytes, including the 4-byte header. Freeblocks are always connected in order of increasing offset. The second field of the b-tree page header is the offset of the first freeblock, or zero if there are no freeblocks on the page. In a well-formed b-tree page, there will always be at least one cell before the first freeblock.A freeblock requires at least 4 bytes of space.",
                 offset + 1,
                 2,
@@ -30,37 +32,41 @@ impl PageHeaderPart {
                         Some(v) => Value::U16(v),
                     }
                 },
-                ""
+                Style::Plain
             )),
             Rc::new(Field::new(
+                FieldKind::PageCellCount,
                 "Number of cells on the page. A page might contain no cells, which is only possible for a root page of a table that contains no rows. SQLite strives to place cells as far toward the end of the b-tree page as it can, in order to leave space for future growth of the cell pointer array.",
                 offset + 3,
                 2,
                 Value::U16(page.page_header.cell_num),
-                ""
+                Style::Plain
             )),
             Rc::new(Field::new(
+                FieldKind::PageCellContentStart,
                 "Start of the cell content area. A zero value for this integer is interpreted as 65536. SQLite strives to place cells as far toward the end of the b-tree page as it can, in order to leave space for future growth of the cell pointer array. If a page contains no cells, then the offset to the cell content area will equal the page size minus the bytes of reserved space.",
                 offset + 5,
                 2,
                 Value::CellStartOffset(page.page_header.cell_start_offset),
-                ""
+                Style::Plain
             )),
             Rc::new(Field::new(
+                FieldKind::PageFragmentedBytes,
                 "The number of fragmented free bytes within the cell content area. If there is an isolated group of 1, 2, or 3 unused bytes within the cell content area, those bytes comprise a fragment. The total number of bytes in all fragments is stored in the fifth field of the b-tree page header. In a well-formed b-tree page, the total number of bytes in fragments may not exceed 60. The total amount of free space on a b-tree page consists of the size of the unallocated region plus the total size of all freeblocks plus the number of fragmented free bytes. SQLite may from time to time reorganize a b-tree page so that there are no freeblocks or fragment bytes, all unused bytes are contained in the unallocated space region, and all cells are packed tightly at the end of the page. This is called 'defragmenting' the b-tree page.",
                 offset + 7,
                 1,
                 Value::U8(page.page_header.fragmented_free_bytes),
-                ""
+                Style::Plain
             )),
         ];
         if let Some(v) = page.page_header.page_num {
             let page_num = Rc::new(Field::new(
+                FieldKind::PageRightmostPointer,
                 "The right-most pointer. This value appears in the header of interior b-tree pages only and is omitted from all other pages.",
                 offset + 8,
                 4,
                 Value::PageNumber(v),
-                ""
+                Style::Plain
             ));
             fields.push(page_num);
         }
@@ -77,8 +83,8 @@ impl Part for PageHeaderPart {
         "The b-tree page header is 8 bytes in size for leaf pages and 12 bytes for interior pages. All multibyte values in the page header are big-endian.The cell pointer array of a b-tree page immediately follows the b-tree page header."
     }
 
-    fn color(&self) -> String {
-        "green".to_string()
+    fn role(&self) -> Role {
+        Role::Header
     }
 
     fn fields(&self) -> &[Rc<Field>] {
@@ -97,11 +103,12 @@ impl CellPointerPart {
         offset += page.page_header.size;
         let fields = page.cell_pointer.array.iter().map(|ptr| {
             let field = Rc::new(Field::new(
+                FieldKind::CellPointer,
                 "2-byte integer offsets to the cell contents. Cell content is stored in the cell content region of the b-tree page. SQLite strives to place cells as far toward the end of the b-tree page as it can, in order to leave space for future growth of the cell pointer array. If a page contains no cells (which is only possible for a root page of a table that contains no rows) then the offset to the cell content area will equal the page size minus the bytes of reserved space. If the database uses a 65536-byte page size and the reserved space is zero (the usual value for reserved space) then the cell content offset of an empty page wants to be 65536. However, that integer is too large to be stored in a 2-byte unsigned integer, so a value of 0 is used in its place.",
                 offset,
                 CELL_PTR_SIZE,
                 Value::CellStartOffset(*ptr),
-                ""
+                Style::Plain
             ));
             offset += CELL_PTR_SIZE;
             field
@@ -119,8 +126,8 @@ impl Part for CellPointerPart {
         "The cell pointer array of a b-tree page immediately follows the b-tree page header. Let K be the number of cells on the btree. The cell pointer array consists of K 2-byte integer offsets to the cell contents. The cell pointers are arranged in key order with left-most cell (the cell with the smallest key) first and the right-most cell (the cell with the largest key) last."
     }
 
-    fn color(&self) -> String {
-        "orange".to_string()
+    fn role(&self) -> Role {
+        Role::Pointers
     }
 
     fn fields(&self) -> &[Rc<Field>] {
@@ -139,11 +146,12 @@ impl UnallocatedPart {
         offset += page.page_header.size + page.page_header.cell_num as usize * CELL_PTR_SIZE;
 
         let fields = vec![Rc::new(Field::new(
+            FieldKind::Unallocated,
             "The total amount of free space on a b-tree page consists of the size of the unallocated region plus the total size of all freeblocks plus the number of fragmented free bytes. SQLite may from time to time reorganize a b-tree page so that there are no freeblocks or fragment bytes, all unused bytes are contained in the unallocated space region, and all cells are packed tightly at the end of the page. This is called 'defragmenting' the b-tree page.",
             offset,
             page.unallocated.len(),
             Value::Unallocated(page.unallocated.as_slice().into()),
-            ""
+            Style::Plain
         ))];
         Self { fields }
     }
@@ -158,8 +166,8 @@ impl Part for UnallocatedPart {
         "The area in between the last cell pointer array entry and the beginning of the first cell is the unallocated region. SQLite strives to place cells as far toward the end of the b-tree page as it can, in order to leave space for future growth of the cell pointer array."
     }
 
-    fn color(&self) -> String {
-        "green".to_string()
+    fn role(&self) -> Role {
+        Role::Unused
     }
 
     fn fields(&self) -> &[Rc<Field>] {
@@ -167,28 +175,163 @@ impl Part for UnallocatedPart {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct FreeBlockPart {
+    id: usize,
+    fields: Vec<Rc<Field>>,
+}
+
+impl FreeBlockPart {
+    pub fn new(block: &FreeBlock, id: usize) -> Self {
+        let fields = vec![
+            Rc::new(Field::new(
+                FieldKind::FreeblockNext,
+                "Offset of the next freeblock in the chain, or zero if the freeblock is the last on the chain. Freeblocks are always connected in order of increasing offset.",
+                block.offset,
+                2,
+                Value::U16(block.next),
+                Style::CellHeader,
+            )),
+            Rc::new(Field::new(
+                FieldKind::FreeblockSize,
+                "Size of the freeblock in bytes, including the 4-byte header.",
+                block.offset + 2,
+                2,
+                Value::U16(block.size),
+                Style::CellHeader,
+            )),
+            Rc::new(Field::new(
+                FieldKind::FreeblockContent,
+                "Leftovers of the deleted content. SQLite doesn't clean them up unless secure_delete is on, the space is going to be overwritten by the next cell, which fits into the freeblock.",
+                block.offset + FREE_BLOCK_HEADER_SIZE,
+                block.unallocated.len(),
+                Value::Unallocated(block.unallocated.as_slice().into()),
+                Style::Plain,
+            )),
+        ];
+        Self { id, fields }
+    }
+}
+
+impl Part for FreeBlockPart {
+    fn label(&self) -> String {
+        format!("Freeblock {}", self.id)
+    }
+
+    fn desc(&self) -> &'static str {
+        "A freeblock is a structure used to identify unallocated space within the cell content area, usually left after a cell was deleted. Freeblocks are organized as a chain, which starts at the offset from the page header. Adjacent freeblocks are merged together and new cells are carved out of them when they fit. A freeblock requires at least 4 bytes of space, smaller gaps become fragmented bytes."
+    }
+
+    fn role(&self) -> Role {
+        Role::Unused
+    }
+
+    fn fields(&self) -> &[Rc<Field>] {
+        self.fields.as_slice()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FragmentPart {
+    id: usize,
+    fields: Vec<Rc<Field>>,
+}
+
+impl FragmentPart {
+    pub fn new(fragment: &Fragment, id: usize) -> Self {
+        let fields = vec![Rc::new(Field::new(
+            FieldKind::Fragment,
+            "Leftovers of the previous content, which are too small to hold a freeblock header. Nothing points at these bytes, only their total is counted in the page header, they are reclaimed when the page is defragmented.",
+            fragment.offset,
+            fragment.unallocated.len(),
+            Value::Unallocated(fragment.unallocated.as_slice().into()),
+            Style::Plain,
+        ))];
+        Self { id, fields }
+    }
+}
+
+impl Part for FragmentPart {
+    fn label(&self) -> String {
+        format!("Fragment {}", self.id)
+    }
+
+    fn desc(&self) -> &'static str {
+        "An isolated group of 1, 2, or 3 unused bytes within the cell content area is a fragment, usually left when a new cell is carved out of a freeblock slightly bigger than the cell."
+    }
+
+    fn role(&self) -> Role {
+        Role::Unused
+    }
+
+    fn fields(&self) -> &[Rc<Field>] {
+        self.fields.as_slice()
+    }
+}
+
+/// Name of the b-tree, names of its record values, the number of PRIMARY KEY
+/// columns, if it holds a WITHOUT ROWID table, and the meaning of an internal table,
+/// which isn't known by its name.
+pub type Columns<'a> = (&'a str, &'a [String], Option<usize>, Option<InternalTable>);
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct CellPart {
     id: usize,
     fields: Vec<Rc<Field>>,
+    /// Offset of the cell, which payload spilled onto overflow pages.
+    overflow_cell: Option<usize>,
 }
 
 impl CellPart {
-    pub fn new(cell: &Cell, offset: usize, id: usize) -> Self {
+    pub fn new(cell: &Cell, offset: usize, id: usize, columns: Columns) -> Self {
         let fields = match cell {
-            Cell::TableLeaf(c) => Self::table_leaf_fields(c, offset),
+            Cell::TableLeaf(c) => Self::table_leaf_fields(c, offset, columns),
             Cell::TableInterior(c) => Self::table_interior_fields(c, offset),
-            Cell::IndexLeaf(c) => Self::index_leaf_fields(c, offset),
-            Cell::IndexInterior(c) => Self::index_interior_fields(c, offset),
+            Cell::IndexLeaf(c) => Self::index_leaf_fields(c, offset, columns),
+            Cell::IndexInterior(c) => Self::index_interior_fields(c, offset, columns),
+        };
+        let overflow = match cell {
+            Cell::TableLeaf(c) => c.overflow.is_some(),
+            Cell::IndexLeaf(c) => c.overflow.is_some(),
+            Cell::IndexInterior(c) => c.overflow.is_some(),
+            Cell::TableInterior(_) => false,
         };
-        Self { fields, id }
+        Self {
+            fields,
+            id,
+            overflow_cell: overflow.then_some(offset),
+        }
+    }
+
+    pub fn rowid(&self) -> Option<i64> {
+        self.fields.iter().find_map(|f| match &f.value {
+            Value::Varint(v) if f.kind == FieldKind::CellRowid => Some(v.value),
+            _ => None,
+        })
+    }
+
+    /// Record value of the column.
+    pub fn value(&self, column: &str) -> Option<&Rc<Field>> {
+        self.fields
+            .iter()
+            .find(|f| f.kind == FieldKind::RecordValue && f.column.as_deref() == Some(column))
+    }
+
+    /// Take the record value of the column out of the cell, when another part
+    /// describes its bytes.
+    pub fn take_value(&mut self, column: &str) -> Option<Rc<Field>> {
+        let n = self.fields.iter().position(|f| {
+            f.kind == FieldKind::RecordValue && f.column.as_deref() == Some(column)
+        })?;
+        Some(self.fields.remove(n))
     }
 
-    fn table_leaf_fields(cell: &TableLeafCell, offset: usize) -> Vec<Rc<Field>> {
+    fn table_leaf_fields(cell: &TableLeafCell, offset: usize, columns: Columns) -> Vec<Rc<Field>> {
         let rowid_offset = offset + cell.payload_varint.bytes.len();
-        let cell_header_style = "bg-slate-300";
+        let cell_header_style = Style::CellHeader;
         let mut fields = vec![
             Rc::new(Field::new(
+                FieldKind::CellPayloadSize,
                 "Cell Header. A varint, which is the total number of bytes of payload, including any overflow.",
                 offset,
                 cell.payload_varint.bytes.len(),
@@ -196,6 +339,7 @@ impl CellPart {
                 cell_header_style,
             )),
             Rc::new(Field::new(
+                FieldKind::CellRowid,
                 "Cell Header. A varint which is the integer key, a.k.a. 'rowid'.",
                 rowid_offset,
                 cell.rowid_varint.bytes.len(),
@@ -204,15 +348,16 @@ impl CellPart {
             )),
         ];
         let offset = rowid_offset + cell.rowid_varint.bytes.len();
-        let offset = Self::payload_fields(&cell.payload, &mut fields, offset);
+        let offset = Self::payload_fields(&cell.record, &mut fields, offset, columns);
         Self::overflow_fields(&cell.overflow, &mut fields, offset);
         fields
     }
 
     fn table_interior_fields(cell: &TableInteriorCell, offset: usize) -> Vec<Rc<Field>> {
-        let cell_header_style = "bg-slate-300";
+        let cell_header_style = Style::CellHeader;
         vec![
             Rc::new(Field::new(
+                FieldKind::CellLeftChild,
                 "Page number of the left child.",
                 offset,
                 4,
@@ -220,8 +365,9 @@ impl CellPart {
                 cell_header_style,
             )),
             Rc::new(Field::new(
+                FieldKind::CellRowid,
                 "A varint which is the integer key, a.k.a. 'rowid'.",
-                4,
+                offset + 4,
                 cell.rowid_varint.bytes.len(),
                 Value::Varint(cell.rowid_varint.clone()),
                 cell_header_style,
@@ -229,10 +375,15 @@ impl CellPart {
         ]
     }
 
-    fn index_leaf_fields(cell: &IndexLeafCell, mut offset: usize) -> Vec<Rc<Field>> {
-        let cell_header_style = "bg-slate-300";
+    fn index_leaf_fields(
+        cell: &IndexLeafCell,
+        mut offset: usize,
+        columns: Columns,
+    ) -> Vec<Rc<Field>> {
+        let cell_header_style = Style::CellHeader;
         let mut fields = vec![
             Rc::new(Field::new(
+                FieldKind::CellPayloadSize,
                 "Cell Header. A varint, which is the total number of bytes of payload, including any overflow.",
                 offset,
                 cell.payload_varint.bytes.len(),
@@ -241,15 +392,20 @@ impl CellPart {
             )),
         ];
         offset += cell.payload_varint.bytes.len();
-        let offset = Self::payload_fields(&cell.payload, &mut fields, offset);
+        let offset = Self::payload_fields(&cell.record, &mut fields, offset, columns);
         Self::overflow_fields(&cell.overflow, &mut fields, offset);
         fields
     }
 
-    fn index_interior_fields(cell: &IndexInteriorCell, mut offset: usize) -> Vec<Rc<Field>> {
-        let cell_header_style = "bg-slate-300";
+    fn index_interior_fields(
+        cell: &IndexInteriorCell,
+        mut offset: usize,
+        columns: Columns,
+    ) -> Vec<Rc<Field>> {
+        let cell_header_style = Style::CellHeader;
         let mut fields = vec![
             Rc::new(Field::new(
+                FieldKind::CellLeftChild,
                 "Page number of the left child.",
                 offset,
                 4,
@@ -257,58 +413,103 @@ impl CellPart {
                 cell_header_style,
             )),
             Rc::new(Field::new(
+                FieldKind::CellPayloadSize,
                 "Cell Header. A varint, which is the total number of bytes of payload, including any overflow.",
-                offset,
+                offset + 4,
                 cell.payload_varint.bytes.len(),
                 Value::Varint(cell.payload_varint.clone()),
                 cell_header_style,
             )),
         ];
-        offset += cell.payload_varint.bytes.len();
-        let offset = Self::payload_fields(&cell.payload, &mut fields, offset);
+        offset += 4 + cell.payload_varint.bytes.len();
+        let offset = Self::payload_fields(&cell.record, &mut fields, offset, columns);
         Self::overflow_fields(&cell.overflow, &mut fields, offset);
         fields
     }
 
-    fn payload_fields(payload: &Record, fields: &mut Vec<Rc<Field>>, mut offset: usize) -> usize {
-        let record_header_style = "bg-slate-330";
+    fn payload_fields(
+        record: &LazyRecord,
+        fields: &mut Vec<Rc<Field>>,
+        mut offset: usize,
+        (table, columns, key_columns, internal): Columns,
+    ) -> usize {
+        let start = offset;
+        let header = record.header();
+        let record_header_style = Style::RecordHeader;
         fields.push(
             Rc::new(Field::new(
+                FieldKind::RecordHeaderSize,
                 "Cell Payload: Record Header. First value is varint, which determines total number of bytes in the header, including the size of varint.",
                 offset,
-                payload.header.size.bytes.len(),
-                Value::Varint(payload.header.size.clone()),
+                header.size.bytes.len(),
+                Value::Varint(header.size.clone()),
                 record_header_style,
             ))
         );
-        offset += payload.header.size.bytes.len();
-        for datatype in &payload.header.datatypes {
+        // Datatypes past the b-tree page are shown on the overflow page.
+        let header_end = offset + header.size.value as usize - header.spilled;
+        offset += header.size.bytes.len();
+        for datatype in &header.datatypes {
+            if offset >= header_end {
+                break;
+            }
+            let size = datatype.bytes.len().min(header_end - offset);
             fields.push(
                 Rc::new(Field::new(
+                    FieldKind::RecordSerialType,
                     "Cell Payload: Record Header. Second value(s) are one or more additional varints, one per column, which determine the datatype of each column ('serial types').",
                     offset,
-                    datatype.bytes.len(),
+                    size,
                     Value::Varint(datatype.clone()),
                     record_header_style,
                 ))
             );
-            offset += datatype.bytes.len();
+            offset += size;
         }
 
-        for record in &payload.values {
+        // Values are decoded only now, when the page is shown.
+        let payload = match record.get() {
+            Ok(payload) => payload,
+            // Bytes of the values are shown as they are, if they fail to decode.
+            Err(_) => {
+                let body = record.bytes().get(header_end - start..).unwrap_or_default();
+                fields.push(Rc::new(Field::new(
+                    FieldKind::Corrupt,
+                    "Cell Payload: Record Payload, which fails to decode: one of the values doesn't match its serial type, for example a text isn't valid in the text encoding of the database.",
+                    offset,
+                    body.len(),
+                    Value::Unallocated(body.into()),
+                    Style::Plain,
+                )));
+                return offset + body.len();
+            }
+        };
+        for (n, record) in payload.values.iter().enumerate() {
             let size = record.bytes.as_ref().map_or(0, |b| b.len());
             let style = if size == 0 {
-                "pattern-vertical-lines pattern-white pattern-bg-slate-200 pattern-size-1 pattern-opacity-60 bg-slate-360"
+                Style::EmptyRecordValue
             } else {
-                "bg-slate-360"
+                Style::RecordValue
             };
-            fields.push(Rc::new(Field::new(
-                "Cell Payload: Record Payload. The values for each column in the record immediately follow the header. For serial types 0, 8, 9, 12, and 13, the value is zero bytes in length. If all columns are of these types then the body section of the record is empty. A record might have fewer values than the number of columns in the corresponding table. This can happen, for example, after an ALTER TABLE ... ADD COLUMN SQL statement has increased the number of columns in the table schema without modifying preexisting rows in the table. Missing values at the end of the record are filled in using the default value for the corresponding columns defined in the table schema.",
-                offset,
-                size,
-                Value::Record(record.clone()),
-                style,
-            )));
+            let desc = match key_columns {
+                Some(key) if n < key => "Cell Payload: Record Payload, PRIMARY KEY column of a WITHOUT ROWID table. Such a table has no rowid and is stored in an index b-tree instead of a table b-tree: records start with the PRIMARY KEY columns, which entries of the b-tree are ordered by, and the key is unique, so it finds a row the way rowid does in ordinary tables.",
+                Some(_) => "Cell Payload: Record Payload, data column of a WITHOUT ROWID table. Columns outside of the PRIMARY KEY follow the key columns in the order of the table definition. They are stored in the same index b-tree entry as the key, so the row is read without the second lookup, which an index of an ordinary table needs to get to the table row by rowid.",
+                None => internal
+                    .or_else(|| internal_table(table))
+                    .and_then(|t| t.column_desc(columns.get(n)?))
+                    .unwrap_or("Cell Payload: Record Payload. The values for each column in the record immediately follow the header. For serial types 0, 8, 9, 12, and 13, the value is zero bytes in length. If all columns are of these types then the body section of the record is empty. A record might have fewer values than the number of columns in the corresponding table. This can happen, for example, after an ALTER TABLE ... ADD COLUMN SQL statement has increased the number of columns in the table schema without modifying preexisting rows in the table. Missing values at the end of the record are filled in using the default value for the corresponding columns defined in the table schema."),
+            };
+            fields.push(Rc::new(
+                Field::new(
+                    FieldKind::RecordValue,
+                    desc,
+                    offset,
+                    size,
+                    Value::Record(record.clone()),
+                    style,
+                )
+                .with_column(table, columns.get(n).cloned()),
+            ));
             offset += size;
         }
         offset
@@ -321,11 +522,12 @@ impl CellPart {
     ) {
         if let Some(overflow) = overflow {
             fields.push(Rc::new(Field::new(
+                FieldKind::CellOverflowPage,
                 "Cell Payload: Page Overflow. When the payload of a b-tree cell is too large for the b-tree page, the surplus is spilled onto overflow pages. Overflow pages form a linked list. The first four bytes of each overflow page are a big-endian integer which is the page number of the next page in the chain, or zero for the final page in the chain. The fifth byte through the last usable byte are used to hold overflow content.",
                 offset,
                 4,
                 Value::PageNumber(overflow.page),
-                "bg-slate-390",
+                Style::Overflow,
             )))
         }
     }
@@ -340,15 +542,19 @@ impl Part for CellPart {
         "The format of a cell depends on which kind of b-tree page the cell appears on. Cell elements like number of bytes of payload and rowid are encoded by a variable-length integer or 'varint', which is a static Huffman encoding of 64-bit twos-complement integers, that uses less space for small positive values."
     }
 
-    fn color(&self) -> String {
-        if self.id % 2 == 0 {
-            "green".to_string()
+    fn role(&self) -> Role {
+        if self.id.is_multiple_of(2) {
+            Role::Content
         } else {
-            "orange".to_string()
+            Role::ContentAlt
         }
     }
 
     fn fields(&self) -> &[Rc<Field>] {
         self.fields.as_slice()
     }
+
+    fn overflow_cell(&self) -> Option<usize> {
+        self.overflow_cell
+    }
 }
@@ -0,0 +1,66 @@
+//! Previews of the blob content recognized by `content`: images as thumbnails and
+//! the leading text.
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+
+use crate::content::{self, BlobContent};
+
+/// Kind of the blob content, if it's recognized, with a preview on demand.
+#[component]
+pub fn BlobPreview(bytes: Vec<u8>) -> Element {
+    let mut open = use_signal(|| false);
+    let content = BlobContent::detect(&bytes)?;
+    // Zlib streams are previewed by what they decompress to.
+    let (data, shown) = match &content {
+        BlobContent::Zlib(data) => (data.clone(), BlobContent::detect(data)),
+        _ => (bytes, Some(content.clone())),
+    };
+    let previewable = matches!(
+        shown,
+        Some(BlobContent::Png | BlobContent::Jpeg | BlobContent::Text)
+    );
+    rsx! {
+        div {
+            class: "pt-4 text-xs",
+            div {
+                class: "flex items-center gap-1",
+                span { "Looks like {content.label()}" }
+                if previewable {
+                    button {
+                        class: "btn btn-xs btn-ghost",
+                        class: if open() {"btn-active"},
+                        onclick: move |_| open.set(!open()),
+                        "Preview"
+                    }
+                }
+            }
+            if open() {
+                ContentPreview { content: shown, data }
+            }
+        }
+    }
+}
+
+/// Image as a thumbnail or the leading text of the content.
+#[component]
+pub fn ContentPreview(content: Option<BlobContent>, data: Vec<u8>) -> Element {
+    match content {
+        Some(BlobContent::Text) => rsx! {
+            pre {
+                class: "mt-1 p-2 max-h-64 overflow-auto whitespace-pre-wrap bg-base-200",
+                {content::text(&data).map(content::text_preview).unwrap_or_default()}
+            }
+        },
+        Some(image) => match image.mime() {
+            Some(mime) => rsx! {
+                img {
+                    class: "mt-1 max-h-48 max-w-full bg-base-200",
+                    src: content::data_url(mime, &data),
+                }
+            },
+            None => rsx! {},
+        },
+        None => rsx! {},
+    }
+}
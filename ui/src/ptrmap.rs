@@ -0,0 +1,101 @@
+//! Pointer map page UI representation and description
+use std::rc::Rc;
+
+use parser::*;
+
+use crate::{Field, FieldKind, Part, Role, Style, Value};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PtrmapEntryPart {
+    id: usize,
+    page_num: usize,
+    fields: Vec<Rc<Field>>,
+}
+
+impl PtrmapEntryPart {
+    pub fn new(entry: &PtrmapEntry, id: usize) -> Self {
+        let offset = (id - 1) * PTRMAP_ENTRY_SIZE;
+        let fields = vec![
+            Rc::new(Field::new(
+                FieldKind::PtrmapType,
+                "Type of the page: 1 for a b-tree root page, 2 for a free page, 3 for the first page of an overflow chain, 4 for the rest of the overflow pages and 5 for a non-root b-tree page.",
+                offset,
+                1,
+                Value::PtrmapType(entry.ptype),
+                Style::CellHeader,
+            )),
+            Rc::new(Field::new(
+                FieldKind::PtrmapParent,
+                "Parent page number: the b-tree page with the cell for the first overflow page, the previous overflow page for the rest of the chain, the b-tree page pointing to a non-root b-tree page. Zero for root and free pages, which have no parent.",
+                offset + 1,
+                4,
+                Value::PageNumber(entry.parent),
+                Style::Plain,
+            )),
+        ];
+        Self {
+            id,
+            page_num: entry.page_num,
+            fields,
+        }
+    }
+}
+
+impl Part for PtrmapEntryPart {
+    fn label(&self) -> String {
+        format!("Entry of Page {}", self.page_num)
+    }
+
+    fn desc(&self) -> &'static str {
+        "Each 5-byte entry describes one of the pages following the pointer map page, in order. When auto-vacuum relocates a page to the end of the freelist gap, its entry tells which parent has to be updated with the new page number, without scanning the whole database."
+    }
+
+    fn role(&self) -> Role {
+        if self.id.is_multiple_of(2) {
+            Role::Content
+        } else {
+            Role::ContentAlt
+        }
+    }
+
+    fn fields(&self) -> &[Rc<Field>] {
+        self.fields.as_slice()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnallocatedPtrmapPart {
+    fields: Vec<Rc<Field>>,
+}
+
+impl UnallocatedPtrmapPart {
+    pub fn new(unallocated: &[u8], offset: usize) -> Self {
+        let fields = vec![Rc::new(Field::new(
+            FieldKind::Unallocated,
+            "",
+            offset,
+            unallocated.len(),
+            Value::Unallocated(unallocated.into()),
+            Style::Plain,
+        ))];
+        Self { fields }
+    }
+}
+
+impl Part for UnallocatedPtrmapPart {
+    fn label(&self) -> String {
+        "Unallocated space".to_string()
+    }
+
+    fn desc(&self) -> &'static str {
+        "Room for the entries of the pages, which don't exist yet. The last pointer map page gets them as the database grows, a new pointer map page is added once it's full."
+    }
+
+    fn role(&self) -> Role {
+        Role::Unused
+    }
+
+    fn fields(&self) -> &[Rc<Field>] {
+        self.fields.as_slice()
+    }
+}
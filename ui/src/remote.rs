@@ -0,0 +1,94 @@
+//! Remote database, which pages are fetched one by one with Range requests as they're
+//! opened, so files too large to be downloaded can still be explored.
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use parser::{DBHeader, Error, StdError, Storage, DB_HEADER_SIZE};
+
+use crate::browser::fetch_range;
+
+/// Remote databases larger than this are fetched page by page, smaller ones whole.
+pub const WHOLE_FILE_LIMIT: usize = 64 << 20;
+
+#[derive(Debug, Clone)]
+pub struct PageSource {
+    pub url: String,
+    size: usize,
+    page_size: usize,
+    /// Fetched pages by page number, shared by the clones.
+    pages: Rc<RefCell<BTreeMap<usize, &'static [u8]>>>,
+}
+
+impl PageSource {
+    /// Source of the file of `size` bytes, which starts with the database header.
+    pub fn new(url: String, size: usize, header: &[u8]) -> Result<Self, StdError> {
+        let header: &[u8; DB_HEADER_SIZE] = header
+            .get(..DB_HEADER_SIZE)
+            .ok_or("The file is too short for a database header.")?
+            .try_into()?;
+        let page_size = DBHeader::try_from(header)?.page_size as usize;
+        Ok(Self {
+            url,
+            size,
+            page_size,
+            pages: Rc::new(RefCell::new(BTreeMap::new())),
+        })
+    }
+
+    /// Number of the pages the file has room for.
+    pub fn pages_total(&self) -> usize {
+        self.size.div_ceil(self.page_size)
+    }
+
+    /// Bytes of the page, if it's fetched already.
+    pub fn page(&self, page_num: usize) -> Option<&'static [u8]> {
+        self.pages.borrow().get(&page_num).copied()
+    }
+
+    /// Fetch the page with a single Range request, unless it's fetched already.
+    pub async fn fetch(&self, page_num: usize) -> Result<&'static [u8], StdError> {
+        if let Some(page) = self.page(page_num) {
+            return Ok(page);
+        }
+        if page_num == 0 || page_num > self.pages_total() {
+            return Err(Error::OutOfBounds {
+                page: page_num,
+                total: self.pages_total(),
+            }
+            .into());
+        }
+        let start = (page_num - 1) * self.page_size;
+        let mut chunk = fetch_range(&self.url, start, self.page_size).await?;
+        chunk.bytes.truncate(self.page_size);
+        let page: &'static [u8] = Box::leak(chunk.bytes.into_boxed_slice());
+        self.pages.borrow_mut().insert(page_num, page);
+        Ok(page)
+    }
+}
+
+/// Only the fetched pages are read, the callers fetch the pages they need first.
+impl Storage for PageSource {
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn read_at(&self, offset: usize, buf: &mut [u8]) -> Result<usize, Error> {
+        let mut read = 0;
+        while read < buf.len() && offset + read < self.size {
+            let at = offset + read;
+            let page_num = at / self.page_size + 1;
+            let page = self
+                .page(page_num)
+                .ok_or_else(|| Error::Io(format!("page {} isn't fetched yet", page_num)))?;
+            let start = (at % self.page_size).min(page.len());
+            let n = (page.len() - start).min(buf.len() - read);
+            if n == 0 {
+                break;
+            }
+            buf[read..read + n].copy_from_slice(&page[start..start + n]);
+            read += n;
+        }
+        Ok(read)
+    }
+}
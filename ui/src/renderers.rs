@@ -0,0 +1,32 @@
+//! Registration point for custom record value renderers, so application specific
+//! formats (protobuf blobs, UUIDs, packed timestamps) can be shown without forking.
+//!
+//! ```ignore
+//! ui::renderers::register_renderer(|table, column, bytes| {
+//!     (table == "users" && column == "id" && bytes.len() == 16).then(|| hex::encode(bytes))
+//! });
+//! ```
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Maps (table, column, value bytes) to a custom text, None to leave the value as is.
+pub type Renderer = Rc<dyn Fn(&str, &str, &[u8]) -> Option<String>>;
+
+thread_local! {
+    static RENDERERS: RefCell<Vec<Renderer>> = const { RefCell::new(vec![]) };
+}
+
+/// Register renderer before launching the app. Renderers are tried in the order of
+/// registration, the first one to return Some wins.
+pub fn register_renderer(renderer: impl Fn(&str, &str, &[u8]) -> Option<String> + 'static) {
+    RENDERERS.with(|r| r.borrow_mut().push(Rc::new(renderer)));
+}
+
+/// Custom rendering of the value, if any of the registered renderers knows it.
+pub fn render(table: &str, column: &str, bytes: &[u8]) -> Option<String> {
+    RENDERERS.with(|r| {
+        r.borrow()
+            .iter()
+            .find_map(|render| render(table, column, bytes))
+    })
+}
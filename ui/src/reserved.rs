@@ -0,0 +1,42 @@
+//! Reserved space UI representation and description
+use std::rc::Rc;
+
+use crate::{Field, FieldKind, Part, Role, Style, Value};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReservedSpacePart {
+    fields: Vec<Rc<Field>>,
+}
+
+impl ReservedSpacePart {
+    /// Reserved space always takes the tail of the page.
+    pub fn new(reserved: &[u8], page_size: usize) -> Self {
+        let fields = vec![Rc::new(Field::new(
+            FieldKind::Reserved,
+            "Size of the reserved space is set by the database header for every page of the file. SQLite core never reads or writes these bytes, the usable size of a page, which the b-tree, overflow and freelist math is based on, is the page size less the reserved space.",
+            page_size - reserved.len(),
+            reserved.len(),
+            Value::Unallocated(reserved.into()),
+            Style::Plain,
+        ))];
+        Self { fields }
+    }
+}
+
+impl Part for ReservedSpacePart {
+    fn label(&self) -> String {
+        "Reserved space".to_string()
+    }
+
+    fn desc(&self) -> &'static str {
+        "Extensions, like the encryption ones, keep per-page information such as a nonce or a checksum in the bytes at the end of each page."
+    }
+
+    fn role(&self) -> Role {
+        Role::Unused
+    }
+
+    fn fields(&self) -> &[Rc<Field>] {
+        self.fields.as_slice()
+    }
+}
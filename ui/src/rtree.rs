@@ -0,0 +1,144 @@
+//! Node of an R*Tree index, kept as a blob in a cell of the `%_node` shadow table,
+//! with the bounding boxes of its cells laid out field by field.
+use std::rc::Rc;
+
+use parser::*;
+
+use crate::pages::CellPart;
+use crate::{Field, FieldKind, Part, Role, Style, Value};
+
+/// Node blob of the cell taken out of it into its own part. None if the cell
+/// spills onto overflow pages or the blob isn't a node of the R*Tree.
+pub fn node_part(cell: &mut CellPart, shadow: &RtreeShadow, id: usize) -> Option<RtreeNodePart> {
+    if cell.overflow_cell().is_some() {
+        return None;
+    }
+    let nodeno = cell.rowid()?;
+    let field = cell.value("data")?;
+    let Value::Record(value) = &field.value else {
+        return None;
+    };
+    let bytes = value.bytes.as_deref()?;
+    let node = RtreeNode::new(bytes, shadow.dimensions(), shadow.int32).ok()?;
+    let part = RtreeNodePart::new(&node, bytes, field.offset, nodeno, shadow, id);
+    cell.take_value("data");
+    Some(part)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RtreeNodePart {
+    nodeno: i64,
+    /// Number of the cell, which holds the node.
+    id: usize,
+    fields: Vec<Rc<Field>>,
+}
+
+impl RtreeNodePart {
+    pub fn new(
+        node: &RtreeNode,
+        bytes: &[u8],
+        offset: usize,
+        nodeno: i64,
+        shadow: &RtreeShadow,
+        id: usize,
+    ) -> Self {
+        let is_root = nodeno == RTREE_ROOT_NODE;
+        let depth_desc = if is_root {
+            "Depth of the tree: 0 if the root node is a leaf, otherwise the number of levels of interior nodes under the root."
+        } else {
+            "Depth of the tree, only the root node keeps it, it's 0 in the other nodes."
+        };
+        let id_desc = match (is_root, node.depth) {
+            (true, 0) => "Rowid of the entry: the root node is a leaf, so its cells are the entries of the R*Tree table.",
+            (true, _) => "Number of the child node: the bounding box of the cell holds all the boxes of the child node.",
+            _ => "Rowid of the entry on a leaf node, number of the child node on an interior one: the %_rowid table lists the leaf node of every entry, the %_parent table the parent of every node.",
+        };
+        let coord_desc = if shadow.int32 {
+            "Minimum or maximum of a dimension of the bounding box, a 4-byte integer of rtree_i32."
+        } else {
+            "Minimum or maximum of a dimension of the bounding box, a 4-byte float. Values are rounded so the box only grows: minimums down, maximums up, a search never misses an entry because of it."
+        };
+        let mut fields = vec![
+            Rc::new(Field::new(
+                FieldKind::RtreeDepth,
+                depth_desc,
+                offset,
+                2,
+                Value::U16(node.depth),
+                Style::RecordHeader,
+            )),
+            Rc::new(Field::new(
+                FieldKind::RtreeCellCount,
+                "Number of cells in the node. Nodes have the same size, so the number of cells a node fits depends on the number of dimensions.",
+                offset + 2,
+                2,
+                Value::U16(node.cells.len() as u16),
+                Style::RecordHeader,
+            )),
+        ];
+        for cell in &node.cells {
+            fields.push(Rc::new(Field::new(
+                FieldKind::RtreeCellId,
+                id_desc,
+                offset + cell.offset,
+                RTREE_CELL_ID_SIZE,
+                Value::I64(cell.id),
+                Style::RecordValue,
+            )));
+            for (n, coord) in cell.coords.iter().enumerate() {
+                let column = shadow.coordinates.get(n).cloned();
+                fields.push(Rc::new(
+                    Field::new(
+                        FieldKind::RtreeCoordinate,
+                        coord_desc,
+                        offset + cell.offset + RTREE_CELL_ID_SIZE + n * RTREE_COORD_SIZE,
+                        RTREE_COORD_SIZE,
+                        Value::Coord(*coord),
+                        Style::RecordValue,
+                    )
+                    .with_column(&shadow.vtab, column),
+                ));
+            }
+        }
+        let unused = bytes.get(node.cells_end()..).unwrap_or_default();
+        if !unused.is_empty() {
+            fields.push(Rc::new(Field::new(
+                FieldKind::RtreeUnused,
+                "Space of the node without cells, zeroed. Every node takes the same size, so cells are added and removed in place.",
+                offset + node.cells_end(),
+                unused.len(),
+                Value::Unallocated(unused.into()),
+                Style::EmptyRecordValue,
+            )));
+        }
+        Self { nodeno, id, fields }
+    }
+
+    /// Page offset of the node blob.
+    pub fn offset(&self) -> usize {
+        self.fields.first().map_or(0, |f| f.offset)
+    }
+}
+
+impl Part for RtreeNodePart {
+    fn label(&self) -> String {
+        format!("R*Tree Node {}", self.nodeno)
+    }
+
+    fn desc(&self) -> &'static str {
+        "Node of the R*Tree index, stored as the data blob of the %_node table. Every cell is a bounding box: on leaf nodes it's the box of an entry with its rowid, on interior nodes the box around all the cells of a child node, so a search only descends into the children, which boxes overlap the queried area."
+    }
+
+    fn role(&self) -> Role {
+        // The same color as the cell the node is kept in.
+        if self.id.is_multiple_of(2) {
+            Role::Content
+        } else {
+            Role::ContentAlt
+        }
+    }
+
+    fn fields(&self) -> &[Rc<Field>] {
+        self.fields.as_slice()
+    }
+}
@@ -0,0 +1,144 @@
+//! SQLite Archive view: files of the `sqlar` table and the places their content is kept at.
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+use dioxus_logger::tracing::error;
+use parser::{SqlarEntry, SQLAR_DATA};
+
+use crate::browser::download;
+use crate::chains::ChunkLinks;
+use crate::content::BlobContent;
+use crate::index::{jump_to_field, jump_to_value};
+use crate::preview::ContentPreview;
+use crate::state::AppState;
+
+/// Files of an SQLite Archive, any of them opens with the places its content is kept at.
+#[component]
+pub fn SqlarListing(root: usize) -> Element {
+    let viewer = use_context::<AppState>().viewer;
+    let mut opened = use_signal(|| None::<usize>);
+    let entries = viewer
+        .read()
+        .table_rows(root)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(SqlarEntry::new)
+        .collect::<Vec<_>>();
+    let entry = opened().and_then(|n| entries.get(n)).cloned();
+    rsx! {
+        div {
+            class: "pt-4 text-xs",
+            div {
+                class: "font-medium",
+                "SQLite Archive, {entries.len()} entries"
+            }
+            table {
+                class: "table table-xs",
+                thead {
+                    tr {
+                        th { "Name" }
+                        th { "Mode" }
+                        th { "Size" }
+                        th { "Stored" }
+                    }
+                }
+                tbody {
+                    for (n, entry) in entries.iter().enumerate() {
+                        tr {
+                            class: "hover cursor-pointer",
+                            class: if opened() == Some(n) {"bg-base-200"},
+                            onclick: move |_| opened.set(Some(n)),
+                            td {
+                                class: "max-w-48 truncate",
+                                title: "{entry.name}",
+                                "{entry.name}"
+                            }
+                            td { class: "font-mono", {entry.permissions()} }
+                            td {
+                                if entry.size >= 0 {"{entry.size}"} else {"─"}
+                            }
+                            td {
+                                title: if entry.is_compressed() {"Compressed with zlib"},
+                                {entry.stored_size().to_string()},
+                                if entry.is_compressed() {" (zlib)"}
+                            }
+                        }
+                    }
+                }
+            }
+            if let Some(entry) = entry {
+                SqlarFile { entry }
+            }
+        }
+    }
+}
+
+/// Content of the archived file and the cell and the overflow pages, which keep it.
+#[component]
+fn SqlarFile(entry: SqlarEntry) -> Element {
+    let viewer = use_context::<AppState>().viewer;
+    let (page_num, cell_offset) = (entry.page_num, entry.cell_offset);
+    let chunks = viewer
+        .read()
+        .reassemble(page_num, cell_offset)
+        .ok()
+        .and_then(|payload| payload.values.into_iter().nth(SQLAR_DATA))
+        .map(|value| value.chunks)
+        .unwrap_or_default();
+    let file_name = entry
+        .name
+        .rsplit('/')
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    let content = entry.content();
+    rsx! {
+        div {
+            class: "pt-2",
+            div {
+                class: "flex flex-wrap items-center gap-1",
+                span { class: "font-medium", "{entry.name}, {entry.kind()}" }
+                button {
+                    class: "btn btn-ghost btn-xs",
+                    onclick: move |_| jump_to_field(page_num, cell_offset),
+                    "Cell"
+                }
+                if entry.data.is_some() {
+                    button {
+                        class: "btn btn-ghost btn-xs",
+                        onclick: move |_| jump_to_value(page_num, cell_offset, SQLAR_DATA),
+                        "Data"
+                    }
+                }
+            }
+            if chunks.len() > 1 {
+                div {
+                    class: "flex flex-wrap items-center",
+                    "Stored in",
+                    ChunkLinks { chunks }
+                }
+            }
+            match content {
+                Err(e) => rsx! {
+                    div {
+                        class: "pt-1 text-orange-700",
+                        "Failed to decompress: {e}"
+                    }
+                },
+                Ok(bytes) if entry.data.is_some() => rsx! {
+                    button {
+                        class: "btn btn-xs btn-secondary mt-1",
+                        onclick: move |_| {
+                            if let Err(err) = download(&file_name, &bytes) {
+                                error!("Failed to save {}: {}", file_name, err);
+                            }
+                        },
+                        "Save {bytes.len()} bytes"
+                    }
+                    ContentPreview { content: BlobContent::detect(&bytes), data: bytes.clone() }
+                },
+                Ok(_) => rsx! {},
+            }
+        }
+    }
+}
@@ -3,10 +3,14 @@
 use std::rc::Rc;
 
 use dioxus::prelude::*;
+use parser::{KeySearch, StdError, TextEncoding};
 
-use crate::included_db::SIMPLE_DB;
+use crate::browser::{storage_get, storage_set};
+use crate::diff::Compare;
+use crate::i18n::Language;
+use crate::included_db::{INCLUDED_DB, SIMPLE_DB};
 use crate::viewer::Viewer;
-use crate::{Field, PageView, Part};
+use crate::{Field, PageView, Part, Role};
 
 #[derive(Clone, Debug)]
 pub struct AppState {
@@ -17,6 +21,77 @@ pub struct AppState {
     pub selected_part: Signal<Rc<dyn Part>>,
     pub locked_field: Signal<Option<(usize, usize)>>,
     pub format: Signal<Format>,
+    pub hex_dump: Signal<bool>,
+    /// Selected page is shown as plain bytes instead of its parsed fields.
+    pub raw_mode: Signal<bool>,
+    pub hex_settings: Signal<HexSettings>,
+    pub value_limit: Signal<ValueLimit>,
+    pub hex_style: Signal<HexStyle>,
+    /// Settings drawer is open.
+    pub settings: Signal<bool>,
+    /// Page offsets of the first and the last fields in the visible part of the grid.
+    pub viewport: Signal<Option<(usize, usize)>>,
+    /// Position of the first field mounted in the grid, counting fields of all the parts.
+    pub grid_window: Signal<usize>,
+    pub loading: Signal<bool>,
+    /// Index of the shown snapshot for multi-snapshot examples.
+    pub snapshot: Signal<usize>,
+    pub search: Signal<Search>,
+    /// Pages visited by the last rowid lookup.
+    pub key_search: Signal<Option<KeySearch>>,
+    /// Database the current one is compared with.
+    pub compare: Signal<Option<Compare>>,
+    /// Show the compared page side by side instead of marking changes in place.
+    pub diff_split: Signal<bool>,
+    /// Command palette is open.
+    pub commands: Signal<bool>,
+    pub addressing: Signal<Addressing>,
+    /// Page kept next to the selected one, with its own selected field.
+    pub pinned_page: Signal<Option<Rc<dyn PageView>>>,
+    pub pinned_field: Signal<Option<(usize, usize)>>,
+    pub bookmarks: Signal<Bookmarks>,
+    /// The UI is served by `sqlite-repr serve` with a database from the disk.
+    pub local_db: Signal<bool>,
+    /// Script of the database built in the SQL playground.
+    pub user_db: Signal<Option<String>>,
+    /// URL of the database fetched from the web.
+    pub remote_db: Signal<Option<String>>,
+    /// Bytes of the remote database received so far and its size, while it's fetched.
+    pub fetched: Signal<Option<(usize, Option<usize>)>>,
+    pub history: Signal<History>,
+    pub palette: Signal<Palette>,
+    pub theme: Signal<Theme>,
+    /// Language of the descriptions.
+    pub language: Signal<Language>,
+}
+
+/// Numbers of the visited pages to go back and forward to, the latest are the last.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct History {
+    pub back: Vec<usize>,
+    pub forward: Vec<usize>,
+}
+
+impl History {
+    /// Remember the page left for another one, going forward isn't possible anymore.
+    pub fn visit(&mut self, left: usize) {
+        self.back.push(left);
+        self.forward.clear();
+    }
+
+    /// Page to go back to from the current one.
+    pub fn back(&mut self, current: usize) -> Option<usize> {
+        let page = self.back.pop()?;
+        self.forward.push(current);
+        Some(page)
+    }
+
+    /// Page to go forward to from the current one.
+    pub fn forward(&mut self, current: usize) -> Option<usize> {
+        let page = self.forward.pop()?;
+        self.back.push(current);
+        Some(page)
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -26,12 +101,575 @@ pub enum Format {
     Text,
 }
 
+impl Format {
+    /// Format, which follows this one in the toolbar, wrapping around.
+    pub fn next(&self) -> Self {
+        match self {
+            Format::Hybrid => Format::Hex,
+            Format::Hex => Format::Text,
+            Format::Text => Format::Hybrid,
+        }
+    }
+}
+
+/// How the offsets of the fields are shown.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Addressing {
+    /// From the start of the page.
+    Page,
+    /// From the start of the database file.
+    File,
+}
+
+impl Addressing {
+    pub fn toggled(&self) -> Self {
+        match self {
+            Addressing::Page => Addressing::File,
+            Addressing::File => Addressing::Page,
+        }
+    }
+
+    /// Offset within the page as it's shown.
+    pub fn offset(&self, page: &dyn PageView, offset: usize) -> usize {
+        match self {
+            Addressing::Page => offset,
+            Addressing::File => page.base_offset() + offset,
+        }
+    }
+}
+
+pub const HEX_ROW_SIZES: [usize; 3] = [8, 16, 32];
+pub const HEX_GROUP_SIZES: [usize; 3] = [1, 2, 4];
+
+/// Layout of the hex dump, kept in the browser storage between sessions.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HexSettings {
+    pub row_size: usize,
+    pub group: usize,
+}
+
+impl Default for HexSettings {
+    fn default() -> Self {
+        Self {
+            row_size: 16,
+            group: 1,
+        }
+    }
+}
+
+impl HexSettings {
+    const STORAGE_KEY: &'static str = "hex-settings";
+
+    /// Restore settings saved as "<row size>:<group>", defaults if there is nothing valid.
+    pub fn load() -> Self {
+        let parsed = storage_get(Self::STORAGE_KEY).and_then(|value| {
+            let (row_size, group) = value.split_once(':')?;
+            Some(Self {
+                row_size: row_size.parse().ok()?,
+                group: group.parse().ok()?,
+            })
+        });
+        match parsed {
+            Some(settings)
+                if HEX_ROW_SIZES.contains(&settings.row_size)
+                    && HEX_GROUP_SIZES.contains(&settings.group) =>
+            {
+                settings
+            }
+            _ => Self::default(),
+        }
+    }
+
+    pub fn save(&self) {
+        storage_set(
+            Self::STORAGE_KEY,
+            &format!("{}:{}", self.row_size, self.group),
+        );
+    }
+}
+
+pub const HEX_VALUE_GROUPS: [usize; 4] = [1, 2, 4, 8];
+
+/// How the hex of the values is written, kept in the browser storage between sessions.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HexStyle {
+    /// Bytes written together, without a space between them.
+    pub group: usize,
+    pub uppercase: bool,
+    /// Every group starts with 0x.
+    pub prefix: bool,
+    /// Values of 2 to 8 bytes are annotated with their big-endian reading.
+    pub big_endian: bool,
+}
+
+impl Default for HexStyle {
+    fn default() -> Self {
+        Self {
+            group: 1,
+            uppercase: true,
+            prefix: false,
+            big_endian: false,
+        }
+    }
+}
+
+impl HexStyle {
+    const STORAGE_KEY: &'static str = "hex-style";
+
+    /// Restore the style saved as "<group>:<uppercase>:<prefix>:<big-endian>" with
+    /// the flags as 0 or 1, the default if there is nothing valid.
+    pub fn load() -> Self {
+        let parsed = storage_get(Self::STORAGE_KEY).and_then(|value| {
+            let parts = value.split(':').collect::<Vec<_>>();
+            let [group, uppercase, prefix, big_endian] = parts.as_slice() else {
+                return None;
+            };
+            let flag = |v: &str| match v {
+                "0" => Some(false),
+                "1" => Some(true),
+                _ => None,
+            };
+            Some(Self {
+                group: group.parse().ok()?,
+                uppercase: flag(uppercase)?,
+                prefix: flag(prefix)?,
+                big_endian: flag(big_endian)?,
+            })
+        });
+        parsed
+            .filter(|style| HEX_VALUE_GROUPS.contains(&style.group))
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        storage_set(
+            Self::STORAGE_KEY,
+            &format!(
+                "{}:{}:{}:{}",
+                self.group, self.uppercase as u8, self.prefix as u8, self.big_endian as u8
+            ),
+        );
+    }
+
+    /// Hex of the bytes in groups separated by spaces.
+    pub fn hex(&self, bytes: &[u8]) -> String {
+        bytes
+            .chunks(self.group)
+            .map(|chunk| {
+                let digits = chunk
+                    .iter()
+                    .map(|b| match self.uppercase {
+                        true => format!("{:02X}", b),
+                        false => format!("{:02x}", b),
+                    })
+                    .collect::<String>();
+                match self.prefix {
+                    true => format!("0x{digits}"),
+                    false => digits,
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Limits to choose from, in bytes.
+pub const VALUE_LIMITS: [usize; 5] = [10, 32, 128, 1024, 8192];
+
+/// Bytes of long values shown in the grid before the rest is cut, unless the field
+/// is expanded. Kept in the browser storage between sessions.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ValueLimit(pub usize);
+
+impl Default for ValueLimit {
+    fn default() -> Self {
+        Self(VALUE_LIMITS[0])
+    }
+}
+
+impl ValueLimit {
+    const STORAGE_KEY: &'static str = "value-limit";
+
+    pub fn load() -> Self {
+        storage_get(Self::STORAGE_KEY)
+            .and_then(|value| value.parse().ok())
+            .filter(|limit| VALUE_LIMITS.contains(limit))
+            .map_or_else(Self::default, Self)
+    }
+
+    pub fn save(&self) {
+        storage_set(Self::STORAGE_KEY, &self.0.to_string());
+    }
+}
+
+/// Colors of the part roles.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Palette {
+    Classic,
+    /// Blue and orange, which stay apart with the common kinds of color blindness.
+    ColorblindSafe,
+}
+
+impl Palette {
+    const STORAGE_KEY: &'static str = "palette";
+    pub const ALL: [Palette; 2] = [Palette::Classic, Palette::ColorblindSafe];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Palette::Classic => "Classic",
+            Palette::ColorblindSafe => "Colorblind safe",
+        }
+    }
+
+    /// Tailwind color name of the role, shades are picked by the UI.
+    pub fn color(&self, role: Role) -> &'static str {
+        match (self, role) {
+            (_, Role::Unused) => "slate",
+            (Palette::Classic, Role::Header | Role::Content) => "green",
+            (Palette::Classic, Role::Pointers | Role::ContentAlt) => "orange",
+            (Palette::ColorblindSafe, Role::Header | Role::Content) => "blue",
+            (Palette::ColorblindSafe, Role::Pointers | Role::ContentAlt) => "orange",
+        }
+    }
+
+    /// Restore the palette saved by its name, the classic one if there is nothing valid.
+    pub fn load() -> Self {
+        let saved = storage_get(Self::STORAGE_KEY);
+        Self::ALL
+            .into_iter()
+            .find(|p| saved.as_deref() == Some(p.name()))
+            .unwrap_or(Palette::Classic)
+    }
+
+    pub fn save(&self) {
+        storage_set(Self::STORAGE_KEY, self.name());
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Theme {
+    Light,
+    Dark,
+}
+
+impl Theme {
+    const STORAGE_KEY: &'static str = "theme";
+
+    pub fn toggled(&self) -> Self {
+        match self {
+            Theme::Light => Theme::Dark,
+            Theme::Dark => Theme::Light,
+        }
+    }
+
+    /// Name of the daisyUI theme, see tailwind.config.js.
+    pub fn daisy_theme(&self) -> &'static str {
+        match self {
+            Theme::Light => "custom",
+            Theme::Dark => "custom-dark",
+        }
+    }
+
+    /// Shade of the palette colors for the borders and offsets of the fields.
+    pub fn part_shade(&self) -> &'static str {
+        match self {
+            Theme::Light => "800",
+            Theme::Dark => "600",
+        }
+    }
+
+    /// Background of the fields and the header bar.
+    pub fn surface(&self) -> &'static str {
+        match self {
+            Theme::Light => "bg-slate-200",
+            Theme::Dark => "bg-slate-800",
+        }
+    }
+
+    /// Restore the saved theme, the light one if there is nothing valid.
+    pub fn load() -> Self {
+        match storage_get(Self::STORAGE_KEY).as_deref() {
+            Some("dark") => Theme::Dark,
+            _ => Theme::Light,
+        }
+    }
+
+    pub fn save(&self) {
+        let name = match self {
+            Theme::Light => "light",
+            Theme::Dark => "dark",
+        };
+        storage_set(Self::STORAGE_KEY, name);
+    }
+}
+
+/// Action of the command palette.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    Page(usize),
+    /// File offset.
+    Offset(usize),
+    Tree(String),
+}
+
+impl Command {
+    /// Parse "go to page 37", "go to offset 0x3A00" or "open tree idx_stars_name",
+    /// "go to" and "open" might be omitted. Offsets are decimal or hex with 0x prefix.
+    pub fn parse(input: &str) -> Result<Self, StdError> {
+        let input = input.trim();
+        let input = ["go to ", "open "]
+            .iter()
+            .find_map(|prefix| {
+                let head = input.get(..prefix.len())?;
+                head.eq_ignore_ascii_case(prefix)
+                    .then(|| input[prefix.len()..].trim_start())
+            })
+            .unwrap_or(input);
+        let (word, arg) = input
+            .split_once(char::is_whitespace)
+            .ok_or("Try \"page 37\", \"offset 0x3A00\" or \"tree idx_stars_name\"")?;
+        let arg = arg.trim();
+        match word.to_lowercase().as_str() {
+            "page" => Ok(Self::Page(arg.parse()?)),
+            "offset" => Ok(Self::Offset(
+                match arg.strip_prefix("0x").or_else(|| arg.strip_prefix("0X")) {
+                    Some(digits) => usize::from_str_radix(digits, 16)?,
+                    None => arg.parse()?,
+                },
+            )),
+            "tree" => Ok(Self::Tree(arg.to_string())),
+            _ => Err(format!("Unknown command: {}", word).into()),
+        }
+    }
+}
+
+/// Field marked by the user, with an optional note.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bookmark {
+    pub db: String,
+    pub page: usize,
+    /// Page offset of the field.
+    pub offset: usize,
+    pub note: String,
+}
+
+/// Bookmarks of all databases, kept in the browser storage between sessions.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Bookmarks(Vec<Bookmark>);
+
+impl Bookmarks {
+    const STORAGE_KEY: &'static str = "bookmarks";
+
+    /// Restore bookmarks saved as a JSON array, entries which aren't valid are skipped.
+    pub fn load() -> Self {
+        let saved = storage_get(Self::STORAGE_KEY)
+            .and_then(|value| serde_json::from_str::<serde_json::Value>(&value).ok());
+        let entries = saved
+            .as_ref()
+            .and_then(|value| value.as_array())
+            .map(|entries| {
+                entries
+                    .iter()
+                    .filter_map(|entry| {
+                        Some(Bookmark {
+                            db: entry.get("db")?.as_str()?.to_string(),
+                            page: entry.get("page")?.as_u64()? as usize,
+                            offset: entry.get("offset")?.as_u64()? as usize,
+                            note: entry.get("note")?.as_str()?.to_string(),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self(entries)
+    }
+
+    pub fn save(&self) {
+        let entries = self
+            .0
+            .iter()
+            .map(|b| {
+                serde_json::json!({
+                    "db": b.db,
+                    "page": b.page,
+                    "offset": b.offset,
+                    "note": b.note,
+                })
+            })
+            .collect::<Vec<_>>();
+        storage_set(
+            Self::STORAGE_KEY,
+            &serde_json::Value::from(entries).to_string(),
+        );
+    }
+
+    /// Bookmarks of the database in the page order.
+    pub fn of_db(&self, db: &str) -> Vec<Bookmark> {
+        let mut bookmarks = self
+            .0
+            .iter()
+            .filter(|b| b.db == db)
+            .cloned()
+            .collect::<Vec<_>>();
+        bookmarks.sort_by_key(|b| (b.page, b.offset));
+        bookmarks
+    }
+
+    pub fn get(&self, db: &str, page: usize, offset: usize) -> Option<&Bookmark> {
+        self.0
+            .iter()
+            .find(|b| b.db == db && b.page == page && b.offset == offset)
+    }
+
+    /// Add the bookmark or replace the note of the existing one.
+    pub fn set(&mut self, bookmark: Bookmark) {
+        self.remove(&bookmark.db, bookmark.page, bookmark.offset);
+        self.0.push(bookmark);
+        self.save();
+    }
+
+    pub fn remove(&mut self, db: &str, page: usize, offset: usize) {
+        self.0
+            .retain(|b| !(b.db == db && b.page == page && b.offset == offset));
+        self.save();
+    }
+}
+
+pub const MAX_SEARCH_HITS: usize = 1000;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SearchMode {
+    Text,
+    Hex,
+}
+
+/// Last edited input of the varint playground: the integer or its bytes in hex.
+#[derive(Clone, Debug, PartialEq)]
+pub enum VarintInput {
+    Value(String),
+    Bytes(String),
+}
+
+/// Occurrences of a byte sequence in the database file.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Search {
+    pub needle: Vec<u8>,
+    /// File offsets of the matches, at most MAX_SEARCH_HITS of them.
+    pub hits: Vec<usize>,
+    /// Whether there are more matches than kept.
+    pub truncated: bool,
+}
+
+impl Search {
+    /// Bytes to look for: text in the database encoding or hex digits, whitespace
+    /// and 0x prefix of every group of digits are allowed, e.g. "0x53 0x51".
+    pub fn parse(
+        query: &str,
+        mode: SearchMode,
+        text_encoding: TextEncoding,
+    ) -> Result<Vec<u8>, StdError> {
+        match mode {
+            SearchMode::Text => Ok(text_encoding.encode(query)),
+            SearchMode::Hex => {
+                let digits = query
+                    .split_whitespace()
+                    .map(|d| d.strip_prefix("0x").or(d.strip_prefix("0X")).unwrap_or(d))
+                    .collect::<String>();
+                Ok(hex::decode(digits)?)
+            }
+        }
+    }
+
+    pub fn run(bytes: &[u8], needle: Vec<u8>) -> Self {
+        if needle.is_empty() {
+            return Self::default();
+        }
+        let mut hits = bytes
+            .windows(needle.len())
+            .enumerate()
+            .filter(|(_, w)| *w == needle.as_slice())
+            .map(|(n, _)| n)
+            .take(MAX_SEARCH_HITS + 1)
+            .collect::<Vec<_>>();
+        let truncated = hits.len() > MAX_SEARCH_HITS;
+        hits.truncate(MAX_SEARCH_HITS);
+        Self {
+            needle,
+            hits,
+            truncated,
+        }
+    }
+
+    /// Whether any match overlaps `size` bytes at the file offset.
+    pub fn covers(&self, offset: usize, size: usize) -> bool {
+        let len = self.needle.len();
+        self.hits
+            .iter()
+            .any(|&hit| hit < offset + size.max(1) && offset < hit + len)
+    }
+}
+
+/// Selected database, page and locked field, encoded into the URL path as
+/// `<db>/page/<n>/part/<n>/field/<n>`, so links point to exact bytes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeepLink {
+    pub db: String,
+    pub page: usize,
+    /// Part and field indexes of the locked field.
+    pub field: Option<(usize, usize)>,
+}
+
+impl DeepLink {
+    /// Path segment of the included database name, e.g. "overflow_pages".
+    pub fn slug(name: &str) -> String {
+        name.to_lowercase().replace([' ', '-'], "_")
+    }
+
+    /// Parse path segments, anything before the database name is ignored, as it's
+    /// the path of the app root itself.
+    pub fn from_route(route: &[String]) -> Option<Self> {
+        let (start, db) = route.iter().enumerate().find_map(|(n, segment)| {
+            INCLUDED_DB
+                .iter()
+                .find(|(name, _)| Self::slug(name) == *segment)
+                .map(|(name, _)| (n, name.to_string()))
+        })?;
+
+        let mut link = Self {
+            db,
+            page: 1,
+            field: None,
+        };
+        let (mut part, mut field) = (None, None);
+        for pair in route[start + 1..].chunks(2) {
+            let [key, value] = pair else { break };
+            match (key.as_str(), value.parse().ok()) {
+                ("page", Some(v)) => link.page = v,
+                ("part", Some(v)) => part = Some(v),
+                ("field", Some(v)) => field = Some(v),
+                _ => break,
+            }
+        }
+        link.field = part.zip(field);
+        Some(link)
+    }
+
+    pub fn to_path(&self) -> String {
+        let mut path = format!("{}/page/{}", Self::slug(&self.db), self.page);
+        if let Some((part, field)) = self.field {
+            path.push_str(&format!("/part/{}/field/{}", part, field));
+        }
+        path
+    }
+}
+
 impl AppState {
     pub fn init() -> Self {
         // preloaded db shouldn't fail
         let viewer =
-            Viewer::new_from_included(SIMPLE_DB).expect("Viewer failed to init for preloaded db.");
+            Viewer::new_from_inline(SIMPLE_DB).expect("Viewer failed to init for preloaded db.");
         let page = viewer.get_page(1);
+        viewer.touch(&page);
         let part = viewer.get_part(&page, 0);
         let field = viewer.get_field(&part, 0);
 
@@ -42,7 +680,122 @@ impl AppState {
             selected_field: Signal::new(field),
             locked_field: Signal::new(None),
             format: Signal::new(Format::Hybrid),
+            hex_dump: Signal::new(false),
+            raw_mode: Signal::new(false),
+            hex_settings: Signal::new(HexSettings::load()),
+            value_limit: Signal::new(ValueLimit::load()),
+            hex_style: Signal::new(HexStyle::load()),
+            settings: Signal::new(false),
+            viewport: Signal::new(None),
+            grid_window: Signal::new(0),
+            loading: Signal::new(false),
+            snapshot: Signal::new(0),
+            search: Signal::new(Search::default()),
+            key_search: Signal::new(None),
+            compare: Signal::new(None),
+            diff_split: Signal::new(false),
+            commands: Signal::new(false),
+            addressing: Signal::new(Addressing::Page),
+            pinned_page: Signal::new(None),
+            pinned_field: Signal::new(None),
+            bookmarks: Signal::new(Bookmarks::load()),
+            local_db: Signal::new(false),
+            user_db: Signal::new(None),
+            remote_db: Signal::new(None),
+            fetched: Signal::new(None),
+            history: Signal::new(History::default()),
+            palette: Signal::new(Palette::load()),
+            theme: Signal::new(Theme::load()),
+            language: Signal::new(Language::load()),
             viewer: Signal::new(viewer),
         }
     }
+
+    /// Replace current database with a new viewer and select its first page.
+    pub fn set_viewer(&self, name: String, viewer: Viewer) {
+        let page = viewer.get_page(1);
+        viewer.touch(&page);
+        let part = viewer.get_part(&page, 0);
+        let field = viewer.get_field(&part, 0);
+
+        let mut state = self.clone();
+        *state.current_db.write() = name;
+        *state.selected_page.write() = page;
+        *state.selected_part.write() = part;
+        *state.selected_field.write() = field;
+        *state.locked_field.write() = None;
+        *state.snapshot.write() = 0;
+        *state.search.write() = Search::default();
+        *state.key_search.write() = None;
+        *state.history.write() = History::default();
+        *state.pinned_page.write() = None;
+        *state.pinned_field.write() = None;
+        // Nothing but the bytes of the encrypted pages makes sense.
+        if viewer.encryption.is_some() {
+            *state.format.write() = Format::Hex;
+        }
+        *state.viewer.write() = viewer;
+    }
+
+    /// Swap viewer to another snapshot of the same database, staying on the
+    /// selected page if it still exists.
+    pub fn set_snapshot(&self, snapshot: usize, viewer: Viewer) {
+        let id = (self.selected_page)().id();
+        let page = match viewer.pages.get(&id) {
+            Some(page) => page.clone(),
+            None => viewer.get_page(1),
+        };
+        viewer.touch(&page);
+        let part = viewer.get_part(&page, 0);
+        let field = viewer.get_field(&part, 0);
+        // Pinned page follows the snapshot too, if it still exists.
+        let pinned = (self.pinned_page)().and_then(|p| viewer.pages.get(&p.id()).cloned());
+
+        let mut state = self.clone();
+        *state.selected_page.write() = page;
+        *state.selected_part.write() = part;
+        *state.selected_field.write() = field;
+        *state.locked_field.write() = None;
+        *state.pinned_field.write() = None;
+        *state.pinned_page.write() = pinned;
+        *state.snapshot.write() = snapshot;
+        *state.search.write() = Search::default();
+        *state.key_search.write() = None;
+        *state.viewer.write() = viewer;
+    }
+
+    /// Location of the current selection.
+    pub fn deep_link(&self) -> DeepLink {
+        DeepLink {
+            db: (self.current_db)(),
+            page: (self.selected_page)().id(),
+            field: (self.locked_field)(),
+        }
+    }
+
+    /// Select the page and lock the field from the link, if the loaded database has them.
+    pub fn restore(&self, link: &DeepLink) {
+        let viewer = self.viewer.read();
+        let page = match link.page {
+            // Journal is a pseudo page with id 0.
+            0 => viewer.journal.clone(),
+            n => viewer.pages.get(&n).cloned(),
+        };
+        let Some(page) = page else {
+            return;
+        };
+        let (np, nf) = link
+            .field
+            .filter(|&(np, nf)| page.parts().get(np).is_some_and(|p| nf < p.fields().len()))
+            .unwrap_or((0, 0));
+        let part = viewer.get_part(&page, np);
+        let field = viewer.get_field(&part, nf);
+        drop(viewer);
+
+        let mut state = self.clone();
+        *state.selected_page.write() = page;
+        *state.selected_part.write() = part;
+        *state.selected_field.write() = field;
+        *state.locked_field.write() = link.field.filter(|&f| f == (np, nf));
+    }
 }
@@ -0,0 +1,124 @@
+//! Standalone SVG picture of a page: its fields laid out as boxes colored by part,
+//! the way the Visual area shows them, to be used outside of the app.
+use crate::state::{HexStyle, Palette};
+use crate::{Field, PageView};
+
+const WIDTH: usize = 1200;
+const MARGIN: usize = 16;
+/// Width of a character of the monospace font at the used font size.
+const CHAR_WIDTH: usize = 7;
+const LINE_HEIGHT: usize = 14;
+const PADDING: usize = 4;
+const BORDER: usize = 4;
+const GAP: usize = 6;
+/// Values are trimmed the same way as in the grid, the text is cut to fit a box.
+const VALUE_LIMIT: usize = 10;
+const TEXT_LIMIT: usize = 32;
+
+/// Fields of the page with their offsets, text and hex values.
+pub fn page_svg(page: &dyn PageView, palette: Palette) -> String {
+    let mut boxes = String::new();
+    let (mut x, mut y) = (MARGIN, MARGIN + 2 * LINE_HEIGHT);
+    let row_height = LINE_HEIGHT + BORDER + 2 * LINE_HEIGHT + 2 * PADDING + GAP;
+    for part in page.parts().iter() {
+        let color = hex_color(palette.color(part.role()));
+        for field in part.fields() {
+            let offset = field.offset.to_string();
+            let text = cut(&field_text(field));
+            let hex = cut(&field.trim_hex(VALUE_LIMIT, &HexStyle::default()));
+            let chars = [&offset, &text, &hex]
+                .iter()
+                .map(|s| s.chars().count())
+                .max()
+                .unwrap_or_default();
+            let width = (chars * CHAR_WIDTH + 2 * PADDING).min(WIDTH - 2 * MARGIN);
+            if x + width > WIDTH - MARGIN && x > MARGIN {
+                x = MARGIN;
+                y += row_height;
+            }
+            let top = y + LINE_HEIGHT;
+            let height = BORDER + 2 * LINE_HEIGHT + 2 * PADDING;
+            boxes.push_str(&format!(
+                concat!(
+                    "<g data-field=\"{kind:?}\"><title>{title}</title>",
+                    "<text x=\"{x}\" y=\"{label_y}\" fill=\"{color}\" font-weight=\"bold\">{offset}</text>",
+                    "<rect x=\"{x}\" y=\"{top}\" width=\"{width}\" height=\"{height}\" fill=\"#e2e8f0\" stroke=\"#94a3b8\"/>",
+                    "<rect x=\"{x}\" y=\"{top}\" width=\"{width}\" height=\"{border}\" fill=\"{color}\"/>",
+                    "<text x=\"{text_x}\" y=\"{text_y}\">{text}</text>",
+                    "<text x=\"{text_x}\" y=\"{hex_y}\">{hex}</text></g>\n"
+                ),
+                kind = field.kind,
+                title = escape(&format!("{}: {}", part.label(), field.desc)),
+                x = x,
+                label_y = y + LINE_HEIGHT - PADDING,
+                color = color,
+                offset = offset,
+                top = top,
+                width = width,
+                height = height,
+                border = BORDER,
+                text_x = x + PADDING,
+                text_y = top + BORDER + PADDING + LINE_HEIGHT - 3,
+                hex_y = top + BORDER + PADDING + 2 * LINE_HEIGHT - 3,
+                text = escape(&text),
+                hex = escape(&hex),
+            ));
+            x += width + GAP;
+        }
+    }
+    let height = y + row_height + MARGIN;
+    format!(
+        concat!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" ",
+            "viewBox=\"0 0 {width} {height}\" font-family=\"monospace\" font-size=\"11\">\n",
+            "<rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n",
+            "<text x=\"{margin}\" y=\"{title_y}\" font-size=\"14\" font-weight=\"bold\">{title}</text>\n",
+            "{boxes}</svg>\n"
+        ),
+        width = WIDTH,
+        height = height,
+        margin = MARGIN,
+        title_y = MARGIN + LINE_HEIGHT,
+        title = escape(&format!("Page {}: {}", page.id(), page.label())),
+        boxes = boxes,
+    )
+}
+
+/// Text of the value as the trimmed field of the grid shows it.
+fn field_text(field: &Field) -> String {
+    let text = field
+        .custom_render()
+        .unwrap_or_else(|| field.trim_str(VALUE_LIMIT));
+    match &field.column {
+        Some(column) => format!("{column}: {text}"),
+        None => text,
+    }
+}
+
+fn cut(text: &str) -> String {
+    match text.char_indices().nth(TEXT_LIMIT) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// Tailwind colors of the palette at the shade of the light theme borders.
+fn hex_color(name: &str) -> &'static str {
+    match name {
+        "green" => "#166534",
+        "orange" => "#9a3412",
+        "blue" => "#1e40af",
+        _ => "#475569",
+    }
+}
+
+/// Control characters aren't allowed in XML even as references, they are dropped.
+fn escape(text: &str) -> String {
+    text.chars()
+        .filter(|c| !c.is_control())
+        .collect::<String>()
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
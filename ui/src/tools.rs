@@ -0,0 +1,275 @@
+//! Calculators for the formulas of the file format: serial types, payload overflow
+//! and varints.
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+use parser::{payload_math, PayloadSplit, RecordCode, TextEncoding, Varint};
+
+use crate::state::{AppState, Search, SearchMode, VarintInput};
+use crate::usage::BTreeFill;
+
+/// Small calculators for the formulas of the file format.
+pub fn ToolsTab() -> Element {
+    rsx! {
+        div {
+            class: "pt-4 max-w-96 text-xs",
+            SerialTypeCalculator { }
+            PayloadCalculator { }
+            BTreeFill { }
+        }
+    }
+}
+
+/// Serial type code to the type and size of the value, and the size of a text
+/// or BLOB to its serial type.
+pub fn SerialTypeCalculator() -> Element {
+    let mut code = use_signal(|| "13".to_string());
+    let mut size = use_signal(|| "5".to_string());
+    let described = match code().trim().parse::<i64>() {
+        Ok(n) => match RecordCode::describe(n) {
+            Some(text) => format!(
+                "{}, takes {} byte(s) in the record body",
+                text,
+                RecordCode::size(n)
+            ),
+            None => "Serial types are never negative".to_string(),
+        },
+        Err(e) => format!("Invalid serial type: {e}"),
+    };
+    let codes = match size().trim().parse::<usize>() {
+        Ok(n) => format!(
+            "Text: N = {n} * 2 + 13 = {}, BLOB: N = {n} * 2 + 12 = {}",
+            RecordCode::text(n),
+            RecordCode::blob(n)
+        ),
+        Err(e) => format!("Invalid size: {e}"),
+    };
+    rsx! {
+        div {
+            class: "font-medium",
+            "Serial types"
+        }
+        div {
+            class: "pt-1",
+            "Record header keeps a serial type per value: small codes stand for fixed-size numbers and constants, larger ones carry the size of a text (odd) or BLOB (even) in them."
+        }
+        div {
+            class: "pt-2 flex items-center space-x-2",
+            input {
+                class: "input input-bordered input-xs w-24",
+                placeholder: "Serial type",
+                value: "{code}",
+                oninput: move |e| code.set(e.value()),
+                onkeydown: move |e| e.stop_propagation(),
+            }
+            div { "{described}" }
+        }
+        div {
+            class: "pt-2 flex items-center space-x-2",
+            input {
+                class: "input input-bordered input-xs w-24",
+                placeholder: "Size in bytes",
+                value: "{size}",
+                oninput: move |e| size.set(e.value()),
+                onkeydown: move |e| e.stop_propagation(),
+            }
+            div { "{codes}" }
+        }
+    }
+}
+
+/// Whether a cell of the payload size spills onto overflow pages with the page
+/// size of the current database.
+pub fn PayloadCalculator() -> Element {
+    let viewer = use_context::<AppState>().viewer;
+    let mut size = use_signal(|| "1000".to_string());
+    let mut table = use_signal(|| true);
+    let (page_size, reserved) = {
+        let header = &viewer.read().db_header;
+        (header.page_size, header.reserved_page_space)
+    };
+    let u = payload_math::usable_size(page_size, reserved);
+    let x = match table() {
+        true => payload_math::table_max_local(u),
+        false => payload_math::index_max_local(u),
+    };
+    let x_formula = match table() {
+        true => "u - 35",
+        false => "((u - 12) * 64 / 255) - 23",
+    };
+    let split = size()
+        .trim()
+        .parse::<u64>()
+        .map(|p| PayloadSplit::new(u, x, p));
+    rsx! {
+        div {
+            class: "pt-6 font-medium",
+            "Payload overflow"
+        }
+        div {
+            class: "pt-1",
+            "Page size {page_size} with {reserved} reserved byte(s) leaves u = {u} usable bytes. A cell keeps up to x bytes of payload on the b-tree page, larger payloads keep at least m bytes and spill the rest onto overflow pages of u - 4 content bytes each."
+        }
+        div {
+            class: "pt-2 flex items-center space-x-2",
+            input {
+                class: "input input-bordered input-xs w-24",
+                placeholder: "Payload size",
+                value: "{size}",
+                oninput: move |e| size.set(e.value()),
+                onkeydown: move |e| e.stop_propagation(),
+            }
+            for (is_table, name) in [(true, "Table leaf"), (false, "Index")] {
+                button {
+                    class: "btn btn-xs",
+                    class: if table() == is_table {"btn-active"},
+                    onclick: move |_| table.set(is_table),
+                    "{name}"
+                }
+            }
+        }
+        match split {
+            Err(e) => rsx! {
+                div {
+                    class: "pt-2 text-orange-700",
+                    "Invalid payload size: {e}"
+                }
+            },
+            Ok(split) => rsx! {
+                table {
+                    class: "table table-xs mt-2",
+                    tbody {
+                        tr { td { "x = {x_formula}" } td { "{split.max_local}" } }
+                        tr { td { "m = ((u - 12) * 32 / 255) - 23" } td { "{split.min_local}" } }
+                        if let Some(k) = split.k {
+                            tr { td { "k = m + ((p - m) % (u - 4))" } td { "{k}" } }
+                        }
+                        tr { td { "On the b-tree page" } td { "{split.local} byte(s)" } }
+                        tr { td { "On overflow pages" } td { "{split.overflow} byte(s)" } }
+                        tr { td { "Overflow pages" } td { "{split.overflow_pages}" } }
+                    }
+                }
+            },
+        }
+    }
+}
+
+/// Encode an integer into varint bytes or decode them back, starting from the
+/// value of the selected field.
+#[component]
+pub fn VarintPlayground(value: i64) -> Element {
+    // Edits are kept while the same field stays selected.
+    let mut input = use_signal(|| None::<(i64, VarintInput)>);
+    let current = match input() {
+        Some((origin, edited)) if origin == value => edited,
+        _ => VarintInput::Value(value.to_string()),
+    };
+    let (value_text, bytes_text, result) = match current {
+        VarintInput::Value(text) => {
+            let result = text.trim().parse::<i64>().map(Varint::encode);
+            let bytes = match &result {
+                Ok(varint) => hex_bytes(&varint.bytes),
+                Err(_) => String::new(),
+            };
+            (text, bytes, result.map_err(|e| e.to_string()))
+        }
+        VarintInput::Bytes(text) => {
+            let result = Search::parse(&text, SearchMode::Hex, TextEncoding::UTF8)
+                .map_err(|e| e.to_string())
+                .and_then(|bytes| match bytes.is_empty() {
+                    true => Err("No bytes to decode".to_string()),
+                    false => Ok(Varint::new(&bytes)),
+                });
+            let value = match &result {
+                Ok(varint) => varint.value.to_string(),
+                Err(_) => String::new(),
+            };
+            (value, text, result)
+        }
+    };
+    let incomplete = result
+        .as_ref()
+        .is_ok_and(|v| v.bytes.len() < 9 && v.bytes.last().is_some_and(|b| b & 0x80 != 0));
+    // Every byte in binary, the continuation bit apart from the value bits.
+    let result = result.map(|varint| {
+        varint
+            .bytes
+            .iter()
+            .enumerate()
+            .map(|(n, byte)| match n {
+                8 => (
+                    format!("{:08b}", byte),
+                    "All 8 bits of the ninth byte are the value",
+                ),
+                _ => (
+                    format!("{}|{:07b}", byte >> 7, byte & 0x7f),
+                    "Continuation bit | 7 bits of the value",
+                ),
+            })
+            .collect::<Vec<_>>()
+    });
+    rsx! {
+        div {
+            class: "pt-6 text-xs",
+            div {
+                class: "font-medium",
+                "Varint playground"
+            }
+            div {
+                class: "pt-1",
+                "Lower 7 bits of each byte hold the value, the high bit tells whether another byte follows. The ninth byte, if there is one, holds all 8 bits, so negative values always take 9 bytes."
+            }
+            div {
+                class: "flex pt-2 space-x-2",
+                input {
+                    class: "input input-bordered input-xs w-48",
+                    placeholder: "Integer",
+                    value: "{value_text}",
+                    oninput: move |e| input.set(Some((value, VarintInput::Value(e.value())))),
+                    onkeydown: move |e| e.stop_propagation(),
+                }
+                input {
+                    class: "input input-bordered input-xs w-64",
+                    placeholder: "Hex bytes",
+                    value: "{bytes_text}",
+                    oninput: move |e| input.set(Some((value, VarintInput::Bytes(e.value())))),
+                    onkeydown: move |e| e.stop_propagation(),
+                }
+            }
+            match result {
+                Err(e) => rsx! {
+                    div {
+                        class: "pt-2 text-orange-700",
+                        "{e}"
+                    }
+                },
+                Ok(bits) => rsx! {
+                    div {
+                        class: "flex flex-wrap pt-2 gap-2 font-mono",
+                        for (bits, title) in bits {
+                            div {
+                                title: title,
+                                "{bits}"
+                            }
+                        }
+                    }
+                    if incomplete {
+                        div {
+                            class: "pt-2 text-orange-700",
+                            "Incomplete: the last byte has the continuation bit set"
+                        }
+                    }
+                },
+            }
+        }
+    }
+}
+
+/// Bytes as space separated hex pairs.
+fn hex_bytes(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
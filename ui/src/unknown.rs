@@ -0,0 +1,42 @@
+//! Unrecognized structure UI representation and description
+use std::rc::Rc;
+
+use crate::{Field, FieldKind, Part, Role, Style, Value};
+
+/// Raw bytes of a structure, which the parser can't interpret.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownStructurePart {
+    fields: Vec<Rc<Field>>,
+}
+
+impl UnknownStructurePart {
+    pub fn new(bytes: &[u8], offset: usize, reason: &'static str) -> Self {
+        let fields = vec![Rc::new(Field::new(
+            FieldKind::Unknown,
+            reason,
+            offset,
+            bytes.len(),
+            Value::Unallocated(bytes.into()),
+            Style::Plain,
+        ))];
+        Self { fields }
+    }
+}
+
+impl Part for UnknownStructurePart {
+    fn label(&self) -> String {
+        "Unrecognized structure".to_string()
+    }
+
+    fn desc(&self) -> &'static str {
+        "This part of the file is in use, but its format isn't interpreted here: it's either not implemented yet or comes from a format extension. The raw bytes are shown as is, the rest of the file is unaffected."
+    }
+
+    fn role(&self) -> Role {
+        Role::Unused
+    }
+
+    fn fields(&self) -> &[Rc<Field>] {
+        self.fields.as_slice()
+    }
+}
@@ -0,0 +1,41 @@
+//! Unreferenced page UI representation and description
+use std::rc::Rc;
+
+use crate::{Field, FieldKind, Part, Role, Style, Value};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnreferencedPart {
+    fields: Vec<Rc<Field>>,
+}
+
+impl UnreferencedPart {
+    pub fn new(bytes: &[u8]) -> Self {
+        let fields = vec![Rc::new(Field::new(
+            FieldKind::Unreferenced,
+            "Raw content of the page. It doesn't look like a b-tree page and nothing points to it, so there is no way to tell which structure it used to belong to.",
+            0,
+            bytes.len(),
+            Value::Unallocated(bytes.into()),
+            Style::Plain,
+        ))];
+        Self { fields }
+    }
+}
+
+impl Part for UnreferencedPart {
+    fn label(&self) -> String {
+        "Unreferenced content".to_string()
+    }
+
+    fn desc(&self) -> &'static str {
+        "Leftovers of a previous use of the page: an overflow page of a deleted row, a page of a dropped table, or content left behind by an interrupted write."
+    }
+
+    fn role(&self) -> Role {
+        Role::Unused
+    }
+
+    fn fields(&self) -> &[Rc<Field>] {
+        self.fields.as_slice()
+    }
+}
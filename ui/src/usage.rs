@@ -0,0 +1,151 @@
+//! How the file and the pages are used: bytes of every b-tree, their average fill
+//! and the breakdown of a single page.
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+
+use crate::state::AppState;
+use crate::Role;
+
+/// Pages and bytes of every b-tree and the rest of the file.
+pub fn StorageTab() -> Element {
+    let viewer = use_context::<AppState>().viewer;
+    let report = viewer.read().storage_report();
+    let file_size = viewer.read().size();
+    rsx! {
+        div {
+            class: "pt-4 max-w-96 text-xs",
+            div {
+                class: "font-medium",
+                "Storage report, {file_size} bytes"
+            }
+            table {
+                class: "table table-xs mt-1",
+                thead {
+                    tr {
+                        th { "Name" }
+                        th { "Pages" }
+                        th { "Overflow" }
+                        th { "Bytes" }
+                        th { "File" }
+                    }
+                }
+                tbody {
+                    for row in report {
+                        tr {
+                            td {
+                                class: "truncate",
+                                title: "{row.kind}",
+                                "{row.name}"
+                            }
+                            td { "{row.pages}" }
+                            td { "{row.overflow_pages}" }
+                            td { "{row.bytes}" }
+                            td { {format!("{:.1}%", row.share * 100.0)} }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Average share of the usable page space in use for every b-tree.
+pub fn BTreeFill() -> Element {
+    let viewer = use_context::<AppState>().viewer;
+    let fill = viewer.read().btree_fill();
+    rsx! {
+        div {
+            class: "pt-6 font-medium",
+            "B-tree fill"
+        }
+        table {
+            class: "table table-xs mt-1",
+            thead {
+                tr {
+                    th { "B-tree" }
+                    th { "Pages" }
+                    th { "Average fill" }
+                }
+            }
+            tbody {
+                for (name, pages, average) in fill {
+                    tr {
+                        td { class: "truncate", "{name}" }
+                        td { "{pages}" }
+                        td { {format!("{:.1}%", average * 100.0)} }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Stacked bar of how the bytes of a b-tree page are spent.
+#[component]
+pub fn PageUsage(page_num: usize) -> Element {
+    let viewer = use_context::<AppState>().viewer;
+    let palette = use_context::<AppState>().palette;
+    let stats = viewer.read().page_stats(page_num)?;
+    let total = stats.used() + stats.free() + stats.reserved;
+    let segments = [
+        (
+            "Database header",
+            stats.db_header,
+            format!("bg-{}-800", palette().color(Role::Header)),
+        ),
+        (
+            "Page header",
+            stats.page_header,
+            format!("bg-{}-600", palette().color(Role::Header)),
+        ),
+        (
+            "Cell pointers",
+            stats.cell_pointers,
+            format!("bg-{}-600", palette().color(Role::Pointers)),
+        ),
+        (
+            "Cells",
+            stats.cells,
+            format!("bg-{}-700", palette().color(Role::Content)),
+        ),
+        ("Unallocated", stats.unallocated, "bg-slate-300".to_string()),
+        ("Freeblocks", stats.freeblocks, "bg-slate-500".to_string()),
+        ("Fragmented", stats.fragmented, "bg-slate-700".to_string()),
+        ("Reserved", stats.reserved, "bg-slate-900".to_string()),
+    ]
+    .into_iter()
+    .filter(|(_, size, _)| *size > 0)
+    .map(|(label, size, class)| {
+        let width = format!("{:.2}%", size as f64 * 100.0 / total.max(1) as f64);
+        (label, size, class, width)
+    })
+    .collect::<Vec<_>>();
+    let fill = format!("{:.1}%", stats.fill() * 100.0);
+    rsx! {
+        div {
+            class: "pt-2 text-xs",
+            div {
+                class: "flex w-full h-3",
+                for (label, size, class, width) in segments.clone() {
+                    div {
+                        class: "h-full {class}",
+                        style: "width: {width}",
+                        title: "{label}: {size} byte(s)",
+                    }
+                }
+            }
+            div {
+                class: "flex flex-wrap pt-1 space-x-3 tracking-tighter",
+                div { class: "font-medium", "{fill} in use" }
+                for (label, size, class, _) in segments {
+                    div {
+                        class: "flex items-center space-x-1",
+                        div { class: "w-2 h-2 {class}" }
+                        div { "{label} {size}" }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,150 @@
+//! Walkthrough of the VACUUM example, which compares its snapshots before and after VACUUM.
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+use dioxus_logger::tracing::error;
+
+use crate::included_db::VACUUM_DB;
+use crate::index::{
+    compare_snapshot_name, jump_to_field, load_compare, load_snapshot, update_selected_page,
+};
+use crate::state::AppState;
+use crate::viewer::Viewer;
+
+/// Walk through the VACUUM example: the database after VACUUM compared with the
+/// fragmented one, what happened to the freelist pages and which pages were rewritten.
+pub fn VacuumWalkthrough() -> Element {
+    let app_state = use_context::<AppState>();
+    let current_db = app_state.current_db;
+    let viewer = app_state.viewer;
+    let compare = app_state.compare;
+    let snapshot = app_state.snapshot;
+    let mut loading = app_state.loading;
+    if current_db() != VACUUM_DB {
+        return rsx! {};
+    }
+    let snapshots = Viewer::included_snapshots(VACUUM_DB);
+    let last = snapshots.len() - 1;
+    let before = compare_snapshot_name(snapshots[0].0);
+    let compared = compare
+        .read()
+        .as_ref()
+        .filter(|c| snapshot() == last && c.name == before)
+        .map(|c| (c.vacuumed(&viewer.read()), c.base.db_header.clone()));
+    let open_page = move |page_num: usize| {
+        let page = viewer.read().get_page(page_num as u32);
+        update_selected_page(page);
+    };
+    let start = move |_| {
+        let app_state = app_state.clone();
+        *loading.write() = true;
+        spawn(async move {
+            let loaded = match load_snapshot(app_state.clone(), last).await {
+                Ok(()) => load_compare(app_state, "snapshot:0".to_string()).await,
+                err => err,
+            };
+            if let Err(err) = loaded {
+                error!("Failed to compare the snapshots: {}", err);
+            }
+            *loading.write() = false;
+        });
+    };
+    let header = viewer.read().db_header.clone();
+    rsx! {
+        div {
+            role: "alert",
+            class: "alert rounded-none text-xs items-start",
+            div {
+                div {
+                    class: "font-bold pb-1",
+                    "Before and after VACUUM"
+                }
+                match compared {
+                    None => rsx! {
+                        div {
+                            class: "flex items-center",
+                            "VACUUM copies the database into a new file without the free space and writes it back."
+                            button {
+                                class: "btn btn-xs btn-secondary ml-2",
+                                disabled: loading(),
+                                onclick: start,
+                                "Compare the vacuumed database with the fragmented one"
+                            }
+                        }
+                    },
+                    Some((vacuumed, base_header)) => rsx! {
+                        div {
+                            class: "flex flex-wrap items-center gap-1",
+                            "1. The file shrinks:"
+                            button {
+                                class: "btn btn-xs btn-ghost",
+                                title: "Open the database size field of the header",
+                                onclick: move |_| jump_to_field(1, 28),
+                                "{base_header.db_size} → {header.db_size} pages"
+                            }
+                            "with"
+                            button {
+                                class: "btn btn-xs btn-ghost",
+                                title: "Open the freelist pages field of the header",
+                                onclick: move |_| jump_to_field(1, 36),
+                                "{base_header.freelist_total} → {header.freelist_total} freelist pages"
+                            }
+                        }
+                        div {
+                            class: "flex flex-wrap items-center gap-1",
+                            "2. Freelist pages are gone, reused or cut off with the end of the file:"
+                            for page_num in vacuumed.reclaimed {
+                                if page_num <= header.db_size as usize {
+                                    button {
+                                        class: "btn btn-xs btn-ghost",
+                                        title: "Reused, open the page",
+                                        onclick: move |_| open_page(page_num),
+                                        "{page_num}"
+                                    }
+                                } else {
+                                    span {
+                                        class: "px-1 line-through opacity-60",
+                                        title: "Cut off, the file ends before the page",
+                                        "{page_num}"
+                                    }
+                                }
+                            }
+                        }
+                        div {
+                            class: "flex flex-wrap items-center gap-1",
+                            "3. B-trees are copied one after another from page 2:"
+                            for (name, from, to) in vacuumed.moved {
+                                button {
+                                    class: "btn btn-xs btn-ghost",
+                                    title: "Open the new root page",
+                                    onclick: move |_| open_page(to),
+                                    "{name}: {from} → {to}"
+                                }
+                            }
+                        }
+                        div {
+                            class: "flex flex-wrap items-center gap-1",
+                            "4. Pages rewritten in place or left behind, the grid marks the changes:"
+                            for page_num in vacuumed.rewritten {
+                                if page_num <= header.db_size as usize {
+                                    button {
+                                        class: "btn btn-xs btn-ghost",
+                                        title: "Rewritten, open the page",
+                                        onclick: move |_| open_page(page_num),
+                                        "{page_num}"
+                                    }
+                                } else {
+                                    span {
+                                        class: "px-1 line-through opacity-60",
+                                        title: "Copied to another page, the file ends before this one",
+                                        "{page_num}"
+                                    }
+                                }
+                            }
+                        }
+                    },
+                }
+            }
+        }
+    }
+}
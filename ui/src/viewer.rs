@@ -1,69 +1,1123 @@
 //! Database UI Viewer.
 
-use std::collections::BTreeMap;
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
 use std::rc::Rc;
 
 use parser::*;
 
-use crate::included_db::INCLUDED_DB;
-use crate::{BTreeNodeView, BTreeView, Field, PageElementBuilder, PageLayout, PageView, Part};
+use crate::included_db::{DBSource, INCLUDED_DB, INCLUDED_JOURNAL};
+use crate::remote::PageSource;
+use crate::{
+    BTreeNodeView, BTreeView, Field, FieldKind, FreelistTrunkView, FreelistView, PageElement,
+    PageElementBuilder, PageLayout, PageSummary, PageView, Part, Value,
+};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Viewer {
-    pub included_db: BTreeMap<&'static str, (&'static [u8], &'static [&'static str])>,
-    pub pages: Vec<Rc<dyn PageView>>,
+    /// Hash of the database content, identifies Viewer in the cache.
+    pub hash: u64,
+    #[allow(clippy::type_complexity)]
+    pub included_db:
+        BTreeMap<&'static str, (DBSource, &'static str, &'static [(&'static str, DBSource)])>,
+    /// Raw database content.
+    pub bytes: &'static [u8],
+    pub db_header: Rc<DBHeader>,
+    pub pages: BTreeMap<usize, Rc<dyn PageView>>,
     pub btrees: Vec<BTreeView>,
+    /// Freelist trunk pages with their leaves, the pages themselves are in `pages`.
+    pub freelist: FreelistView,
+    /// Summaries of the pages for the page list, made once all of them are loaded.
+    pub summaries: BTreeMap<usize, PageSummary>,
+    /// Rollback journal, shown as a separate pseudo page.
+    pub journal: Option<Rc<dyn PageView>>,
+    pub journal_bytes: Option<&'static [u8]>,
+    /// Super-journal the rollback journal points to, if it's a multi-database transaction.
+    pub super_journal: Option<SuperJournal>,
+    /// Original content of the pages kept in the rollback journal, by page number.
+    pub journal_pages: BTreeMap<usize, Rc<dyn PageView>>,
+    /// Page numbers and page offsets of the fields, which point to the page, by its number.
+    pub pointers: BTreeMap<usize, Vec<(usize, usize)>>,
+    /// Database header fields, which disagree with the content of the file.
+    pub diagnostics: HashMap<FieldKind, Vec<String>>,
+    /// Shadow tables of the virtual tables by their names.
+    pub shadows: HashMap<String, ShadowTable>,
+    /// Remote database, which pages are fetched as they're opened, its bytes are empty.
+    pub remote: Option<PageSource>,
+    /// Why the file is taken for an encrypted database, its pages are shown as raw bytes.
+    pub encryption: Option<Encryption>,
+    /// SQLCipher settings the database was decrypted with, its reserved space keeps
+    /// the IV and the HMAC of every page.
+    pub cipher: Option<SqlCipherSettings>,
+    /// Pages shown last, the most recent first, see `Viewer::touch`. Shared by the
+    /// clones, so the cached Viewer keeps the same pages built.
+    shown: Rc<RefCell<VecDeque<Rc<dyn PageView>>>>,
 }
 
 pub type Result<T, E = StdError> = std::result::Result<T, E>;
 
-impl Viewer {
-    pub fn new_from_included(name: &str) -> Result<Self, StdError> {
-        let included_db: BTreeMap<&'static str, (&'static [u8], &'static [&'static str])> =
-            BTreeMap::from_iter(INCLUDED_DB.iter().copied());
-        let (bytes, _) = included_db.get(name).ok_or("This db is not included.")?;
+/// Space taken by a b-tree or by other pages of the file, see `Viewer::storage_report`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StorageRow {
+    pub name: String,
+    pub kind: String,
+    /// B-tree pages, or all pages for the rows other than b-trees.
+    pub pages: usize,
+    pub overflow_pages: usize,
+    pub bytes: usize,
+    /// Share of the file, from 0 to 1.
+    pub share: f64,
+}
+
+/// Overflow pages of a b-tree cell in the order they're linked, see `Viewer::overflow_chain`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OverflowChain {
+    pub owner: OverflowOwner,
+    pub pages: Vec<usize>,
+    /// Next page of the last one: 0 ends the chain, anything else is where it breaks,
+    /// either an unreadable page or one already in the chain.
+    pub end: usize,
+}
+
+/// Pages, which keep their built parts, so switching back to one of them is instant.
+/// Parts of the pages shown before are dropped and built again if they're shown.
+const SHOWN_PAGES: usize = 32;
+
+thread_local! {
+    /// Fully loaded Viewers, so switching between databases doesn't parse them again.
+    static CACHE: RefCell<HashMap<u64, Viewer>> = RefCell::new(HashMap::new());
+}
+
+/// Builds Viewer step by step: first everything necessary to render the header
+/// and the first page, then one b-tree per step.
+#[derive(Debug)]
+pub struct ViewerLoader {
+    reader: Reader,
+    schema: VecDeque<TableLeafCell>,
+    reconciled: bool,
+    /// Root pages of the schema entries, which b-trees failed to parse, with the error.
+    corrupt: BTreeMap<usize, Error>,
+}
+
+impl ViewerLoader {
+    /// Parse freelist trunks and schema b-tree, the rest is loaded by `load_next`.
+    pub fn start(bytes: &'static [u8]) -> Result<(Viewer, Self), StdError> {
         let reader = Reader::new(bytes)?;
-        let size = reader.db_header.page_size as usize;
-        let mut pages_map: BTreeMap<usize, Rc<dyn PageView>> = BTreeMap::new();
+        let mut viewer =
+            Viewer::empty(Viewer::content_hash(bytes), bytes, reader.db_header.clone());
 
-        // Check if there are freelist pages.
+        // Trunk pages of the freelist, its leaves are loaded once b-trees are.
         let freelist_page = reader.db_header.first_free_page_num as usize;
-        if freelist_page != 0 {
-            if let Ok(page) = reader.get_trunk_freelist_page(freelist_page) {
-                Self::load_freelist_pages(page, freelist_page, &mut pages_map, &reader)?;
+        viewer.freelist = Viewer::load_freelist_trunks(freelist_page, &mut viewer.pages, &reader);
+
+        // Lock-byte page is never a part of b-trees or freelist.
+        if let Some(page_num) = reader.lock_byte_page() {
+            let page_element = PageLayout::LockByte(reader.get_lock_byte_page(page_num)?);
+            let size = reader.db_header.page_size as usize;
+            viewer.pages.insert(
+                page_num,
+                Rc::new(PageElementBuilder::new(page_element, size, page_num).build()),
+            );
+        }
+
+        // Pointer maps are laid out by the page numbers, nothing points at them.
+        for page_num in reader.ptrmap_pages() {
+            let size = reader.db_header.page_size as usize;
+            let page_element = match reader.get_ptrmap_page(page_num) {
+                Ok(page) => PageLayout::Ptrmap(page),
+                Err(e) => PageLayout::Corrupt(reader.get_partial_page(page_num), e),
             };
+            viewer.pages.insert(
+                page_num,
+                Rc::new(PageElementBuilder::new(page_element, size, page_num).build()),
+            );
+        }
+
+        let schema: Vec<TableLeafCell> = reader.get_schema_cells();
+        let entries = schema
+            .iter()
+            .filter_map(|cell| Some(SchemaEntry::new(&cell.payload().ok()?.values)))
+            .collect::<Vec<_>>();
+        viewer.shadows = shadow_tables(&entries).into_iter().collect();
+
+        let mut corrupt = BTreeMap::new();
+        match reader.get_schema_btree() {
+            Ok(schema_tree) => viewer.load_btree(schema_tree, &reader),
+            Err(e) => {
+                corrupt.insert(1, e);
+            }
+        }
+
+        Ok((
+            viewer,
+            Self {
+                reader,
+                schema: schema.into(),
+                reconciled: false,
+                corrupt,
+            },
+        ))
+    }
+
+    /// Load next b-tree into the Viewer, when all of them are loaded pick up
+    /// unreferenced pages. False if there is nothing left to load.
+    pub fn load_next(&mut self, viewer: &mut Viewer) -> Result<bool, StdError> {
+        match self.schema.pop_front() {
+            None if self.reconciled => Ok(false),
+            None => {
+                Viewer::load_freelist_leaves(
+                    &viewer.freelist.leaves(),
+                    &mut viewer.pages,
+                    &self.reader,
+                );
+                self.load_unreferenced(viewer);
+                viewer.index_pointers();
+                viewer.validate_header();
+                viewer.summarize_pages();
+                self.reconciled = true;
+                Ok(true)
+            }
+            Some(cell) => {
+                match BTree::new(&cell, &self.reader) {
+                    Ok(tree) => viewer.load_btree(tree, &self.reader),
+                    // Keep the rest of the file explorable, the root page is shown as is.
+                    Err(e) => {
+                        let values = cell.payload().map(|p| p.values.as_slice());
+                        if let Some(root) = BTree::root_page(values.unwrap_or_default()) {
+                            self.corrupt.insert(root, e);
+                        }
+                    }
+                }
+                Ok(true)
+            }
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.schema.is_empty() && self.reconciled
+    }
+
+    /// Every page is expected to be a part of some b-tree or the freelist,
+    /// try to parse the ones, which are not reachable from them.
+    fn load_unreferenced(&self, viewer: &mut Viewer) {
+        let size = self.reader.db_header.page_size as usize;
+        for page_num in 1..=self.reader.pages_total() {
+            if viewer.pages.contains_key(&page_num) {
+                continue;
+            }
+            if let Some(error) = self.corrupt.get(&page_num) {
+                let bytes = self.reader.get_partial_page(page_num);
+                let page_element = PageLayout::Corrupt(bytes, error.clone());
+                viewer.pages.insert(
+                    page_num,
+                    Rc::new(PageElementBuilder::new(page_element, size, page_num).build()),
+                );
+                continue;
+            }
+            let page_element = match self.reader.get_btree_page(page_num) {
+                Ok(page) => PageLayout::Btree(page),
+                Err(_) => match self.reader.get_raw_page(page_num) {
+                    Ok(bytes) => PageLayout::Unreferenced(bytes),
+                    // Truncated file, the page isn't complete.
+                    Err(e) => {
+                        let bytes = self.reader.get_partial_page(page_num);
+                        let page_element = PageLayout::Corrupt(bytes, e);
+                        viewer.pages.insert(
+                            page_num,
+                            Rc::new(PageElementBuilder::new(page_element, size, page_num).build()),
+                        );
+                        continue;
+                    }
+                },
+            };
+            viewer.pages.insert(
+                page_num,
+                Rc::new(
+                    PageElementBuilder::new(page_element, size, page_num)
+                        .unreferenced()
+                        .build(),
+                ),
+            );
+        }
+    }
+}
+
+impl Viewer {
+    /// Viewer without any pages loaded yet.
+    fn empty(hash: u64, bytes: &'static [u8], db_header: Rc<DBHeader>) -> Self {
+        Self {
+            hash,
+            included_db: BTreeMap::from_iter(INCLUDED_DB.iter().copied()),
+            bytes,
+            db_header,
+            pages: BTreeMap::new(),
+            btrees: vec![],
+            freelist: FreelistView::default(),
+            summaries: BTreeMap::new(),
+            journal: None,
+            journal_bytes: None,
+            super_journal: None,
+            journal_pages: BTreeMap::new(),
+            pointers: BTreeMap::new(),
+            diagnostics: HashMap::new(),
+            shadows: HashMap::new(),
+            remote: None,
+            encryption: None,
+            cipher: None,
+            shown: Rc::new(RefCell::new(VecDeque::new())),
+        }
+    }
+
+    /// Viewer of the encrypted database, which pages are only laid out, there's
+    /// nothing to parse without the key. None if the file isn't encrypted.
+    pub fn encrypted(bytes: &'static [u8]) -> Option<Self> {
+        let encryption = Encryption::detect(&bytes)?;
+        let db_header = Rc::new(encryption.placeholder_header());
+        let mut viewer = Self::empty(Self::content_hash(bytes), bytes, db_header);
+        for (n, page) in bytes.chunks(encryption.page_size).enumerate() {
+            let page_num = n + 1;
+            let page_element = PageLayout::Encrypted(page.to_vec());
+            viewer.pages.insert(
+                page_num,
+                Rc::new(
+                    PageElementBuilder::new(page_element, encryption.page_size, page_num).build(),
+                ),
+            );
+        }
+        viewer.encryption = Some(encryption);
+        Some(viewer)
+    }
+
+    /// Viewer of the remote database with only its first page fetched. B-trees
+    /// aren't traversed, each page is loaded on its own once it's fetched.
+    pub fn from_remote(source: PageSource) -> Result<Self, StdError> {
+        let reader = Reader::with_storage(source.clone())?;
+        let hash = Self::content_hash(source.url.as_bytes());
+        let mut viewer = Self::empty(hash, &[], reader.db_header.clone());
+        viewer.remote = Some(source);
+        viewer.load_remote_page(1);
+        Ok(viewer)
+    }
+
+    /// Page of the remote database, which isn't fetched yet.
+    pub fn is_pending(&self, page_num: usize) -> bool {
+        self.remote.as_ref().is_some_and(|source| {
+            (1..=source.pages_total()).contains(&page_num) && source.page(page_num).is_none()
+        })
+    }
+
+    /// Build the fetched page of the remote database. Without the b-trees, which
+    /// refer to it, the page is recognized only by its number or as a b-tree page.
+    pub fn load_remote_page(&mut self, page_num: usize) -> Rc<dyn PageView> {
+        let size = self.db_header.page_size as usize;
+        let page_element = match self.reader() {
+            Ok(reader) if reader.lock_byte_page() == Some(page_num) => {
+                match reader.get_lock_byte_page(page_num) {
+                    Ok(page) => PageLayout::LockByte(page),
+                    Err(e) => PageLayout::Corrupt(reader.get_partial_page(page_num), e),
+                }
+            }
+            Ok(reader) if reader.ptrmap_pages().contains(&page_num) => {
+                match reader.get_ptrmap_page(page_num) {
+                    Ok(page) => PageLayout::Ptrmap(page),
+                    Err(e) => PageLayout::Corrupt(reader.get_partial_page(page_num), e),
+                }
+            }
+            Ok(reader) => match reader.get_btree_page(page_num) {
+                Ok(page) => PageLayout::Btree(page),
+                Err(_) => match reader.get_raw_page(page_num) {
+                    Ok(bytes) => PageLayout::Unknown(
+                        bytes,
+                        "Page of a remote database, which is explored page by page: only b-tree pages are recognized without reading the b-trees and the freelist, which refer to the page.",
+                    ),
+                    Err(e) => PageLayout::Corrupt(reader.get_partial_page(page_num), e),
+                },
+            },
+            Err(e) => PageLayout::Corrupt(vec![], e),
+        };
+        let page: Rc<dyn PageView> =
+            Rc::new(PageElementBuilder::new(page_element, size, page_num).build());
+        self.pages.insert(page_num, page.clone());
+        page
+    }
+
+    /// Size of the database file in bytes.
+    pub fn size(&self) -> usize {
+        match &self.remote {
+            Some(source) => source.size(),
+            None => self.bytes.len(),
+        }
+    }
+
+    /// Reader of the database, the remote one reads only the fetched pages.
+    fn reader(&self) -> Result<Reader, Error> {
+        match &self.remote {
+            Some(source) => Reader::with_storage(source.clone()),
+            None => Reader::new(self.bytes),
+        }
+    }
+
+    /// Create Viewer for one of the included databases, fetching its bytes if needed.
+    pub async fn new_from_included(name: &str) -> Result<Self, StdError> {
+        let bytes = Self::included_bytes(name).await?;
+        Self::new_from_bytes(bytes)
+    }
+
+    /// Create Viewer for an included database, which is compiled into the binary.
+    pub fn new_from_inline(name: &str) -> Result<Self, StdError> {
+        match Self::included_source(name)? {
+            DBSource::Inline(bytes) => Self::new_from_bytes(bytes),
+            DBSource::Asset(_) => Err("This db is not inlined, it must be fetched.".into()),
+        }
+    }
+
+    /// Get bytes of an included database, fetching them if needed.
+    pub async fn included_bytes(name: &str) -> Result<&'static [u8], StdError> {
+        Self::included_source(name)?.load().await
+    }
+
+    /// Get bytes of the rollback journal shipped with an included database, if any.
+    pub async fn included_journal_bytes(name: &str) -> Result<Option<&'static [u8]>, StdError> {
+        match INCLUDED_JOURNAL.iter().find(|(n, _)| *n == name) {
+            Some((_, source)) => Ok(Some(source.load().await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Ordered snapshots of an included database, empty if there is just one state.
+    pub fn included_snapshots(name: &str) -> &'static [(&'static str, DBSource)] {
+        INCLUDED_DB
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map_or(&[], |(_, (_, _, snapshots))| *snapshots)
+    }
+
+    fn included_source(name: &str) -> Result<DBSource, StdError> {
+        INCLUDED_DB
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, (source, _, _))| *source)
+            .ok_or_else(|| "This db is not included.".into())
+    }
+
+    pub fn new_from_bytes(bytes: &'static [u8]) -> Result<Self, StdError> {
+        if let Some(viewer) = Self::from_cache(bytes) {
+            return Ok(viewer);
+        }
+        if let Some(viewer) = Self::encrypted(bytes) {
+            return Ok(viewer);
+        }
+        let (mut viewer, mut loader) = ViewerLoader::start(bytes)?;
+        while loader.load_next(&mut viewer)? {}
+        viewer.store_in_cache();
+        Ok(viewer)
+    }
+
+    pub fn content_hash(bytes: &[u8]) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        bytes.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Get already loaded Viewer for the same database content.
+    pub fn from_cache(bytes: &[u8]) -> Option<Self> {
+        let hash = Self::content_hash(bytes);
+        CACHE.with(|c| c.borrow().get(&hash).cloned())
+    }
+
+    /// Remember the page as shown, parts of the page shown the longest ago are dropped.
+    pub fn touch(&self, page: &Rc<dyn PageView>) {
+        let mut shown = self.shown.borrow_mut();
+        shown.retain(|p| !Rc::ptr_eq(p, page));
+        shown.push_front(page.clone());
+        while shown.len() > SHOWN_PAGES {
+            if let Some(page) = shown.pop_back() {
+                page.release_parts();
+            }
+        }
+    }
+
+    /// Remember fully loaded Viewer.
+    pub fn store_in_cache(&self) {
+        CACHE.with(|c| c.borrow_mut().insert(self.hash, self.clone()));
+    }
+
+    /// Whole parsed representation of the database as pretty printed JSON.
+    pub fn to_json(&self) -> Result<String, StdError> {
+        let reader = self.reader()?;
+        Ok(serde_json::to_string_pretty(&Dump::new(&reader)?)?)
+    }
+
+    /// Parse rollback journal, which belongs to this database.
+    pub fn attach_journal(&mut self, bytes: &'static [u8]) -> Result<(), StdError> {
+        let journal = Journal::try_from(bytes)?;
+        let size = self.db_header.page_size as usize;
+        for record in journal.records() {
+            let page_num = record.page_num as usize;
+            // Only b-tree pages are shown side by side with the database ones.
+            let from_buf = (self.db_header.clone(), page_num, record.data.as_slice());
+            if let Ok(page) = Page::try_from(from_buf) {
+                let page_element = PageLayout::Btree(page);
+                self.journal_pages.insert(
+                    page_num,
+                    Rc::new(PageElementBuilder::new(page_element, size, page_num).build()),
+                );
+            }
+        }
+        let page_element = PageLayout::Journal(journal);
+        self.journal = Some(Rc::new(
+            PageElementBuilder::new(page_element, bytes.len(), 0).build(),
+        ));
+        self.journal_bytes = Some(bytes);
+        Ok(())
+    }
+
+    /// Parse the super-journal, which the rollback journal points to.
+    pub fn attach_super_journal(&mut self, bytes: &[u8]) -> Result<(), StdError> {
+        self.super_journal = Some(SuperJournal::try_from(bytes)?);
+        Ok(())
+    }
+
+    /// Raw bytes of the page, field offsets are relative to them.
+    pub fn page_bytes(&self, page: &dyn PageView) -> &'static [u8] {
+        match page.id() {
+            // Journal pseudo page covers the whole journal file.
+            0 => self.journal_bytes.unwrap_or_default(),
+            id => match &self.remote {
+                Some(source) => source.page(id).unwrap_or_default(),
+                None => page.slice(self.bytes),
+            },
+        }
+    }
+
+    /// Raw bytes of the field of the page.
+    pub fn field_bytes(&self, page: &dyn PageView, field: &Field) -> &'static [u8] {
+        field.slice(self.page_bytes(page))
+    }
+
+    /// Original content of the page from the rollback journal.
+    pub fn journal_page(&self, page_num: usize) -> Option<Rc<dyn PageView>> {
+        self.journal_pages.get(&page_num).cloned()
+    }
+
+    fn load_btree(&mut self, tree: BTree, reader: &Reader) {
+        let size = reader.db_header.page_size as usize;
+        let mut view_root = BTreeNodeView::default();
+        let columns = Rc::new(tree.columns);
+        let shadow = self.shadows.get(&tree.name).cloned();
+        Self::load_btree_node(
+            tree.root,
+            &mut self.pages,
+            &mut view_root,
+            size,
+            (&tree.name, &columns, tree.key_columns, &shadow),
+        );
+        self.btrees.push(BTreeView {
+            ttype: tree.ttype,
+            name: tree.name,
+            tbl_name: tree.tbl_name,
+            root: view_root,
+            columns,
+            key_columns: tree.key_columns,
+            shadow,
+        })
+    }
+
+    /// Logical rows of the b-tree with the root page, read again from the raw bytes.
+    pub fn table_rows(&self, root: usize) -> Result<Vec<Row>, Error> {
+        let reader = self.reader()?;
+        BTreeNode::new(root, &reader)?.rows(&reader)
+    }
+
+    /// Entries of the sqlite_schema table, which b-tree starts at page 1.
+    pub fn schema_entries(&self) -> Result<Vec<SchemaEntry>> {
+        let rows = self.table_rows(1)?;
+        Ok(rows
+            .iter()
+            .map(|row| SchemaEntry::new(&row.values))
+            .collect())
+    }
+
+    /// Signs that the database wasn't closed cleanly, so the main file alone might
+    /// not reflect the logical content of the database.
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = vec![];
+        let header = &self.db_header;
+
+        let journal = self.journal_bytes.and_then(|b| Journal::try_from(b).ok());
+        if let Some(journal) = journal {
+            let valid = journal.records().filter(|r| r.valid).count();
+            if valid > 0 {
+                warnings.push(format!(
+                    "Hot journal holds the original content of {} pages: the transaction wasn't finished, \
+                    the database file may be half-written and SQLite would roll it back before the next read.",
+                    valid
+                ));
+            }
+            if let Some(pointer) = journal.super_journal.filter(|p| p.valid) {
+                let children = match &self.super_journal {
+                    Some(super_journal) => format!(
+                        "it lists the journals of all the databases in the transaction: {}.",
+                        super_journal
+                            .children
+                            .iter()
+                            .map(|(_, name)| name.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                    None => "it isn't loaded here, if it's gone the transaction has committed and the journal is stale.".to_string(),
+                };
+                warnings.push(format!(
+                    "The journal is a part of a multi-database transaction with the super-journal {}, {}",
+                    pointer.name, children
+                ));
+            }
+        }
+
+        if header.write_version == 2 || header.read_version == 2 {
+            warnings.push(
+                "The database is in WAL mode: committed transactions might still live in the -wal file \
+                until they're checkpointed, and the -wal file isn't loaded here."
+                    .to_string(),
+            );
+        }
+
+        if header.db_size != 0 && header.file_change_counter != header.version_valid_for_number {
+            warnings.push(format!(
+                "In-header database size is stale: file change counter {} doesn't match version-valid-for number {}, \
+                so the size was written by an older SQLite or the header was updated partially.",
+                header.file_change_counter, header.version_valid_for_number
+            ));
+        }
+
+        if let Some(counted) = self.freelist_count() {
+            if counted != header.freelist_total as usize {
+                warnings.push(format!(
+                    "Header counts {} freelist pages, but the freelist has {}.",
+                    header.freelist_total, counted
+                ));
+            }
+        }
+        warnings
+    }
+
+    /// Mismatches of the field with the content of the file, only the database header
+    /// fields of page 1 are checked.
+    pub fn diagnostics(&self, page_num: usize, field: &Field) -> &[String] {
+        match page_num {
+            1 => self.diagnostics.get(&field.kind).map_or(&[], Vec::as_slice),
+            _ => &[],
+        }
+    }
+
+    /// Decoded value of an FTS5 shadow table: blocks of the full-text index, leaf
+    /// page numbers of the segment b-trees and token counts. Empty for other values.
+    pub fn fts5_notes(&self, part: &dyn Part, field: &Field) -> Vec<String> {
+        let (Some(table), Some(column), Value::Record(value)) =
+            (&field.table, &field.column, &field.value)
+        else {
+            return vec![];
+        };
+        let Some(ShadowTable::Fts5(shadow)) = self.shadows.get(table) else {
+            return vec![];
+        };
+        let bytes = value.bytes.as_deref().unwrap_or_default();
+        let notes = match (shadow.table, column.to_ascii_lowercase().as_str()) {
+            (Fts5Table::Data, "block") => {
+                // Rowid of the block is the rowid of the cell, id is its alias.
+                let rowid = part.fields().iter().find_map(|f| match &f.value {
+                    Value::Varint(v) if f.kind == FieldKind::CellRowid => Some(v.value),
+                    _ => None,
+                });
+                let Some(rowid) = rowid else {
+                    return vec![];
+                };
+                Fts5Block::new(rowid, bytes).map(|block| fts5_block_notes(&block, bytes, shadow))
+            }
+            (Fts5Table::Idx, "pgno") => {
+                let pgno = match value.value {
+                    RecordType::I8(v) => v as i64,
+                    RecordType::I16(v) => v as i64,
+                    RecordType::I24(v) | RecordType::I32(v) => v as i64,
+                    RecordType::I48(v) | RecordType::I64(v) => v,
+                    RecordType::Zero(v) | RecordType::One(v) => v as i64,
+                    _ => return vec![],
+                };
+                Ok(vec![format!(
+                    "Leaf page {} of the segment{}.",
+                    pgno >> 1,
+                    match pgno & 1 {
+                        1 => ", the doclist of its first term has a doclist index",
+                        _ => "",
+                    }
+                )])
+            }
+            (Fts5Table::Docsize, "sz") => fts5_docsize(bytes).map(|sizes| {
+                let sizes = sizes.iter().map(u64::to_string).collect::<Vec<_>>();
+                vec![format!("Tokens per column: {}.", sizes.join(", "))]
+            }),
+            _ => return vec![],
+        };
+        notes.unwrap_or_else(|e| vec![e.to_string()])
+    }
+
+    /// Bytes read as the structure, line by line, whatever the page they're on
+    /// is parsed as. The cell payload is decoded as far as the bytes go.
+    pub fn interpret(&self, bytes: &[u8], how: Interpretation) -> Result<Vec<String>, StdError> {
+        let describe = |code: i64| {
+            RecordCode::describe(code).unwrap_or_else(|| "Invalid serial type".to_string())
+        };
+        let lines = match how {
+            Interpretation::Varint => {
+                let varint = read_varint(bytes)?;
+                vec![
+                    format!("{} in {} byte(s).", varint.value, varint.bytes.len()),
+                    format!("As a serial type: {}.", describe(varint.value)),
+                ]
+            }
+            Interpretation::U32 => {
+                let value = read_u32(bytes)?;
+                let mut lines = vec![format!("{} (0x{:08x}).", value, value)];
+                if (1..=self.db_header.db_size).contains(&value) {
+                    lines.push(format!("As a page number: page {} of the database.", value));
+                }
+                lines
+            }
+            Interpretation::RecordHeader => {
+                let header = read_record_header(bytes)?;
+                let mut lines = vec![format!(
+                    "Header of {} bytes, {} serial type(s).",
+                    header.size.value,
+                    header.datatypes.len()
+                )];
+                for (n, datatype) in header.datatypes.iter().enumerate() {
+                    lines.push(format!(
+                        "Column {}: {}, {}.",
+                        n,
+                        datatype.value,
+                        describe(datatype.value)
+                    ));
+                }
+                if header.spilled > 0 {
+                    lines.push(format!(
+                        "{} header byte(s) past the selection.",
+                        header.spilled
+                    ));
+                }
+                lines
+            }
+            Interpretation::Cell(page_type) => {
+                let cell = read_cell(page_type, self.db_header.clone(), bytes)?;
+                let mut lines = vec![format!("{} of {} bytes.", how, cell.size(&self.db_header))];
+                match &cell {
+                    Cell::TableInterior(c) => {
+                        lines.push(format!("Left child page: {}.", c.left_page_number));
+                        lines.push(format!("Rowid: {}.", c.rowid_varint.value));
+                    }
+                    Cell::TableLeaf(c) => {
+                        lines.push(format!("Payload: {} bytes.", c.payload_varint.value));
+                        lines.push(format!("Rowid: {}.", c.rowid_varint.value));
+                    }
+                    Cell::IndexLeaf(c) => {
+                        lines.push(format!("Payload: {} bytes.", c.payload_varint.value));
+                    }
+                    Cell::IndexInterior(c) => {
+                        lines.push(format!("Left child page: {}.", c.left_page_number));
+                        lines.push(format!("Payload: {} bytes.", c.payload_varint.value));
+                    }
+                }
+                if let Some(record) = cell.payload() {
+                    for (n, value) in record?.values.iter().enumerate() {
+                        lines.push(format!("Column {}: {}.", n, Value::Record(value.clone())));
+                    }
+                }
+                let overflow = match &cell {
+                    Cell::TableLeaf(c) => c.overflow.as_ref(),
+                    Cell::IndexLeaf(c) => c.overflow.as_ref(),
+                    Cell::IndexInterior(c) => c.overflow.as_ref(),
+                    Cell::TableInterior(_) => None,
+                };
+                if let Some(overflow) = overflow {
+                    lines.push(format!(
+                        "Payload continues on overflow page {}.",
+                        overflow.page
+                    ));
+                }
+                lines
+            }
+        };
+        Ok(lines)
+    }
+
+    /// Split of the reserved space of the decrypted SQLCipher page into the IV, the
+    /// HMAC and the padding. Empty for other fields or databases.
+    pub fn cipher_notes(&self, field: &Field) -> Vec<String> {
+        let (Some(cipher), FieldKind::Reserved, Value::Unallocated(bytes)) =
+            (&self.cipher, &field.kind, &field.value)
+        else {
+            return vec![];
+        };
+        let hex = |bytes: &[u8]| {
+            bytes
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<String>()
+        };
+        let (iv, rest) = bytes.split_at(SQLCIPHER_IV_SIZE.min(bytes.len()));
+        let (hmac, padding) = rest.split_at(cipher.hmac.size().min(rest.len()));
+        let mut notes = vec![
+            format!("Decrypted with {}.", cipher),
+            format!("IV of the page: {}.", hex(iv)),
+            format!(
+                "{} of the ciphertext, the IV and the page number: {}.",
+                cipher.hmac,
+                hex(hmac)
+            ),
+        ];
+        if !padding.is_empty() {
+            notes.push(format!(
+                "{} byte(s) of padding to whole AES blocks.",
+                padding.len()
+            ));
+        }
+        notes
+    }
+
+    /// Cross-check the database header with the file: its size, the freelist
+    /// and the text of the schema table.
+    fn validate_header(&mut self) {
+        let header = self.db_header.clone();
+        let mut diagnostics = HashMap::<FieldKind, Vec<String>>::new();
+
+        let page_size = header.page_size as usize;
+        let file_pages = self.bytes.len() / page_size;
+        if !self.bytes.len().is_multiple_of(page_size) {
+            diagnostics.entry(FieldKind::DbPageSize).or_default().push(format!(
+                "File size {} isn't a multiple of the page size: the last {} byte(s) don't make a whole page.",
+                self.bytes.len(),
+                self.bytes.len() % page_size
+            ));
+        }
+        if header.db_size != 0 {
+            let size = diagnostics.entry(FieldKind::DbSizeInPages).or_default();
+            if header.file_change_counter != header.version_valid_for_number {
+                size.push(format!(
+                    "Not valid: file change counter {} doesn't match version-valid-for number {}, so SQLite computes the size from the file instead, {} page(s).",
+                    header.file_change_counter, header.version_valid_for_number, file_pages
+                ));
+            } else if header.db_size as usize != file_pages {
+                size.push(format!(
+                    "The file holds {} page(s), not {}.",
+                    file_pages, header.db_size
+                ));
+            }
+        }
+
+        match self.freelist_count() {
+            Some(counted) if counted != header.freelist_total as usize => {
+                diagnostics.entry(FieldKind::DbFreelistPages).or_default().push(format!(
+                    "The freelist has {} page(s), trunk pages included.",
+                    counted
+                ));
+            }
+            Some(_) => {}
+            None => diagnostics.entry(FieldKind::DbFirstFreelistTrunk).or_default().push(
+                "The freelist can't be followed from this page: a trunk page is out of the file or the chain loops back.".to_string(),
+            ),
+        }
+
+        if let Some(actual) = self.schema_encoding() {
+            if actual != header.text_encoding {
+                diagnostics
+                    .entry(FieldKind::DbTextEncoding)
+                    .or_default()
+                    .push(format!(
+                        "Text of the schema table reads as {}, not as {}.",
+                        actual, header.text_encoding
+                    ));
+            }
+        }
+
+        diagnostics.retain(|_, messages| !messages.is_empty());
+        self.diagnostics = diagnostics;
+    }
+
+    /// Encoding the types of the schema table entries read back in. They are plain
+    /// ASCII words, so only the right encoding gets all of them. None if the schema is empty.
+    fn schema_encoding(&self) -> Option<TextEncoding> {
+        const TYPES: [&str; 4] = ["table", "index", "view", "trigger"];
+        let declared = self.db_header.text_encoding;
+        let others = [
+            TextEncoding::UTF8,
+            TextEncoding::UTF16le,
+            TextEncoding::UTF16be,
+        ]
+        .into_iter()
+        .filter(|e| *e != declared);
+        for encoding in std::iter::once(declared).chain(others) {
+            let mut reader = self.reader().ok()?;
+            reader.db_header = Rc::new(DBHeader {
+                text_encoding: encoding,
+                ..(*self.db_header).clone()
+            });
+            let cells = reader.get_schema_cells();
+            let typed = cells.iter().all(|cell| {
+                matches!(
+                    cell.payload().ok().and_then(|p| p.values.first()).map(|v| &v.value),
+                    Some(RecordType::Text(Some(t))) if TYPES.contains(&t.as_str())
+                )
+            });
+            if !cells.is_empty() && typed {
+                return Some(encoding);
+            }
+        }
+        None
+    }
+
+    /// Number of pages on the freelist, trunk pages included, None if it can't be read.
+    fn freelist_count(&self) -> Option<usize> {
+        let reader = self.reader().ok()?;
+        let mut count = 0;
+        let mut page_num = self.db_header.first_free_page_num as usize;
+        while page_num != 0 {
+            // Trunk chain longer than the database must be a loop.
+            if count > reader.pages_total() {
+                return None;
+            }
+            let trunk = reader.get_trunk_freelist_page(page_num).ok()?;
+            count += 1 + trunk.leaf_page_amount as usize;
+            page_num = trunk.next_page as usize;
+        }
+        Some(count)
+    }
+
+    /// Summarize the loaded pages, so the page list doesn't go through them on every render.
+    fn summarize_pages(&mut self) {
+        self.summaries = self
+            .pages
+            .iter()
+            .map(|(&page_num, page)| (page_num, page.summary()))
+            .collect();
+    }
+
+    /// Index page number fields of the loaded pages by the page they point to.
+    /// Unreferenced pages aren't in use, so what they point to is left out.
+    /// Parts are built for it, the ones of the pages, which aren't shown, are dropped after.
+    fn index_pointers(&mut self) {
+        self.pointers.clear();
+        let shown = self.shown.borrow();
+        for page in self.pages.values().filter(|p| !p.unreferenced()) {
+            for part in page.parts().iter() {
+                for field in part.fields() {
+                    if let Ok(target) = field.try_page_number() {
+                        self.pointers
+                            .entry(target as usize)
+                            .or_default()
+                            .push((page.id(), field.offset));
+                    }
+                }
+            }
+            if !shown.iter().any(|p| Rc::ptr_eq(p, page)) {
+                page.release_parts();
+            }
         }
+    }
+
+    /// Pages and bytes of every b-tree, the freelist and the rest of the file,
+    /// similar to what sqlite3_analyzer reports.
+    pub fn storage_report(&self) -> Vec<StorageRow> {
+        let page_size = self.db_header.page_size as usize;
+        let total = self.size().div_ceil(page_size).max(1);
+        let row = |name: &str, kind: &str, pages: usize, overflow_pages: usize| StorageRow {
+            name: name.to_string(),
+            kind: kind.to_string(),
+            pages,
+            overflow_pages,
+            bytes: (pages + overflow_pages) * page_size,
+            share: (pages + overflow_pages) as f64 / total as f64,
+        };
 
-        let btrees = reader.get_btrees()?;
-        let mut view_trees = vec![];
-        for tree in btrees {
-            let mut view_root = BTreeNodeView::default();
-            Self::load_btree_node(tree.root, &mut pages_map, &mut view_root, size);
-            view_trees.push(BTreeView {
-                ttype: tree.ttype,
-                name: tree.name,
-                root: view_root,
+        let mut rows = self
+            .btrees
+            .iter()
+            .map(|b| {
+                row(
+                    &b.name,
+                    &b.ttype,
+                    b.root.pages().len(),
+                    b.root.overflow_pages(),
+                )
             })
+            .collect::<Vec<_>>();
+        let freelist = self.freelist_count().unwrap_or(0);
+        rows.push(row("Freelist", "freelist", freelist, 0));
+        let ptrmap = self.reader().map_or(0, |r| r.ptrmap_pages().len());
+        if ptrmap > 0 {
+            rows.push(row("Pointer map", "ptrmap", ptrmap, 0));
         }
+        let accounted = rows
+            .iter()
+            .map(|r| r.pages + r.overflow_pages)
+            .sum::<usize>();
+        // Lock-byte page and pages, which nothing refers to.
+        rows.push(row("Other", "other", total.saturating_sub(accounted), 0));
+        rows
+    }
+
+    /// Space usage of the b-tree page, None for pages of other kinds.
+    pub fn page_stats(&self, page_num: usize) -> Option<PageStats> {
+        if !self
+            .btrees
+            .iter()
+            .any(|b| b.root.pages().contains(&page_num))
+        {
+            return None;
+        }
+        let reader = self.reader().ok()?;
+        let page = reader.get_btree_page(page_num).ok()?;
+        Some(PageStats::new(&page))
+    }
+
+    /// What inserting a cell with the payload of the size would do to the b-tree page,
+    /// None for pages of other kinds. Rowid of the cell follows the largest one on the page.
+    pub fn simulate_insertion(&self, page_num: usize, payload_size: u64) -> Option<Insertion> {
+        self.page_stats(page_num)?;
+        let reader = self.reader().ok()?;
+        let page = reader.get_btree_page(page_num).ok()?;
+        let rowid = page
+            .cells
+            .iter()
+            .filter_map(|cell| match cell {
+                Cell::TableLeaf(c) => Some(c.rowid_varint.value),
+                Cell::TableInterior(c) => Some(c.rowid_varint.value),
+                _ => None,
+            })
+            .max()
+            .unwrap_or_default();
+        Some(Insertion::new(&page, payload_size, rowid.saturating_add(1)))
+    }
+
+    /// Name, number of pages and average fill of every b-tree.
+    pub fn btree_fill(&self) -> Vec<(String, usize, f64)> {
+        let Ok(reader) = self.reader() else {
+            return vec![];
+        };
+        self.btrees
+            .iter()
+            .map(|btree| {
+                let fills = btree
+                    .root
+                    .pages()
+                    .into_iter()
+                    .filter_map(|n| reader.get_btree_page(n).ok())
+                    .map(|page| PageStats::new(&page).fill())
+                    .collect::<Vec<_>>();
+                let average = fills.iter().sum::<f64>() / fills.len().max(1) as f64;
+                (btree.name.clone(), fills.len(), average)
+            })
+            .collect()
+    }
 
-        let pages: Vec<Rc<dyn PageView>> = pages_map.into_values().collect();
+    /// B-tree, which the page belongs to, and the page numbers from its root to the page.
+    pub fn btree_path(&self, page_num: usize) -> Option<(&BTreeView, Vec<usize>)> {
+        self.btrees
+            .iter()
+            .find_map(|btree| Some((btree, btree.root.path_to(page_num)?)))
+    }
+
+    /// Payload of the cell at the page offset, joined with its overflow pages.
+    pub fn reassemble(&self, page_num: usize, cell_offset: usize) -> Result<Reassembled, Error> {
+        let reader = self.reader()?;
+        Reassembled::new(page_num, cell_offset, &reader)
+    }
 
-        Ok(Self {
-            included_db,
+    /// Overflow chain the overflow page belongs to, followed from the cell which owns it.
+    pub fn overflow_chain(&self, page: &dyn PageView) -> Option<OverflowChain> {
+        let owner = page.overflow_owner()?;
+        let reader = self.reader().ok()?;
+        let btree_page = reader.get_btree_page(owner.page_num).ok()?;
+        let overflow = btree_page.cells.get(owner.cell)?.overflow()?;
+        let mut pages = vec![];
+        let mut next = overflow.page as usize;
+        while next != 0 && !pages.contains(&next) {
+            let Ok(buf) = reader.get_raw_page(next) else {
+                break;
+            };
+            pages.push(next);
+            next = read_u32(&buf).unwrap_or_default() as usize;
+        }
+        Some(OverflowChain {
+            owner,
             pages,
-            btrees: view_trees,
+            end: next,
         })
     }
 
+    /// Look up the rowid in the table b-tree with the root page.
+    pub fn search_rowid(&self, root: usize, rowid: i64) -> Result<KeySearch, Error> {
+        let reader = self.reader()?;
+        KeySearch::rowid(root, rowid, &reader)
+    }
+
+    /// Page number and offset of the first freeblock on each of the pages, where
+    /// deleted content might still be found.
+    pub fn freeblocks(&self, pages: &[usize]) -> Vec<(usize, usize)> {
+        let Ok(reader) = self.reader() else {
+            return vec![];
+        };
+        pages
+            .iter()
+            .filter_map(|&n| {
+                let page = reader.get_btree_page(n).ok()?;
+                page.free_blocks.first().map(|b| (n, b.offset))
+            })
+            .collect()
+    }
+
+    /// Page number and the page offset of the byte at the file offset.
+    pub fn page_for_offset(&self, offset: usize) -> Option<(usize, usize)> {
+        if offset >= self.size() {
+            return None;
+        }
+        let size = self.db_header.page_size as usize;
+        Some((offset / size + 1, offset % size))
+    }
+
+    /// B-tree of the table or index, names are case-insensitive as they are in SQL.
+    pub fn tree_by_name(&self, name: &str) -> Option<&BTreeView> {
+        self.btrees
+            .iter()
+            .find(|btree| btree.name.eq_ignore_ascii_case(name))
+    }
+
     pub fn included_dbnames(&self) -> Vec<String> {
         self.included_db.keys().map(|k| k.to_string()).collect()
     }
 
+    /// Get loaded page, pages outside of the file or not loaded yet are shown as
+    /// corrupt with whatever bytes the file has for them.
     pub fn get_page(&self, id: u32) -> Rc<dyn PageView> {
-        self.pages
-            .get(id as usize - 1)
-            .expect("Page is outside of Viewer range.")
-            .clone()
+        let id = id as usize;
+        if let Some(page) = self.pages.get(&id) {
+            return page.clone();
+        }
+        let size = self.db_header.page_size as usize;
+        let start = id
+            .saturating_sub(1)
+            .saturating_mul(size)
+            .min(self.bytes.len());
+        let bytes = self.bytes[start..(start + size).min(self.bytes.len())].to_vec();
+        let total = self.size() / size;
+        let error = match id == 0 || id > total {
+            true => Error::OutOfBounds { page: id, total },
+            false if self.remote.is_some() => {
+                Error::Malformed(format!("Page {} isn't fetched yet.", id))
+            }
+            false => Error::Malformed(format!("Page {} isn't loaded yet.", id)),
+        };
+        Rc::new(PageElementBuilder::new(PageLayout::Corrupt(bytes, error), size, id).build())
     }
 
     pub fn get_part(&self, page: &Rc<dyn PageView>, index: usize) -> Rc<dyn Part> {
@@ -85,11 +1139,24 @@ impl Viewer {
         pmap: &mut BTreeMap<usize, Rc<dyn PageView>>,
         view_root: &mut BTreeNodeView,
         size: usize,
+        (table, columns, key_columns, shadow): (
+            &str,
+            &Rc<Vec<String>>,
+            Option<usize>,
+            &Option<ShadowTable>,
+        ),
     ) {
+        view_root.cells = node.page.page_header.cell_num as usize;
         let page_element = PageLayout::Btree(node.page);
         pmap.insert(
             node.page_num,
-            Rc::new(PageElementBuilder::new(page_element, size, node.page_num).build()),
+            Rc::new(
+                PageElementBuilder::new(page_element, size, node.page_num)
+                    .columns(table, columns.clone())
+                    .key_columns(key_columns)
+                    .shadow(shadow.clone())
+                    .build(),
+            ),
         );
         view_root.page_num = node.page_num;
 
@@ -105,49 +1172,253 @@ impl Viewer {
             }
         }
 
-        if let Some(children) = node.children {
-            for child in children {
-                let mut view_child = BTreeNodeView::default();
-                Self::load_btree_node(child, pmap, &mut view_child, size);
-                view_root.children.push(view_child);
+        // Children, which failed to parse, are still a part of the tree, in their place.
+        let mut corrupt = node.corrupt.into_iter();
+        for child in node.children.into_iter().flatten() {
+            match child {
+                Some(child) => {
+                    let mut view_child = BTreeNodeView::default();
+                    Self::load_btree_node(
+                        child,
+                        pmap,
+                        &mut view_child,
+                        size,
+                        (table, columns, key_columns, shadow),
+                    );
+                    view_root.children.push(view_child);
+                }
+                None => {
+                    let Some(corrupt) = corrupt.next() else {
+                        continue;
+                    };
+                    let page_element = PageLayout::Corrupt(corrupt.bytes, corrupt.error);
+                    pmap.insert(
+                        corrupt.page_num,
+                        Rc::new(
+                            PageElementBuilder::new(page_element, size, corrupt.page_num).build(),
+                        ),
+                    );
+                    view_root.children.push(BTreeNodeView {
+                        page_num: corrupt.page_num,
+                        ..Default::default()
+                    });
+                }
             }
         }
     }
 
-    fn load_freelist_pages(
-        page: TrunkFreelistPage,
-        page_num: usize,
+    /// Load the chain of freelist trunk pages and return it with the numbers of their
+    /// leaf pages, which are loaded later by `load_freelist_leaves`. Trunk page, which
+    /// fails to parse, is shown with the error and ends the chain.
+    fn load_freelist_trunks(
+        first_page: usize,
         pages: &mut BTreeMap<usize, Rc<dyn PageView>>,
         reader: &Reader,
-    ) -> Result<(), StdError> {
+    ) -> FreelistView {
         let page_size = reader.db_header.page_size as usize;
-        let page_element = PageLayout::TrunkFreelist(page.clone());
-        pages.insert(
-            page_num,
-            Rc::new(PageElementBuilder::new(page_element, page_size, page_num).build()),
-        );
+        let mut trunks = vec![];
+        let mut page_num = first_page;
+        // Trunk, which is already loaded, means the chain loops back.
+        while page_num != 0 && !pages.contains_key(&page_num) {
+            let page = match reader.get_trunk_freelist_page(page_num) {
+                Ok(page) => page,
+                Err(e) => {
+                    let bytes = reader.get_partial_page(page_num);
+                    let page_element = PageLayout::Corrupt(bytes, e);
+                    pages.insert(
+                        page_num,
+                        Rc::new(PageElementBuilder::new(page_element, page_size, page_num).build()),
+                    );
+                    break;
+                }
+            };
+            trunks.push(FreelistTrunkView {
+                page_num,
+                leaves: page
+                    .leaf_page_numbers
+                    .iter()
+                    .flatten()
+                    .map(|&lpn| lpn as usize)
+                    .collect(),
+            });
+            let next_page = page.next_page as usize;
+            let page_element = PageLayout::TrunkFreelist(page);
+            pages.insert(
+                page_num,
+                Rc::new(Self::with_stale_content(
+                    PageElementBuilder::new(page_element, page_size, page_num),
+                    reader,
+                )),
+            );
+            page_num = next_page;
+        }
+        FreelistView {
+            trunks,
+            end: page_num,
+        }
+    }
 
-        // Follow leaf pages from the trunk.
-        if let Some(leaf_page_numbers) = page.leaf_page_numbers {
-            for lpn in leaf_page_numbers {
-                let lpn = lpn as usize;
-                let leaf = reader.get_leaf_freelist_page(lpn)?;
-                let page_element = PageLayout::LeafFreelist(leaf);
-                pages.insert(
-                    lpn,
-                    Rc::new(PageElementBuilder::new(page_element, page_size, lpn).build()),
-                );
+    /// Load freelist leaf pages, the ones which can't be read are shown with the error.
+    /// Pages, which are already taken by b-trees, are left as they are.
+    fn load_freelist_leaves(
+        leaves: &[usize],
+        pages: &mut BTreeMap<usize, Rc<dyn PageView>>,
+        reader: &Reader,
+    ) {
+        let page_size = reader.db_header.page_size as usize;
+        for &lpn in leaves {
+            if pages.contains_key(&lpn) {
+                continue;
             }
-        };
+            let page = match reader.get_leaf_freelist_page(lpn) {
+                Ok(leaf) => Self::with_stale_content(
+                    PageElementBuilder::new(PageLayout::LeafFreelist(leaf), page_size, lpn),
+                    reader,
+                ),
+                Err(e) => {
+                    let bytes = reader.get_partial_page(lpn);
+                    PageElementBuilder::new(PageLayout::Corrupt(bytes, e), page_size, lpn).build()
+                }
+            };
+            pages.insert(lpn, Rc::new(page));
+        }
+    }
+
+    /// Freed pages keep their old content, unless it's overwritten: try to read
+    /// it as the b-tree page it might have been.
+    fn with_stale_content(builder: PageElementBuilder, reader: &Reader) -> PageElement {
+        match reader.get_btree_page(builder.id) {
+            Ok(page) => {
+                let stale =
+                    PageElementBuilder::new(PageLayout::Btree(page), builder.size, builder.id)
+                        .stale()
+                        .build();
+                builder.alternate(Rc::new(stale)).build()
+            }
+            Err(_) => builder.build(),
+        }
+    }
+}
 
-        // Follow further trunk pages.
-        match page.next_page {
-            0 => Ok(()),
-            page_num => {
-                let page_num = page_num as usize;
-                let next_page = reader.get_trunk_freelist_page(page_num)?;
-                Self::load_freelist_pages(next_page, page_num, pages, reader)
+/// Terms of a leaf page described, the rest is only counted.
+const FTS5_TERMS_SHOWN: usize = 32;
+
+/// Lines describing the block of the `%_data` table, offsets are counted from the
+/// start of the block.
+fn fts5_block_notes(block: &Fts5Block, bytes: &[u8], shadow: &Fts5Shadow) -> Vec<String> {
+    let join = |values: &[u64]| {
+        values
+            .iter()
+            .map(u64::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    match block {
+        Fts5Block::Averages(averages) => vec![format!(
+            "Averages record of '{}': {} row(s), tokens in each column: {}.",
+            shadow.vtab,
+            averages.rows,
+            join(&averages.tokens)
+        )],
+        Fts5Block::Structure(structure) => {
+            let mut notes = vec![format!(
+                "Structure record of '{}'{}: cookie {}, {} segment(s) written so far.",
+                shadow.vtab,
+                if structure.v2 { ", version 2" } else { "" },
+                structure.cookie,
+                structure.write_counter
+            )];
+            for (n, level) in structure.levels.iter().enumerate() {
+                let segments = level
+                    .segments
+                    .iter()
+                    .map(|s| format!("{} (pages {}..{})", s.id, s.first_page, s.last_page))
+                    .collect::<Vec<_>>();
+                notes.push(format!(
+                    "Level {}: {}{}.",
+                    n,
+                    match segments.is_empty() {
+                        true => "no segments".to_string(),
+                        false => format!("segment(s) {}", segments.join(", ")),
+                    },
+                    match level.merge {
+                        0 => String::new(),
+                        merge => format!(", {} of them being merged", merge),
+                    }
+                ));
+            }
+            notes
+        }
+        Fts5Block::Leaf(leaf) => {
+            let mut notes = vec![format!(
+                "Leaf page {} of segment {}: {}, the page index starts at {}.",
+                leaf.id.pgno,
+                leaf.id.segid,
+                match leaf.first_rowid {
+                    0 => "no rowid starts on it".to_string(),
+                    offset => format!("the first rowid is at {}", offset),
+                },
+                leaf.pgidx
+            )];
+            let first = leaf.terms.first().map_or(leaf.pgidx, |t| t.offset);
+            if first > FTS5_LEAF_HEADER_SIZE {
+                notes.push(format!(
+                    "Bytes {}..{} continue the doclist of the last term of the previous page.",
+                    FTS5_LEAF_HEADER_SIZE, first
+                ));
+            }
+            for term in leaf.terms.iter().take(FTS5_TERMS_SHOWN) {
+                let (start, size) = term.doclist;
+                let index = match term.index {
+                    0 => String::new(),
+                    n => format!(" of prefix index {}", n),
+                };
+                let docs = match shadow.full_detail {
+                    true => Some(fts5_doclist(&bytes[start..start + size])),
+                    false => None,
+                };
+                let doclist = match docs {
+                    Some(docs) => docs
+                        .iter()
+                        .map(|doc| {
+                            let positions = doc
+                                .positions
+                                .iter()
+                                .map(|(column, token)| format!("c{}:{}", column, token))
+                                .collect::<Vec<_>>();
+                            match doc.deleted {
+                                true => format!("{} (deleted)", doc.rowid),
+                                false => format!("{} ({})", doc.rowid, positions.join(" ")),
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    None => format!("{} byte(s) of doclist", size),
+                };
+                notes.push(format!(
+                    "{}: '{}'{}, rows {}.",
+                    term.offset,
+                    String::from_utf8_lossy(&term.term),
+                    index,
+                    doclist
+                ));
+            }
+            if leaf.terms.len() > FTS5_TERMS_SHOWN {
+                notes.push(format!(
+                    "And {} more term(s).",
+                    leaf.terms.len() - FTS5_TERMS_SHOWN
+                ));
             }
+            notes
         }
+        Fts5Block::DoclistIndex(id) => vec![format!(
+            "Page {} of a doclist index of segment {}, height {}: it keeps the first rowid of every leaf page a long doclist spans, so a rowid is found without reading the whole doclist.",
+            id.pgno, id.segid, id.height
+        )],
+        Fts5Block::Tombstone(id) => vec![format!(
+            "Tombstone page {} of segment {}: hash table of the rowids deleted from the segment.",
+            id.pgno,
+            id.segid - (1 << 16)
+        )],
     }
 }
@@ -8,12 +8,14 @@ module.exports = {
     "pattern-white",
     "pattern-size-1",
     "pattern-bg-slate-200",
+    "pattern-bg-slate-800",
     "locked",
+    // Palette colors are put into class names at runtime, e.g. the minimap slices.
     {
-        pattern: /(bg|text|border)-(orange|green|slate)-(600|700|800)/,
+        pattern: /(bg|text|border)-(orange|green|blue|slate)-(600|700|800)/,
     },
     {
-        pattern: /bg-slate-([1-4][0-9]0)/,
+        pattern: /bg-slate-([1-9][0-9]0)/,
     },
   ],
   daisyui: {
@@ -23,6 +25,11 @@ module.exports = {
           ...require("daisyui/src/theming/themes")["corporate"],
         "primary": "D9DDE0", 
         }
+      },
+      {
+        "custom-dark": {
+          ...require("daisyui/src/theming/themes")["dark"],
+        }
       }
     ],
   },
@@ -0,0 +1,123 @@
+//! Development tasks, run with `cargo xtask <task>`.
+//!
+//! `fixtures` generates the included example databases of the UI from their
+//! SQL scripts in `ui/fixtures`, the same scripts the UI shows as the statements
+//! each example was created with.
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rusqlite::functions::{Context, FunctionFlags};
+use rusqlite::Connection;
+
+type StdError = Box<dyn std::error::Error>;
+
+/// Examples compiled into the UI binary, the rest are served as static assets.
+/// Inline ones are tracked by git, as the UI doesn't build without them.
+const INLINE: &[&str] = &["simple"];
+
+/// Comment, which marks the point to copy the next numbered snapshot of the database at.
+const SNAPSHOT: &str = "-- snapshot";
+
+fn main() -> Result<(), StdError> {
+    match std::env::args().nth(1).as_deref() {
+        Some("fixtures") => fixtures(),
+        _ => Err("Usage: cargo xtask fixtures".into()),
+    }
+}
+
+fn fixtures() -> Result<(), StdError> {
+    // Scripts refer to the data files in `ui/dev` relative to the UI root.
+    std::env::set_current_dir(Path::new(env!("CARGO_MANIFEST_DIR")).join("../ui"))?;
+    let mut scripts = fs::read_dir("fixtures")?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<PathBuf>, _>>()?;
+    scripts.retain(|path| path.extension().is_some_and(|ext| ext == "sql"));
+    scripts.sort();
+
+    for script in scripts {
+        let name = script
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .ok_or_else(|| format!("Unexpected script name: {}", script.display()))?;
+        let dir = match INLINE.contains(&name) {
+            true => "included",
+            false => "assets/included",
+        };
+        fs::create_dir_all(dir)?;
+        let out = Path::new(dir).join(name);
+        let written = generate(name, &fs::read_to_string(&script)?, &out)
+            .map_err(|e| format!("{}: {}", script.display(), e))?;
+        for path in written {
+            println!("{}", path.display());
+        }
+    }
+    Ok(())
+}
+
+/// Run the script against a new database and copy it to `out` once the script is over,
+/// or to `out-1`, `out-2` and so on at each snapshot comment. Transaction, which is
+/// still open at the end of the script, is copied together with its hot journal.
+/// Returns the paths of the written files.
+fn generate(name: &str, script: &str, out: &Path) -> Result<Vec<PathBuf>, StdError> {
+    let tmp = std::env::temp_dir().join("sqlite-repr-fixtures");
+    fs::create_dir_all(&tmp)?;
+    let db = tmp.join(name);
+    let journal = with_suffix(&db, "-journal");
+    for path in [&db, &journal] {
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+    }
+
+    let conn = Connection::open(&db)?;
+    register_functions(&conn)?;
+    let mut batch = String::new();
+    let mut written = vec![];
+    for line in script.lines() {
+        if line.starts_with(SNAPSHOT) {
+            conn.execute_batch(&batch)?;
+            batch.clear();
+            let snapshot = with_suffix(out, &format!("-{}", written.len() + 1));
+            fs::copy(&db, &snapshot)?;
+            written.push(snapshot);
+        } else {
+            batch.push_str(line);
+            batch.push('\n');
+        }
+    }
+    conn.execute_batch(&batch)?;
+    if written.is_empty() {
+        fs::copy(&db, out)?;
+        written.push(out.to_path_buf());
+        if journal.exists() {
+            let hot_journal = with_suffix(out, "-journal");
+            fs::copy(&journal, &hot_journal)?;
+            written.push(hot_journal);
+        }
+    }
+    Ok(written)
+}
+
+/// Helpers to load the data files: `readfile(path)` returns the content as a blob,
+/// `readlines(path)` returns a JSON array of its lines for `json_each`.
+fn register_functions(conn: &Connection) -> rusqlite::Result<()> {
+    let flags = FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC;
+    conn.create_scalar_function("readfile", 1, flags, read)?;
+    conn.create_scalar_function("readlines", 1, flags, |ctx| {
+        let text = String::from_utf8(read(ctx)?)
+            .map_err(|e| rusqlite::Error::UserFunctionError(e.into()))?;
+        serde_json::to_string(&text.lines().collect::<Vec<_>>())
+            .map_err(|e| rusqlite::Error::UserFunctionError(e.into()))
+    })
+}
+
+fn read(ctx: &Context) -> rusqlite::Result<Vec<u8>> {
+    let path = ctx.get::<String>(0)?;
+    fs::read(path).map_err(|e| rusqlite::Error::UserFunctionError(e.into()))
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(suffix);
+    PathBuf::from(path)
+}